@@ -44,19 +44,19 @@ mod tests {
     use ethers::{
         providers::Middleware,
         signers::{LocalWallet, Signer},
-        types::{
-            transaction::eip2718::TypedTransaction, BlockId, BlockNumber, TransactionRequest, H160,
-            H256, U256, U64,
-        },
+        types::{transaction::eip2718::TypedTransaction, TransactionRequest, H256, U256},
     };
     use ethers_solc::{artifacts::Source, CompilerInput, EvmVersion, Solc};
     use hex_literal::hex;
+    use rosetta_chain_testing::{
+        funded_account, serialized_block_producer, BlockProducer, BlockProducerConfig,
+    };
     use rosetta_client::Wallet;
     use rosetta_config_ethereum::{AtBlock, CallResult};
     use rosetta_core::BlockchainClient;
     use rosetta_server_ethereum::MaybeWsEthereumClient;
     use sha3::Digest;
-    use std::{collections::BTreeMap, future::Future, path::Path, time::Duration};
+    use std::{collections::BTreeMap, future::Future, path::Path, pin::Pin, time::Duration};
 
     /// Account used to fund other testing accounts.
     const FUNDING_ACCOUNT_PRIVATE_KEY: [u8; 32] =
@@ -67,7 +67,6 @@ mod tests {
         hex!("b6b15c8cb491557369f3c7d2c287b053eb229daa9c22138887752191c9520659");
 
     /// Arbitrum rpc url
-    const ARBITRUM_RPC_HTTP_URL: &str = "http://127.0.0.1:8547";
     const ARBITRUM_RPC_WS_URL: &str = "ws://127.0.0.1:8548";
 
     type WsProvider = ethers::providers::Provider<ethers::providers::Ws>;
@@ -81,167 +80,103 @@ mod tests {
         }
     }
 
-    /// Send funds from funding account to the provided account.
-    /// This function is can be called concurrently.
-    async fn sync_send_funds(dest: H160, amount: u128) -> Result<()> {
-        // Guarantee the funding account nonce is incremented atomically
-        static NONCE: tokio::sync::Mutex<u64> = tokio::sync::Mutex::const_new(0);
-
-        // Connect to the provider
-        let wallet = LocalWallet::from_bytes(&FUNDING_ACCOUNT_PRIVATE_KEY)?;
-        let provider =
-            ethers::providers::Provider::<ethers::providers::Http>::try_from(ARBITRUM_RPC_HTTP_URL)
-                .context("Failed to create HTTP provider")?
-                .interval(Duration::from_secs(1));
-
-        // Retrieve chain id
-        let chain_id = provider.get_chainid().await?.as_u64();
-
-        // Acquire nonce lock
-        let mut nonce_lock = NONCE.lock().await;
-
-        // Initialize nonce if necessary
-        if *nonce_lock == 0 {
-            // retrieve the current nonce, used to initialize the nonce if necessary, once
-            // `OnceLock` doesn't support async functions
-            let current_nonce = provider
-                .get_transaction_count(wallet.address(), Some(BlockId::Number(BlockNumber::Latest)))
-                .await?
-                .as_u64();
-            *nonce_lock = current_nonce;
-        }
-
-        // Create a transaction request
-        let transaction_request = TransactionRequest {
-            from: None,
-            to: Some(ethers::types::NameOrAddress::Address(dest)),
-            value: Some(U256::from(amount)),
-            gas: Some(U256::from(210_000)),
-            gas_price: Some(U256::from(500_000_000)),
-            nonce: Some(U256::from(*nonce_lock)),
-            data: None,
-            chain_id: Some(chain_id.into()),
-        };
-
-        // Sign and send the transaction
-        let tx: TypedTransaction = transaction_request.into();
-        let signature = wallet.sign_transaction(&tx).await?;
-        let tx = tx.rlp_signed(&signature);
-        let pending_tx = provider.send_raw_transaction(tx).await?;
+    /// Creates a random account and send funds to it
+    async fn create_test_account(initial_balance: u128) -> Result<[u8; 32]> {
+        let client = MaybeWsEthereumClient::new(
+            "arbitrum",
+            "dev",
+            ARBITRUM_RPC_WS_URL,
+            Some(FUNDING_ACCOUNT_PRIVATE_KEY),
+        )
+        .await?;
+        let (private_key, _) = funded_account(&client, initial_balance).await?;
+        Ok(private_key)
+    }
 
-        // Increment and release nonce lock
-        // increment only after successfully send the tx to avoid nonce gaps
-        *nonce_lock += 1;
-        drop(nonce_lock);
+    /// Sends transactions to force arbitrum to mine new blocks.
+    struct ArbitrumBlockProducer {
+        wallet: LocalWallet,
+        provider: WsProvider,
+        tx: TypedTransaction,
+        /// Read on the first block, once the producer holds the lock, so concurrent tests
+        /// don't reuse a nonce.
+        nonce: Option<u64>,
+    }
 
-        // Verify if the tx reverted
-        let receipt =
-            pending_tx.confirmations(1).await?.context("failed to retrieve tx receipt")?;
-        if !matches!(receipt.status, Some(U64([1]))) {
-            anyhow::bail!("Transaction reverted: {:?}", receipt.transaction_hash);
+    impl ArbitrumBlockProducer {
+        async fn connect() -> Result<Self> {
+            // Connect to arbitrum node
+            let wallet = LocalWallet::from_bytes(&BLOCK_INCREMENTER_PRIVATE_KEY)?;
+            let provider = WsProvider::connect(ARBITRUM_RPC_WS_URL)
+                .await
+                .map(|provider| provider.interval(Duration::from_millis(500)))?;
+
+            // Retrieve chain id
+            let chain_id = provider.get_chainid().await?.as_u64();
+
+            // Create a transaction request
+            let transaction_request = TransactionRequest {
+                from: None,
+                to: Some(wallet.address().into()),
+                value: None,
+                gas: Some(U256::from(210_000)),
+                gas_price: Some(U256::from(500_000_000)),
+                nonce: None,
+                data: None,
+                chain_id: Some(chain_id.into()),
+            };
+            let tx: TypedTransaction = transaction_request.into();
+            Ok(Self { wallet, provider, tx, nonce: None })
         }
-        Ok(())
     }
 
-    /// Creates a random account and send funds to it
-    async fn create_test_account(initial_balance: u128) -> Result<[u8; 32]> {
-        use ethers::core::k256::ecdsa::SigningKey;
-        use rand_core::OsRng;
-        let signing_key = SigningKey::random(&mut OsRng);
-        let address = ::ethers::utils::secret_key_to_address(&signing_key);
-        sync_send_funds(address, initial_balance).await?;
-        Ok(signing_key.to_bytes().into())
+    impl BlockProducer for ArbitrumBlockProducer {
+        fn produce_block(&mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+            Box::pin(async move {
+                // Retrieve current nonce
+                let nonce = match self.nonce {
+                    Some(nonce) => nonce,
+                    None => self
+                        .provider
+                        .get_transaction_count(self.wallet.address(), None)
+                        .await?
+                        .as_u64(),
+                };
+
+                // Set tx nonce
+                self.tx.set_nonce(nonce);
+
+                // Increment nonce
+                self.nonce = Some(nonce + 1);
+
+                // Sign and send the transaction
+                let signature = self.wallet.sign_transaction(&self.tx).await?;
+                let tx: ethers::types::Bytes = self.tx.rlp_signed(&signature);
+                let receipt = self
+                    .provider
+                    .send_raw_transaction(tx)
+                    .await?
+                    .confirmations(1)
+                    .await?
+                    .context("tx receipt not found")?;
+
+                // Verify if the tx reverted
+                if receipt.status.map(|status| status.as_u64()) != Some(1) {
+                    anyhow::bail!("Transaction reverted: {receipt:?}");
+                }
+                Ok(())
+            })
+        }
     }
 
     /// Run the test in another thread while sending txs to force arbitrum to mine new blocks
     /// # Panic
     /// Panics if the future panics
     async fn run_test<Fut: Future<Output = ()> + Send + 'static>(future: Fut) {
-        // Guarantee that only one test is incrementing blocks at a time
-        static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
-
-        // Run the test in another thread
-        let test_handler = tokio::spawn(future);
-
-        // Acquire Lock
-        let guard = LOCK.lock().await;
-
-        // Check if the test is finished after acquiring the lock
-        if test_handler.is_finished() {
-            // Release lock
-            drop(guard);
-
-            // Now is safe to panic
-            if let Err(err) = test_handler.await {
-                std::panic::resume_unwind(err.into_panic());
-            }
-            return;
-        }
-
-        // Connect to arbitrum node
-        let wallet = LocalWallet::from_bytes(&BLOCK_INCREMENTER_PRIVATE_KEY).unwrap();
-        let provider = WsProvider::connect(ARBITRUM_RPC_WS_URL)
-            .await
-            .map(|provider| provider.interval(Duration::from_millis(500)))
-            .unwrap();
-
-        // Retrieve chain id
-        let chain_id = provider.get_chainid().await.unwrap().as_u64();
-
-        // Retrieve current nonce
-        let mut nonce = provider
-            .get_transaction_count(wallet.address(), None)
-            .await
-            .expect("failed to retrieve account nonce")
-            .as_u64();
-
-        // Create a transaction request
-        let transaction_request = TransactionRequest {
-            from: None,
-            to: Some(wallet.address().into()),
-            value: None,
-            gas: Some(U256::from(210_000)),
-            gas_price: Some(U256::from(500_000_000)),
-            nonce: None,
-            data: None,
-            chain_id: Some(chain_id.into()),
-        };
-        let mut tx: TypedTransaction = transaction_request.into();
-
-        // Mine a new block by sending a transaction until the test finishes
-        while !test_handler.is_finished() {
-            // Set tx nonce
-            tx.set_nonce(nonce);
-
-            // Increment nonce
-            nonce += 1;
-
-            // Sign and send the transaction
-            let signature = wallet.sign_transaction(&tx).await.expect("failed to sign tx");
-            let tx: ethers::types::Bytes = tx.rlp_signed(&signature);
-            let receipt = provider
-                .send_raw_transaction(tx)
-                .await
-                .unwrap()
-                .confirmations(1)
-                .await
-                .unwrap()
-                .expect("tx receipt not found");
-
-            // Verify if the tx reverted
-            assert!(receipt.status.unwrap().as_u64() == 1, "Transaction reverted: {receipt:?}");
-
-            // Wait 500ms for the tx to be mined
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-        }
-        // Release lock
-        drop(guard);
-
-        // Now is safe to panic
-        if let Err(err) = test_handler.await {
-            // Resume the panic on the main task
-            std::panic::resume_unwind(err.into_panic());
-        }
+        let producer = ArbitrumBlockProducer::connect().await.unwrap();
+        // Wait 500ms for the tx to be mined
+        let config = BlockProducerConfig::new(producer, Duration::from_millis(500));
+        serialized_block_producer(future, config).await;
     }
 
     #[tokio::test]
@@ -303,6 +238,31 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_funded_account() {
+        let client = MaybeWsEthereumClient::new(
+            "arbitrum",
+            "dev",
+            ARBITRUM_RPC_WS_URL,
+            Some(FUNDING_ACCOUNT_PRIVATE_KEY),
+        )
+        .await
+        .expect("Error creating ArbitrumClient");
+        let balance = 5 * u128::pow(10, client.config().currency_decimals);
+        let (private_key, wallet) = funded_account(&client, balance).await.unwrap();
+        assert_eq!(wallet.balance().await.unwrap(), balance);
+
+        // The returned key is the account of the wallet
+        let address = rosetta_client::crypto::SecretKey::from_bytes(
+            rosetta_client::crypto::Algorithm::EcdsaRecoverableSecp256k1,
+            &private_key,
+        )
+        .unwrap()
+        .public_key()
+        .to_address(client.config().address_format);
+        assert_eq!(wallet.account().address, address.address());
+    }
+
     fn compile_snippet(source: &str) -> Result<Vec<u8>> {
         let solc = Solc::default();
         let source = format!("contract Contract {{ {source} }}");
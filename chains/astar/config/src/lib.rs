@@ -52,6 +52,7 @@ pub fn config(network: &str) -> Result<BlockchainConfig> {
         currency_decimals: 18,
         node_uri: NodeUri::parse("ws://127.0.0.1:9945")?,
         node_image: "staketechnologies/astar-collator:v5.28.0-rerun",
+        node_entrypoint: None,
         node_command: Arc::new(|network, port| {
             let mut params = vec![
                 "astar-collator".into(),
@@ -69,5 +70,6 @@ pub fn config(network: &str) -> Result<BlockchainConfig> {
         node_additional_ports: &[],
         connector_port: 8083,
         testnet: network != "astar",
+        block_explorer_url: None,
     })
 }
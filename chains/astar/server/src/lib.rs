@@ -13,7 +13,7 @@ use rosetta_core::{
         address::{Address, AddressFormat},
         PublicKey,
     },
-    types::{BlockIdentifier, PartialBlockIdentifier},
+    types::{BlockIdentifier, BlockTag, PartialBlockIdentifier},
     BlockchainClient, BlockchainConfig,
 };
 use rosetta_server::ws::default_client;
@@ -45,12 +45,13 @@ pub mod ext {
     pub use subxt;
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct AstarMetadataParams(pub EthereumMetadataParams);
 
 #[derive(Deserialize, Serialize)]
 pub struct AstarMetadata(pub EthereumMetadata);
 
+#[derive(Clone)]
 pub struct AstarClient {
     client: MaybeWsEthereumClient,
     ws_client: OnlineClient<PolkadotConfig>,
@@ -83,6 +84,92 @@ impl AstarClient {
         Ok(Self { client: ethereum_client, ws_client: substrate_client, rpc_methods })
     }
 
+    /// Verifies the node is synced, see [`MaybeWsEthereumClient::preflight`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the node is unreachable or isn't synced.
+    pub async fn preflight(&self, max_blocks_behind: u64) -> Result<()> {
+        self.client.preflight(max_blocks_behind).await
+    }
+
+    /// Returns the size of the code `init_code` would deploy, see
+    /// [`MaybeWsEthereumClient::deployed_code_size`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the init code reverts or the node doesn't support state overrides.
+    pub async fn deployed_code_size(&self, from: [u8; 20], init_code: &[u8]) -> Result<usize> {
+        self.client.deployed_code_size(from, init_code).await
+    }
+
+    /// Returns the client of the EVM json-rpc api of the node.
+    pub const fn ethereum_client(&self) -> &MaybeWsEthereumClient {
+        &self.client
+    }
+
+    /// Resolves the substrate block referenced by `block_hash`, which can be either the hash of
+    /// an ethereum block or the hash of a substrate block. Ethereum blocks are tried first, and
+    /// mapped to the substrate block with the same number.
+    async fn substrate_block_from_hash(
+        &self,
+        block_hash: &[u8; 32],
+    ) -> Result<BlockRef<subxt::utils::H256>> {
+        let ethereum_block =
+            self.client.call(&EthQuery::GetBlockByHash(H256(*block_hash).into())).await.map(
+                |result| match result {
+                    EthQueryResult::GetBlockByHash(block) => block,
+                    _ => unreachable!(),
+                },
+            );
+
+        if let Ok(Some(ethereum_block)) = ethereum_block {
+            // Convert ethereum block to substrate block by fetching the block by number.
+            let substrate_block_number = BlockNumber::Number(ethereum_block.header().number());
+            let substrate_block_hash = self
+                .rpc_methods
+                .chain_get_block_hash(Some(substrate_block_number))
+                .await?
+                .map(BlockRef::from_hash)
+                .ok_or_else(|| anyhow::anyhow!("no block hash found"))?;
+
+            // Verify if the ethereum block belongs to this substrate block.
+            let query_current_eth_block = astar_metadata::storage().ethereum().current_block();
+
+            // Fetch ethereum block from `ethereum.current_block` state.
+            let Some(actual_eth_block) = self
+                .ws_client
+                .storage()
+                .at(substrate_block_hash.clone())
+                .fetch(&query_current_eth_block)
+                .await?
+            else {
+                // This error should not happen, once all astar blocks must have one
+                // ethereum block
+                anyhow::bail!("[report this bug!] no ethereum block found for astar at block {substrate_block_hash:?}");
+            };
+
+            // Verify if the ethereum block hash matches the provided ethereum block hash.
+            // TODO: compute the block hash
+            if U256(actual_eth_block.header.number.0) !=
+                U256::from(ethereum_block.header().number())
+            {
+                anyhow::bail!("ethereum block hash mismatch");
+            }
+            if actual_eth_block.header.parent_hash.as_fixed_bytes() !=
+                &ethereum_block.header().header().parent_hash.0
+            {
+                anyhow::bail!("ethereum block hash mismatch");
+            }
+            return Ok(substrate_block_hash);
+        }
+
+        // Not an ethereum block, so it must be a substrate block hash
+        let substrate_block_hash = subxt::utils::H256(*block_hash);
+        if self.rpc_methods.chain_get_header(Some(substrate_block_hash)).await?.is_none() {
+            anyhow::bail!("no block found with hash {substrate_block_hash:?}");
+        }
+        Ok(BlockRef::from_hash(substrate_block_hash))
+    }
+
     async fn account_info(
         &self,
         address: &Address,
@@ -98,72 +185,19 @@ impl AstarClient {
         let storage_query =
             subxt::dynamic::storage("System", "Account", vec![SubtxValue::from_bytes(account)]);
 
-        // TODO: Change the `PartialBlockIdentifier` for distinguish between ethereum blocks and
-        // substrate blocks.
         let block_hash = match maybe_block {
+            Some(PartialBlockIdentifier { tag: Some(BlockTag::Finalized), .. }) => {
+                BlockRef::from_hash(self.rpc_methods.chain_get_finalized_head().await?)
+            },
+            // The genesis state, only available on archive nodes
+            Some(PartialBlockIdentifier { tag: Some(BlockTag::Earliest), .. }) => self
+                .rpc_methods
+                .chain_get_block_hash(Some(BlockNumber::Number(0)))
+                .await?
+                .map(BlockRef::from_hash)
+                .ok_or_else(|| anyhow::anyhow!("genesis block not found"))?,
             Some(PartialBlockIdentifier { hash: Some(block_hash), .. }) => {
-                // If a hash if provided, we don't know if it's a ethereum block hash or substrate
-                // block hash. We try to fetch the block using ethereum first, and
-                // if it fails, we try to fetch it using substrate.
-                let ethereum_block = self
-                    .client
-                    .call(&EthQuery::GetBlockByHash(H256(*block_hash).into()))
-                    .await
-                    .map(|result| match result {
-                        EthQueryResult::GetBlockByHash(block) => block,
-                        _ => unreachable!(),
-                    });
-
-                if let Ok(Some(ethereum_block)) = ethereum_block {
-                    // Convert ethereum block to substrate block by fetching the block by number.
-                    let substrate_block_number =
-                        BlockNumber::Number(ethereum_block.header().number());
-                    let substrate_block_hash = self
-                        .rpc_methods
-                        .chain_get_block_hash(Some(substrate_block_number))
-                        .await?
-                        .map(BlockRef::from_hash)
-                        .ok_or_else(|| anyhow::anyhow!("no block hash found"))?;
-
-                    // Verify if the ethereum block belongs to this substrate block.
-                    let query_current_eth_block =
-                        astar_metadata::storage().ethereum().current_block();
-
-                    // Fetch ethereum block from `ethereum.current_block` state.
-                    let Some(actual_eth_block) = self
-                        .ws_client
-                        .storage()
-                        .at(substrate_block_hash.clone())
-                        .fetch(&query_current_eth_block)
-                        .await?
-                    else {
-                        // This error should not happen, once all astar blocks must have one
-                        // ethereum block
-                        anyhow::bail!("[report this bug!] no ethereum block found for astar at block {substrate_block_hash:?}");
-                    };
-
-                    // Verify if the ethereum block hash matches the provided ethereum block hash.
-                    // TODO: compute the block hash
-                    if U256(actual_eth_block.header.number.0) !=
-                        U256::from(ethereum_block.header().number())
-                    {
-                        anyhow::bail!("ethereum block hash mismatch");
-                    }
-                    if actual_eth_block.header.parent_hash.as_fixed_bytes() !=
-                        &ethereum_block.header().header().parent_hash.0
-                    {
-                        anyhow::bail!("ethereum block hash mismatch");
-                    }
-                    substrate_block_hash
-                } else {
-                    self.rpc_methods
-                        .chain_get_block_hash(Some(BlockNumber::Hex(U256::from_big_endian(
-                            block_hash,
-                        ))))
-                        .await?
-                        .map(BlockRef::from_hash)
-                        .ok_or_else(|| anyhow::anyhow!("no block hash found"))?
-                }
+                self.substrate_block_from_hash(block_hash).await?
             },
             Some(PartialBlockIdentifier { index: Some(block_number), .. }) => {
                 // If a block number is provided, the value is the same for ethereum blocks and
@@ -44,7 +44,7 @@ mod tests {
     use ethers::types::H256;
     use ethers_solc::{artifacts::Source, CompilerInput, EvmVersion, Solc};
     use hex_literal::hex;
-    use rosetta_chain_testing::run_test;
+    use rosetta_chain_testing::{funded_account, run_test};
     use rosetta_client::Wallet;
     use rosetta_config_ethereum::{AtBlock, CallResult};
     use rosetta_core::BlockchainClient;
@@ -107,16 +107,17 @@ mod tests {
             )
             .await
             .expect("Error creating AvalancheClient");
+            let value = 10 * u128::pow(10, client.config().currency_decimals);
+            let (funding_key, _) = funded_account(&client, 2 * value).await.unwrap();
             let wallet = Wallet::from_config(
                 client.config().clone(),
                 AVALANCHE_RPC_WS_URL,
                 None,
-                Some(FUNDING_ACCOUNT_PRIVATE_KEY),
+                Some(funding_key),
             )
             .await
             .unwrap();
-            let value = 10 * u128::pow(10, client.config().currency_decimals);
-            let _ = wallet.faucet(value, Some(25_000_000_000)).await;
+            wallet.faucet(value, Some(25_000_000_000)).await.unwrap();
             let amount = wallet.balance().await.unwrap();
             assert_eq!(amount, value);
         })
@@ -161,11 +162,12 @@ mod tests {
             .await
             .expect("Error creating AvalancheClient");
             let faucet = 10 * u128::pow(10, client.config().currency_decimals);
+            let (funding_key, _) = funded_account(&client, 2 * faucet).await.unwrap();
             let wallet = Wallet::from_config(
                 client.config().clone(),
                 AVALANCHE_RPC_WS_URL,
                 None,
-                Some(FUNDING_ACCOUNT_PRIVATE_KEY),
+                Some(funding_key),
             )
             .await
             .unwrap();
@@ -214,11 +216,12 @@ mod tests {
             .await
             .expect("Error creating AvalancheClient");
             let faucet = 10 * u128::pow(10, client.config().currency_decimals);
+            let (funding_key, _) = funded_account(&client, 2 * faucet).await.unwrap();
             let wallet = Wallet::from_config(
                 client.config().clone(),
                 AVALANCHE_RPC_WS_URL,
                 None,
-                Some(FUNDING_ACCOUNT_PRIVATE_KEY),
+                Some(funding_key),
             )
             .await
             .unwrap();
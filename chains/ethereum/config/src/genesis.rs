@@ -0,0 +1,117 @@
+use crate::{evm_config, rstd, Address};
+use rosetta_core::BlockchainConfig;
+
+/// Private key of the account which seals the blocks of the dev node, it is the well known
+/// first account of hardhat, anvil and other development tools.
+const DEV_SIGNER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Address of [`DEV_SIGNER_KEY`].
+const DEV_SIGNER_ADDRESS: &str = "f39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+/// Balance of the dev signer, it is used by the faucet to fund the test accounts.
+const DEV_SIGNER_BALANCE: u128 = u128::MAX >> 1;
+
+/// Directory where the dev chain is initialized inside the node container.
+const DEV_DATADIR: &str = "/tmp/dev";
+
+/// An account pre-funded at genesis in the dev node.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GenesisAccount {
+    pub address: Address,
+    /// Balance in wei.
+    pub balance: u128,
+}
+
+impl GenesisAccount {
+    #[must_use]
+    pub const fn new(address: Address, balance: u128) -> Self {
+        Self { address, balance }
+    }
+}
+
+/// Builds the geth genesis JSON used by the dev node, this is equivalent to the genesis
+/// generated by `geth --dev` (clique with a period of 1 second and chain id 1337) plus the
+/// provided `accounts`.
+///
+/// The genesis follows the geth format:
+/// ```json
+/// {
+///   "config": { "chainId": 1337, "clique": { "period": 1, "epoch": 30000 }, ... },
+///   "gasLimit": "0xaf79e0",
+///   "extradata": "0x<32 zero bytes><dev signer address><65 zero bytes>",
+///   "alloc": { "<address>": { "balance": "0x<wei>" }, ... }
+/// }
+/// ```
+#[must_use]
+pub fn dev_genesis(accounts: &[GenesisAccount]) -> String {
+    let mut alloc = format!(r#""{DEV_SIGNER_ADDRESS}":{{"balance":"{DEV_SIGNER_BALANCE:#x}"}}"#);
+    for account in accounts {
+        alloc.push_str(&format!(
+            r#","{}":{{"balance":"{:#x}"}}"#,
+            const_hex::encode(account.address.as_bytes()),
+            account.balance
+        ));
+    }
+    let extradata = format!("0x{}{DEV_SIGNER_ADDRESS}{}", "00".repeat(32), "00".repeat(65));
+    format!(
+        concat!(
+            r#"{{"config":{{"chainId":1337,"homesteadBlock":0,"eip150Block":0,"eip155Block":0,"#,
+            r#""eip158Block":0,"byzantiumBlock":0,"constantinopleBlock":0,"petersburgBlock":0,"#,
+            r#""istanbulBlock":0,"muirGlacierBlock":0,"berlinBlock":0,"londonBlock":0,"#,
+            r#""arrowGlacierBlock":0,"grayGlacierBlock":0,"#,
+            r#""clique":{{"period":1,"epoch":30000}}}},"#,
+            r#""difficulty":"0x1","gasLimit":"0xaf79e0","extradata":"{}","alloc":{{{}}}}}"#,
+        ),
+        extradata, alloc
+    )
+}
+
+/// Returns the ethereum `dev` config, with the given `accounts` funded at genesis.
+///
+/// Instead of letting `geth --dev` generate its own genesis, the node container initializes
+/// a datadir with [`dev_genesis`] and imports the dev signer key before starting geth.
+#[must_use]
+pub fn dev_config_with_genesis_accounts(accounts: &[GenesisAccount]) -> BlockchainConfig {
+    let mut config = evm_config("ethereum", "dev", "ETH", 1, true);
+    let node_command = config.node_command.clone();
+    let genesis = dev_genesis(accounts);
+    config.node_entrypoint = Some(&["/bin/sh", "-c"]);
+    config.node_command = rstd::sync::Arc::new(move |network, port| {
+        let args = node_command(network, port).join(" ");
+        let script = [
+            format!("printf '%s' '{genesis}' > /tmp/genesis.json"),
+            format!("printf '%s' '{DEV_SIGNER_KEY}' > /tmp/key"),
+            "printf '' > /tmp/password".into(),
+            format!(
+                "geth account import --datadir {DEV_DATADIR} --password /tmp/password /tmp/key"
+            ),
+            format!("geth init --datadir {DEV_DATADIR} /tmp/genesis.json"),
+            format!("exec geth --datadir {DEV_DATADIR} --password /tmp/password {args}"),
+        ];
+        vec![script.join(" && ")]
+    });
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genesis_includes_custom_accounts() {
+        let account = GenesisAccount::new(Address::repeat_byte(0x42), 1_000_000_000_000_000_000);
+        let genesis: serde_json::Value = serde_json::from_str(&dev_genesis(&[account])).unwrap();
+        assert_eq!(genesis["config"]["chainId"], 1337);
+        assert_eq!(genesis["config"]["clique"]["period"], 1);
+        let alloc = genesis["alloc"].as_object().unwrap();
+        assert_eq!(alloc.len(), 2);
+        assert_eq!(
+            alloc["4242424242424242424242424242424242424242"]["balance"],
+            "0xde0b6b3a7640000"
+        );
+        assert!(alloc.contains_key(DEV_SIGNER_ADDRESS));
+        let extradata = genesis["extradata"].as_str().unwrap();
+        assert_eq!(extradata.len(), 2 + (32 + 20 + 65) * 2);
+        assert!(extradata.contains(DEV_SIGNER_ADDRESS));
+    }
+}
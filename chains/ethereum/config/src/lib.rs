@@ -1,26 +1,34 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
+mod genesis;
 mod types;
+#[cfg(feature = "default-crypto")]
+mod unsigned_tx;
 mod util;
 
+#[cfg(feature = "std")]
+pub use genesis::{dev_config_with_genesis_accounts, dev_genesis, GenesisAccount};
 use rosetta_config_astar::config as astar_config;
 use rosetta_core::{
     crypto::{address::AddressFormat, Algorithm},
     BlockchainConfig, NodeUri,
 };
-use rosetta_ethereum_types::TxHash;
+use rosetta_ethereum_types::{TxHash, U256};
 pub use types::{
-    Address, AtBlock, BlockFull, Bloom, CallContract, CallResult, EIP1186ProofResponse,
-    EthereumMetadata, EthereumMetadataParams, FilterBlockOption, GetBalance, GetProof,
-    GetStorageAt, GetTransactionCount, GetTransactionReceipt, Header, Log, PartialBlock, Query,
-    QueryItem, QueryResult, SealedHeader, SignedTransaction, StorageProof, TransactionReceipt,
-    H256,
+    Address, AtBlock, BlockFull, Bloom, Bytes, CallContract, CallResult, EIP1186ProofResponse,
+    EstimateGas, EthereumMetadata, EthereumMetadataParams, FilterBlockOption, GetBalance, GetCode,
+    GetProof, GetStorageAt, GetTransactionCount, GetTransactionReceipt, Header, Log, PartialBlock,
+    Query, QueryItem, QueryResult, SealedHeader, SignedTransaction, StorageProof,
+    TransactionReceipt, H256,
 };
+#[cfg(feature = "default-crypto")]
+pub use unsigned_tx::UnsignedEthereumTx;
 
 pub mod query {
     pub use crate::types::{
-        CallContract, GetBalance, GetBlock, GetBlockByHash, GetLogs, GetProof, GetStorageAt,
-        GetTransactionReceipt, Query, QueryItem, QueryResult,
+        CallContract, EstimateGas, GetBalance, GetBlock, GetBlockByHash, GetCode, GetLogs,
+        GetProof, GetStorageAt, GetTransactionReceipt, Query, QueryItem, QueryResult,
     };
 }
 
@@ -79,6 +87,54 @@ impl SubmitResult {
             Self::Timeout { .. } => None,
         }
     }
+
+    /// Returns the price paid per unit of gas, which is the `effectiveGasPrice` from the receipt,
+    /// or `None` if the transaction wasn't included in a block.
+    #[must_use]
+    pub const fn effective_gas_price(&self) -> Option<U256> {
+        match self {
+            Self::Executed { receipt, .. } => receipt.effective_gas_price,
+            Self::Timeout { .. } => None,
+        }
+    }
+}
+
+/// Computes the hash of a signed transaction offline, `signed_raw` is the transaction as sent to
+/// `eth_sendRawTransaction`: either a legacy RLP list or an EIP-2718 `type || payload` envelope.
+/// Typed transactions wrapped in an RLP byte string, as found in block bodies, are unwrapped.
+///
+/// # Errors
+/// Returns `Err` if `signed_raw` isn't a transaction envelope.
+#[cfg(feature = "default-crypto")]
+pub fn compute_tx_hash(signed_raw: &[u8]) -> anyhow::Result<TxHash> {
+    use rosetta_ethereum_types::{
+        crypto::{Crypto, DefaultCrypto},
+        ext::rlp::Rlp,
+    };
+
+    let envelope = match signed_raw {
+        [] => anyhow::bail!("empty transaction"),
+        // Legacy transaction, an RLP list
+        [0xc0..=0xff, ..] => signed_raw,
+        // EIP-2718 typed transaction
+        [0x00..=0x7f, _, ..] => signed_raw,
+        [0x00..=0x7f] => anyhow::bail!("typed transaction without payload"),
+        // Typed transaction wrapped in an RLP byte string
+        [0x80..=0xbf, ..] => {
+            let info = Rlp::new(signed_raw)
+                .payload_info()
+                .map_err(|error| anyhow::anyhow!("invalid transaction envelope: {error}"))?;
+            if info.header_len + info.value_len != signed_raw.len() {
+                anyhow::bail!("invalid transaction envelope: unexpected trailing bytes");
+            }
+            let envelope = &signed_raw[info.header_len..];
+            match envelope {
+                [0x00..=0x7f, _, ..] => envelope,
+                _ => anyhow::bail!("invalid transaction envelope: expected a typed transaction"),
+            }
+        },
+    };
+    Ok(DefaultCrypto::keccak256(envelope))
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -208,14 +264,17 @@ impl rosetta_core::traits::Block for BlockFull {
 /// # Errors
 /// Returns `Err` if the network is not supported
 pub fn polygon_config(network: &str) -> anyhow::Result<BlockchainConfig> {
-    let (network, bip44_id, is_dev) = match network {
-        "dev" => ("dev", 1, true),
-        "mumbai" => ("mumbai", 60, true),
-        "amoy" => ("amoy", 60, true),
-        "mainnet" => ("mainnet", 966, false),
+    let (network, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", 1, true, None),
+        "mumbai" => ("mumbai", 60, true, None),
+        "amoy" => ("amoy", 60, true, Some("https://amoy.polygonscan.com")),
+        "mainnet" => ("mainnet", 966, false, Some("https://polygonscan.com")),
         _ => anyhow::bail!("unsupported network: {}", network),
     };
-    Ok(evm_config("polygon", network, "MATIC", bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("polygon", network, "MATIC", bip44_id, is_dev)
+    })
 }
 
 /// Retrieve the [`BlockchainConfig`] from the provided arbitrum `network`
@@ -224,14 +283,17 @@ pub fn polygon_config(network: &str) -> anyhow::Result<BlockchainConfig> {
 /// Returns `Err` if the network is not supported
 pub fn arbitrum_config(network: &str) -> anyhow::Result<BlockchainConfig> {
     // All available networks in arbitrum are listed here:
-    let (network, bip44_id, is_dev) = match network {
-        "dev" => ("dev", 1, true),
-        "goerli" => ("goerli", 421_613, true),
-        "sepolia" => ("sepolia", 421_614, true),
-        "mainnet" => ("mainnet", 42161, false),
+    let (network, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", 1, true, None),
+        "goerli" => ("goerli", 421_613, true, None),
+        "sepolia" => ("sepolia", 421_614, true, None),
+        "mainnet" => ("mainnet", 42161, false, Some("https://arbiscan.io")),
         _ => anyhow::bail!("unsupported network: {}", network),
     };
-    Ok(evm_config("arbitrum", network, "ARB", bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("arbitrum", network, "ARB", bip44_id, is_dev)
+    })
 }
 
 /// Retrieve the [`BlockchainConfig`] from the provided binance `network`
@@ -240,13 +302,16 @@ pub fn arbitrum_config(network: &str) -> anyhow::Result<BlockchainConfig> {
 /// Returns `Err` if the network is not supported
 pub fn binance_config(network: &str) -> anyhow::Result<BlockchainConfig> {
     // All available networks in binance are listed here:
-    let (network, bip44_id, is_dev) = match network {
-        "dev" => ("dev", 1, true),
-        "testnet" => ("testnet", 97, true),
-        "mainnet" => ("mainnet", 56, false),
+    let (network, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", 1, true, None),
+        "testnet" => ("testnet", 97, true, None),
+        "mainnet" => ("mainnet", 56, false, Some("https://bscscan.com")),
         _ => anyhow::bail!("unsupported network: {}", network),
     };
-    Ok(evm_config("binance", network, "bnb", bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("binance", network, "bnb", bip44_id, is_dev)
+    })
 }
 
 /// Retrieve the [`BlockchainConfig`] from the provided avalanche `network`
@@ -255,13 +320,16 @@ pub fn binance_config(network: &str) -> anyhow::Result<BlockchainConfig> {
 /// Returns `Err` if the network is not supported
 pub fn avalanche_config(network: &str) -> anyhow::Result<BlockchainConfig> {
     // All available networks are listed here:
-    let (network, bip44_id, is_dev) = match network {
-        "dev" => ("dev", 1, true),
-        "fuji" => ("goerli", 1, true),
-        "mainnet" => ("mainnet", 42161, false),
+    let (network, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", 1, true, None),
+        "fuji" => ("goerli", 1, true, None),
+        "mainnet" => ("mainnet", 42161, false, Some("https://snowtrace.io")),
         _ => anyhow::bail!("unsupported network: {}", network),
     };
-    Ok(evm_config("avalanche", network, "AVAX", bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("avalanche", network, "AVAX", bip44_id, is_dev)
+    })
 }
 
 /// Retrieve the [`BlockchainConfig`] from the provided base `network`
@@ -270,13 +338,16 @@ pub fn avalanche_config(network: &str) -> anyhow::Result<BlockchainConfig> {
 /// Returns `Err` if the network is not supported
 pub fn base_config(network: &str) -> anyhow::Result<BlockchainConfig> {
     // All available networks are listed here:
-    let (network, bip44_id, is_dev) = match network {
-        "dev" => ("dev", 1, true),
-        "sepolia" => ("sepolia", 84532, true),
-        "mainnet" => ("mainnet", 8453, false),
+    let (network, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", 1, true, None),
+        "sepolia" => ("sepolia", 84532, true, None),
+        "mainnet" => ("mainnet", 8453, false, Some("https://basescan.org")),
         _ => anyhow::bail!("unsupported network: {}", network),
     };
-    Ok(evm_config("avalanche", network, "AVAX", bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("avalanche", network, "AVAX", bip44_id, is_dev)
+    })
 }
 
 /// Retrieve the [`BlockchainConfig`] from the provided ethereum `network`
@@ -284,11 +355,11 @@ pub fn base_config(network: &str) -> anyhow::Result<BlockchainConfig> {
 /// # Errors
 /// Returns `Err` if the network is not supported
 pub fn config(network: &str) -> anyhow::Result<BlockchainConfig> {
-    let (network, symbol, bip44_id, is_dev) = match network {
-        "dev" => ("dev", "ETH", 1, true),
-        "mainnet" => ("mainnet", "ETH", 60, false),
-        "goerli" => ("goerli", "TST", 60, true),
-        "sepolia" => ("sepolia", "SepoliaETH", 60, true),
+    let (network, symbol, bip44_id, is_dev, explorer) = match network {
+        "dev" => ("dev", "ETH", 1, true, None),
+        "mainnet" => ("mainnet", "ETH", 60, false, Some("https://etherscan.io")),
+        "goerli" => ("goerli", "TST", 60, true, None),
+        "sepolia" => ("sepolia", "SepoliaETH", 60, true, Some("https://sepolia.etherscan.io")),
 
         // Polygon
         "polygon-local" => return polygon_config("dev"),
@@ -323,7 +394,10 @@ pub fn config(network: &str) -> anyhow::Result<BlockchainConfig> {
         network => return astar_config(network),
     };
 
-    Ok(evm_config("ethereum", network, symbol, bip44_id, is_dev))
+    Ok(BlockchainConfig {
+        block_explorer_url: explorer,
+        ..evm_config("ethereum", network, symbol, bip44_id, is_dev)
+    })
 }
 
 fn evm_config(
@@ -349,11 +423,16 @@ fn evm_config(
             NodeUri::parse("ws://127.0.0.1:8545").expect("uri is valid; qed")
         },
         node_image: "ethereum/client-go:v1.12.2",
+        node_entrypoint: None,
         node_command: rstd::sync::Arc::new(|network, port| {
-            let mut params = if network == "dev" {
-                vec!["--dev".into(), "--dev.period=1".into(), "--ipcdisable".into()]
+            // The miner api is only exposed in dev, so tests can pause the block production
+            let (mut params, apis) = if network == "dev" {
+                (
+                    vec!["--dev".into(), "--dev.period=1".into(), "--ipcdisable".into()],
+                    "eth,debug,admin,txpool,web3,net,miner",
+                )
             } else {
-                vec!["--syncmode=full".into()]
+                (vec!["--syncmode=full".into()], "eth,debug,admin,txpool,web3,net")
             };
             params.extend_from_slice(&[
                 "--http".into(),
@@ -361,12 +440,12 @@ fn evm_config(
                 format!("--http.port={port}"),
                 "--http.vhosts=*".into(),
                 "--http.corsdomain=*".into(),
-                "--http.api=eth,debug,admin,txpool,web3,net".into(),
+                format!("--http.api={apis}"),
                 "--ws".into(),
                 "--ws.addr=0.0.0.0".into(),
                 format!("--ws.port={port}"),
                 "--ws.origins=*".into(),
-                "--ws.api=eth,debug,admin,txpool,web3,net".into(),
+                format!("--ws.api={apis}"),
                 "--ws.rpcprefix=/".into(),
             ]);
             params
@@ -374,5 +453,73 @@ fn evm_config(
         node_additional_ports: &[],
         connector_port: 8081,
         testnet: is_dev,
+        block_explorer_url: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_tx_hash_of_envelopes() {
+        // EIP-155 example transaction
+        let legacy = hex_literal::hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+        assert_eq!(
+            compute_tx_hash(&legacy).unwrap(),
+            TxHash::from(hex_literal::hex!(
+                "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+            ))
+        );
+
+        // A typed transaction hashes the same bare or wrapped in an RLP byte string
+        let typed = hex_literal::hex!("02c90180808080808080c0");
+        let wrapped = hex_literal::hex!("8b02c90180808080808080c0");
+        let expected = TxHash::from(hex_literal::hex!(
+            "85f23b4c1d3d39bb551f12847bf6123319ed584cbff9bdfc3e81409192d69edf"
+        ));
+        assert_eq!(compute_tx_hash(&typed).unwrap(), expected);
+        assert_eq!(compute_tx_hash(&wrapped).unwrap(), expected);
+    }
+
+    #[test]
+    fn compute_tx_hash_rejects_invalid_envelopes() {
+        assert!(compute_tx_hash(&[]).is_err());
+        assert!(compute_tx_hash(&[0x02]).is_err());
+        // Wrapped legacy transaction
+        assert!(compute_tx_hash(&[0x82, 0xc1, 0x80]).is_err());
+        // Trailing bytes after the wrapped transaction
+        assert!(compute_tx_hash(&[0x82, 0x02, 0xc0, 0x00]).is_err());
+    }
+
+    #[test]
+    fn polygon_add_chain_params() {
+        let config = polygon_config("mainnet").unwrap();
+        let params = config.to_add_chain_params(137, &["https://polygon-rpc.com"]).unwrap();
+        assert_eq!(
+            params,
+            serde_json::json!({
+                "chainId": "0x89",
+                "chainName": "Polygon mainnet",
+                "nativeCurrency": { "name": "MATIC", "symbol": "MATIC", "decimals": 18 },
+                "rpcUrls": ["https://polygon-rpc.com"],
+                "blockExplorerUrls": ["https://polygonscan.com"],
+            })
+        );
+
+        // The block explorer is part of each chain config
+        let config = base_config("mainnet").unwrap();
+        assert_eq!(config.block_explorer_url, Some("https://basescan.org"));
+
+        // Unknown networks have no block explorer
+        let config = polygon_config("dev").unwrap();
+        let params = config.to_add_chain_params(1337, &["http://127.0.0.1:8545"]).unwrap();
+        assert_eq!(params["chainId"], "0x539");
+        assert!(params.get("blockExplorerUrls").is_none());
+        assert!(config.to_add_chain_params(1337, &[]).is_err());
+
+        // Only EVM configs are supported
+        let config = BlockchainConfig { address_format: AddressFormat::Bech32("bc"), ..config };
+        assert!(config.to_add_chain_params(1337, &["http://127.0.0.1:8545"]).is_err());
     }
 }
@@ -1,7 +1,7 @@
 pub use rosetta_ethereum_backend::FilterBlockOption;
 pub use rosetta_ethereum_types::{
-    rpc::RpcTransaction, Address, AtBlock, Block, Bloom, EIP1186ProofResponse, Header, Log,
-    StorageProof, TransactionReceipt, H256, U256,
+    rpc::RpcTransaction, AccessList, Address, AtBlock, Block, Bloom, Bytes, EIP1186ProofResponse,
+    Header, Log, StorageProof, TransactionReceipt, H256, U256,
 };
 
 use rosetta_core::traits::Query as QueryT;
@@ -93,6 +93,10 @@ pub struct EthereumMetadataParams {
     pub amount: [u64; 4],
     #[cfg_attr(feature = "serde", serde(with = "bytes_to_hex"))]
     pub data: Vec<u8>,
+    /// Attach the EIP-2930 access list generated by `eth_createAccessList` to the transaction,
+    /// the gas limit is set to the gas used with the access list, unless `gas_limit` is provided.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_access_list: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -112,6 +116,16 @@ pub struct EthereumMetadata {
     pub max_fee_per_gas: [u64; 4],
     #[cfg_attr(feature = "serde", serde(default, with = "uint_to_hex"))]
     pub gas_limit: u64,
+    /// Minimum gas price accepted by the chain, `max_fee_per_gas` is never below it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_gas_price: [u64; 4],
+    /// Gas price of the transaction on chains which don't report a base fee, a legacy or
+    /// EIP-2930 transaction is built instead of an EIP-1559 one and the fee caps are ignored.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_price: Option<[u64; 4]>,
+    /// EIP-2930 access list attached to the transaction.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub access_list: AccessList,
 }
 
 ///·Returns·the·balance·of·the·account·of·given·address.
@@ -172,6 +186,31 @@ impl QueryT for CallContract {
 }
 impl_query_item!(CallContract);
 
+/// Estimates the gas used by a message call against the state of the given block, fails if the
+/// node pruned the state of the block.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EstimateGas {
+    /// The address the transaction is sent from.
+    pub from: Option<Address>,
+    /// The address the transaction is directed to.
+    pub to: Address,
+    /// Integer of the value sent with this transaction.
+    pub value: U256,
+    /// Hash of the method signature and encoded parameters.
+    #[cfg_attr(feature = "serde", serde(with = "bytes_to_hex"))]
+    pub data: Vec<u8>,
+    /// Estimate at block
+    pub block: AtBlock,
+}
+
+impl QueryT for EstimateGas {
+    type Result = U256;
+}
+impl_query_item!(EstimateGas);
+
 /// Returns the account and storage values of the specified account including the Merkle-proof.
 /// This call can be used to verify that the data you are pulling from is not tampered with.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -210,6 +249,23 @@ impl QueryT for GetStorageAt {
 }
 impl_query_item!(GetStorageAt);
 
+/// Returns the code at a given address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+#[cfg_attr(feature = "scale-codec", derive(parity_scale_codec::Encode, parity_scale_codec::Decode))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetCode {
+    /// Account address
+    pub address: Address,
+    /// Code at the block
+    pub block: AtBlock,
+}
+
+impl QueryT for GetCode {
+    type Result = Bytes;
+}
+impl_query_item!(GetCode);
+
 /// Returns the account and storage values, including the Merkle proof, of the specified
 /// account.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -339,6 +395,9 @@ pub enum Query {
     /// Returns the value from a storage position at a given address.
     #[cfg_attr(feature = "serde", serde(rename = "eth_getStorageAt"))]
     GetStorageAt(GetStorageAt),
+    /// Returns the code at a given address.
+    #[cfg_attr(feature = "serde", serde(rename = "eth_getCode"))]
+    GetCode(GetCode),
     /// Returns the receipt of a transaction by transaction hash.
     #[cfg_attr(feature = "serde", serde(rename = "eth_getTransactionReceipt"))]
     GetTransactionReceipt(GetTransactionReceipt),
@@ -346,6 +405,9 @@ pub enum Query {
     /// chain.
     #[cfg_attr(feature = "serde", serde(rename = "eth_call"))]
     CallContract(CallContract),
+    /// Estimates the gas used by a message call against the state of the given block.
+    #[cfg_attr(feature = "serde", serde(rename = "eth_estimateGas"))]
+    EstimateGas(EstimateGas),
     /// Returns the account and storage values of the specified account including the
     /// Merkle-proof. This call can be used to verify that the data you are pulling
     /// from is not tampered with.
@@ -466,6 +528,9 @@ pub enum QueryResult {
     /// Returns the value from a storage position at a given address.
     #[cfg_attr(feature = "serde", serde(rename = "eth_getStorageAt"))]
     GetStorageAt(<GetStorageAt as QueryT>::Result),
+    /// Returns the code at a given address.
+    #[cfg_attr(feature = "serde", serde(rename = "eth_getCode"))]
+    GetCode(<GetCode as QueryT>::Result),
     /// Returns the receipt of a transaction by transaction hash.
     #[cfg_attr(feature = "serde", serde(rename = "eth_getTransactionReceipt"))]
     GetTransactionReceipt(<GetTransactionReceipt as QueryT>::Result),
@@ -473,6 +538,9 @@ pub enum QueryResult {
     /// chain.
     #[cfg_attr(feature = "serde", serde(rename = "eth_call"))]
     CallContract(<CallContract as QueryT>::Result),
+    /// Estimates the gas used by a message call against the state of the given block.
+    #[cfg_attr(feature = "serde", serde(rename = "eth_estimateGas"))]
+    EstimateGas(<EstimateGas as QueryT>::Result),
     /// Returns the account and storage values of the specified account including the
     /// Merkle-proof. This call can be used to verify that the data you are pulling
     /// from is not tampered with.
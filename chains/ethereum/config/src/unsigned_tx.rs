@@ -0,0 +1,272 @@
+use rosetta_core::crypto::{Algorithm, SecretKey};
+use rosetta_ethereum_types::{
+    crypto::{Keypair, Signer},
+    ext::rlp::Rlp,
+    rlp_utils::RlpDecodableTransaction,
+    transactions::{signature::Signature, GasPrice},
+    Address, Bytes, TransactionT, TypedTransaction, H256, U256,
+};
+
+/// An unsigned transaction which can be inspected and modified before it is signed, for
+/// instance to bump the gas price of a transaction built by a third party.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnsignedEthereumTx(TypedTransaction);
+
+impl UnsignedEthereumTx {
+    /// Decodes an unsigned legacy, EIP-2930 or EIP-1559 transaction.
+    ///
+    /// # Errors
+    /// Returns `Err` if the transaction type is unknown or the transaction is malformed.
+    pub fn decode(raw: &[u8]) -> anyhow::Result<Self> {
+        TypedTransaction::rlp_decode_unsigned(&Rlp::new(raw))
+            .map(Self)
+            .map_err(|error| anyhow::anyhow!("invalid unsigned transaction: {error}"))
+    }
+
+    /// Encodes the transaction without signature.
+    #[must_use]
+    pub fn encode(&self) -> Bytes {
+        self.0.encode(None)
+    }
+
+    /// Hash signed by the sender, changes with every field of the transaction.
+    #[must_use]
+    pub fn signing_hash(&self) -> H256 {
+        self.0.sighash()
+    }
+
+    /// Signs the transaction with `secret_key`, returns the signed transaction as expected by
+    /// `eth_sendRawTransaction`.
+    ///
+    /// # Errors
+    /// Returns `Err` if `secret_key` isn't a secp256k1 key.
+    pub fn sign(&self, secret_key: &SecretKey) -> anyhow::Result<Bytes> {
+        if !matches!(
+            secret_key.algorithm(),
+            Algorithm::EcdsaSecp256k1 | Algorithm::EcdsaRecoverableSecp256k1
+        ) {
+            anyhow::bail!("unsupported {:?} key, expected a secp256k1 key", secret_key.algorithm());
+        }
+        let keypair = Keypair::from_bytes(secret_key.to_bytes())
+            .map_err(|error| anyhow::anyhow!("invalid secret key: {error}"))?;
+        let signature = keypair
+            .sign_prehash(self.signing_hash(), self.0.chain_id())
+            .map_err(|error| anyhow::anyhow!("failed to sign the transaction: {error}"))?;
+        Ok(self.encode_signed(&signature))
+    }
+
+    /// Encodes the transaction with a `signature` of the [`Self::signing_hash`].
+    #[must_use]
+    pub fn encode_signed(&self, signature: &Signature) -> Bytes {
+        self.0.encode(Some(signature))
+    }
+
+    /// The decoded transaction.
+    #[must_use]
+    pub const fn transaction(&self) -> &TypedTransaction {
+        &self.0
+    }
+
+    /// The chain id, `None` for legacy transactions without replay protection.
+    #[must_use]
+    pub fn chain_id(&self) -> Option<u64> {
+        self.0.chain_id()
+    }
+
+    /// The nonce of the sender.
+    #[must_use]
+    pub fn nonce(&self) -> u64 {
+        self.0.nonce()
+    }
+
+    /// Sets the nonce of the sender.
+    pub fn set_nonce(&mut self, nonce: u64) {
+        match &mut self.0 {
+            TypedTransaction::Legacy(tx) => tx.nonce = nonce,
+            TypedTransaction::Eip2930(tx) => tx.nonce = nonce,
+            TypedTransaction::Eip1559(tx) => tx.nonce = nonce,
+        }
+    }
+
+    /// The maximum gas the transaction can use.
+    #[must_use]
+    pub fn gas_limit(&self) -> u64 {
+        self.0.gas_limit()
+    }
+
+    /// Sets the maximum gas the transaction can use.
+    pub fn set_gas_limit(&mut self, gas_limit: u64) {
+        match &mut self.0 {
+            TypedTransaction::Legacy(tx) => tx.gas_limit = gas_limit,
+            TypedTransaction::Eip2930(tx) => tx.gas_limit = gas_limit,
+            TypedTransaction::Eip1559(tx) => tx.gas_limit = gas_limit,
+        }
+    }
+
+    /// The gas price of legacy and EIP-2930 transactions, or the fee caps of EIP-1559
+    /// transactions.
+    #[must_use]
+    pub fn gas_price(&self) -> GasPrice {
+        self.0.gas_price()
+    }
+
+    /// Sets the gas price of a legacy or EIP-2930 transaction.
+    ///
+    /// # Errors
+    /// Returns `Err` for EIP-1559 transactions, which have fee caps instead, see
+    /// [`Self::set_fee_caps`].
+    pub fn set_gas_price(&mut self, gas_price: U256) -> anyhow::Result<()> {
+        match &mut self.0 {
+            TypedTransaction::Legacy(tx) => tx.gas_price = gas_price,
+            TypedTransaction::Eip2930(tx) => tx.gas_price = gas_price,
+            TypedTransaction::Eip1559(_) => {
+                anyhow::bail!("EIP-1559 transactions have fee caps instead of a gas price")
+            },
+        }
+        Ok(())
+    }
+
+    /// Sets the fee caps of an EIP-1559 transaction.
+    ///
+    /// # Errors
+    /// Returns `Err` for legacy and EIP-2930 transactions, which have a gas price instead, see
+    /// [`Self::set_gas_price`].
+    pub fn set_fee_caps(
+        &mut self,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    ) -> anyhow::Result<()> {
+        let TypedTransaction::Eip1559(tx) = &mut self.0 else {
+            anyhow::bail!("only EIP-1559 transactions have fee caps");
+        };
+        tx.max_fee_per_gas = max_fee_per_gas;
+        tx.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        Ok(())
+    }
+
+    /// The recipient, `None` for contract deployments.
+    #[must_use]
+    pub fn to(&self) -> Option<Address> {
+        self.0.to()
+    }
+
+    /// The value transferred to the recipient.
+    #[must_use]
+    pub fn value(&self) -> U256 {
+        self.0.value()
+    }
+
+    /// The call data, or the init code of contract deployments.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        self.0.data()
+    }
+
+    /// Sets the call data, or the init code of contract deployments.
+    pub fn set_data(&mut self, data: Bytes) {
+        match &mut self.0 {
+            TypedTransaction::Legacy(tx) => tx.data = data,
+            TypedTransaction::Eip2930(tx) => tx.data = data,
+            TypedTransaction::Eip1559(tx) => tx.data = data,
+        }
+    }
+}
+
+impl From<TypedTransaction> for UnsignedEthereumTx {
+    fn from(transaction: TypedTransaction) -> Self {
+        Self(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosetta_ethereum_types::{
+        crypto::{Crypto, DefaultCrypto},
+        transactions::{Eip1559Transaction, LegacyTransaction},
+        AccessList,
+    };
+
+    fn secret_key() -> SecretKey {
+        SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap()
+    }
+
+    /// Decodes a signed transaction, returns the transaction and the recovered sender.
+    fn recover(signed: &[u8]) -> (TypedTransaction, Address) {
+        let (tx, signature) = TypedTransaction::rlp_decode(&Rlp::new(signed), true).unwrap();
+        let sender = DefaultCrypto::secp256k1_ecdsa_recover(&signature.unwrap(), tx.sighash());
+        (tx, sender.unwrap())
+    }
+
+    #[test]
+    fn bumps_the_gas_price_and_resigns() {
+        let tx = LegacyTransaction {
+            nonce: 7,
+            gas_price: U256::from(10),
+            gas_limit: 21000,
+            to: Some(Address::repeat_byte(0x33)),
+            value: U256::from(100),
+            data: Bytes::default(),
+            chain_id: Some(1337),
+        };
+        let mut unsigned = UnsignedEthereumTx::decode(&tx.encode(None)).unwrap();
+        assert_eq!(unsigned.transaction(), &TypedTransaction::Legacy(tx));
+        let (_, sender) = recover(&unsigned.sign(&secret_key()).unwrap());
+
+        let signing_hash = unsigned.signing_hash();
+        unsigned.set_gas_price(U256::from(15)).unwrap();
+        assert_ne!(unsigned.signing_hash(), signing_hash);
+        assert!(unsigned.set_fee_caps(U256::from(20), U256::from(2)).is_err());
+
+        let (resigned, resigned_sender) = recover(&unsigned.sign(&secret_key()).unwrap());
+        assert_eq!(resigned_sender, sender);
+        assert_eq!(resigned.gas_price(), GasPrice::Legacy(U256::from(15)));
+        assert_eq!(resigned.nonce(), 7);
+    }
+
+    #[test]
+    fn modifies_eip1559_transactions() {
+        let tx = Eip1559Transaction {
+            chain_id: 1337,
+            nonce: 3,
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(20),
+            gas_limit: 50000,
+            to: Some(Address::repeat_byte(0x33)),
+            value: U256::zero(),
+            data: Bytes::from(vec![0xaa]),
+            access_list: AccessList::default(),
+        };
+        let mut unsigned = UnsignedEthereumTx::decode(&tx.encode(None)).unwrap();
+        let (_, sender) = recover(&unsigned.sign(&secret_key()).unwrap());
+
+        unsigned.set_nonce(4);
+        unsigned.set_gas_limit(60000);
+        unsigned.set_fee_caps(U256::from(30), U256::from(3)).unwrap();
+        unsigned.set_data(Bytes::from(vec![0xbb, 0xcc]));
+        assert!(unsigned.set_gas_price(U256::from(15)).is_err());
+        // The modified transaction is encoded with the new fields
+        assert_eq!(UnsignedEthereumTx::decode(&unsigned.encode()).unwrap(), unsigned);
+
+        let (resigned, resigned_sender) = recover(&unsigned.sign(&secret_key()).unwrap());
+        assert_eq!(resigned_sender, sender);
+        assert_eq!(resigned.nonce(), 4);
+        assert_eq!(resigned.gas_limit(), 60000);
+        assert_eq!(
+            resigned.gas_price(),
+            GasPrice::Eip1559 {
+                max_priority_fee_per_gas: U256::from(3),
+                max_fee_per_gas: U256::from(30),
+            }
+        );
+        assert_eq!(resigned.data(), &[0xbb, 0xcc]);
+    }
+
+    #[test]
+    fn rejects_non_secp256k1_keys() {
+        let tx = LegacyTransaction { chain_id: Some(1337), ..Default::default() };
+        let unsigned = UnsignedEthereumTx::decode(&tx.encode(None)).unwrap();
+        let key = SecretKey::from_bytes(Algorithm::Ed25519, &[0x42; 32]).unwrap();
+        assert!(unsigned.sign(&key).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
     block_provider::BlockProvider,
-    event_stream::{EthereumEventStream, NewBlock},
+    event_stream::{EthereumEventStream, NewBlock, PauseHandle},
+    gas_cache::GasEstimateCache,
     state::State,
 };
 use futures_util::StreamExt;
@@ -13,6 +14,7 @@ use rosetta_ethereum_backend::{
 };
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -28,7 +30,10 @@ where
     RPC::SubscriptionError: Send + Sync + 'static,
 {
     stream: Option<EthereumEventStream<P, RPC>>,
+    pause: PauseHandle,
     state: State,
+    /// Gas estimate cache invalidated on each new block.
+    gas_cache: Option<Arc<GasEstimateCache>>,
 }
 
 impl<P, RPC> BlockStream<P, RPC>
@@ -44,7 +49,32 @@ where
 {
     #[must_use]
     pub fn new(provider: P, client: RPC, state: State) -> Self {
-        Self { stream: Some(EthereumEventStream::new(client, provider)), state }
+        let stream = EthereumEventStream::new(client, provider);
+        let pause = stream.pause_handle();
+        Self { stream: Some(stream), pause, state, gas_cache: None }
+    }
+
+    /// Invalidates the `gas_cache` whenever a new block is received.
+    #[must_use]
+    pub(crate) fn with_gas_cache(mut self, gas_cache: Option<Arc<GasEstimateCache>>) -> Self {
+        self.gas_cache = gas_cache;
+        self
+    }
+
+    /// Pause the stream, see [`EthereumEventStream::pause`].
+    pub fn pause(&self) {
+        self.pause.pause();
+    }
+
+    /// Resume the stream, see [`EthereumEventStream::resume`].
+    pub fn resume(&self) {
+        self.pause.resume();
+    }
+
+    /// Returns a handle to pause and resume the stream.
+    #[must_use]
+    pub fn pause_handle(&self) -> PauseHandle {
+        self.pause.clone()
     }
 }
 
@@ -78,6 +108,9 @@ where
                         })
                     };
                     let is_finalized = matches!(new_block, NewBlock::Finalized(_));
+                    if let Some(gas_cache) = self.gas_cache.as_deref() {
+                        gas_cache.on_new_block(new_block.sealed_block().header().number());
+                    }
                     if let Err(err) = self.state.import(new_block.into_sealed_block()) {
                         failures += 1;
                         tracing::warn!("failed to import block {block_id} ({failures}): {err:?}");
@@ -95,7 +128,13 @@ where
                     self.stream = Some(stream);
                     break Poll::Ready(Some(event));
                 },
-                Poll::Ready(None) => break Poll::Ready(None),
+                Poll::Ready(None) => {
+                    // Notify the consumer why the stream was closed
+                    if let Some(reason) = stream.close_reason() {
+                        break Poll::Ready(Some(ClientEvent::Close(reason.to_string())));
+                    }
+                    break Poll::Ready(None);
+                },
                 Poll::Pending => {
                     self.stream = Some(stream);
                     break Poll::Pending;
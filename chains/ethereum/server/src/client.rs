@@ -2,13 +2,16 @@
 use crate::{
     block_provider::RpcBlockProvider,
     block_stream::BlockStream,
+    gas_cache::{GasEstimateCache, GasEstimateKey},
     log_filter::LogFilter,
+    log_scan,
     proof::verify_proof,
+    retry::RetryPolicy,
     shared_stream::SharedStream,
     state::State,
     utils::{
-        AtBlockExt, DefaultFeeEstimatorConfig, EthereumRpcExt, PartialBlock,
-        PolygonFeeEstimatorConfig,
+        next_block_base_fee, AtBlockExt, ChainStalled, DefaultFeeEstimatorConfig, EthereumRpcExt,
+        PartialBlock, PolygonFeeEstimatorConfig, WouldRevert, DEFAULT_SUBMIT_TIMEOUT,
     },
 };
 use anyhow::{Context, Result};
@@ -17,29 +20,41 @@ use rosetta_config_ethereum::{
         crypto::{Crypto, DefaultCrypto, Keypair, Signer},
         ext::rlp::Encodable,
         rlp_utils::RlpDecodableTransaction,
-        rpc::CallRequest,
-        transactions::LegacyTransaction,
-        AccessList, AtBlock, Bytes, TransactionT, TypedTransaction, H160, U256,
+        rpc::{CallRequest, RpcTransaction},
+        transactions::{GasPrice, LegacyTransaction},
+        AccessList, AtBlock, Bytes, SealedHeader, TransactionT, TypedTransaction, H160, H256, U256,
     },
     query::GetBlock,
-    CallContract, CallResult, EthereumMetadata, EthereumMetadataParams, GetBalance, GetProof,
-    GetStorageAt, GetTransactionCount, GetTransactionReceipt, Query as EthQuery,
-    QueryResult as EthQueryResult, SubmitResult, Subscription,
+    CallContract, CallResult, EstimateGas, EthereumMetadata, EthereumMetadataParams,
+    FilterBlockOption, GetBalance, GetCode, GetProof, GetStorageAt, GetTransactionCount,
+    GetTransactionReceipt, Log, Query as EthQuery, QueryResult as EthQueryResult, SubmitResult,
+    Subscription, TransactionReceipt,
 };
 
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use rosetta_core::{
+    check_synced,
     crypto::{address::Address, PublicKey},
-    types::{BlockIdentifier, PartialBlockIdentifier},
-    BlockchainConfig, ClientEvent,
+    types::{
+        operation_identifier, AccountIdentifier, Amount, BlockIdentifier, BlockTransaction,
+        CallRequest as RosettaCallRequest, CallResponse, Currency, Operation,
+        PartialBlockIdentifier, Peer, TransactionIdentifier, OPERATION_STATUS_FAILURE,
+        OPERATION_STATUS_SUCCESS,
+    },
+    BlockchainConfig, ClientEvent, InclusionEstimate,
 };
 use rosetta_ethereum_backend::{
     jsonrpsee::{
-        core::client::{ClientT, SubscriptionClientT},
+        core::{
+            client::{ClientT, SubscriptionClientT},
+            params::{ArrayParams, BatchRequestBuilder},
+            rpc_params, ClientError,
+        },
         Adapter,
     },
     BlockRange, EthereumRpc, ExitReason,
 };
+use rosetta_server::ws::{ConnectionStatus, DefaultClient};
 use std::{
     sync::{
         atomic::{self, Ordering},
@@ -48,6 +63,47 @@ use std::{
     time::Duration,
 };
 
+/// Read-only json-rpc methods which can be invoked using [`EthereumClient::rosetta_call`].
+pub const ROSETTA_CALL_METHODS: &[&str] = &[
+    "eth_blockNumber",
+    "eth_call",
+    "eth_chainId",
+    "eth_estimateGas",
+    "eth_feeHistory",
+    "eth_gasPrice",
+    "eth_getBalance",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getCode",
+    "eth_getLogs",
+    "eth_getProof",
+    "eth_getStorageAt",
+    "eth_getTransactionByHash",
+    "eth_getTransactionCount",
+    "eth_getTransactionReceipt",
+    "eth_maxPriorityFeePerGas",
+];
+
+/// Json-rpc error code returned when the method doesn't exist or isn't available.
+const METHOD_NOT_FOUND_CODE: i32 = -32601;
+
+/// Json-rpc error code returned when the execution reverted, the revert data is in the error data.
+const EXECUTION_REVERTED_CODE: i32 = 3;
+
+/// Json-rpc error code of the generic server errors, some nodes use it for reverts.
+const SERVER_ERROR_CODE: i32 = -32000;
+
+/// Priority fee suggested by [`EthereumClient::suggest_fees`] when the node doesn't support
+/// `eth_feeHistory`, 1 gwei.
+pub const FALLBACK_PRIORITY_FEE: u128 = 1_000_000_000;
+
+/// Number of blocks queried per `eth_getLogs` request by [`EthereumClient::stream_logs`].
+pub const LOGS_CHUNK_SIZE: u64 = 1000;
+
+/// Number of recent blocks used to estimate the block time and how many transactions are
+/// included per block, see [`EthereumClient::inclusion_estimate`].
+pub const INCLUSION_ESTIMATE_BLOCKS: u64 = 10;
+
 pub type BlockStreamType<P> = SharedStream<BlockStream<RpcBlockProvider<Adapter<P>>, Adapter<P>>>;
 
 /// Strategy used to determine the finalized block
@@ -78,6 +134,94 @@ impl BlockFinalityStrategy {
     }
 }
 
+/// Returns the minimum gas price known for the chain, transactions below it are rejected.
+fn default_min_gas_price(config: &BlockchainConfig) -> u128 {
+    match config.blockchain {
+        // Arbitrum minimum base fee is 0.01 gwei
+        "arbitrum" => 10_000_000,
+        _ => 0,
+    }
+}
+
+/// Returns `true` if a json-rpc error means the method isn't supported by the node, some nodes
+/// don't use the standard error code.
+fn is_method_not_found(code: i32, message: &str) -> bool {
+    let message = message.to_lowercase();
+    code == METHOD_NOT_FOUND_CODE ||
+        message.contains("method not found") ||
+        (message.contains("method") && message.contains("does not exist"))
+}
+
+/// Returns `true` if `error` is a json-rpc error reporting that the execution reverted, either the
+/// standard code or a server error carrying the revert data.
+fn is_revert_error(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|error| error.downcast_ref::<ClientError>())
+        .any(|error| {
+            matches!(
+                error,
+                ClientError::Call(error) if error.code() == EXECUTION_REVERTED_CODE ||
+                    (error.code() == SERVER_ERROR_CODE && error.data().is_some())
+            )
+        })
+}
+
+/// Returns how long [`EthereumClient::submit`] waits for a transaction to be included by default.
+fn default_submit_timeout(config: &BlockchainConfig) -> Duration {
+    match (config.blockchain, config.testnet) {
+        // Ethereum mainnet produces a block every 12 seconds, and a transaction may wait a few
+        // blocks when the network is congested
+        ("ethereum", false) => Duration::from_secs(180),
+        ("polygon", false) => Duration::from_secs(60),
+        _ => DEFAULT_SUBMIT_TIMEOUT,
+    }
+}
+
+/// Number of the genesis block, the first block served by the nodes of the chain. Chains
+/// migrated to a new client, like Arbitrum One to Nitro, start after the blocks of the previous
+/// client.
+fn genesis_block_number(config: &BlockchainConfig) -> u64 {
+    match (config.blockchain, config.network) {
+        // Nitro genesis, the previous blocks are only served by Arbitrum Classic nodes
+        ("arbitrum", "mainnet") => 22_207_817,
+        _ => 0,
+    }
+}
+
+/// Genesis hash of the well known public networks, verified by [`EthereumClient::preflight`].
+fn known_genesis_hash(config: &BlockchainConfig) -> Option<&'static str> {
+    let genesis_hash = match (config.blockchain, config.network) {
+        ("ethereum", "mainnet") => {
+            "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+        },
+        ("ethereum", "sepolia") => {
+            "25a5cc106eea7138acab33231d7160d69cb777ee0c2c553fcddf5138993e6dd9"
+        },
+        ("polygon", "mainnet") => {
+            "a9c28ce2141b56c474f1dc504bee9b01eb1bd7d1a507580d5519d4437a97de1b"
+        },
+        _ => return None,
+    };
+    Some(genesis_hash)
+}
+
+/// Chain id of the well known public networks, verified by [`EthereumClient::preflight`].
+fn known_chain_id(config: &BlockchainConfig) -> Option<u64> {
+    let chain_id = match (config.blockchain, config.network) {
+        ("ethereum", "mainnet") => 1,
+        ("ethereum", "sepolia") => 11_155_111,
+        ("polygon", "mainnet") => 137,
+        ("polygon", "amoy") => 80_002,
+        ("arbitrum", "mainnet") => 42_161,
+        ("base", "mainnet") => 8_453,
+        ("binance", "mainnet") => 56,
+        ("avalanche", "mainnet") => 43_114,
+        _ => return None,
+    };
+    Some(chain_id)
+}
+
 pub struct EthereumClient<P> {
     chain_id: u64,
     config: BlockchainConfig,
@@ -87,6 +231,15 @@ pub struct EthereumClient<P> {
     nonce: Arc<std::sync::atomic::AtomicU64>,
     private_key: Option<[u8; 32]>,
     log_filter: Arc<std::sync::Mutex<LogFilter>>,
+    gas_cache: Option<Arc<GasEstimateCache>>,
+    min_gas_price: U256,
+    fixed_gas_limit: Option<u64>,
+    submit_timeout: Duration,
+    stall_timeout: Option<Duration>,
+    block_receipts_unsupported: Arc<atomic::AtomicBool>,
+    fallback_gas_price: Option<U256>,
+    fee_history_unsupported: Arc<atomic::AtomicBool>,
+    retry_policy: RetryPolicy,
     // event_stream: SharedStream<BlockStream<Adapter<P>>>
 }
 
@@ -104,6 +257,15 @@ where
             nonce: self.nonce.clone(),
             private_key: self.private_key,
             log_filter: self.log_filter.clone(),
+            gas_cache: self.gas_cache.clone(),
+            min_gas_price: self.min_gas_price,
+            fixed_gas_limit: self.fixed_gas_limit,
+            submit_timeout: self.submit_timeout,
+            stall_timeout: self.stall_timeout,
+            block_receipts_unsupported: self.block_receipts_unsupported.clone(),
+            fallback_gas_price: self.fallback_gas_price,
+            fee_history_unsupported: self.fee_history_unsupported.clone(),
+            retry_policy: self.retry_policy,
         }
     }
 }
@@ -123,12 +285,13 @@ where
         // Get the chain id
         let chain_id = backend.chain_id().await?;
 
-        // Get the genesis block
-        let at = AtBlock::At(rosetta_config_ethereum::ext::types::BlockIdentifier::Number(0));
+        // Get the genesis block by number, the earliest block of a pruned node is its first
+        // unpruned block
+        let genesis_number = genesis_block_number(&config);
         let genesis_block = backend
-            .block(at)
+            .block(AtBlock::from(genesis_number))
             .await?
-            .ok_or_else(|| anyhow::format_err!("FATAL: genesis block not found"))?
+            .ok_or_else(|| anyhow::format_err!("FATAL: genesis block {genesis_number} not found"))?
             .try_seal()
             .map_err(|_| {
                 anyhow::format_err!(
@@ -150,6 +313,8 @@ where
         } else {
             (None, Arc::new(atomic::AtomicU64::new(0)))
         };
+        let min_gas_price = U256::from(default_min_gas_price(&config));
+        let submit_timeout = default_submit_timeout(&config);
         Ok(Self {
             chain_id,
             config,
@@ -159,8 +324,82 @@ where
             nonce,
             private_key,
             log_filter: Arc::new(std::sync::Mutex::new(LogFilter::new())),
+            gas_cache: None,
+            min_gas_price,
+            fixed_gas_limit: None,
+            submit_timeout,
+            stall_timeout: None,
+            block_receipts_unsupported: Arc::new(atomic::AtomicBool::new(false)),
+            fallback_gas_price: None,
+            fee_history_unsupported: Arc::new(atomic::AtomicBool::new(false)),
+            retry_policy: RetryPolicy::default(),
         })
     }
+
+    /// Enables caching of `eth_estimateGas` results for the given `ttl`, cached entries are
+    /// also discarded whenever a new block is observed: by [`Self::metadata`],
+    /// [`Self::current_block`] or the stream returned by [`Self::listen`]. Pass `None` to disable
+    /// the cache.
+    #[must_use]
+    pub fn with_gas_estimate_cache(mut self, ttl: Option<Duration>) -> Self {
+        self.gas_cache = ttl.map(|ttl| Arc::new(GasEstimateCache::new(ttl)));
+        self
+    }
+
+    /// Overrides the minimum gas price floor, the fee suggestion is never below this value.
+    #[must_use]
+    pub const fn with_min_gas_price(mut self, min_gas_price: U256) -> Self {
+        self.min_gas_price = min_gas_price;
+        self
+    }
+
+    /// Overrides the gas price suggested when the node doesn't support `eth_feeHistory`, by
+    /// default the `eth_gasPrice` of the node is used, see [`Self::suggest_fees`].
+    #[must_use]
+    pub const fn with_fallback_gas_price(mut self, gas_price: Option<U256>) -> Self {
+        self.fallback_gas_price = gas_price;
+        self
+    }
+
+    /// Disables the automatic gas estimation, `eth_estimateGas` is never called and transactions
+    /// use `gas_limit` unless one is provided in [`EthereumMetadataParams`]. Useful on chains
+    /// where `eth_estimateGas` is unreliable.
+    ///
+    /// Warning: the sender may overpay for gas if `gas_limit` is much higher than the gas used
+    /// by the transaction. Pass `None` to enable the gas estimation again.
+    #[must_use]
+    pub const fn with_fixed_gas_limit(mut self, gas_limit: Option<u64>) -> Self {
+        self.fixed_gas_limit = gas_limit;
+        self
+    }
+
+    /// Sets how long [`EthereumClient::submit`] waits for the transaction to be included in a
+    /// block before returning [`SubmitResult::Timeout`], the default depends on the chain.
+    #[must_use]
+    pub const fn with_submit_timeout(mut self, submit_timeout: Duration) -> Self {
+        self.submit_timeout = submit_timeout;
+        self
+    }
+
+    /// Sets how long to wait for a new block while waiting for a transaction to be included,
+    /// after which [`EthereumClient::submit`] fails with [`ChainStalled`]. Disabled by default,
+    /// the timeout must be longer than a few block times of the chain, ex:
+    /// [`DEFAULT_STALL_TIMEOUT`](crate::DEFAULT_STALL_TIMEOUT) on dev chains.
+    #[must_use]
+    pub const fn with_stall_timeout(mut self, stall_timeout: Option<Duration>) -> Self {
+        self.stall_timeout = stall_timeout;
+        self
+    }
+
+    /// Sets how the read-only requests, ex: [`EthereumClient::call`], [`EthereumClient::balance`]
+    /// or [`EthereumClient::metadata`], are retried when they fail with a transient transport
+    /// error, ex: a connection reset or a HTTP 429 response. The submissions and the faucet are
+    /// never retried. Pass [`RetryPolicy::fail_fast`] to disable the retries.
+    #[must_use]
+    pub const fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 }
 
 impl<P> EthereumClient<P>
@@ -180,17 +419,28 @@ where
 
     #[allow(clippy::missing_errors_doc)]
     pub async fn current_block(&self) -> Result<BlockIdentifier> {
+        self.retry_policy.retry(|| self.current_block_once()).await
+    }
+
+    async fn current_block_once(&self) -> Result<BlockIdentifier> {
         let Some(block) = self.backend.block(AtBlock::Latest).await? else {
             anyhow::bail!("[report this bug] latest block not found");
         };
         let Some(hash) = block.hash else {
             anyhow::bail!("[report this bug] api returned latest block without hash");
         };
+        if let Some(gas_cache) = self.gas_cache.as_deref() {
+            gas_cache.on_new_block(block.header.number);
+        }
         Ok(BlockIdentifier { index: block.header.number, hash: hash.0 })
     }
 
     #[allow(clippy::missing_errors_doc)]
     pub async fn finalized_block(&self, latest_block: Option<u64>) -> Result<PartialBlock> {
+        self.retry_policy.retry(|| self.finalized_block_once(latest_block)).await
+    }
+
+    async fn finalized_block_once(&self, latest_block: Option<u64>) -> Result<PartialBlock> {
         let number: AtBlock = match self.block_finality_strategy {
             BlockFinalityStrategy::Confirmations(confirmations) => {
                 let latest_block = match latest_block {
@@ -202,8 +452,8 @@ where
                         .context("Failed to retrieve latest block number")?,
                 };
                 let block_number = latest_block.saturating_sub(confirmations);
-                // If the number is zero, the latest finalized is the genesis block
-                if block_number == 0 {
+                // Blocks before the genesis don't exist, the latest finalized is the genesis
+                if block_number <= self.genesis_block.header().header().number {
                     return Ok(self.genesis_block.clone());
                 }
                 AtBlock::At(block_number.into())
@@ -220,11 +470,79 @@ where
         Ok(finalized_block)
     }
 
+    /// Returns only the header of the block at `at`, the block body is dropped.
+    ///
+    /// When `at` is a block hash, the header hash is computed from its fields and verified
+    /// against the requested hash.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn header(&self, at: AtBlock) -> Result<Option<SealedHeader>> {
+        self.retry_policy.retry(|| self.header_once(at)).await
+    }
+
+    async fn header_once(&self, at: AtBlock) -> Result<Option<SealedHeader>> {
+        // `eth_getBlockByNumber` with `full=false`, so only transaction hashes are returned
+        let Some(block) = self.backend.block(at).await? else {
+            return Ok(None);
+        };
+        let Some(hash) = block.hash else {
+            anyhow::bail!("api returned an invalid block at {at}: block hash missing");
+        };
+        let header = SealedHeader::new(block.header, hash);
+        if let AtBlock::At(rosetta_config_ethereum::ext::types::BlockIdentifier::Hash(expected)) =
+            at
+        {
+            let computed = header.header().compute_hash::<DefaultCrypto>();
+            if computed != expected || hash != expected {
+                anyhow::bail!(
+                    "header hash mismatch, expected {expected:?}, got {hash:?}, computed {computed:?}"
+                );
+            }
+        }
+        Ok(Some(header))
+    }
+
+    /// Returns the minimum gas price accepted by the chain: the per-chain floor, or the one set
+    /// with [`Self::with_min_gas_price`].
+    #[must_use]
+    pub const fn min_gas_price(&self) -> U256 {
+        self.min_gas_price
+    }
+
+    /// Returns the base fee of the latest block and the predicted base fee of the next block,
+    /// computed from the latest block gas usage as specified by EIP-1559.
+    ///
+    /// Fails if the chain doesn't support EIP-1559.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn base_fee(&self) -> Result<(U256, U256)> {
+        self.retry_policy.retry(|| self.base_fee_once()).await
+    }
+
+    async fn base_fee_once(&self) -> Result<(U256, U256)> {
+        let Some(block) = self.backend.block(AtBlock::Latest).await? else {
+            anyhow::bail!("latest block not found");
+        };
+        let Some(base_fee_per_gas) = block.header.base_fee_per_gas else {
+            anyhow::bail!("pre-EIP-1559 chain: latest block has no base fee");
+        };
+        let base_fee_per_gas = U256::from(base_fee_per_gas);
+        let next_base_fee =
+            next_block_base_fee(base_fee_per_gas, block.header.gas_used, block.header.gas_limit);
+        Ok((base_fee_per_gas, next_base_fee))
+    }
+
     #[allow(clippy::missing_errors_doc)]
     pub async fn balance(
         &self,
         address: &Address,
         block_identifier: &PartialBlockIdentifier,
+    ) -> Result<u128> {
+        self.retry_policy.retry(|| self.balance_once(address, block_identifier)).await
+    }
+
+    async fn balance_once(
+        &self,
+        address: &Address,
+        block_identifier: &PartialBlockIdentifier,
     ) -> Result<u128> {
         // Convert `PartialBlockIdentifier` to `AtBlock`
         let at_block = AtBlock::from_partial_identifier(block_identifier);
@@ -235,6 +553,499 @@ where
         Ok(balance)
     }
 
+    /// Fetches the balances of `addresses` with a single json-rpc batch of `eth_getBalance`, the
+    /// balances are returned in the same order as `addresses`.
+    ///
+    /// # Errors
+    /// Returns `Err` with the first address whose balance can't be fetched.
+    pub async fn balances(
+        &self,
+        addresses: &[Address],
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<Vec<u128>> {
+        self.retry_policy
+            .retry(|| self.balances_once(addresses, block_identifier))
+            .await
+    }
+
+    async fn balances_once(
+        &self,
+        addresses: &[Address],
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<Vec<u128>> {
+        if addresses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let at_block = AtBlock::from_partial_identifier(block_identifier);
+        let mut batch = BatchRequestBuilder::new();
+        for address in addresses {
+            let address: H160 = address.address().parse()?;
+            batch.insert("eth_getBalance", rpc_params![address, at_block])?;
+        }
+        // The responses are ordered like the requests, whatever the order the node replies in
+        let responses = self.backend.0.batch_request::<U256>(batch).await?;
+        responses
+            .into_iter()
+            .zip(addresses)
+            .map(|(response, address)| {
+                let balance = response.map_err(|error| {
+                    anyhow::format_err!(
+                        "failed to fetch the balance of {}: {error}",
+                        address.address()
+                    )
+                })?;
+                u128::try_from(balance).map_err(|err| {
+                    anyhow::format_err!("balance overflow of {}: {err}", address.address())
+                })
+            })
+            .collect()
+    }
+
+    /// Streams the logs matching `contracts` and `topics` between the blocks `from` and `to`
+    /// (inclusive), the range is queried in chunks of [`LOGS_CHUNK_SIZE`] blocks so the logs
+    /// are never fully materialized in memory. Logs are yielded in the same order as `GetLogs`.
+    pub fn stream_logs(
+        &self,
+        contracts: Vec<H160>,
+        topics: Vec<H256>,
+        from: u64,
+        to: u64,
+    ) -> impl Stream<Item = Result<Log>> + Send + '_ {
+        self.stream_logs_with_chunk_size(contracts, topics, from, to, LOGS_CHUNK_SIZE)
+    }
+
+    pub(crate) fn stream_logs_with_chunk_size(
+        &self,
+        contracts: Vec<H160>,
+        topics: Vec<H256>,
+        from: u64,
+        to: u64,
+        chunk_size: u64,
+    ) -> impl Stream<Item = Result<Log>> + Send + '_ {
+        let chunk_size = chunk_size.max(1);
+        let start = if from <= to { Some(from) } else { None };
+        futures_util::stream::try_unfold(start, move |chunk_start| {
+            let contracts = contracts.clone();
+            let topics = topics.clone();
+            async move {
+                let Some(chunk_start) = chunk_start else {
+                    return Ok(None);
+                };
+                let chunk_end = chunk_start.saturating_add(chunk_size - 1).min(to);
+                let block_range = BlockRange {
+                    address: contracts,
+                    topics,
+                    filter: FilterBlockOption::Range {
+                        from_block: Some(AtBlock::from(chunk_start)),
+                        to_block: Some(AtBlock::from(chunk_end)),
+                    },
+                };
+                let logs = self.backend.get_logs(block_range).await?;
+                let next_chunk = chunk_end.checked_add(1).filter(|next| *next <= to);
+                let logs = futures_util::stream::iter(logs.into_iter().map(Ok));
+                Ok::<_, anyhow::Error>(Some((logs, next_chunk)))
+            }
+        })
+        .try_flatten()
+    }
+
+    /// Returns the logs matching `contracts` and `topics` between the blocks `from` and `to`
+    /// (inclusive). The block headers are fetched first, and `eth_getLogs` is only called for the
+    /// ranges whose `logsBloom` may contain a match, which is much cheaper for sparse logs.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn scan_logs(
+        &self,
+        contracts: Vec<H160>,
+        topics: Vec<H256>,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<Log>> {
+        log_scan::scan_logs(
+            from,
+            to,
+            &contracts,
+            &topics,
+            |number| async move {
+                let Some(block) = self.backend.block(AtBlock::from(number)).await? else {
+                    anyhow::bail!("block {number} not found");
+                };
+                Ok(block.header.logs_bloom)
+            },
+            |from_block, to_block| {
+                let block_range = BlockRange {
+                    address: contracts.clone(),
+                    topics: topics.clone(),
+                    filter: FilterBlockOption::Range {
+                        from_block: Some(AtBlock::from(from_block)),
+                        to_block: Some(AtBlock::from(to_block)),
+                    },
+                };
+                async move { Ok(self.backend.get_logs(block_range).await?) }
+            },
+        )
+        .await
+    }
+
+    /// Returns the receipts of all the transactions in the block `at`, in the same order as the
+    /// transactions. Uses `eth_getBlockReceipts` when the node supports it, otherwise the
+    /// receipts are fetched with a single json-rpc batch of `eth_getTransactionReceipt`, the
+    /// fallback is remembered once the node reports the method is not found.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn block_receipts(&self, at: AtBlock) -> Result<Vec<TransactionReceipt>> {
+        self.retry_policy.retry(|| self.block_receipts_once(at)).await
+    }
+
+    async fn block_receipts_once(&self, at: AtBlock) -> Result<Vec<TransactionReceipt>> {
+        if !self.block_receipts_unsupported.load(Ordering::Relaxed) {
+            let result = self
+                .backend
+                .0
+                .request::<Option<Vec<TransactionReceipt>>, _>(
+                    "eth_getBlockReceipts",
+                    rpc_params![at],
+                )
+                .await;
+            match result {
+                Ok(Some(receipts)) => return Ok(receipts),
+                Ok(None) => anyhow::bail!("block {at} not found"),
+                Err(ClientError::Call(error))
+                    if is_method_not_found(error.code(), error.message()) =>
+                {
+                    tracing::debug!(
+                        "eth_getBlockReceipts not supported, using eth_getTransactionReceipt"
+                    );
+                    self.block_receipts_unsupported.store(true, Ordering::Relaxed);
+                },
+                Err(error) => return Err(error.into()),
+            }
+        }
+        self.batched_block_receipts(at).await
+    }
+
+    /// Returns the peers the node is connected to, from `admin_peers`. Nodes which don't expose
+    /// the admin api are only supported when `net_peerCount` reports no peers.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn peers(&self) -> Result<Vec<Peer>> {
+        self.retry_policy.retry(|| self.peers_once()).await
+    }
+
+    async fn peers_once(&self) -> Result<Vec<Peer>> {
+        let result = self
+            .backend
+            .0
+            .request::<Vec<serde_json::Value>, _>("admin_peers", rpc_params![])
+            .await;
+        match result {
+            Ok(peers) => peers
+                .into_iter()
+                .map(|peer| {
+                    let peer_id = peer
+                        .get("id")
+                        .and_then(serde_json::Value::as_str)
+                        .context("admin_peers returned a peer without id")?
+                        .to_string();
+                    Ok(Peer { peer_id, metadata: Some(peer) })
+                })
+                .collect(),
+            Err(ClientError::Call(error)) if is_method_not_found(error.code(), error.message()) => {
+                let count =
+                    self.backend.0.request::<U256, _>("net_peerCount", rpc_params![]).await?;
+                if !count.is_zero() {
+                    anyhow::bail!("the node has {count} peers but doesn't expose admin_peers");
+                }
+                Ok(Vec::new())
+            },
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Verifies the node is reachable, serves the chain of the config when its chain id is well
+    /// known, and is at most `max_blocks_behind` blocks behind the highest block announced by
+    /// its peers, from `eth_syncing`.
+    ///
+    /// # Errors
+    /// Will return `Err` when the node is unreachable, serves another chain, or isn't synced.
+    pub async fn preflight(&self, max_blocks_behind: u64) -> Result<()> {
+        if let Some(expected) = known_chain_id(&self.config) {
+            if self.chain_id != expected {
+                anyhow::bail!(
+                    "node serves chain id {}, expected {expected} for {} {}",
+                    self.chain_id,
+                    self.config.blockchain,
+                    self.config.network
+                );
+            }
+        }
+        if let Some(expected) = known_genesis_hash(&self.config) {
+            let genesis_hash = hex::encode(self.genesis_block().hash);
+            if genesis_hash != expected {
+                anyhow::bail!(
+                    "node serves the chain with genesis 0x{genesis_hash}, expected 0x{expected} \
+                     for {} {}",
+                    self.config.blockchain,
+                    self.config.network
+                );
+            }
+        }
+        let syncing = self
+            .backend
+            .0
+            .request::<serde_json::Value, _>("eth_syncing", rpc_params![])
+            .await
+            .context("node is unreachable")?;
+        // `eth_syncing` returns `false` once the node is synced
+        if let Some(status) = syncing.as_object() {
+            let block = |name: &str| {
+                status
+                    .get(name)
+                    .and_then(serde_json::Value::as_str)
+                    .and_then(|number| {
+                        u64::from_str_radix(number.trim_start_matches("0x"), 16).ok()
+                    })
+                    .with_context(|| format!("eth_syncing returned an invalid {name}"))
+            };
+            check_synced(block("currentBlock")?, block("highestBlock")?, max_blocks_behind)?;
+        }
+        Ok(())
+    }
+
+    /// Estimates whether a transaction is included before the `deadline` timestamp, ex: the
+    /// deadline of a permit. The block time and the transactions included per block are read
+    /// from the last [`INCLUSION_ESTIMATE_BLOCKS`] blocks, the transactions ahead from
+    /// `txpool_status`. Nodes which don't expose the txpool api are assumed to have no pending
+    /// transactions.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn inclusion_estimate(&self, deadline: u64) -> Result<InclusionEstimate> {
+        #[derive(serde::Deserialize)]
+        struct TxPoolStatus {
+            pending: U256,
+        }
+
+        let Some(latest) = self.backend.block(AtBlock::Latest).await? else {
+            anyhow::bail!("latest block not found");
+        };
+        let latest_number = latest.header.number;
+        let oldest_number = latest_number.saturating_sub(INCLUSION_ESTIMATE_BLOCKS - 1);
+        let mut oldest_timestamp = latest.header.timestamp;
+        let mut per_block = latest.transactions.len() as u64;
+        for number in oldest_number..latest_number {
+            let Some(block) = self.backend.block(AtBlock::At(number.into())).await? else {
+                anyhow::bail!("block {number} not found");
+            };
+            if number == oldest_number {
+                oldest_timestamp = block.header.timestamp;
+            }
+            per_block = per_block.max(block.transactions.len() as u64);
+        }
+        let block_time = latest
+            .header
+            .timestamp
+            .saturating_sub(oldest_timestamp)
+            .checked_div(latest_number - oldest_number)
+            .unwrap_or(1)
+            .max(1);
+        // The transaction can be included in any block whose timestamp isn't after the deadline
+        let remaining_blocks = deadline.saturating_sub(latest.header.timestamp) / block_time;
+
+        let result =
+            self.backend.0.request::<TxPoolStatus, _>("txpool_status", rpc_params![]).await;
+        let pending = match result {
+            Ok(status) => u64::try_from(status.pending).unwrap_or(u64::MAX),
+            Err(ClientError::Call(error)) if is_method_not_found(error.code(), error.message()) => {
+                0
+            },
+            Err(error) => return Err(error.into()),
+        };
+        Ok(InclusionEstimate::new(pending, per_block, remaining_blocks))
+    }
+
+    /// Returns all transactions in the block `at` with their operations. Every transaction pays
+    /// a `FEE` of `gas_used * effective_gas_price` from the sender, even if it reverted. In
+    /// EIP-1559 blocks a `FEE_BURN` operation reports the part of the fee which is burnt,
+    /// `gas_used * base_fee_per_gas`. The rest of the fee is credited to the block's `miner` by a
+    /// `FEE_REWARD` operation, so the fee operations always balance. Static block rewards, which
+    /// are not paid by transactions, are not reported. The value transferred by a transaction is
+    /// reported as `TRANSFER` operations, marked as [`OPERATION_STATUS_FAILURE`] if it
+    /// reverted. Transfers performed by internal calls are not included.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn block_transactions(&self, at: AtBlock) -> Result<Vec<BlockTransaction>> {
+        self.retry_policy.retry(|| self.block_transactions_once(at)).await
+    }
+
+    async fn block_transactions_once(&self, at: AtBlock) -> Result<Vec<BlockTransaction>> {
+        let Some(block) = self.backend.block_full::<RpcTransaction>(at).await? else {
+            anyhow::bail!("block {at} not found");
+        };
+        let block_hash = block.hash.with_context(|| format!("block {at} has no hash"))?;
+        // Read the receipts by hash, so they belong to the same block even if the chain reorgs
+        let receipts = self.block_receipts_once(AtBlock::from(block_hash)).await?;
+        if receipts.len() != block.transactions.len() {
+            anyhow::bail!(
+                "block {at} has {} transactions but {} receipts",
+                block.transactions.len(),
+                receipts.len()
+            );
+        }
+        let currency = Currency {
+            symbol: self.config.currency_symbol.to_string(),
+            decimals: self.config.currency_decimals,
+            metadata: None,
+        };
+        let operation =
+            |index: usize, address: Option<H160>, value: String, status: &str| Operation {
+                operation_identifier: operation_identifier(index),
+                r#type: "TRANSFER".into(),
+                status: Some(status.into()),
+                account: address.map(|address| AccountIdentifier {
+                    address: format!("{address:?}"),
+                    sub_account: None,
+                    metadata: None,
+                }),
+                amount: Some(Amount::new(value, currency.clone())),
+                ..Operation::default()
+            };
+
+        let mut transactions = Vec::with_capacity(receipts.len());
+        for (tx, receipt) in block.transactions.into_iter().zip(receipts) {
+            let success = receipt.status_code == Some(1);
+            let status = if success { OPERATION_STATUS_SUCCESS } else { OPERATION_STATUS_FAILURE };
+            let gas_used = receipt.gas_used.unwrap_or_default();
+            let gas_price = receipt.effective_gas_price.or(tx.gas_price).unwrap_or_default();
+
+            // The fee is paid even if the transaction reverted
+            let fee = gas_used.saturating_mul(gas_price);
+            let mut operations = vec![Operation {
+                r#type: "FEE".into(),
+                ..operation(0, Some(tx.from), format!("-{fee}"), OPERATION_STATUS_SUCCESS)
+            }];
+            let mut burnt = U256::zero();
+            if let Some(base_fee) = block.header.base_fee_per_gas {
+                burnt = gas_used.saturating_mul(U256::from(base_fee));
+                operations.push(Operation {
+                    r#type: "FEE_BURN".into(),
+                    related_operations: Some(vec![operation_identifier(0)]),
+                    ..operation(1, None, burnt.to_string(), OPERATION_STATUS_SUCCESS)
+                });
+            }
+            // The rest of the fee, the priority tip, goes to the block's fee recipient
+            let reward = fee.saturating_sub(burnt);
+            operations.push(Operation {
+                r#type: "FEE_REWARD".into(),
+                related_operations: Some(vec![operation_identifier(0)]),
+                ..operation(
+                    operations.len(),
+                    Some(block.header.beneficiary),
+                    reward.to_string(),
+                    OPERATION_STATUS_SUCCESS,
+                )
+            });
+            if !tx.value.is_zero() {
+                let to = tx.to.or(receipt.contract_address);
+                let debit_index = operations.len();
+                let debit = format!("-{}", tx.value);
+                operations.push(operation(debit_index, Some(tx.from), debit, status));
+                operations.push(Operation {
+                    related_operations: Some(vec![operation_identifier(debit_index)]),
+                    ..operation(operations.len(), to, tx.value.to_string(), status)
+                });
+            }
+            transactions.push(BlockTransaction {
+                transaction_identifier: TransactionIdentifier::new(format!(
+                    "{:?}",
+                    receipt.transaction_hash
+                )),
+                index: receipt.transaction_index,
+                success,
+                operations,
+            });
+        }
+        Ok(transactions)
+    }
+
+    /// Fetches the receipts of the block `at` with a json-rpc batch of `eth_getTransactionReceipt`.
+    pub(crate) async fn batched_block_receipts(
+        &self,
+        at: AtBlock,
+    ) -> Result<Vec<TransactionReceipt>> {
+        let Some(block) = self.backend.block(at).await? else {
+            anyhow::bail!("block {at} not found");
+        };
+        if block.transactions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut batch = BatchRequestBuilder::new();
+        for tx_hash in &block.transactions {
+            batch.insert("eth_getTransactionReceipt", rpc_params![tx_hash])?;
+        }
+        let responses = self.backend.0.batch_request::<Option<TransactionReceipt>>(batch).await?;
+        responses
+            .into_iter()
+            .zip(&block.transactions)
+            .map(|(response, tx_hash)| match response {
+                Ok(Some(receipt)) => Ok(receipt),
+                Ok(None) => anyhow::bail!("receipt of {tx_hash:?} not found"),
+                Err(error) => {
+                    anyhow::bail!("failed to fetch the receipt of {tx_hash:?}: {error}")
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the size of the code `init_code` would deploy when sent by `from`. The init code
+    /// is executed with `eth_call` as the code of a temporary account using a state override,
+    /// so the returned code isn't subject to the EIP-170 limit and can be measured.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn deployed_code_size(&self, from: [u8; 20], init_code: &[u8]) -> Result<usize> {
+        let address = H160::from_low_u64_be(0xc0de);
+        let call = serde_json::json!({ "from": H160(from), "to": address });
+        let mut overrides = serde_json::Map::new();
+        overrides.insert(
+            format!("{address:?}"),
+            serde_json::json!({ "code": format!("0x{}", hex::encode(init_code)) }),
+        );
+        let code: String = self
+            .backend
+            .0
+            .request("eth_call", rpc_params![call, AtBlock::Latest, overrides])
+            .await?;
+        let code = hex::decode(code.trim_start_matches("0x")).context("invalid eth_call result")?;
+        Ok(code.len())
+    }
+
+    /// Handles a Rosetta `/call` request, the `method` is forwarded to the node as a json-rpc call
+    /// and `parameters` must be the list of positional json-rpc params, ex:
+    /// `{ "method": "eth_getBalance", "parameters": ["0x...", "latest"] }`.
+    ///
+    /// Only the read-only methods listed in [`ROSETTA_CALL_METHODS`] are supported.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn rosetta_call(&self, request: &RosettaCallRequest) -> Result<CallResponse> {
+        self.retry_policy.retry(|| self.rosetta_call_once(request)).await
+    }
+
+    async fn rosetta_call_once(&self, request: &RosettaCallRequest) -> Result<CallResponse> {
+        let method = request.method.as_str();
+        if !ROSETTA_CALL_METHODS.contains(&method) {
+            anyhow::bail!("unsupported method: {method}");
+        }
+        let mut params = ArrayParams::new();
+        match &request.parameters {
+            serde_json::Value::Null => {},
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    params.insert(value)?;
+                }
+            },
+            serde_json::Value::Object(values) if values.is_empty() => {},
+            _ => anyhow::bail!("invalid parameters, expected a list of positional params"),
+        }
+        let result: serde_json::Value = self.backend.0.request(method, params).await?;
+        Ok(CallResponse {
+            result,
+            // The chain id never changes, other methods depends on the chain state
+            idempotent: method == "eth_chainId",
+        })
+    }
+
     #[allow(clippy::single_match_else, clippy::missing_errors_doc)]
     pub async fn faucet(
         &self,
@@ -247,11 +1058,19 @@ where
                 let chain_id = self.chain_id;
                 let wallet = Keypair::from_bytes(private_key)?;
                 let address: H160 = address.address().parse()?;
-                let nonce = self.nonce.load(Ordering::Relaxed);
+
+                // Re-sync the nonce with the node, the funding account may be shared by
+                // multiple clients
+                let pending_nonce =
+                    self.backend.get_transaction_count(wallet.address(), AtBlock::Pending).await?;
+                let nonce =
+                    self.nonce.fetch_max(pending_nonce, Ordering::SeqCst).max(pending_nonce);
                 let gas_price = if let Some(high_gas_price) = high_gas_price {
                     U256::from(high_gas_price)
                 } else {
-                    U256::from(500_000_000) // Default gas price
+                    // Default gas price, or the node's gas price if higher
+                    let gas_price = self.backend.gas_price().await?;
+                    std::cmp::max(U256::from(500_000_000), gas_price)
                 };
                 // Create a transaction request
                 let transaction_request = LegacyTransaction {
@@ -267,9 +1086,14 @@ where
                 let signature = wallet.sign_prehash(tx.sighash(), Some(chain_id))?;
                 let raw_tx = tx.encode(Some(&signature));
                 let tx_hash = self.backend.send_raw_transaction(raw_tx).await?;
+                // Increment only after successfully send the tx to avoid nonce gaps
+                self.nonce.fetch_max(nonce + 1, Ordering::SeqCst);
 
                 // Wait for the transaction to be mined
-                let receipt = self.backend.wait_for_transaction_receipt(tx_hash).await?;
+                let receipt = self
+                    .backend
+                    .wait_for_transaction_receipt(tx_hash, self.submit_timeout, self.stall_timeout)
+                    .await?;
 
                 // Check if the transaction was successful
                 if !matches!(receipt.status_code, Some(1)) {
@@ -288,12 +1112,7 @@ where
                     .context("no accounts found")?;
                 let address: H160 = address.address().parse()?;
 
-                let (max_fee_per_gas, max_priority_fee_per_gas) =
-                    if self.config().blockchain == "polygon" {
-                        self.backend.estimate_eip1559_fees::<PolygonFeeEstimatorConfig>().await?
-                    } else {
-                        self.backend.estimate_eip1559_fees::<DefaultFeeEstimatorConfig>().await?
-                    };
+                let (max_fee_per_gas, max_priority_fee_per_gas) = self.suggest_fees().await?;
                 let tx = CallRequest {
                     from: Some(coinbase),
                     to: Some(address),
@@ -310,7 +1129,10 @@ where
                 };
 
                 let tx_hash = self.backend.send_transaction(&tx).await?;
-                let receipt = self.backend.wait_for_transaction_receipt(tx_hash).await?;
+                let receipt = self
+                    .backend
+                    .wait_for_transaction_receipt(tx_hash, self.submit_timeout, self.stall_timeout)
+                    .await?;
                 if !matches!(receipt.status_code, Some(1)) {
                     anyhow::bail!("Transaction reverted: {tx_hash}");
                 }
@@ -324,14 +1146,35 @@ where
         &self,
         public_key: &PublicKey,
         options: &EthereumMetadataParams,
+    ) -> Result<EthereumMetadata> {
+        self.retry_policy.retry(|| self.metadata_once(public_key, options)).await
+    }
+
+    async fn metadata_once(
+        &self,
+        public_key: &PublicKey,
+        options: &EthereumMetadataParams,
     ) -> Result<EthereumMetadata> {
         let from: H160 = public_key.to_address(self.config().address_format).address().parse()?;
         let to = options.destination.map(H160);
-        let (max_fee_per_gas, max_priority_fee_per_gas) = if self.config().blockchain == "polygon" {
-            self.backend.estimate_eip1559_fees::<PolygonFeeEstimatorConfig>().await?
-        } else {
-            self.backend.estimate_eip1559_fees::<DefaultFeeEstimatorConfig>().await?
+        let Some(latest) = self.backend.block(AtBlock::Latest).await? else {
+            anyhow::bail!("latest block not found");
         };
+        if let Some(gas_cache) = self.gas_cache.as_deref() {
+            gas_cache.on_new_block(latest.header.number);
+        }
+        // Some chains rejects transactions below a minimum gas price
+        let min_gas_price = self.min_gas_price;
+        // Chains without base fee only accept transactions with a gas price, and can't estimate
+        // the EIP-1559 fees
+        let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) =
+            if latest.header.base_fee_per_gas.is_some() {
+                let (max_fee_per_gas, max_priority_fee_per_gas) = self.suggest_fees().await?;
+                (None, std::cmp::max(max_fee_per_gas, min_gas_price), max_priority_fee_per_gas)
+            } else {
+                let gas_price = std::cmp::max(self.backend.gas_price().await?, min_gas_price);
+                (Some(gas_price), gas_price, gas_price)
+            };
         let chain_id = self.backend.chain_id().await?;
 
         let nonce = if let Some(nonce) = options.nonce {
@@ -343,20 +1186,29 @@ where
             from: Some(from),
             to,
             gas_limit: None,
-            gas_price: None,
+            gas_price,
             value: Some(U256(options.amount)),
             data: Some(options.data.clone().into()),
             nonce: None,
             chain_id: None, // Astar doesn't support this field for eth_call
-            max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+            max_priority_fee_per_gas: gas_price.is_none().then_some(max_priority_fee_per_gas),
             access_list: AccessList::default(),
-            max_fee_per_gas: Some(max_fee_per_gas),
-            transaction_type: Some(2),
+            max_fee_per_gas: gas_price.is_none().then_some(max_fee_per_gas),
+            transaction_type: gas_price.is_none().then_some(2),
+        };
+        let gas_used = if options.auto_access_list {
+            let result = self.backend.create_access_list(&tx, AtBlock::Latest).await?;
+            tx.access_list = result.access_list;
+            Some(u64::try_from(result.gas_used).unwrap_or(u64::MAX))
+        } else {
+            None
         };
-        let gas_limit = if let Some(gas_limit) = options.gas_limit {
+        let gas_limit = if let Some(gas_limit) = options.gas_limit.or(self.fixed_gas_limit) {
             gas_limit
+        } else if let Some(gas_used) = gas_used {
+            gas_used
         } else {
-            let gas_limit = self.backend.estimate_gas(&tx, AtBlock::Latest).await?;
+            let gas_limit = self.estimate_gas(&tx, AtBlock::Latest).await?;
             u64::try_from(gas_limit).unwrap_or(u64::MAX)
         };
 
@@ -368,14 +1220,99 @@ where
             max_priority_fee_per_gas: max_priority_fee_per_gas.0,
             max_fee_per_gas: max_fee_per_gas.0,
             gas_limit,
+            min_gas_price: min_gas_price.0,
+            gas_price: gas_price.map(|gas_price| gas_price.0),
+            access_list: tx.access_list,
         })
     }
 
+    /// Suggests the `(max_fee_per_gas, max_priority_fee_per_gas)` of a new transaction from the
+    /// fee history of the recent blocks. Once the node reports `eth_feeHistory` is not found, the
+    /// `eth_gasPrice` or the fallback gas price is suggested with a [`FALLBACK_PRIORITY_FEE`].
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn suggest_fees(&self) -> Result<(U256, U256)> {
+        if !self.fee_history_unsupported.load(Ordering::Relaxed) {
+            let result = if self.config().blockchain == "polygon" {
+                self.backend.estimate_eip1559_fees::<PolygonFeeEstimatorConfig>().await
+            } else {
+                self.backend.estimate_eip1559_fees::<DefaultFeeEstimatorConfig>().await
+            };
+            let unsupported =
+                match result.as_ref().map_err(anyhow::Error::downcast_ref::<ClientError>) {
+                    Err(Some(ClientError::Call(error))) => {
+                        is_method_not_found(error.code(), error.message())
+                    },
+                    _ => false,
+                };
+            if !unsupported {
+                return result;
+            }
+            if !self.fee_history_unsupported.swap(true, Ordering::Relaxed) {
+                tracing::warn!("eth_feeHistory not supported, suggesting fees from eth_gasPrice");
+            }
+        }
+        let gas_price = match self.fallback_gas_price {
+            Some(gas_price) => gas_price,
+            None => self.backend.gas_price().await?,
+        };
+        let max_priority_fee_per_gas = std::cmp::min(U256::from(FALLBACK_PRIORITY_FEE), gas_price);
+        Ok((gas_price, max_priority_fee_per_gas))
+    }
+
+    /// Estimates the gas of `tx`, fails with [`WouldRevert`] if the transaction reverts.
+    async fn estimate_gas(&self, tx: &CallRequest, at: AtBlock) -> Result<U256> {
+        let error = match self.estimate_gas_cached(tx, at).await {
+            Ok(gas) => return Ok(gas),
+            Err(error) => error,
+        };
+        if !is_revert_error(&error) {
+            return Err(error);
+        }
+        // Replay the call to retrieve the revert reason
+        match self.backend.call(tx, at).await {
+            Ok(exit_reason @ ExitReason::Revert(_)) => {
+                let reason = exit_reason.revert_msg().map(ToString::to_string);
+                Err(WouldRevert { reason }.into())
+            },
+            _ => Err(error),
+        }
+    }
+
+    /// Estimates the gas of `tx`, using the cached result if the gas estimate cache is enabled.
+    async fn estimate_gas_cached(&self, tx: &CallRequest, at: AtBlock) -> Result<U256> {
+        let Some(gas_cache) = self.gas_cache.as_deref() else {
+            return Ok(self.backend.estimate_gas(tx, at).await?);
+        };
+        let key = GasEstimateKey {
+            from: tx.from,
+            to: tx.to,
+            data: tx.data.clone().unwrap_or_default(),
+            value: tx.value.unwrap_or_default(),
+            block: at,
+        };
+        let gas = gas_cache
+            .get_or_try_insert_with(key, || self.backend.estimate_gas(tx, at))
+            .await?;
+        Ok(gas)
+    }
+
     #[allow(clippy::missing_errors_doc)]
     pub async fn submit(&self, transaction: &[u8]) -> Result<SubmitResult> {
+        self.submit_with_broadcast_callback(transaction, |_| {}).await
+    }
+
+    /// Same as [`Self::submit`], `on_broadcast` is called with the transaction hash once the node
+    /// accepted the transaction, before waiting for its receipt. It isn't called when the
+    /// transaction is already included.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn submit_with_broadcast_callback(
+        &self,
+        transaction: &[u8],
+        on_broadcast: impl FnOnce(H256) + Send,
+    ) -> Result<SubmitResult> {
         // Check if the transaction is valid and signed
         let rlp = rosetta_config_ethereum::ext::types::ext::rlp::Rlp::new(transaction);
-        let (tx_hash, call_request) = match TypedTransaction::rlp_decode(&rlp, true) {
+        let (tx_hash, gas_price, call_request) = match TypedTransaction::rlp_decode(&rlp, true) {
             Ok((tx, Some(signature))) => {
                 let tx_hash = tx.compute_tx_hash(&signature);
                 let sender = DefaultCrypto::secp256k1_ecdsa_recover(&signature, tx.sighash())?;
@@ -394,7 +1331,7 @@ where
                     max_fee_per_gas: None,
                     transaction_type: None,
                 };
-                (tx_hash, call_request)
+                (tx_hash, tx.gas_price(), call_request)
             },
             Ok((_, None)) => {
                 anyhow::bail!("Invalid Transaction: not signed");
@@ -403,7 +1340,8 @@ where
         };
 
         // Check if the transaction is already included in a block
-        if let Some(receipt) = self.backend.transaction_receipt(tx_hash).await? {
+        if let Some(mut receipt) = self.backend.transaction_receipt(tx_hash).await? {
+            self.fill_effective_gas_price(&mut receipt, gas_price).await;
             return Ok(self.backend.get_call_result(receipt, call_request).await);
         }
 
@@ -416,21 +1354,74 @@ where
                 anyhow::bail!("Transaction hash mismatch, expect {tx_hash}, got {actual_hash}");
             }
         }
+        on_broadcast(tx_hash);
 
         // Wait for the transaction receipt
-        let Ok(receipt) = self.backend.wait_for_transaction_receipt(tx_hash).await else {
-            tracing::warn!("Transaction receipt timeout: {tx_hash:?}");
-            return Ok(SubmitResult::Timeout { tx_hash });
+        let mut receipt = match self
+            .backend
+            .wait_for_transaction_receipt(tx_hash, self.submit_timeout, self.stall_timeout)
+            .await
+        {
+            Ok(receipt) => receipt,
+            Err(error) if error.is::<ChainStalled>() => return Err(error),
+            Err(_) => {
+                tracing::warn!("Transaction receipt timeout: {tx_hash:?}");
+                return Ok(SubmitResult::Timeout { tx_hash });
+            },
         };
         tracing::debug!(
             "Transaction included in a block: {tx_hash:?}, status: {:?}",
             receipt.status_code
         );
+        self.fill_effective_gas_price(&mut receipt, gas_price).await;
         Ok(self.backend.get_call_result(receipt, call_request).await)
     }
 
-    #[allow(clippy::too_many_lines, clippy::missing_errors_doc)]
+    /// Some nodes omit the `effectiveGasPrice` from the receipt, in this case it is computed from
+    /// the transaction `gas_price` and the base fee of the block which included it.
+    ///
+    /// The transaction is already included, so failing to fetch the block leaves the price unset
+    /// instead of failing the submission.
+    async fn fill_effective_gas_price(
+        &self,
+        receipt: &mut TransactionReceipt,
+        gas_price: GasPrice,
+    ) {
+        if receipt.effective_gas_price.is_some() {
+            return;
+        }
+        receipt.effective_gas_price = match gas_price {
+            GasPrice::Legacy(gas_price) => Some(gas_price),
+            GasPrice::Eip1559 { max_priority_fee_per_gas, max_fee_per_gas } => {
+                match self.backend.block(AtBlock::from(receipt.block_hash)).await {
+                    Ok(block) => {
+                        block.and_then(|block| block.header.base_fee_per_gas).map(|base_fee| {
+                            let gas_price =
+                                U256::from(base_fee).saturating_add(max_priority_fee_per_gas);
+                            std::cmp::min(gas_price, max_fee_per_gas)
+                        })
+                    },
+                    Err(error) => {
+                        tracing::warn!(
+                            "failed to compute the effective gas price of {:?}: {error:?}",
+                            receipt.transaction_hash
+                        );
+                        None
+                    },
+                }
+            },
+        };
+    }
+
+    /// Executes the query `req`, the query is retried according to the retry policy when it fails
+    /// with a transient transport error, see [`Self::with_retry_policy`].
+    #[allow(clippy::missing_errors_doc)]
     pub async fn call(&self, req: &EthQuery) -> Result<EthQueryResult> {
+        self.retry_policy.retry(|| self.call_once(req)).await
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn call_once(&self, req: &EthQuery) -> Result<EthQueryResult> {
         let result = match req {
             EthQuery::GetBalance(GetBalance { address, block }) => {
                 let balance = self.backend.get_balance(*address, *block).await?;
@@ -444,6 +1435,10 @@ where
                 let value = self.backend.storage(*address, *at, *block).await?;
                 EthQueryResult::GetStorageAt(value)
             },
+            EthQuery::GetCode(GetCode { address, block }) => {
+                let code = self.backend.get_code(*address, *block).await?;
+                EthQueryResult::GetCode(code)
+            },
             EthQuery::GetTransactionReceipt(GetTransactionReceipt { tx_hash }) => {
                 let receipt = self.backend.transaction_receipt(*tx_hash).await?;
                 EthQueryResult::GetTransactionReceipt(receipt)
@@ -470,6 +1465,23 @@ where
                 };
                 EthQueryResult::CallContract(result)
             },
+            EthQuery::EstimateGas(EstimateGas { from, to, data, value, block }) => {
+                let call = CallRequest {
+                    from: *from,
+                    to: Some(*to),
+                    data: Some(Bytes::from_iter(data)),
+                    value: Some(*value),
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                    chain_id: None,
+                    max_priority_fee_per_gas: None,
+                    access_list: AccessList::default(),
+                    max_fee_per_gas: None,
+                    transaction_type: None,
+                };
+                EthQueryResult::EstimateGas(self.estimate_gas(&call, *block).await?)
+            },
             EthQuery::GetProof(GetProof { account, storage_keys, block }) => {
                 let proof_data = self.backend.get_proof(*account, storage_keys, *block).await?;
 
@@ -556,12 +1568,22 @@ where
             block_provider,
             self.backend.clone(),
             State::new(best_finalized_block),
-        );
+        )
+        .with_gas_cache(self.gas_cache.clone());
         match stream.next().await {
             Some(ClientEvent::Close(msg)) => anyhow::bail!(msg),
             None => anyhow::bail!("Failed to open the event stream"),
             Some(_) => {},
         }
-        Ok(SharedStream::new(stream, 100))
+        let pause = stream.pause_handle();
+        Ok(SharedStream::new(stream, 100).with_pause_handle(pause))
+    }
+}
+
+impl EthereumClient<DefaultClient> {
+    /// Returns the status of the websocket connection, without making any request.
+    #[must_use]
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.backend.0.connection_status()
     }
 }
@@ -9,7 +9,14 @@ use rosetta_ethereum_backend::{
     jsonrpsee::core::client::{error::Error as RpcError, Subscription},
     EthereumPubSub,
 };
-use std::{pin::Pin, task::Poll};
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Poll, Waker},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NewBlock {
@@ -43,6 +50,54 @@ impl NewBlock {
     }
 }
 
+/// Pauses and resumes an [`EthereumEventStream`], can be shared with the consumers of the
+/// stream.
+#[derive(Debug, Clone, Default)]
+pub struct PauseHandle {
+    inner: Arc<PauseState>,
+}
+
+#[derive(Debug, Default)]
+struct PauseState {
+    paused: AtomicBool,
+    /// Waker of the paused stream
+    waker: Mutex<Option<Waker>>,
+}
+
+impl PauseHandle {
+    /// Pause the stream, the upstream subscriptions are no longer polled until [`Self::resume`]
+    /// is called.
+    pub fn pause(&self) {
+        self.inner.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resume the stream, the blocks received while paused are emitted first.
+    pub fn resume(&self) {
+        self.inner.paused.store(false, Ordering::SeqCst);
+        let waker = self.inner.waker.lock().ok().and_then(|mut waker| waker.take());
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if the stream is paused, and registers the waker to be notified on resume.
+    fn poll_paused(&self, cx: &std::task::Context<'_>) -> bool {
+        if !self.is_paused() {
+            return false;
+        }
+        if let Ok(mut waker) = self.inner.waker.lock() {
+            *waker = Some(cx.waker().clone());
+        }
+        // Resumed while the waker was registered
+        self.is_paused()
+    }
+}
+
 impl From<NewBlock> for SealedBlock<H256> {
     fn from(new_block: NewBlock) -> Self {
         match new_block {
@@ -66,6 +121,14 @@ where
     new_head_stream: Option<NewHeadsStream<RPC>>,
     /// Finalized blocks stream
     finalized_stream: Option<FinalizedBlockStream<P>>,
+    /// When paused, neither the new heads nor the finalized blocks are polled
+    pause: PauseHandle,
+    /// `true` if the stream was paused since the last new head
+    was_paused: bool,
+    /// Number of the last new head emitted
+    last_head: Option<u64>,
+    /// Reason why the stream was closed, if any
+    close_reason: Option<String>,
 }
 
 impl<P, RPC> EthereumEventStream<P, RPC>
@@ -83,6 +146,60 @@ where
         Self {
             new_head_stream: Some(NewHeadsStream::new(client)),
             finalized_stream: Some(FinalizedBlockStream::new(provider)),
+            pause: PauseHandle::default(),
+            was_paused: false,
+            last_head: None,
+            close_reason: None,
+        }
+    }
+
+    /// Pause the stream, see [`PauseHandle::pause`]. The new heads received meanwhile are
+    /// buffered by the subscription, if some are dropped the stream is closed once resumed.
+    pub fn pause(&self) {
+        self.pause.pause();
+    }
+
+    /// Resume the stream, see [`PauseHandle::resume`].
+    pub fn resume(&self) {
+        self.pause.resume();
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Returns a handle to pause and resume the stream.
+    #[must_use]
+    pub fn pause_handle(&self) -> PauseHandle {
+        self.pause.clone()
+    }
+
+    /// Returns the reason why the stream was closed, if any.
+    pub fn close_reason(&self) -> Option<&str> {
+        self.close_reason.as_deref()
+    }
+
+    /// Checks the new heads are consecutive after a pause, closes the stream if blocks were
+    /// dropped meanwhile.
+    fn on_new_head(&mut self, number: u64) -> bool {
+        let was_paused = std::mem::take(&mut self.was_paused);
+        match self.last_head {
+            Some(last_head) if was_paused && number > last_head + 1 => {
+                let reason = format!(
+                    "new heads {}..{number} were dropped while the event stream was paused",
+                    last_head + 1
+                );
+                tracing::warn!("{reason}");
+                self.close_reason = Some(reason);
+                self.new_head_stream = None;
+                self.finalized_stream = None;
+                false
+            },
+            _ => {
+                self.last_head = Some(number);
+                true
+            },
         }
     }
 }
@@ -105,6 +222,16 @@ where
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         // Check if the stream is close
+        if self.finalized_stream.is_none() {
+            return Poll::Ready(None);
+        }
+
+        // Stop polling the upstream while paused
+        if self.pause.poll_paused(cx) {
+            self.was_paused = true;
+            return Poll::Pending;
+        }
+
         let Some(mut finalized_stream) = self.finalized_stream.take() else {
             return Poll::Ready(None);
         };
@@ -133,6 +260,9 @@ where
         match new_head_stream.poll_next_unpin(cx) {
             Poll::Ready(Some(block)) => {
                 self.new_head_stream = Some(new_head_stream);
+                if !self.on_new_head(block.header().number()) {
+                    return Poll::Ready(None);
+                }
                 Poll::Ready(Some(NewBlock::new_head(block)))
             },
             Poll::Ready(None) => {
@@ -146,3 +276,111 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block_provider::RpcBlockProvider,
+        client::BlockFinalityStrategy,
+        tests::{client_from_config, ws_client},
+    };
+    use rosetta_core::{types::BlockIdentifier, BlockOrIdentifier, ClientEvent};
+    use rosetta_docker::{run_test, Env};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn pause_resume_doesnt_skip_blocks() -> anyhow::Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("event-stream-pause", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env).backend;
+            let provider = RpcBlockProvider::new(
+                client.clone(),
+                Duration::from_secs(1),
+                BlockFinalityStrategy::Finalized,
+            )
+            .await
+            .unwrap();
+            let mut stream = EthereumEventStream::new(client, provider);
+
+            let mut last_head: Option<u64> = None;
+            let mut assert_next_head = |block: &NewBlock| {
+                let NewBlock::NewHead(block) = block else {
+                    return;
+                };
+                let number = block.header().number();
+                if let Some(last_number) = last_head {
+                    assert_eq!(number, last_number + 1, "new head skipped");
+                }
+                last_head = Some(number);
+            };
+
+            for _ in 0..5 {
+                let block = stream.next().await.unwrap();
+                assert_next_head(&block);
+            }
+
+            // Nothing is emitted while paused, the dev node mines a block every second
+            stream.pause();
+            assert!(stream.is_paused());
+            let result = tokio::time::timeout(Duration::from_secs(5), stream.next()).await;
+            assert!(result.is_err());
+
+            stream.resume();
+            for _ in 0..10 {
+                let block = stream.next().await.unwrap();
+                assert_next_head(&block);
+            }
+            assert!(stream.close_reason().is_none());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pause_resume_the_listen_stream() -> anyhow::Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("event-stream-listen-pause", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            let mut stream = client.listen().await.unwrap();
+
+            let mut last_head: Option<u64> = None;
+            let mut next_head = |event: ClientEvent<BlockIdentifier, _>| {
+                let block = match event {
+                    ClientEvent::NewHead(BlockOrIdentifier::Identifier(block)) => block,
+                    ClientEvent::Close(reason) => panic!("stream closed: {reason}"),
+                    _ => return,
+                };
+                if let Some(last_number) = last_head {
+                    assert_eq!(block.index, last_number + 1, "new head skipped");
+                }
+                last_head = Some(block.index);
+            };
+
+            for _ in 0..5 {
+                next_head(stream.next().await.unwrap());
+            }
+
+            // The upstream isn't polled while paused
+            stream.pause();
+            assert!(stream.is_paused());
+            let result = tokio::time::timeout(Duration::from_secs(5), stream.next()).await;
+            assert!(result.is_err());
+
+            stream.resume();
+            for _ in 0..10 {
+                next_head(stream.next().await.unwrap());
+            }
+        })
+        .await;
+        Ok(())
+    }
+}
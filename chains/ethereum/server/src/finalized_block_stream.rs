@@ -236,6 +236,7 @@ mod tests {
     use super::*;
     use crate::{
         block_provider::RpcBlockProvider, client::BlockFinalityStrategy, MaybeWsEthereumClient,
+        Transport,
     };
     use futures_util::StreamExt;
     use rosetta_core::BlockchainConfig;
@@ -245,7 +246,7 @@ mod tests {
         config: BlockchainConfig,
     ) -> anyhow::Result<MaybeWsEthereumClient> {
         let url = config.node_uri.to_string();
-        MaybeWsEthereumClient::from_config(config, url.as_str(), None).await
+        MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto).await
     }
 
     #[tokio::test]
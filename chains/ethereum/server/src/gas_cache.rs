@@ -0,0 +1,200 @@
+use hashbrown::HashMap;
+use rosetta_config_ethereum::ext::types::{AtBlock, Bytes, H160, U256};
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Default time-to-live of a cached gas estimation.
+pub const DEFAULT_GAS_ESTIMATE_TTL: Duration = Duration::from_secs(6);
+
+/// Key used to identify an `eth_estimateGas` request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GasEstimateKey {
+    pub from: Option<H160>,
+    pub to: Option<H160>,
+    pub data: Bytes,
+    pub value: U256,
+    pub block: AtBlock,
+}
+
+struct Inner {
+    /// Highest block number observed, used to invalidate the cache when a new block arrives.
+    best_block: u64,
+    entries: HashMap<GasEstimateKey, (Instant, U256)>,
+}
+
+/// Small TTL cache for `eth_estimateGas` results, invalidated on every new block.
+pub struct GasEstimateCache {
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl GasEstimateCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, inner: Mutex::new(Inner { best_block: 0, entries: HashMap::new() }) }
+    }
+
+    /// Notify the cache about a new block, all cached entries are discarded if `number` is
+    /// higher than the best block seen so far.
+    pub fn on_new_block(&self, number: u64) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if number > inner.best_block {
+            inner.best_block = number;
+            inner.entries.clear();
+        }
+    }
+
+    /// Returns the cached estimate for `key`, if any and not expired.
+    pub fn get(&self, key: &GasEstimateKey) -> Option<U256> {
+        let mut inner = self.inner.lock().ok()?;
+        match inner.entries.get(key) {
+            Some((created_at, gas)) if created_at.elapsed() < self.ttl => Some(*gas),
+            Some(_) => {
+                inner.entries.remove(key);
+                None
+            },
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: GasEstimateKey, gas: U256) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        // Drop expired entries, so the cache doesn't grow unbounded
+        let ttl = self.ttl;
+        inner.entries.retain(|_, (created_at, _)| created_at.elapsed() < ttl);
+        inner.entries.insert(key, (Instant::now(), gas));
+    }
+
+    /// Returns the cached estimate for `key`, or computes it using `estimate` and caches it.
+    pub async fn get_or_try_insert_with<F, Fut, E>(
+        &self,
+        key: GasEstimateKey,
+        estimate: F,
+    ) -> Result<U256, E>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<U256, E>> + Send,
+    {
+        if let Some(gas) = self.get(&key) {
+            return Ok(gas);
+        }
+        let gas = estimate().await?;
+        self.insert(key, gas);
+        Ok(gas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{client::EthereumClient, tests::mock_genesis};
+    use rosetta_config_ethereum::EthereumMetadataParams;
+    use rosetta_core::crypto::{Algorithm, SecretKey};
+    use rosetta_ethereum_backend::jsonrpsee::core::{
+        client::{BatchResponse, ClientT},
+        params::BatchRequestBuilder,
+        traits::ToRpcParams,
+        ClientError,
+    };
+    use serde::de::DeserializeOwned;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// JSON-RPC client of a node at the genesis block, counting the `eth_estimateGas` requests.
+    #[derive(Clone, Default)]
+    struct CountingClient {
+        estimates: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl ClientT for CountingClient {
+        async fn notification<Params>(
+            &self,
+            method: &str,
+            _params: Params,
+        ) -> Result<(), ClientError>
+        where
+            Params: ToRpcParams + Send,
+        {
+            Err(ClientError::Custom(format!("unexpected notification {method}")))
+        }
+
+        async fn request<R, Params>(&self, method: &str, _params: Params) -> Result<R, ClientError>
+        where
+            R: DeserializeOwned,
+            Params: ToRpcParams + Send,
+        {
+            let result = match method {
+                "eth_chainId" => serde_json::json!("0x539"),
+                "eth_getBlockByNumber" => {
+                    let mut block = mock_genesis();
+                    block.as_object_mut().unwrap().remove("baseFeePerGas");
+                    block
+                },
+                "eth_gasPrice" => serde_json::json!("0x3b9aca00"),
+                "eth_estimateGas" => {
+                    self.estimates.fetch_add(1, Ordering::SeqCst);
+                    serde_json::json!("0x5208")
+                },
+                method => return Err(ClientError::Custom(format!("unexpected call {method}"))),
+            };
+            serde_json::from_value(result).map_err(ClientError::ParseError)
+        }
+
+        async fn batch_request<'a, R>(
+            &self,
+            _batch: BatchRequestBuilder<'a>,
+        ) -> Result<BatchResponse<'a, R>, ClientError>
+        where
+            R: DeserializeOwned + std::fmt::Debug + 'a,
+        {
+            Err(ClientError::Custom("unexpected batch request".into()))
+        }
+    }
+
+    /// Requests the metadata of the same transaction twice, returns the number of gas estimates
+    /// sent to the node.
+    async fn estimates_of_identical_metadata(ttl: Duration) -> usize {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let rpc_client = CountingClient::default();
+        let client = EthereumClient::new(config, rpc_client.clone(), None)
+            .await
+            .unwrap()
+            .with_gas_estimate_cache(Some(ttl));
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let params = EthereumMetadataParams {
+            nonce: Some(0),
+            gas_limit: None,
+            destination: Some([0x01; 20]),
+            amount: [0; 4],
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+            auto_access_list: false,
+        };
+        for _ in 0..2 {
+            let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+            assert_eq!(metadata.gas_limit, 21_000);
+        }
+        rpc_client.estimates.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn same_estimate_within_ttl_hits_rpc_once() {
+        assert_eq!(estimates_of_identical_metadata(Duration::from_secs(60)).await, 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refreshed() {
+        assert_eq!(estimates_of_identical_metadata(Duration::ZERO).await, 2);
+    }
+}
@@ -1,15 +1,25 @@
 use anyhow::Result;
-pub use client::{BlockStreamType, EthereumClient};
+pub use client::{
+    BlockStreamType, EthereumClient, FALLBACK_PRIORITY_FEE, INCLUSION_ESTIMATE_BLOCKS,
+    LOGS_CHUNK_SIZE,
+};
+use futures_util::StreamExt;
+use rosetta_config_ethereum::ext::types::H256;
 pub use rosetta_config_ethereum::{
     EthereumMetadata, EthereumMetadataParams, Event, Query as EthQuery, QueryItem,
     QueryResult as EthQueryResult, SubmitResult, Subscription,
 };
+pub use rosetta_core::types::{
+    BlockTransaction, OPERATION_STATUS_FAILURE, OPERATION_STATUS_SUCCESS,
+};
 use rosetta_core::{
     crypto::{address::Address, PublicKey},
-    types::{BlockIdentifier, PartialBlockIdentifier},
-    BlockchainClient, BlockchainConfig,
+    types::{BlockIdentifier, CallRequest, CallResponse, PartialBlockIdentifier, Peer},
+    BlockOrIdentifier, BlockchainClient, BlockchainConfig, ClientEvent, InclusionEstimate,
 };
+pub use rosetta_server::ws::ConnectionStatus;
 use rosetta_server::ws::{default_client, default_http_client, DefaultClient, HttpClient};
+use std::time::Duration;
 use url::Url;
 
 mod block_provider;
@@ -17,15 +27,24 @@ mod block_stream;
 mod client;
 mod event_stream;
 mod finalized_block_stream;
+mod gas_cache;
 mod log_filter;
+mod log_scan;
 mod multi_block;
 mod new_heads;
 mod proof;
+mod retry;
 mod shared_stream;
 mod state;
 mod utils;
 
-pub use event_stream::EthereumEventStream;
+pub use event_stream::{EthereumEventStream, PauseHandle};
+pub use gas_cache::DEFAULT_GAS_ESTIMATE_TTL;
+pub use retry::{RetryPolicy, DEFAULT_RETRY_ATTEMPTS, DEFAULT_RETRY_BASE_DELAY};
+pub use utils::{
+    ChainStalled, CodeSizeExceeded, WouldRevert, DEFAULT_STALL_TIMEOUT, DEFAULT_SUBMIT_TIMEOUT,
+    MAX_CODE_SIZE,
+};
 
 pub mod config {
     pub use rosetta_config_ethereum::*;
@@ -42,6 +61,19 @@ pub mod ext {
     pub use rosetta_ethereum_backend as backend;
 }
 
+/// Transport used by [`MaybeWsEthereumClient`] to connect to the node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// Connect using HTTP, `ws` and `wss` urls are connected as `http` and `https`.
+    Http,
+    /// Connect using websockets, `http` and `https` urls are connected as `ws` and `wss`. Useful
+    /// for providers which serve both transports on the same endpoint.
+    Ws,
+    /// Detect the transport from the url scheme.
+    #[default]
+    Auto,
+}
+
 #[derive(Clone)]
 pub enum MaybeWsEthereumClient {
     Http(EthereumClient<HttpClient>),
@@ -69,10 +101,10 @@ impl MaybeWsEthereumClient {
             "base" => rosetta_config_ethereum::base_config(network)?,
             blockchain => anyhow::bail!("unsupported blockchain: {blockchain}"),
         };
-        Self::from_config(config, addr, private_key).await
+        Self::from_config(config, addr, private_key, Transport::Auto).await
     }
 
-    /// Creates a new ethereum client from `config` and `addr`
+    /// Creates a new ethereum client from `config` and `addr`, connected using `transport`.
     ///
     /// # Errors
     /// Will return `Err` when the network is invalid, or when the provided `addr` is unreacheable.
@@ -80,8 +112,20 @@ impl MaybeWsEthereumClient {
         config: BlockchainConfig,
         addr: S,
         private_key: Option<[u8; 32]>,
+        transport: Transport,
     ) -> Result<Self> {
-        let uri = Url::parse(addr.as_ref())?;
+        let mut uri = Url::parse(addr.as_ref())?;
+        let scheme = match (transport, uri.scheme()) {
+            (Transport::Ws, "http") => Some("ws"),
+            (Transport::Ws, "https") => Some("wss"),
+            (Transport::Http, "ws") => Some("http"),
+            (Transport::Http, "wss") => Some("https"),
+            _ => None,
+        };
+        if let Some(scheme) = scheme {
+            uri.set_scheme(scheme)
+                .map_err(|()| anyhow::anyhow!("cannot connect to {uri} using {scheme}"))?;
+        }
         if uri.scheme() == "ws" || uri.scheme() == "wss" {
             tracing::trace!("Initializing Ethereum client with Websocket at {uri}");
             let client = default_client(uri.as_str(), None).await?;
@@ -108,6 +152,127 @@ impl MaybeWsEthereumClient {
         let client = EthereumClient::new(config, client, private_key).await?;
         Ok(Self::Ws(client))
     }
+
+    /// Sets how the queries are retried on transient transport errors, see
+    /// [`EthereumClient::with_retry_policy`].
+    #[must_use]
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        match self {
+            Self::Http(http_client) => Self::Http(http_client.with_retry_policy(retry_policy)),
+            Self::Ws(ws_client) => Self::Ws(ws_client.with_retry_policy(retry_policy)),
+        }
+    }
+
+    /// Enables caching of `eth_estimateGas` results, see
+    /// [`EthereumClient::with_gas_estimate_cache`].
+    #[must_use]
+    pub fn with_gas_estimate_cache(self, ttl: Option<Duration>) -> Self {
+        match self {
+            Self::Http(http_client) => Self::Http(http_client.with_gas_estimate_cache(ttl)),
+            Self::Ws(ws_client) => Self::Ws(ws_client.with_gas_estimate_cache(ttl)),
+        }
+    }
+
+    /// Handles a Rosetta `/call` request, see [`EthereumClient::rosetta_call`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the method is not supported, or the json-rpc call fails.
+    pub async fn rosetta_call(&self, request: &CallRequest) -> Result<CallResponse> {
+        match self {
+            Self::Http(http_client) => http_client.rosetta_call(request).await,
+            Self::Ws(ws_client) => ws_client.rosetta_call(request).await,
+        }
+    }
+
+    /// Returns all transactions in the block `at` with their operations, see
+    /// [`EthereumClient::block_transactions`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the block doesn't exist, or its receipts can't be fetched.
+    pub async fn block_transactions(&self, at: config::AtBlock) -> Result<Vec<BlockTransaction>> {
+        match self {
+            Self::Http(http_client) => http_client.block_transactions(at).await,
+            Self::Ws(ws_client) => ws_client.block_transactions(at).await,
+        }
+    }
+
+    /// Returns the status of the websocket connection, see
+    /// [`EthereumClient::connection_status`]. Http clients don't keep a connection open and
+    /// are always reported as [`ConnectionStatus::Connected`].
+    #[must_use]
+    pub fn connection_status(&self) -> ConnectionStatus {
+        match self {
+            Self::Http(_) => ConnectionStatus::Connected,
+            Self::Ws(ws_client) => ws_client.connection_status(),
+        }
+    }
+
+    /// Verifies the node serves the configured chain and is synced, see
+    /// [`EthereumClient::preflight`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the node is unreachable, serves another chain, or isn't synced.
+    pub async fn preflight(&self, max_blocks_behind: u64) -> Result<()> {
+        match self {
+            Self::Http(http_client) => http_client.preflight(max_blocks_behind).await,
+            Self::Ws(ws_client) => ws_client.preflight(max_blocks_behind).await,
+        }
+    }
+
+    /// Submits the signed `transaction`, `on_broadcast` is called once the node accepted it, see
+    /// [`EthereumClient::submit_with_broadcast_callback`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the transaction is invalid or rejected by the node.
+    pub async fn submit_with_broadcast_callback(
+        &self,
+        transaction: &[u8],
+        on_broadcast: impl FnOnce(H256) + Send,
+    ) -> Result<SubmitResult> {
+        match self {
+            Self::Http(http_client) => {
+                http_client.submit_with_broadcast_callback(transaction, on_broadcast).await
+            },
+            Self::Ws(ws_client) => {
+                ws_client.submit_with_broadcast_callback(transaction, on_broadcast).await
+            },
+        }
+    }
+
+    /// Returns the size of the code `init_code` would deploy, see
+    /// [`EthereumClient::deployed_code_size`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the init code reverts or the node doesn't support state overrides.
+    pub async fn deployed_code_size(&self, from: [u8; 20], init_code: &[u8]) -> Result<usize> {
+        match self {
+            Self::Http(http_client) => http_client.deployed_code_size(from, init_code).await,
+            Self::Ws(ws_client) => ws_client.deployed_code_size(from, init_code).await,
+        }
+    }
+
+    /// Estimates whether a transaction is included before the `deadline` timestamp, see
+    /// [`EthereumClient::inclusion_estimate`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the recent blocks or the transaction pool cannot be read.
+    pub async fn inclusion_estimate(&self, deadline: u64) -> Result<InclusionEstimate> {
+        match self {
+            Self::Http(http_client) => http_client.inclusion_estimate(deadline).await,
+            Self::Ws(ws_client) => ws_client.inclusion_estimate(deadline).await,
+        }
+    }
+
+    /// Returns the peers the node is connected to, see [`EthereumClient::peers`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the node has peers but doesn't expose the admin api.
+    pub async fn peers(&self) -> Result<Vec<Peer>> {
+        match self {
+            Self::Http(http_client) => http_client.peers().await,
+            Self::Ws(ws_client) => ws_client.peers().await,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -176,6 +341,13 @@ impl BlockchainClient for MaybeWsEthereumClient {
         }
     }
 
+    async fn balances(&self, addresses: &[Address], block: &Self::AtBlock) -> Result<Vec<u128>> {
+        match self {
+            Self::Http(http_client) => http_client.balances(addresses, block).await,
+            Self::Ws(ws_client) => ws_client.balances(addresses, block).await,
+        }
+    }
+
     async fn faucet(
         &self,
         address: &Address,
@@ -229,6 +401,39 @@ impl BlockchainClient for MaybeWsEthereumClient {
             Self::Ws(ws_client) => ws_client.subscribe(sub),
         }
     }
+
+    /// Websocket clients wait for the block using the new heads subscription, instead of polling.
+    async fn wait_for_block(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<Self::BlockIdentifier> {
+        let Self::Ws(ws_client) = self else {
+            return rosetta_core::poll_for_block(self, height, timeout).await;
+        };
+        let wait = async {
+            let mut stream = ws_client.listen().await?;
+            let current = self.current_block().await?;
+            if current.index >= height {
+                return Ok(current);
+            }
+            while let Some(event) = stream.next().await {
+                let block = match event {
+                    ClientEvent::NewHead(BlockOrIdentifier::Identifier(block)) => block,
+                    ClientEvent::NewHead(BlockOrIdentifier::Block(block)) => block.block_identifier,
+                    ClientEvent::Close(reason) => {
+                        anyhow::bail!("new heads stream closed: {reason}")
+                    },
+                    _ => continue,
+                };
+                if block.index >= height {
+                    return Ok(block);
+                }
+            }
+            anyhow::bail!("new heads stream closed")
+        };
+        rosetta_core::with_block_timeout(height, timeout, wait).await
+    }
 }
 
 #[allow(clippy::ignored_unit_patterns, clippy::pub_underscore_fields)]
@@ -237,10 +442,18 @@ mod tests {
     use super::*;
     use alloy_sol_types::{sol, SolCall};
     use ethers_solc::{artifacts::Source, CompilerInput, EvmVersion, Solc};
-    use rosetta_config_ethereum::{ext::types::H256, query::GetLogs, AtBlock, CallResult};
+    use rosetta_client::FaucetOptions;
+    use rosetta_config_ethereum::{
+        ext::types::H256,
+        query::{GetBlock, GetLogs},
+        AtBlock, CallResult, FilterBlockOption,
+    };
     use rosetta_docker::{run_test, Env};
     use sha3::Digest;
-    use std::{collections::BTreeMap, path::Path};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::Path,
+    };
 
     sol! {
         interface TestContract {
@@ -249,177 +462,3167 @@ mod tests {
 
             function identity(bool a) external view returns (bool);
         }
+
+        interface RevertContract {
+            function fail() external;
+        }
+
+        interface CounterContract {
+            function counter() external view returns (uint256);
+            function increment() external payable;
+            function fail() external;
+        }
+
+        interface Proxy {
+            function upgradeTo(address implementation) external;
+        }
+
+        interface StorageContract {
+            function touch(address other) external;
+        }
+
+        interface PermitToken {
+            function DOMAIN_SEPARATOR() external view returns (bytes32);
+            function allowance(address owner, address spender) external view returns (uint256);
+        }
     }
 
     pub async fn client_from_config(config: BlockchainConfig) -> Result<MaybeWsEthereumClient> {
         let url = config.node_uri.to_string();
-        MaybeWsEthereumClient::from_config(config, url.as_str(), None).await
+        MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto).await
     }
 
-    #[tokio::test]
-    async fn test_network_status() -> Result<()> {
-        let config = rosetta_config_ethereum::config("dev")?;
-        rosetta_docker::tests::network_status::<MaybeWsEthereumClient, _, _>(
-            client_from_config,
-            config,
-        )
-        .await
+    /// Websocket client of the node started by `env`.
+    pub fn ws_client(env: &Env<MaybeWsEthereumClient>) -> EthereumClient<DefaultClient> {
+        match env.node().as_ref() {
+            MaybeWsEthereumClient::Ws(client) => client.clone(),
+            MaybeWsEthereumClient::Http(_) => panic!("expected a websocket client"),
+        }
     }
 
-    #[tokio::test]
-    async fn test_account() -> Result<()> {
-        let config = rosetta_config_ethereum::config("dev")?;
-        rosetta_docker::tests::account::<MaybeWsEthereumClient, _, _>(client_from_config, config)
-            .await
+    /// Genesis block served by the mock nodes.
+    pub(crate) fn mock_genesis() -> serde_json::Value {
+        let zero = format!("0x{}", "0".repeat(64));
+        serde_json::json!({
+            "hash": format!("0x{}", "11".repeat(32)),
+            "parentHash": zero,
+            "stateRoot": zero,
+            "transactionsRoot": zero,
+            "receiptsRoot": zero,
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "difficulty": "0x0",
+            "number": "0x0",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x0",
+            "timestamp": "0x0",
+            "nonce": "0x0000000000000000",
+            "transactions": [],
+            "uncles": [],
+            "baseFeePerGas": "0x7",
+        })
     }
 
-    #[tokio::test]
-    async fn test_construction() -> Result<()> {
-        let config = rosetta_config_ethereum::config("dev")?;
-        rosetta_docker::tests::construction::<MaybeWsEthereumClient, _, _>(
-            client_from_config,
-            config,
-        )
+    /// Serves the JSON-RPC calls of a node whose best block is `behind` blocks behind the
+    /// highest block of its peers, returns the url of the node.
+    async fn mock_syncing_node(behind: u64) -> String {
+        let syncing = if behind == 0 {
+            serde_json::Value::Bool(false)
+        } else {
+            serde_json::json!({
+                "startingBlock": "0x0",
+                "currentBlock": "0x10",
+                "highestBlock": format!("{:#x}", 0x10 + behind),
+            })
+        };
+        mock_node(move |method| match method {
+            "eth_syncing" => Ok(syncing.clone()),
+            method => panic!("unexpected call {method}"),
+        })
         .await
     }
 
-    fn compile_snippet(source: &str) -> Result<Vec<u8>> {
-        let solc = Solc::default();
-        let source = format!("contract Contract {{ {source} }}");
-        let mut sources = BTreeMap::new();
-        sources.insert(Path::new("contract.sol").into(), Source::new(source));
-        let input = CompilerInput::with_sources(sources)[0]
-            .clone()
-            .evm_version(EvmVersion::Homestead);
-        let output = solc.compile_exact(&input)?;
-        let file = output.contracts.get("contract.sol").unwrap();
-        let contract = file.get("Contract").unwrap();
-        let bytecode = contract
-            .evm
-            .as_ref()
-            .unwrap()
-            .bytecode
-            .as_ref()
-            .unwrap()
-            .object
-            .as_bytes()
-            .unwrap()
-            .to_vec();
-        Ok(bytecode)
+    /// Serves the JSON-RPC calls with `respond`, which returns the result or the error of the
+    /// calls other than `eth_chainId` and `eth_getBlockByNumber`, returns the url of the node.
+    async fn mock_node<F>(respond: F) -> String
+    where
+        F: Fn(&str) -> Result<serde_json::Value, serde_json::Value> + Clone + Send + 'static,
+    {
+        mock_node_with_block(mock_genesis(), respond).await
     }
 
-    #[tokio::test]
-    #[allow(clippy::needless_raw_string_hashes)]
-    async fn test_smart_contract() -> Result<()> {
-        let config = rosetta_config_ethereum::config("dev").unwrap();
+    /// Same as [`mock_node`], but `eth_getBlockByNumber` returns `block`.
+    async fn mock_node_with_block<F>(block: serde_json::Value, respond: F) -> String
+    where
+        F: Fn(&str) -> Result<serde_json::Value, serde_json::Value> + Clone + Send + 'static,
+    {
+        mock_node_with_params(move || block.clone(), move |method, _| respond(method).into()).await
+    }
 
-        let env = Env::new("ethereum-smart-contract", config.clone(), client_from_config).await?;
+    /// Successful receipt of the transaction `tx_hash`, included in block 1.
+    fn mock_receipt(tx_hash: &str) -> serde_json::Value {
+        serde_json::json!({
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", hex::encode([0x22; 32])),
+            "blockNumber": "0x1",
+            "from": format!("0x{}", hex::encode([0x33; 20])),
+            "to": format!("0x{}", hex::encode([0x44; 20])),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [],
+            "status": "0x1",
+            "logsBloom": format!("0x{}", hex::encode([0; 256])),
+        })
+    }
 
-        run_test(env, |env| async move {
-            let wallet = env.ephemeral_wallet().await.unwrap();
+    /// Response of a mock node to a JSON-RPC call.
+    enum MockResponse {
+        /// JSON-RPC result.
+        Result(serde_json::Value),
+        /// JSON-RPC error.
+        Error(serde_json::Value),
+        /// HTTP error status, ex: 429 when the node is rate limiting.
+        Status(u16),
+    }
 
-            let faucet = 100 * u128::pow(10, config.currency_decimals);
-            wallet.faucet(faucet, None).await.unwrap();
+    impl From<Result<serde_json::Value, serde_json::Value>> for MockResponse {
+        fn from(result: Result<serde_json::Value, serde_json::Value>) -> Self {
+            match result {
+                Ok(result) => Self::Result(result),
+                Err(error) => Self::Error(error),
+            }
+        }
+    }
 
-            let bytes = compile_snippet(
-                r"
-                    event AnEvent();
-                    function emitEvent() public {
-                        emit AnEvent();
+    /// Same as [`mock_node_with_block`], but `block` is called on each `eth_getBlockByNumber`, and
+    /// `respond` also receives the parameters of the calls and can reply with an HTTP error status.
+    async fn mock_node_with_params<B, F>(block: B, respond: F) -> String
+    where
+        B: Fn() -> serde_json::Value + Clone + Send + 'static,
+        F: Fn(&str, &serde_json::Value) -> MockResponse + Clone + Send + 'static,
+    {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let respond = respond.clone();
+                let block = block.clone();
+                tokio::spawn(async move {
+                    let mut buffer = [0u8; 8192];
+                    loop {
+                        let Ok(read @ 1..) = stream.read(&mut buffer).await else {
+                            return;
+                        };
+                        let request = String::from_utf8_lossy(&buffer[..read]);
+                        let Some((_, body)) = request.split_once("\r\n\r\n") else {
+                            return;
+                        };
+                        let request: serde_json::Value = serde_json::from_str(body).unwrap();
+                        let response = match request["method"].as_str().unwrap() {
+                            "eth_chainId" => MockResponse::Result(serde_json::json!("0x539")),
+                            "eth_getBlockByNumber" => MockResponse::Result(block()),
+                            method => respond(method, &request["params"]),
+                        };
+                        let (status, body) = match response {
+                            MockResponse::Result(result) => (
+                                "200 OK".to_string(),
+                                serde_json::json!({
+                                    "jsonrpc": "2.0",
+                                    "id": request["id"],
+                                    "result": result,
+                                })
+                                .to_string(),
+                            ),
+                            MockResponse::Error(error) => (
+                                "200 OK".to_string(),
+                                serde_json::json!({
+                                    "jsonrpc": "2.0",
+                                    "id": request["id"],
+                                    "error": error,
+                                })
+                                .to_string(),
+                            ),
+                            MockResponse::Status(status) => (status.to_string(), String::new()),
+                        };
+                        let response = format!(
+                            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                            body.len()
+                        );
+                        if stream.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
                     }
-                ",
-            )
-            .unwrap();
-            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
-            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
-            let contract_address = receipt.contract_address.unwrap();
-            let tx_hash = {
-                let call = TestContract::emitEventCall {};
-                wallet
-                    .eth_send_call(contract_address.0, call.abi_encode(), 0, None, None)
-                    .await
-                    .unwrap()
-                    .tx_hash()
-                    .0
-            };
-            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
-            assert_eq!(receipt.logs.len(), 1);
-            let topic = receipt.logs[0].topics[0];
-            let expected = H256(sha3::Keccak256::digest("AnEvent()").into());
-            assert_eq!(topic, expected);
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
 
-            let block_hash = receipt.block_hash;
-            let block_number = receipt.block_number.unwrap();
-            assert_eq!(topic, expected);
+    #[tokio::test]
+    async fn test_preflight() {
+        use rosetta_client::{client::GenericClient, Blockchain};
+        use rosetta_core::PREFLIGHT_MAX_BLOCKS_BEHIND;
 
-            let logs = wallet
-                .query(GetLogs {
-                    contracts: vec![contract_address],
-                    topics: vec![topic],
-                    block: AtBlock::At(block_hash.into()).into(),
-                })
-                .await
-                .unwrap();
-            assert_eq!(logs.len(), 1);
-            assert_eq!(logs[0].topics[0], topic);
+        let config = rosetta_config_ethereum::config("dev").unwrap();
 
-            let logs = wallet
-                .query(GetLogs {
-                    contracts: vec![contract_address],
-                    topics: vec![topic],
-                    block: AtBlock::At(block_number.into()).into(),
-                })
+        let url = mock_syncing_node(PREFLIGHT_MAX_BLOCKS_BEHIND).await;
+        let client = GenericClient::from_config(config.clone(), &url, None).await;
+        assert!(client.is_ok());
+
+        // Out of sync nodes fail on construction, unless the preflight check is disabled
+        let url = mock_syncing_node(100).await;
+        let Err(error) = GenericClient::from_config(config.clone(), &url, None).await else {
+            panic!("expected the preflight check to fail");
+        };
+        assert_eq!(
+            format!("{error:#}"),
+            "preflight check of ethereum failed: node is not synced: best block 16 is 100 blocks \
+             behind the highest block 116, at most 10 are tolerated"
+        );
+        let client = GenericClient::from_config_with_preflight(config, &url, None, false).await;
+        assert!(client.is_ok());
+        let client =
+            GenericClient::new_with_preflight(Blockchain::Ethereum, "dev", &url, None, false).await;
+        assert!(client.is_ok());
+        assert!(GenericClient::new(Blockchain::Ethereum, "dev", &url, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fee_history_fallback() {
+        use rosetta_config_ethereum::ext::types::U256;
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let fee_history_calls = Arc::new(AtomicUsize::new(0));
+        let calls = fee_history_calls.clone();
+        let url = mock_node(move |method| match method {
+            "eth_feeHistory" => {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(serde_json::json!({
+                    "code": -32601,
+                    "message": "the method eth_feeHistory does not exist/is not available",
+                }))
+            },
+            // 2 gwei
+            "eth_gasPrice" => Ok(serde_json::json!("0x77359400")),
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
                 .await
                 .unwrap();
-            assert_eq!(logs.len(), 1);
-            assert_eq!(logs[0].topics[0], topic);
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+
+        let gas_price = U256::from(2_000_000_000u64);
+        let priority_fee = U256::from(FALLBACK_PRIORITY_FEE);
+        assert_eq!(client.suggest_fees().await.unwrap(), (gas_price, priority_fee));
+        // The fallback is remembered
+        assert_eq!(client.suggest_fees().await.unwrap(), (gas_price, priority_fee));
+        assert_eq!(fee_history_calls.load(Ordering::SeqCst), 1);
+
+        // The fallback gas price is configurable
+        let client = client.with_fallback_gas_price(Some(U256::from(500_000_000u64)));
+        let gas_price = U256::from(500_000_000u64);
+        assert_eq!(client.suggest_fees().await.unwrap(), (gas_price, gas_price));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_without_base_fee() {
+        use rosetta_config_ethereum::ext::types::U256;
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let mut block = mock_genesis();
+        block.as_object_mut().unwrap().remove("baseFeePerGas");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let gas_price_requests = requests.clone();
+        let url = mock_node_with_block(block, move |method| match method {
+            // 2 gwei
+            "eth_gasPrice" => {
+                gas_price_requests.fetch_add(1, Ordering::SeqCst);
+                Ok(serde_json::json!("0x77359400"))
+            },
+            method => panic!("unexpected call {method}"),
         })
         .await;
-        Ok(())
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap();
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+
+        // The fees aren't estimated from the fee history, the gas price is used instead
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let params = EthereumMetadataParams {
+            nonce: Some(0),
+            gas_limit: Some(21_000),
+            destination: Some([0x01; 20]),
+            amount: [0; 4],
+            data: Vec::new(),
+            auto_access_list: false,
+        };
+        let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+        let gas_price = U256::from(2_000_000_000u64);
+        assert_eq!(metadata.gas_price.map(U256), Some(gas_price));
+        assert_eq!(U256(metadata.max_fee_per_gas), gas_price);
+        // The dev chain has no gas price floor
+        assert_eq!(U256(metadata.min_gas_price), U256::zero());
+        assert_eq!(metadata.chain_id, 0x539);
+        assert_eq!(metadata.nonce, 0);
+        assert_eq!(metadata.gas_limit, 21_000);
+        // The gas price is fetched once
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // The gas price is raised to the floor
+        let floor = U256::from(5_000_000_000u64);
+        let client = client.with_min_gas_price(floor);
+        let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+        assert_eq!(metadata.gas_price.map(U256), Some(floor));
+        assert_eq!(U256(metadata.max_fee_per_gas), floor);
+        assert_eq!(U256(metadata.min_gas_price), floor);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
     }
 
     #[tokio::test]
-    #[allow(clippy::needless_raw_string_hashes)]
-    async fn test_smart_contract_view() -> Result<()> {
+    async fn test_base_fee_follows_gas_usage() {
+        use rosetta_config_ethereum::ext::types::U256;
+
+        let base_fee = U256::from(1_000_000_000u64);
+        // The genesis gas limit is 30M, its gas target 15M
+        for (gas_used, expected) in [
+            ("0x1c9c380", std::cmp::Ordering::Greater),
+            ("0xe4e1c0", std::cmp::Ordering::Equal),
+            ("0x5208", std::cmp::Ordering::Less),
+        ] {
+            let config = rosetta_config_ethereum::config("dev").unwrap();
+            let mut block = mock_genesis();
+            block["gasUsed"] = serde_json::json!(gas_used);
+            block["baseFeePerGas"] = serde_json::json!("0x3b9aca00");
+            let url =
+                mock_node_with_block(block, |method| panic!("unexpected call {method}")).await;
+            let client =
+                MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                    .await
+                    .unwrap();
+            let MaybeWsEthereumClient::Http(client) = client else {
+                panic!("expected a http client");
+            };
+            let (current, next) = client.base_fee().await.unwrap();
+            assert_eq!(current, base_fee);
+            assert_eq!(next.cmp(&current), expected, "gas used {gas_used}");
+        }
+
+        // Pre-1559 chains have no base fee
         let config = rosetta_config_ethereum::config("dev").unwrap();
-        let env = Env::new("ethereum-smart-contract-logs-view", config.clone(), client_from_config)
-            .await
-            .unwrap();
+        let mut block = mock_genesis();
+        block.as_object_mut().unwrap().remove("baseFeePerGas");
+        let url = mock_node_with_block(block, |method| panic!("unexpected call {method}")).await;
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap();
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+        assert!(client.base_fee().await.is_err());
+    }
 
-        run_test(env, |env| async move {
-            let wallet = env.ephemeral_wallet().await.unwrap();
-            let faucet = 100 * u128::pow(10, config.currency_decimals);
-            wallet.faucet(faucet, None).await.unwrap();
+    #[tokio::test]
+    async fn test_gas_estimate_cache_invalidated_on_new_heads() {
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+        use std::sync::{
+            atomic::{AtomicU64, AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let head = Arc::new(AtomicU64::new(0));
+        let estimates = Arc::new(AtomicUsize::new(0));
+        let best_block = head.clone();
+        let estimate_calls = estimates.clone();
+        let url = mock_node_with_params(
+            move || {
+                let mut block = mock_genesis();
+                let block_mut = block.as_object_mut().unwrap();
+                block_mut.remove("baseFeePerGas");
+                let number = best_block.load(Ordering::SeqCst);
+                block_mut.insert("number".into(), format!("{number:#x}").into());
+                block
+            },
+            move |method, _| {
+                let result = match method {
+                    "eth_gasPrice" => serde_json::json!("0x3b9aca00"),
+                    "eth_estimateGas" => {
+                        estimate_calls.fetch_add(1, Ordering::SeqCst);
+                        serde_json::json!("0x5208")
+                    },
+                    method => panic!("unexpected call {method}"),
+                };
+                MockResponse::Result(result)
+            },
+        )
+        .await;
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap()
+                .with_gas_estimate_cache(Some(Duration::from_secs(60)));
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let params = EthereumMetadataParams {
+            nonce: Some(0),
+            gas_limit: None,
+            destination: Some([0x01; 20]),
+            amount: [0; 4],
+            data: Vec::new(),
+            auto_access_list: false,
+        };
+        for _ in 0..2 {
+            let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+            assert_eq!(metadata.gas_limit, 21_000);
+        }
+        // The second estimate is cached
+        assert_eq!(estimates.load(Ordering::SeqCst), 1);
+
+        // A new head invalidates the cache
+        head.store(1, Ordering::SeqCst);
+        client.metadata(&secret_key.public_key(), &params).await.unwrap();
+        assert_eq!(estimates.load(Ordering::SeqCst), 2);
+        client.metadata(&secret_key.public_key(), &params).await.unwrap();
+        assert_eq!(estimates.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_revert_error() {
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+
+        // `Error(string)` revert data with the reason "not allowed"
+        let revert_data =
+            format!("0x08c379a0{:064x}{:064x}{:0<64}", 0x20, 11, hex::encode("not allowed"));
+        let reverted = serde_json::json!({
+            "code": 3,
+            "message": "execution reverted: not allowed",
+            "data": revert_data,
+        });
+        let server_error = serde_json::json!({
+            "code": -32000,
+            "message": "execution reverted",
+            "data": revert_data,
+        });
+        // Not a revert, even if the message mentions it, the call isn't replayed
+        let no_data = serde_json::json!({
+            "code": -32000,
+            "message": "gas required exceeds allowance, always failing transaction or reverted",
+        });
+        for (estimate_error, expected) in [
+            (reverted.clone(), Some("not allowed")),
+            (server_error, Some("not allowed")),
+            (no_data, None),
+        ] {
+            let config = rosetta_config_ethereum::config("dev").unwrap();
+            let mut block = mock_genesis();
+            block.as_object_mut().unwrap().remove("baseFeePerGas");
+            let call_error = reverted.clone();
+            let url = mock_node_with_block(block, move |method| match method {
+                "eth_gasPrice" => Ok(serde_json::json!("0x3b9aca00")),
+                "eth_estimateGas" => Err(estimate_error.clone()),
+                "eth_call" if expected.is_some() => Err(call_error.clone()),
+                method => panic!("unexpected call {method}"),
+            })
+            .await;
+            let MaybeWsEthereumClient::Http(client) =
+                MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                    .await
+                    .unwrap()
+            else {
+                panic!("expected a http client");
+            };
+
+            let secret_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let params = EthereumMetadataParams {
+                nonce: Some(0),
+                gas_limit: None,
+                destination: Some([0x01; 20]),
+                amount: [0; 4],
+                data: vec![0xde, 0xad, 0xbe, 0xef],
+                auto_access_list: false,
+            };
+            let error = client.metadata(&secret_key.public_key(), &params).await.unwrap_err();
+            let reason = error.downcast_ref::<WouldRevert>().map(|error| error.reason.as_deref());
+            assert_eq!(reason, expected.map(Some), "{error:#}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_faucet_nonce_and_gas_price() {
+        use rosetta_config_ethereum::ext::types::{
+            ext::rlp::Rlp, rlp_utils::RlpDecodableTransaction, transactions::GasPrice,
+            TransactionT, TypedTransaction, U256,
+        };
+        use rosetta_core::crypto::{address::Address, Algorithm, SecretKey};
+        use std::sync::{Arc, Mutex};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let tx_hash = format!("0x{}", hex::encode([0x11; 32]));
+        let sent = Arc::new(Mutex::new(Vec::<Vec<u8>>::new()));
+        let raw_txs = sent.clone();
+        let receipt_hash = tx_hash.clone();
+        let url = mock_node_with_params(mock_genesis, move |method, params| {
+            let result = match method {
+                // Another client sent 5 transactions with the funding account
+                "eth_getTransactionCount" => serde_json::json!("0x5"),
+                // 1 gwei, above the default gas price
+                "eth_gasPrice" => serde_json::json!("0x3b9aca00"),
+                "eth_sendRawTransaction" => {
+                    let raw_tx = params[0].as_str().unwrap().trim_start_matches("0x");
+                    raw_txs.lock().unwrap().push(hex::decode(raw_tx).unwrap());
+                    serde_json::json!(receipt_hash)
+                },
+                "eth_blockNumber" => serde_json::json!("0x1"),
+                "eth_getTransactionReceipt" => mock_receipt(&receipt_hash),
+                method => panic!("unexpected call {method}"),
+            };
+            MockResponse::Result(result)
+        })
+        .await;
+        let client = MaybeWsEthereumClient::from_config(
+            config.clone(),
+            url.as_str(),
+            Some([0x42; 32]),
+            Transport::Auto,
+        )
+        .await
+        .unwrap();
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x01; 32]).unwrap();
+        let address: Address = secret_key.public_key().to_address(config.address_format);
+        let hash = client.faucet(&address, 1000, None).await.unwrap();
+        assert_eq!(format!("0x{}", hex::encode(hash)), tx_hash);
+
+        // The nonce is re-synced with the pending nonce, and the node's gas price is used
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let (tx, _) = TypedTransaction::rlp_decode(&Rlp::new(&sent[0]), true).unwrap();
+        assert_eq!(tx.nonce(), 5);
+        assert_eq!(tx.gas_price(), GasPrice::Legacy(U256::from(1_000_000_000u64)));
+        assert_eq!(tx.value(), U256::from(1000));
+    }
+
+    #[tokio::test]
+    async fn test_faucet_to_the_funding_account() {
+        use rosetta_client::{client::GenericClient, Wallet};
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let tx_hash = format!("0x{}", hex::encode([0x11; 32]));
+        let url = mock_node(move |method| match method {
+            "eth_getTransactionCount" => Ok(serde_json::json!("0x0")),
+            "eth_gasPrice" => Ok(serde_json::json!("0x3b9aca00")),
+            "eth_sendRawTransaction" => Ok(serde_json::json!(tx_hash)),
+            "eth_blockNumber" => Ok(serde_json::json!("0x1")),
+            "eth_getTransactionReceipt" => Ok(mock_receipt(&tx_hash)),
+            // The balance doesn't move when the funds are sent to the funding account
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let private_key = [0x42; 32];
+        let client =
+            GenericClient::from_config_with_preflight(config, &url, Some(private_key), false)
+                .await
+                .unwrap();
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &private_key).unwrap();
+        let wallet = Wallet::from_signer(client, secret_key).unwrap();
+
+        // Waits for the receipt instead of the balance
+        let hash = tokio::time::timeout(Duration::from_secs(10), wallet.faucet(1000, None))
+            .await
+            .expect("the faucet didn't return")
+            .unwrap();
+        assert_eq!(hash, [0x11; 32]);
+    }
+
+    #[tokio::test]
+    async fn test_effective_gas_price_is_best_effort() {
+        use rosetta_config_ethereum::ext::types::{
+            crypto::{Keypair, Signer},
+            transactions::Eip1559Transaction,
+            TransactionT, TypedTransaction, U256,
+        };
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let keypair = Keypair::from_bytes([0x42; 32]).unwrap();
+        let tx: TypedTransaction = Eip1559Transaction {
+            chain_id: 0x539,
+            nonce: 0,
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            max_fee_per_gas: U256::from(2_000_000_000u64),
+            gas_limit: 21_000,
+            to: Some([0x01; 20].into()),
+            value: U256::from(1000),
+            data: Vec::new().into(),
+            access_list: rosetta_config_ethereum::ext::types::AccessList::default(),
+        }
+        .into();
+        let signature = keypair.sign_prehash(tx.sighash(), Some(0x539)).unwrap();
+        let transaction = tx.encode(Some(&signature)).to_vec();
+        let tx_hash = tx.compute_tx_hash(&signature);
+
+        let sent = Arc::new(AtomicBool::new(false));
+        let node_sent = sent.clone();
+        let receipt_hash = format!("{tx_hash:?}");
+        let url = mock_node(move |method| match method {
+            // The receipt doesn't include the `effectiveGasPrice`
+            "eth_getTransactionReceipt" if node_sent.load(Ordering::SeqCst) => {
+                Ok(mock_receipt(&receipt_hash))
+            },
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Ok(serde_json::Value::Null),
+            "eth_sendRawTransaction" => {
+                node_sent.store(true, Ordering::SeqCst);
+                Ok(serde_json::json!(receipt_hash))
+            },
+            // And the block which included it can't be fetched
+            "eth_getBlockByHash" => {
+                Err(serde_json::json!({ "code": -32603, "message": "internal error" }))
+            },
+            "eth_blockNumber" => Ok(serde_json::json!("0x1")),
+            "eth_call" => Ok(serde_json::json!("0x")),
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap();
+
+        // The included transaction is still reported, without effective gas price
+        let result = client.submit_with_broadcast_callback(&transaction, |_| {}).await.unwrap();
+        assert!(sent.load(Ordering::SeqCst));
+        assert_eq!(result.tx_hash(), tx_hash);
+        assert_eq!(result.receipt().unwrap().effective_gas_price, None);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_callback() {
+        use rosetta_config_ethereum::ext::types::{
+            crypto::{Keypair, Signer},
+            transactions::LegacyTransaction,
+            TransactionT, TypedTransaction, U256,
+        };
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let keypair = Keypair::from_bytes([0x42; 32]).unwrap();
+        let tx: TypedTransaction = LegacyTransaction {
+            to: Some([0x01; 20].into()),
+            value: U256::from(1000),
+            gas_limit: 21_000,
+            gas_price: U256::from(1_000_000_000u64),
+            nonce: 0,
+            data: Vec::new().into(),
+            chain_id: Some(0x539),
+        }
+        .into();
+        let signature = keypair.sign_prehash(tx.sighash(), Some(0x539)).unwrap();
+        let transaction = tx.encode(Some(&signature)).to_vec();
+        let tx_hash = tx.compute_tx_hash(&signature);
+
+        let accepted = Arc::new(AtomicBool::new(false));
+        let sent = Arc::new(AtomicBool::new(false));
+        let (node_accepts, node_sent) = (accepted.clone(), sent.clone());
+        let receipt_hash = format!("{tx_hash:?}");
+        let url = mock_node(move |method| match method {
+            "eth_getTransactionReceipt" if node_sent.load(Ordering::SeqCst) => {
+                Ok(mock_receipt(&receipt_hash))
+            },
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Ok(serde_json::Value::Null),
+            "eth_sendRawTransaction" if node_accepts.load(Ordering::SeqCst) => {
+                node_sent.store(true, Ordering::SeqCst);
+                Ok(serde_json::json!(receipt_hash))
+            },
+            "eth_sendRawTransaction" => {
+                Err(serde_json::json!({ "code": -32000, "message": "nonce too low" }))
+            },
+            "eth_blockNumber" => Ok(serde_json::json!("0x1")),
+            "eth_call" => Ok(serde_json::json!("0x")),
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client =
+            MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap();
+
+        // Rejected transactions are never reported as broadcast
+        let mut broadcast = None;
+        let result = client
+            .submit_with_broadcast_callback(&transaction, |hash| broadcast = Some(hash))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(broadcast, None);
+
+        accepted.store(true, Ordering::SeqCst);
+        let result = client
+            .submit_with_broadcast_callback(&transaction, |hash| broadcast = Some(hash))
+            .await
+            .unwrap();
+        assert!(sent.load(Ordering::SeqCst));
+        assert_eq!(broadcast, Some(tx_hash));
+        assert_eq!(result.tx_hash(), tx_hash);
+
+        // A transaction already included isn't broadcast again
+        let mut broadcast = None;
+        let result = client
+            .submit_with_broadcast_callback(&transaction, |hash| broadcast = Some(hash))
+            .await
+            .unwrap();
+        assert_eq!(broadcast, None);
+        assert_eq!(result.tx_hash(), tx_hash);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_manager_is_opt_in() {
+        use rosetta_client::{client::GenericClient, NonceManagerConfig, Wallet};
+        use rosetta_config_ethereum::ext::types::{
+            ext::rlp::Rlp, rlp_utils::RlpDecodableTransaction, TransactionT, TypedTransaction,
+        };
+        use rosetta_core::{
+            crypto::{Algorithm, SecretKey},
+            types::AccountIdentifier,
+        };
+        use std::sync::{Arc, Mutex};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let mut block = mock_genesis();
+        block.as_object_mut().unwrap().remove("baseFeePerGas");
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let node_sent = sent.clone();
+        let url = mock_node_with_params(
+            move || block.clone(),
+            move |method, params| match method {
+                "eth_getTransactionCount" => MockResponse::Result(serde_json::json!("0x0")),
+                "eth_gasPrice" => MockResponse::Result(serde_json::json!("0x3b9aca00")),
+                "eth_estimateGas" => MockResponse::Result(serde_json::json!("0x5208")),
+                "eth_getTransactionReceipt" | "eth_getTransactionByHash" => {
+                    MockResponse::Result(serde_json::Value::Null)
+                },
+                // Every transaction is rejected, its nonce is never used
+                "eth_sendRawTransaction" => {
+                    let transaction = params[0].as_str().unwrap().trim_start_matches("0x");
+                    node_sent.lock().unwrap().push(hex::decode(transaction).unwrap());
+                    MockResponse::Error(
+                        serde_json::json!({ "code": -32000, "message": "insufficient funds" }),
+                    )
+                },
+                method => panic!("unexpected call {method}"),
+            },
+        )
+        .await;
+        let nonces = || {
+            sent.lock()
+                .unwrap()
+                .drain(..)
+                .map(|transaction| {
+                    let (tx, _) =
+                        TypedTransaction::rlp_decode(&Rlp::new(&transaction), true).unwrap();
+                    tx.nonce()
+                })
+                .collect::<Vec<_>>()
+        };
+        let client = GenericClient::from_config_with_preflight(config, &url, None, false)
+            .await
+            .unwrap();
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let wallet = Wallet::from_signer(client, secret_key).unwrap();
+        let bob = AccountIdentifier::new(format!("0x{}", hex::encode([0x01; 20])));
+
+        // By default every transaction reads its nonce from the node
+        assert!(wallet.transfer(&bob, 1000, None, None).await.is_err());
+        assert!(wallet.transfer(&bob, 1000, None, None).await.is_err());
+        assert_eq!(nonces(), [0, 0]);
+        assert!(wallet.check_nonce_gap().await.unwrap().is_none());
+
+        // The nonce manager must be enabled explicitly, and doesn't leak the rejected nonces
+        let wallet = wallet.with_nonce_manager(NonceManagerConfig::default());
+        assert!(wallet.transfer(&bob, 1000, None, None).await.is_err());
+        assert!(wallet.transfer(&bob, 1000, None, None).await.is_err());
+        assert_eq!(nonces(), [0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_unchecked_code_size() {
+        use rosetta_client::{client::GenericClient, Wallet};
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let mut block = mock_genesis();
+        block.as_object_mut().unwrap().remove("baseFeePerGas");
+        let sent = Arc::new(AtomicUsize::new(0));
+        let node_sent = sent.clone();
+        let url = mock_node_with_block(block, move |method| match method {
+            // The node doesn't support the state overrides
+            "eth_call" => {
+                Err(serde_json::json!({ "code": -32602, "message": "state override unsupported" }))
+            },
+            "eth_getTransactionCount" => Ok(serde_json::json!("0x0")),
+            "eth_gasPrice" => Ok(serde_json::json!("0x3b9aca00")),
+            "eth_estimateGas" => Ok(serde_json::json!("0x5208")),
+            "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Ok(serde_json::Value::Null),
+            "eth_sendRawTransaction" => {
+                node_sent.fetch_add(1, Ordering::SeqCst);
+                Err(serde_json::json!({ "code": -32000, "message": "insufficient funds" }))
+            },
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client = GenericClient::from_config_with_preflight(config, &url, None, false)
+            .await
+            .unwrap();
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let wallet = Wallet::from_signer(client, secret_key).unwrap();
+
+        // The deployment fails before broadcasting when the code size can't be checked
+        let error = wallet.eth_deploy_contract(vec![0x00]).await.unwrap_err();
+        assert!(format!("{error:#}").contains("state override unsupported"), "{error:#}");
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+
+        // Unless explicitly allowed
+        let wallet = wallet.with_unchecked_code_size();
+        let error = wallet.eth_deploy_contract(vec![0x00]).await.unwrap_err();
+        assert!(format!("{error:#}").contains("insufficient funds"), "{error:#}");
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_release_nonce_when_signing_fails() {
+        use rosetta_client::{
+            client::{GenericClient, GenericMetadataParams},
+            NonceManagerConfig, Wallet,
+        };
+        use rosetta_config_ethereum::ext::types::{
+            ext::rlp::Rlp, rlp_utils::RlpDecodableTransaction, TransactionT, TypedTransaction,
+        };
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let mut block = mock_genesis();
+        block.as_object_mut().unwrap().remove("baseFeePerGas");
+        let failed = Arc::new(AtomicBool::new(false));
+        let url = mock_node_with_block(block, move |method| match method {
+            "eth_getTransactionCount" => Ok(serde_json::json!("0x0")),
+            "eth_gasPrice" => Ok(serde_json::json!("0x3b9aca00")),
+            // The first gas estimation fails, after the nonce was allocated
+            "eth_estimateGas" if !failed.swap(true, Ordering::SeqCst) => {
+                Err(serde_json::json!({ "code": -32000, "message": "header not found" }))
+            },
+            "eth_estimateGas" => Ok(serde_json::json!("0x5208")),
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client = GenericClient::from_config_with_preflight(config, &url, None, false)
+            .await
+            .unwrap();
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let wallet = Wallet::from_signer(client, secret_key)
+            .unwrap()
+            .with_nonce_manager(NonceManagerConfig::default());
+
+        let transfer = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+            destination: Some([0x01; 20]),
+            amount: [1000, 0, 0, 0],
+            data: Vec::new(),
+            nonce: None,
+            gas_limit: None,
+            auto_access_list: false,
+        });
+        assert!(wallet.sign_with_auto_nonce(transfer.clone()).await.is_err());
+
+        // The nonce of the failed transaction is allocated again, without leaving a gap
+        let transaction = wallet.sign_with_auto_nonce(transfer).await.unwrap();
+        let (tx, _) = TypedTransaction::rlp_decode(&Rlp::new(&transaction), true).unwrap();
+        assert_eq!(tx.nonce(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retry_rate_limited_requests() {
+        use rosetta_core::{crypto::address::Address, types::PartialBlockIdentifier};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let balance_requests = requests.clone();
+        let url = mock_node_with_params(mock_genesis, move |method, _| match method {
+            // Every other request is rate limited
+            "eth_getBalance" if balance_requests.fetch_add(1, Ordering::SeqCst) % 2 == 0 => {
+                MockResponse::Status(429)
+            },
+            "eth_getBalance" => MockResponse::Result(serde_json::json!("0x3e8")),
+            method => panic!("unexpected call {method}"),
+        })
+        .await;
+        let client =
+            MaybeWsEthereumClient::from_config(config.clone(), url.as_str(), None, Transport::Auto)
+                .await
+                .unwrap();
+        let MaybeWsEthereumClient::Http(client) = client else {
+            panic!("expected a http client");
+        };
+        let address = Address::new(config.address_format, format!("0x{}", "42".repeat(20)));
+        let latest = PartialBlockIdentifier::latest();
+
+        let client = client.with_retry_policy(RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+            jitter: false,
+        });
+        assert_eq!(client.balance(&address, &latest).await.unwrap(), 1000);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let client = client.with_retry_policy(RetryPolicy::fail_fast());
+        assert!(client.balance(&address, &latest).await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_network_status() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev")?;
+        rosetta_docker::tests::network_status::<MaybeWsEthereumClient, _, _>(
+            client_from_config,
+            config,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_fast_startup() -> Result<()> {
+        use rosetta_docker::{EnvBuilder, HealthCheck};
+        use std::time::Instant;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let health_check = HealthCheck {
+            initial_delay: Duration::ZERO,
+            poll_interval: Duration::from_millis(100),
+            max_attempts: 300,
+        };
+        let start = Instant::now();
+        let env = EnvBuilder::new("ethereum-fast-startup")?
+            .health_check(health_check)
+            .build(config, client_from_config)
+            .await?;
+        let elapsed = start.elapsed();
+        env.shutdown().await?;
+        // The dev node is ready in a few seconds
+        assert!(elapsed < Duration::from_secs(15), "startup took {elapsed:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_peers() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev")?;
+        let env = Env::new("ethereum-peers", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            // The dev node is isolated, but the admin api is exposed
+            let peers = env.node().peers().await.unwrap();
+            assert!(peers.is_empty());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_status() -> Result<()> {
+        use rosetta_server::ws::{RetryStrategyConfig, RpcClientConfig};
+        use std::num::NonZeroU32;
+
+        // Give up quickly once the node is gone
+        let rpc_config = RpcClientConfig {
+            retry_strategy: RetryStrategyConfig::FixedInterval(Duration::from_millis(100)),
+            max_reconnect_attempts: NonZeroU32::new(3),
+            ..RpcClientConfig::default()
+        };
+        let config = rosetta_config_ethereum::config("dev")?;
+        let env = Env::new("ethereum-connection-status", config, move |config| {
+            let rpc_config = rpc_config.clone();
+            async move {
+                let url = config.node_uri.to_string();
+                let client = default_client(url.as_str(), Some(rpc_config)).await?;
+                MaybeWsEthereumClient::from_jsonrpsee(config, client, None).await
+            }
+        })
+        .await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            assert_eq!(client.connection_status(), ConnectionStatus::Connected);
+
+            env.stop_node().await.unwrap();
+            let mut status = client.connection_status();
+            for _ in 0..100 {
+                if status != ConnectionStatus::Connected {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                status = client.connection_status();
+            }
+            assert_eq!(status, ConnectionStatus::Reconnecting);
+
+            // The next request reconnects, and gives up after the max attempts
+            client.current_block().await.unwrap_err();
+            assert_eq!(client.connection_status(), ConnectionStatus::Closed);
+            client.current_block().await.unwrap_err();
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_estimate() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev")?;
+        let env = Env::new("ethereum-inclusion-estimate", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            client
+                .wait_for_block(INCLUSION_ESTIMATE_BLOCKS, Duration::from_secs(60))
+                .await
+                .unwrap();
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let Some(latest) = wallet.query(GetBlock(AtBlock::Latest)).await.unwrap() else {
+                panic!("latest block not found");
+            };
+            let timestamp = latest.header().header().timestamp;
+
+            // The pool is empty and a block is produced every second
+            let estimate = client.inclusion_estimate(timestamp + 60).await.unwrap();
+            assert!(estimate.likely, "{estimate:?}");
+            assert!(estimate.margin > 0, "{estimate:?}");
+
+            // The deadline already passed
+            let estimate = client.inclusion_estimate(timestamp - 1).await.unwrap();
+            assert!(!estimate.likely, "{estimate:?}");
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_account() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev")?;
+        rosetta_docker::tests::account::<MaybeWsEthereumClient, _, _>(client_from_config, config)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_construction() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev")?;
+        rosetta_docker::tests::construction::<MaybeWsEthereumClient, _, _>(
+            client_from_config,
+            config,
+        )
+        .await
+    }
+
+    fn compile_snippet(source: &str) -> Result<Vec<u8>> {
+        let solc = Solc::default();
+        let source = format!("contract Contract {{ {source} }}");
+        let mut sources = BTreeMap::new();
+        sources.insert(Path::new("contract.sol").into(), Source::new(source));
+        let input = CompilerInput::with_sources(sources)[0]
+            .clone()
+            .evm_version(EvmVersion::Homestead);
+        let output = solc.compile_exact(&input)?;
+        let file = output.contracts.get("contract.sol").unwrap();
+        let contract = file.get("Contract").unwrap();
+        let bytecode = contract
+            .evm
+            .as_ref()
+            .unwrap()
+            .bytecode
+            .as_ref()
+            .unwrap()
+            .object
+            .as_bytes()
+            .unwrap()
+            .to_vec();
+        Ok(bytecode)
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_smart_contract() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+
+        let env = Env::new("ethereum-smart-contract", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let bytes = compile_snippet(
+                r"
+                    event AnEvent();
+                    function emitEvent() public {
+                        emit AnEvent();
+                    }
+                ",
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+            let tx_hash = {
+                let call = TestContract::emitEventCall {};
+                wallet
+                    .eth_send_call(contract_address.0, call.abi_encode(), 0, None, None)
+                    .await
+                    .unwrap()
+                    .tx_hash()
+                    .0
+            };
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            assert_eq!(receipt.logs.len(), 1);
+            let topic = receipt.logs[0].topics[0];
+            let expected = H256(sha3::Keccak256::digest("AnEvent()").into());
+            assert_eq!(topic, expected);
+
+            let block_hash = receipt.block_hash;
+            let block_number = receipt.block_number.unwrap();
+            assert_eq!(topic, expected);
+
+            let logs = wallet
+                .query(GetLogs {
+                    contracts: vec![contract_address],
+                    topics: vec![topic],
+                    block: AtBlock::At(block_hash.into()).into(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].topics[0], topic);
+
+            let logs = wallet
+                .query(GetLogs {
+                    contracts: vec![contract_address],
+                    topics: vec![topic],
+                    block: AtBlock::At(block_number.into()).into(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].topics[0], topic);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_logs() -> Result<()> {
+        use futures_util::TryStreamExt;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-stream-logs", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let bytes = compile_snippet(
+                r"
+                    event AnEvent();
+                    function emitEvent() public {
+                        emit AnEvent();
+                    }
+                ",
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+            let from = receipt.block_number.unwrap();
+
+            // Emit events in different blocks
+            for _ in 0..3 {
+                let call = TestContract::emitEventCall {};
+                wallet
+                    .eth_send_call(contract_address.0, call.abi_encode(), 0, None, None)
+                    .await
+                    .unwrap();
+            }
+            let topic = H256(sha3::Keccak256::digest("AnEvent()").into());
+            let client = ws_client(env);
+            let to = client.current_block().await.unwrap().index;
+
+            let buffered = wallet
+                .query(GetLogs {
+                    contracts: vec![contract_address],
+                    topics: vec![topic],
+                    block: FilterBlockOption::Range {
+                        from_block: Some(AtBlock::from(from)),
+                        to_block: Some(AtBlock::from(to)),
+                    },
+                })
+                .await
+                .unwrap();
+            assert_eq!(buffered.len(), 3);
+
+            // Use small chunks, so the range is split in multiple requests
+            let streamed: Vec<_> = client
+                .stream_logs_with_chunk_size(vec![contract_address], vec![topic], from, to, 2)
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(streamed, buffered);
+
+            let streamed: Vec<_> = client
+                .stream_logs(vec![contract_address], vec![topic], from, to)
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(streamed, buffered);
+
+            // Only the blocks whose bloom filter matches are queried
+            let scanned =
+                client.scan_logs(vec![contract_address], vec![topic], from, to).await.unwrap();
+            assert_eq!(scanned, buffered);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_smart_contract_view() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-smart-contract-logs-view", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let bytes = compile_snippet(
+                r"
+                function identity(bool a) public view returns (bool) {
+                    return a;
+                }
+            ",
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+
+            let response = {
+                let call = TestContract::identityCall { a: true };
+                wallet
+                    .eth_view_call(contract_address.0, call.abi_encode(), AtBlock::Latest)
+                    .await
+                    .unwrap()
+            };
+            assert_eq!(
+                response,
+                CallResult::Success(
+                    [
+                        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                        0, 0, 0, 0, 0, 0, 1
+                    ]
+                    .to_vec()
+                )
+            );
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_erc20_allowance() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::{H160, U256};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-erc20-allowance", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let owner = env.ephemeral_wallet().await.unwrap();
+            let spender = env.ephemeral_wallet().await.unwrap();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            owner.faucet(faucet, None).await.unwrap();
+
+            let bytes = compile_snippet(
+                r"
+                mapping(address => mapping(address => uint256)) public allowance;
+
+                function approve(address spender, uint256 value) public returns (bool) {
+                    allowance[msg.sender][spender] = value;
+                    return true;
+                }
+            ",
+            )
+            .unwrap();
+            let tx_hash = owner.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = owner.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let token = receipt.contract_address.unwrap();
+
+            let owner_address: H160 = owner.account().address.parse().unwrap();
+            let spender_address: H160 = spender.account().address.parse().unwrap();
+            let allowance = owner.erc20_allowance(token, owner_address, spender_address);
+            assert_eq!(allowance.await.unwrap(), U256::zero());
+
+            let amount = U256::from(1_000_000);
+            let result = owner.erc20_approve(token, spender_address, amount).await.unwrap();
+            assert_eq!(result.receipt().unwrap().status_code, Some(1));
+
+            // The allowance is readable from any wallet
+            let allowance = spender.erc20_allowance(token, owner_address, spender_address);
+            assert_eq!(allowance.await.unwrap(), amount);
+            let reversed = spender.erc20_allowance(token, spender_address, owner_address);
+            assert_eq!(reversed.await.unwrap(), U256::zero());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_submit_permit() -> Result<()> {
+        use rosetta_client::{
+            crypto::{Algorithm, SecretKey},
+            Permit,
+        };
+        use rosetta_config_ethereum::ext::types::{H160, U256};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-submit-permit", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let relayer = env.ephemeral_wallet().await.unwrap();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            relayer.faucet(faucet, None).await.unwrap();
+
+            let bytes = compile_snippet(
+                r#"
+                mapping(address => uint256) public nonces;
+                mapping(address => mapping(address => uint256)) public allowance;
+                bytes32 public DOMAIN_SEPARATOR;
+
+                constructor(uint256 chainId) {
+                    DOMAIN_SEPARATOR = keccak256(abi.encode(
+                        keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"),
+                        keccak256(bytes("Token")),
+                        keccak256(bytes("1")),
+                        chainId,
+                        address(this)
+                    ));
+                }
+
+                function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) public {
+                    require(deadline >= block.timestamp, "expired");
+                    bytes32 digest = keccak256(abi.encodePacked(
+                        "\x19\x01",
+                        DOMAIN_SEPARATOR,
+                        keccak256(abi.encode(
+                            keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"),
+                            owner, spender, value, nonces[owner]++, deadline
+                        ))
+                    ));
+                    address signer = ecrecover(digest, v, r, s);
+                    require(signer != address(0) && signer == owner, "invalid signature");
+                    allowance[owner][spender] = value;
+                }
+            "#,
+            )
+            .unwrap();
+            // Append the `chainId` constructor argument
+            let chain_id = relayer.eth_chain_id().await.unwrap();
+            let mut constructor_arg = [0u8; 32];
+            U256::from(chain_id).to_big_endian(&mut constructor_arg);
+            let bytecode = [bytes, constructor_arg.to_vec()].concat();
+            let tx_hash = relayer.eth_deploy_contract(bytecode).await.unwrap().tx_hash().0;
+            let receipt = relayer.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let token = receipt.contract_address.unwrap();
+
+            // The owner never touches the chain, it only signs the permit
+            let owner_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let owner: H160 =
+                owner_key.public_key().to_address(config.address_format).address().parse().unwrap();
+            let spender: H160 = relayer.account().address.parse().unwrap();
+            let permit = Permit {
+                owner,
+                spender,
+                value: U256::from(1_000_000),
+                deadline: U256::from(u64::MAX),
+            };
+
+            let domain_separator = {
+                let call = PermitToken::DOMAIN_SEPARATORCall {};
+                let CallResult::Success(result) = relayer
+                    .eth_view_call(token.0, call.abi_encode(), AtBlock::Latest)
+                    .await
+                    .unwrap()
+                else {
+                    panic!("failed to read the domain separator");
+                };
+                result
+            };
+            let struct_hash = {
+                let mut encoded = sha3::Keccak256::digest(
+                    "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+                )
+                .to_vec();
+                for address in [owner, spender] {
+                    encoded.extend_from_slice(&[0u8; 12]);
+                    encoded.extend_from_slice(address.as_bytes());
+                }
+                for value in [permit.value, U256::zero(), permit.deadline] {
+                    let mut word = [0u8; 32];
+                    value.to_big_endian(&mut word);
+                    encoded.extend_from_slice(&word);
+                }
+                sha3::Keccak256::digest(encoded)
+            };
+            let digest = sha3::Keccak256::digest(
+                [&[0x19, 0x01], domain_separator.as_slice(), struct_hash.as_slice()].concat(),
+            );
+            let signature = owner_key.sign_prehashed(&digest).unwrap().to_bytes();
+
+            let result = relayer.submit_permit(token, permit, &signature).await.unwrap();
+            let receipt = result.receipt().unwrap();
+            assert_eq!(receipt.status_code, Some(1));
+
+            let allowance = {
+                let call = PermitToken::allowanceCall {
+                    owner: owner.0.into(),
+                    spender: spender.0.into(),
+                };
+                relayer.eth_view_call(token.0, call.abi_encode(), AtBlock::Latest).await.unwrap()
+            };
+            let mut expected = [0u8; 32];
+            permit.value.to_big_endian(&mut expected);
+            assert_eq!(allowance, CallResult::Success(expected.to_vec()));
+
+            // An expired permit must be rejected before submitting it
+            let expired = Permit { deadline: U256::zero(), ..permit };
+            assert!(relayer.submit_permit(token, expired, &signature).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_gas_price_floor() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::U256;
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-min-gas-price", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            // 1000 gwei, way above the dev node gas price
+            let floor = U256::from(1_000_000_000_000u64);
+            let client = match env.node().as_ref() {
+                MaybeWsEthereumClient::Ws(client) => client.clone().with_min_gas_price(floor),
+                MaybeWsEthereumClient::Http(_) => panic!("expected a websocket client"),
+            };
+            assert_eq!(client.min_gas_price(), floor);
+
+            let secret_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let params = EthereumMetadataParams {
+                nonce: None,
+                gas_limit: Some(21_000),
+                destination: Some([0x01; 20]),
+                amount: [0; 4],
+                data: Vec::new(),
+                auto_access_list: false,
+            };
+            let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+            assert_eq!(U256(metadata.min_gas_price), floor);
+            assert!(U256(metadata.max_fee_per_gas) >= floor);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fixed_gas_limit() -> Result<()> {
+        use rosetta_core::crypto::{Algorithm, SecretKey};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-fixed-gas-limit", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+
+            // The account has no funds, so `eth_estimateGas` fails
+            let secret_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let mut amount = [0; 4];
+            amount[0] = u64::pow(10, 18);
+            let params = EthereumMetadataParams {
+                nonce: None,
+                gas_limit: None,
+                destination: Some([0x01; 20]),
+                amount,
+                data: Vec::new(),
+                auto_access_list: false,
+            };
+            assert!(client.metadata(&secret_key.public_key(), &params).await.is_err());
+
+            // With a fixed gas limit the estimation is skipped
+            let client = client.with_fixed_gas_limit(Some(100_000));
+            let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+            assert_eq!(metadata.gas_limit, 100_000);
+
+            // The gas limit provided in the params takes precedence
+            let params = EthereumMetadataParams { gas_limit: Some(21_000), ..params };
+            let metadata = client.metadata(&secret_key.public_key(), &params).await.unwrap();
+            assert_eq!(metadata.gas_limit, 21_000);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_auto_access_list() -> Result<()> {
+        use rosetta_client::client::GenericMetadataParams;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-auto-access-list", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            let bytes = compile_snippet(
+                r"
+                    uint256[8] public values;
+                    uint256 public total;
+                    function sum() public view returns (uint256) {
+                        uint256 s = 0;
+                        for (uint256 i = 0; i < 8; i++) {
+                            s += values[i];
+                        }
+                        return s;
+                    }
+                    function touch(address other) public {
+                        total = Contract(other).sum();
+                    }
+                ",
+            )
+            .unwrap();
+            let mut contracts = Vec::with_capacity(2);
+            for _ in 0..2 {
+                let tx_hash = wallet.eth_deploy_contract(bytes.clone()).await.unwrap().tx_hash().0;
+                let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+                contracts.push(receipt.contract_address.unwrap());
+            }
+
+            // The storage of the other contract is only warm when the access list is attached
+            let data = StorageContract::touchCall { other: contracts[1].0.into() }.abi_encode();
+            let mut gas_used = Vec::with_capacity(2);
+            for auto_access_list in [false, true] {
+                let params = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                    destination: Some(contracts[0].0),
+                    amount: [0; 4],
+                    data: data.clone(),
+                    nonce: None,
+                    gas_limit: None,
+                    auto_access_list,
+                });
+                let tx_hash = wallet.construct(&params).await.unwrap().tx_hash().0;
+                let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+                assert_eq!(receipt.status_code, Some(1));
+                gas_used.push(receipt.gas_used.unwrap());
+            }
+            assert!(gas_used[1] < gas_used[0], "{gas_used:?}");
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_estimate_gas_revert_reason() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-revert-reason", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            let bytes = compile_snippet(
+                r#"
+                    function fail() public {
+                        revert("not allowed");
+                    }
+                "#,
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+
+            let call = RevertContract::failCall {};
+            let error = wallet
+                .eth_send_call_estimate_gas(contract_address.0, call.abi_encode(), 0)
+                .await
+                .unwrap_err();
+            let would_revert = error.downcast_ref::<WouldRevert>().unwrap();
+            assert_eq!(would_revert.reason.as_deref(), Some("not allowed"));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_estimate_gas_at_block() -> Result<()> {
+        use rosetta_config_ethereum::{ext::types::U256, EstimateGas};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-estimate-gas-at", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            let bytes = compile_snippet(
+                r#"
+                    uint256 public counter;
+                    function increment() public payable {
+                        counter += 1;
+                    }
+                "#,
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+            let deployed_at = AtBlock::from(receipt.block_number.unwrap());
+
+            let increment = CounterContract::incrementCall {}.abi_encode();
+            wallet
+                .eth_send_call(contract_address.0, increment.clone(), 0, None, None)
+                .await
+                .unwrap();
+
+            let estimate = |block| EstimateGas {
+                from: Some(wallet.account().address.parse().unwrap()),
+                to: contract_address,
+                value: U256::zero(),
+                data: increment.clone(),
+                block,
+            };
+            // Setting a zero storage slot costs more than updating a non-zero one
+            let past = wallet.query(estimate(deployed_at)).await.unwrap();
+            let latest = wallet.query(estimate(AtBlock::Latest)).await.unwrap();
+            assert!(past > latest, "{past} <= {latest}");
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_atomic_batch() -> Result<()> {
+        use rosetta_client::Wallet;
+        use rosetta_config_ethereum::{
+            ext::types::{Address, U256},
+            GetBalance,
+        };
+
+        async fn counter(wallet: &Wallet, contract: Address) -> U256 {
+            let call = CounterContract::counterCall {};
+            let result = wallet
+                .eth_view_call(contract.0, call.abi_encode(), AtBlock::Latest)
+                .await
+                .unwrap();
+            let CallResult::Success(data) = result else { panic!("counter() failed") };
+            U256::from_big_endian(&data)
+        }
+
+        async fn balance(wallet: &Wallet, address: Address) -> U256 {
+            wallet.query(GetBalance { address, block: AtBlock::Latest }).await.unwrap()
+        }
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-atomic-batch", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            let bytes = compile_snippet(
+                r#"
+                    uint256 public counter;
+                    function increment() public payable {
+                        counter += 1;
+                    }
+                    function fail() public {
+                        revert("batch failed");
+                    }
+                "#,
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract_address = receipt.contract_address.unwrap();
+
+            let increment = CounterContract::incrementCall {}.abi_encode();
+            let fail = CounterContract::failCall {}.abi_encode();
+
+            let result = wallet
+                .atomic_batch(vec![
+                    (contract_address, increment.clone(), 1000),
+                    (contract_address, increment.clone(), 0),
+                ])
+                .await
+                .unwrap();
+            assert_eq!(result.receipt().unwrap().status_code, Some(1));
+            assert_eq!(counter(&wallet, contract_address).await, U256::from(2));
+            assert_eq!(balance(&wallet, contract_address).await, U256::from(1000));
+
+            // The last call reverts, so the increment and the value transfer are reverted too
+            let error = wallet
+                .atomic_batch(vec![
+                    (contract_address, increment, 1000),
+                    (contract_address, fail, 0),
+                ])
+                .await
+                .unwrap_err();
+            let would_revert = error.downcast_ref::<WouldRevert>().unwrap();
+            assert_eq!(would_revert.reason.as_deref(), Some("batch failed"));
+            assert_eq!(counter(&wallet, contract_address).await, U256::from(2));
+            assert_eq!(balance(&wallet, contract_address).await, U256::from(1000));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rosetta_call() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::U256;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-rosetta-call", config, client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let request: CallRequest = serde_json::from_value(serde_json::json!({
+                "method": "eth_gasPrice",
+                "parameters": [],
+            }))
+            .unwrap();
+            let response = client.rosetta_call(&request).await.unwrap();
+            assert!(!response.idempotent);
+            let gas_price = response.result.as_str().unwrap();
+            let gas_price = U256::from_str_radix(gas_price.trim_start_matches("0x"), 16).unwrap();
+            assert!(gas_price > U256::zero());
+
+            // Only read-only methods are allowed
+            let request = CallRequest { method: "eth_sendRawTransaction".into(), ..request };
+            assert!(client.rosetta_call(&request).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_header() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::crypto::DefaultCrypto;
+        use rosetta_ethereum_backend::EthereumRpc;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-header", config.clone(), client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            // Mine a new block
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let client = ws_client(env);
+            let latest = client.current_block().await.unwrap();
+            let at = AtBlock::At(latest.index.into());
+            let header = client.header(at).await.unwrap().unwrap();
+            assert_eq!(header.header().compute_hash::<DefaultCrypto>(), header.hash());
+
+            // The header must be consistent with the full block
+            let block = client.backend.block(at).await.unwrap().unwrap();
+            assert_eq!(Some(header.hash()), block.hash);
+            assert_eq!(header.number(), block.header.number);
+            assert_eq!(header.header().parent_hash, block.header.parent_hash);
+
+            // Fetching by hash verifies the computed hash
+            let by_hash = client.header(AtBlock::from(header.hash())).await.unwrap().unwrap();
+            assert_eq!(by_hash, header);
+            let parent = client
+                .header(AtBlock::from(header.header().parent_hash))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(parent.number() + 1, header.number());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_genesis_block() -> Result<()> {
+        use rosetta_ethereum_backend::EthereumRpc;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-genesis", config, client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            // The genesis is fetched from the node at startup
+            let genesis = client.backend.block(AtBlock::At(0u64.into())).await.unwrap().unwrap();
+            let expected = BlockIdentifier { index: 0, hash: genesis.hash.unwrap().0 };
+            assert_eq!(client.genesis_block(), expected);
+            assert_eq!(env.node().genesis_block(), expected);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_base_fee() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::U256;
+        use rosetta_ethereum_backend::EthereumRpc;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-base-fee", config.clone(), client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            // Read the latest block around the call, so the prediction is known to be based on it
+            let (block, base_fee, next_base_fee) = loop {
+                let before = client.backend.block(AtBlock::Latest).await.unwrap().unwrap();
+                let (base_fee, next_base_fee) = client.base_fee().await.unwrap();
+                let after = client.backend.block(AtBlock::Latest).await.unwrap().unwrap();
+                if before.hash == after.hash {
+                    break (after, base_fee, next_base_fee);
+                }
+            };
+            assert_eq!(block.header.base_fee_per_gas.map(U256::from), Some(base_fee));
+
+            // The base fee can change at most 12.5% per block
+            let max_change = base_fee / 8;
+            assert!(next_base_fee <= base_fee + std::cmp::max(max_change, U256::one()));
+            assert!(next_base_fee >= base_fee - max_change);
+
+            // It moves toward the gas target
+            let gas_target = block.header.gas_limit / 2;
+            match block.header.gas_used.cmp(&gas_target) {
+                std::cmp::Ordering::Greater => assert!(next_base_fee > base_fee),
+                std::cmp::Ordering::Equal => assert_eq!(next_base_fee, base_fee),
+                std::cmp::Ordering::Less => assert!(next_base_fee <= base_fee),
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transfer_confirmations() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-transfer-confirmations", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let value = 10 * u128::pow(10, config.currency_decimals);
+            let result = alice
+                .transfer_with_confirmations(bob.account(), value, None, None, 2)
+                .await
+                .unwrap();
+            let receipt = result.receipt().unwrap();
+            let block_number = receipt.block_number.unwrap();
+
+            // The transaction must be buried under at least 2 blocks
+            let head = env.node().current_block().await.unwrap();
+            assert!(block_number + 2 <= head.index);
+            assert_eq!(bob.balance().await.unwrap(), value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_at() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-nonce-at", config.clone(), client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let previous = AtBlock::At(env.node().current_block().await.unwrap().index.into());
+            let nonce = alice.nonce_at(previous).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            alice.transfer(bob.account(), value, None, None).await.unwrap();
+
+            assert_eq!(alice.nonce_at(AtBlock::Latest).await.unwrap(), nonce + 1);
+            assert_eq!(alice.nonce_at(previous).await.unwrap(), nonce);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_faucet_waits_for_funds() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-faucet-wait", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            for i in 1..=3 {
+                wallet.faucet(value, None).await.unwrap();
+                assert_eq!(wallet.balance().await.unwrap(), i * value);
+            }
+            let tx_hash = wallet
+                .faucet_with_options(value, FaucetOptions { wait: false, ..Default::default() })
+                .await
+                .unwrap();
+            assert_eq!(tx_hash.len(), 32);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_balance_stream() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-balance-stream", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            let mut balances = std::pin::pin!(wallet.balance_stream().await.unwrap());
+            assert_eq!(balances.next().await.unwrap().unwrap(), 0);
+
+            // The funds are emitted once they land, without repeating the previous balance
+            wallet
+                .faucet_with_options(value, FaucetOptions { wait: false, ..Default::default() })
+                .await
+                .unwrap();
+            let balance = tokio::time::timeout(DEFAULT_SUBMIT_TIMEOUT, balances.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(balance, value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_stream() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-nonce-stream", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+            let mut nonces = std::pin::pin!(wallet.nonce_stream().await.unwrap());
+            assert_eq!(nonces.next().await.unwrap(), 0);
+
+            // A transaction sent outside of the stream increments the nonce
+            let value = u128::pow(10, config.currency_decimals);
+            wallet.transfer(bob.account(), value, None, None).await.unwrap();
+            let nonce = tokio::time::timeout(DEFAULT_SUBMIT_TIMEOUT, nonces.next())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(nonce, 1);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_wallet() -> Result<()> {
+        use rosetta_client::{client::GenericClient, AccountDescriptor, Wallet};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-watch-only", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            wallet.faucet(10 * value, None).await.unwrap();
+
+            // The descriptor is portable, it holds no secret
+            let json = serde_json::to_string(&wallet.account_descriptor()).unwrap();
+            let descriptor: AccountDescriptor = serde_json::from_str(&json).unwrap();
+            assert_eq!(descriptor, wallet.account_descriptor());
+
+            let config = env.node().config().clone();
+            let url = config.node_uri.to_string();
+            let client = GenericClient::from_config(config.clone(), &url, None).await.unwrap();
+            let watch_only = Wallet::from_descriptor(client, &descriptor).unwrap();
+            assert_eq!(watch_only.account(), wallet.account());
+            assert_eq!(watch_only.balance().await.unwrap(), 10 * value);
+            assert!(watch_only.transfer(bob.account(), value, None, None).await.is_err());
+
+            // A descriptor of another account is rejected
+            let tampered =
+                AccountDescriptor { address: bob.account().address.clone(), ..descriptor };
+            let client = GenericClient::from_config(config, &url, None).await.unwrap();
+            assert!(Wallet::from_descriptor(client, &tampered).is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_balances_snapshot() -> Result<()> {
+        use rosetta_core::types::AccountIdentifier;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-balances-snapshot", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let mut accounts = Vec::new();
+            let mut expected = Vec::new();
+            for i in 0..10 {
+                let account = env.ephemeral_wallet().await.unwrap();
+                // Only even accounts are funded
+                let value = if i % 2 == 0 { (i + 1) * u128::pow(10, 15) } else { 0 };
+                if value > 0 {
+                    account.faucet(value, None).await.unwrap();
+                }
+                accounts.push(account.account().clone());
+                expected.push(value);
+            }
+            let invalid = AccountIdentifier {
+                address: "not an address".into(),
+                sub_account: None,
+                metadata: None,
+            };
+            accounts.push(invalid);
+
+            let snapshot = wallet.balances_snapshot_with_concurrency(&accounts, 3).await.unwrap();
+            assert_eq!(snapshot.len(), accounts.len());
+            for ((account, balance), (expected_account, expected_balance)) in
+                snapshot.iter().zip(accounts.iter().zip(expected))
+            {
+                assert_eq!(account, expected_account);
+                assert_eq!(*balance.as_ref().unwrap(), expected_balance);
+            }
+            // Errors are returned inline
+            assert!(snapshot.last().unwrap().1.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batched_balances() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-batched-balances", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let mut addresses = Vec::new();
+            let mut expected = Vec::new();
+            for i in 1..=5 {
+                let wallet = env.ephemeral_wallet().await.unwrap();
+                // The third account is left empty
+                let value = if i == 3 { 0 } else { i * u128::pow(10, 15) };
+                if value > 0 {
+                    wallet.faucet(value, None).await.unwrap();
+                }
+                addresses
+                    .push(Address::new(config.address_format, wallet.account().address.clone()));
+                expected.push(value);
+            }
+            let latest = PartialBlockIdentifier::latest();
+            let balances = env.node().balances(&addresses, &latest).await.unwrap();
+            assert_eq!(balances, expected);
+            assert!(env.node().balances(&[], &latest).await.unwrap().is_empty());
+
+            let invalid = Address::new(config.address_format, "not an address".into());
+            addresses.push(invalid);
+            assert!(env.node().balances(&addresses, &latest).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chain_stalled() -> Result<()> {
+        use crate::utils::EthereumRpcExt;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_config_ethereum::ext::types::{H160, U256};
+        use rosetta_ethereum_backend::jsonrpsee::core::{client::ClientT, params::ArrayParams};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-chain-stalled", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            // Pause the block production
+            let _: serde_json::Value =
+                client.backend.0.request("miner_stop", ArrayParams::new()).await.unwrap();
+
+            // The stall detection is opt-in
+            let value = u128::pow(10, config.currency_decimals);
+            let params = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                destination: Some(bob.account().address.parse::<H160>().unwrap().0),
+                amount: U256::from(value).0,
+                data: Vec::new(),
+                nonce: None,
+                gas_limit: None,
+                auto_access_list: false,
+            });
+            let transaction = alice.sign_transaction(&params).await.unwrap();
+            let client = client.with_stall_timeout(Some(DEFAULT_STALL_TIMEOUT));
+            let error = client.submit(&transaction).await.unwrap_err();
+            let stalled = error.downcast_ref::<ChainStalled>().unwrap();
+            assert_eq!(stalled.timeout, DEFAULT_STALL_TIMEOUT);
+
+            // Once the block production resumes, the transaction is included
+            let _: serde_json::Value =
+                client.backend.0.request("miner_start", ArrayParams::new()).await.unwrap();
+            let receipt = client
+                .backend
+                .wait_for_transaction_receipt(stalled.tx_hash, DEFAULT_SUBMIT_TIMEOUT, None)
+                .await;
+            assert!(receipt.is_ok());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_timeout() -> Result<()> {
+        use crate::utils::EthereumRpcExt;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_config_ethereum::{ext::types::H160, SubmitResult};
+        use rosetta_ethereum_backend::jsonrpsee::core::{client::ClientT, params::ArrayParams};
+        use std::time::Instant;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-submit-timeout", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(faucet, None).await.unwrap();
+
+            // Pause the block production
+            let _: serde_json::Value =
+                client.backend.0.request("miner_stop", ArrayParams::new()).await.unwrap();
+
+            let params = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                destination: Some(H160::repeat_byte(0x42).0),
+                amount: [1, 0, 0, 0],
+                data: Vec::new(),
+                nonce: None,
+                gas_limit: None,
+                auto_access_list: false,
+            });
+            let transaction = wallet.sign_transaction(&params).await.unwrap();
+            let client =
+                client.with_submit_timeout(Duration::from_secs(1)).with_stall_timeout(None);
+            let started = Instant::now();
+            let result = client.submit(&transaction).await.unwrap();
+            assert!(started.elapsed() < DEFAULT_SUBMIT_TIMEOUT);
+            let SubmitResult::Timeout { tx_hash } = result else {
+                panic!("expected a timeout, got {result:?}");
+            };
+
+            // Once the block production resumes, the same transaction is included
+            let _: serde_json::Value =
+                client.backend.0.request("miner_start", ArrayParams::new()).await.unwrap();
+            let receipt = client
+                .backend
+                .wait_for_transaction_receipt(tx_hash, DEFAULT_SUBMIT_TIMEOUT, None)
+                .await
+                .unwrap();
+            assert_eq!(receipt.transaction_hash, tx_hash);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_digest() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::{
+            crypto::{Crypto, DefaultCrypto, RecoveryId, Signature},
+            Address, H256, U256,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-sign-digest", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let digest = DefaultCrypto::keccak256(b"order");
+            let signature = wallet.sign_digest(&digest.0).await.unwrap();
+            assert_eq!(signature.len(), 65);
+
+            let signature = Signature {
+                v: RecoveryId::new(u64::from(signature[64])),
+                r: U256::from_big_endian(&signature[0..32]),
+                s: U256::from_big_endian(&signature[32..64]),
+            };
+            let signer = DefaultCrypto::secp256k1_ecdsa_recover(&signature, digest).unwrap();
+            let address: Address = wallet.account().address.parse().unwrap();
+            assert_eq!(signer, address);
+
+            // The signature doesn't recover the signer of another digest
+            let other = H256::repeat_byte(0x42);
+            let recovered = DefaultCrypto::secp256k1_ecdsa_recover(&signature, other);
+            assert_ne!(recovered.ok(), Some(address));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compute_tx_hash() -> Result<()> {
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_config_ethereum::{compute_tx_hash, ext::types::H160};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-compute-tx-hash", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let params = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                destination: Some(H160::repeat_byte(0x42).0),
+                amount: [1, 0, 0, 0],
+                data: Vec::new(),
+                nonce: None,
+                gas_limit: None,
+                auto_access_list: false,
+            });
+            let transaction = wallet.sign_transaction(&params).await.unwrap();
+            let tx_hash = compute_tx_hash(&transaction).unwrap();
+
+            let result = env.node().submit(&transaction).await.unwrap();
+            assert_eq!(result.tx_hash(), tx_hash);
+            assert_eq!(result.receipt().unwrap().transaction_hash, tx_hash);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nonce_gap_recovery() -> Result<()> {
+        use rosetta_client::{
+            client::GenericMetadataParams, NonceGap, NonceGapStrategy, NonceManagerConfig,
+        };
+        use rosetta_config_ethereum::{ext::types::H160, AtBlock};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-nonce-gap", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet =
+                env.ephemeral_wallet().await.unwrap().with_nonce_manager(NonceManagerConfig {
+                    gap_tolerance: 1,
+                    strategy: NonceGapStrategy::Rebroadcast,
+                });
+            wallet.faucet(faucet, None).await.unwrap();
+
+            let transfer = || {
+                GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                    destination: Some(H160::repeat_byte(0x42).0),
+                    amount: [1, 0, 0, 0],
+                    data: Vec::new(),
+                    nonce: None,
+                    gas_limit: None,
+                    auto_access_list: false,
+                })
+            };
+            let first = wallet.sign_with_auto_nonce(transfer()).await.unwrap();
+            let dropped = wallet.sign_with_auto_nonce(transfer()).await.unwrap();
+            let last = wallet.sign_with_auto_nonce(transfer()).await.unwrap();
+            wallet.submit(&first).await.unwrap();
+
+            // The last transaction is stuck behind the dropped one until the gap is recovered
+            let (result, gap) = futures_util::join!(wallet.submit(&last), async {
+                loop {
+                    if let Some(gap) = wallet.check_nonce_gap().await.unwrap() {
+                        break gap;
+                    }
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                }
+            });
+            assert_eq!(gap, NonceGap::Rebroadcast { nonce: 1, transaction: dropped });
+            assert!(result.unwrap().receipt().is_some());
+            assert_eq!(wallet.nonce_at(AtBlock::Latest).await.unwrap(), 3);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_nonce_manager() -> Result<()> {
+        use rosetta_client::NonceManagerConfig;
+        use rosetta_config_ethereum::AtBlock;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-shared-nonce", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let value = u128::pow(10, config.currency_decimals);
+            let wallet = env
+                .ephemeral_wallet()
+                .await
+                .unwrap()
+                .with_nonce_manager(NonceManagerConfig::default());
+            let bob = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(100 * value, None).await.unwrap();
+
+            // Every clone submits concurrently, the shared nonce manager gives each a new nonce
+            let tasks = (0..5)
+                .map(|_| {
+                    let wallet = wallet.clone();
+                    let bob = bob.account().clone();
+                    tokio::spawn(async move { wallet.transfer(&bob, value, None, None).await })
+                })
+                .collect::<Vec<_>>();
+            let mut tx_hashes = BTreeSet::new();
+            for task in tasks {
+                let result = task.await.unwrap().unwrap();
+                assert!(result.receipt().is_some());
+                tx_hashes.insert(result.tx_hash());
+            }
+            assert_eq!(tx_hashes.len(), 5);
+            assert_eq!(wallet.nonce_at(AtBlock::Latest).await.unwrap(), 5);
+            assert_eq!(bob.balance().await.unwrap(), 5 * value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_code_size_exceeded() -> Result<()> {
+        use rosetta_config_ethereum::AtBlock;
+
+        /// Init code deploying `size` zero bytes: `PUSH3 size PUSH1 0 RETURN`
+        fn init_code(size: usize) -> Vec<u8> {
+            let size = u32::try_from(size).unwrap().to_be_bytes();
+            vec![0x62, size[1], size[2], size[3], 0x60, 0x00, 0xf3]
+        }
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-code-size-exceeded", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(faucet, None).await.unwrap();
+            let nonce = wallet.nonce_at(AtBlock::Latest).await.unwrap();
+
+            // Rejected before broadcasting
+            let error = wallet.eth_deploy_contract(init_code(MAX_CODE_SIZE + 1)).await.unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<CodeSizeExceeded>(),
+                Some(&CodeSizeExceeded { size: MAX_CODE_SIZE + 1, limit: MAX_CODE_SIZE })
+            );
+            assert_eq!(wallet.nonce_at(AtBlock::Latest).await.unwrap(), nonce);
+
+            let receipt = wallet.eth_deploy_contract(init_code(100)).await.unwrap();
+            assert!(receipt.receipt().is_some());
+
+            // The limit is configurable
+            let wallet = wallet.with_max_code_size(50);
+            let error = wallet.eth_deploy_contract(init_code(100)).await.unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<CodeSizeExceeded>(),
+                Some(&CodeSizeExceeded { size: 100, limit: 50 })
+            );
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_transferable() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-max-transferable", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let amount = alice.max_transferable().await.unwrap();
+            assert!(amount > 0 && amount < faucet);
+            // One unit more can't pay the fee
+            assert!(alice.transfer(bob.account(), amount + 1, None, None).await.is_err());
+            alice.transfer(bob.account(), amount, None, None).await.unwrap();
+            assert_eq!(bob.balance().await.unwrap(), amount);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transfer_label() -> Result<()> {
+        use std::sync::{Arc, Mutex};
+        use tracing::{
+            field::{Field, Visit},
+            instrument::WithSubscriber,
+            span::{Attributes, Id},
+            Subscriber,
+        };
+        use tracing_subscriber::{
+            layer::{Context, SubscriberExt},
+            Layer,
+        };
+
+        /// Records the `label` of the `transfer` spans.
+        #[derive(Clone, Default)]
+        struct LabelRecorder(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for LabelRecorder {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                if field.name() == "label" {
+                    self.0.lock().unwrap().push(value.to_string());
+                }
+            }
+
+            fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+        }
+
+        impl<S: Subscriber> Layer<S> for LabelRecorder {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                if attrs.metadata().name() == "transfer" {
+                    attrs.record(&mut self.clone());
+                }
+            }
+        }
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-transfer-label", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let recorder = LabelRecorder::default();
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let labeled = alice
+                .transfer_labeled("invoice-42", bob.account(), 1, None, None)
+                .with_subscriber(tracing_subscriber::registry().with(recorder.clone()))
+                .await
+                .unwrap();
+            assert_eq!(labeled.label, "invoice-42");
+            assert!(labeled.result.receipt().is_some());
+            assert_eq!(*recorder.0.lock().unwrap(), vec!["invoice-42".to_string()]);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transfer_progress() -> Result<()> {
+        use rosetta_client::TxProgress;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-transfer-progress", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let mut progress = Vec::new();
+            let result = alice
+                .transfer_with_progress(bob.account(), 1, None, None, |event| progress.push(event))
+                .await
+                .unwrap();
+            let tx_hash = result.tx_hash();
+            let receipt = result.receipt().unwrap();
+            let block = BlockIdentifier::new(receipt.block_number.unwrap(), receipt.block_hash.0);
+            assert_eq!(
+                progress,
+                vec![
+                    TxProgress::Broadcast { tx_hash },
+                    TxProgress::Included { tx_hash, block: block.clone() },
+                    TxProgress::Finalized { tx_hash, block: block.clone() },
+                ]
+            );
+            assert!(env.node().finalized_block().await.unwrap().index >= block.index);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submission_limit() -> Result<()> {
+        use rosetta_client::SubmissionLimit;
+        use rosetta_config_ethereum::AtBlock;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-submission-limit", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet = env
+                .ephemeral_wallet()
+                .await
+                .unwrap()
+                .with_submission_limit(SubmissionLimit { max_in_flight: 1, max_queued: 4 });
+            wallet.faucet(faucet, None).await.unwrap();
+            let nonce = wallet.nonce_at(AtBlock::Latest).await.unwrap();
+
+            // Fires more transfers than the limit, the excess waits for a free slot
+            let destination = env.ephemeral_wallet().await.unwrap();
+            let results = futures_util::future::join_all(
+                (0..5).map(|_| wallet.transfer(destination.account(), 1, None, None)),
+            )
+            .await;
+            for result in results {
+                assert!(result.unwrap().receipt().is_some());
+            }
+            assert_eq!(wallet.nonce_at(AtBlock::Latest).await.unwrap(), nonce + 5);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block_transactions_fee() -> Result<()> {
+        use rosetta_config_ethereum::{query::GetBlock, AtBlock};
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-block-transactions-fee", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
 
             let bytes = compile_snippet(
                 r"
-                function identity(bool a) public view returns (bool) {
-                    return a;
-                }
-            ",
+                    event AnEvent();
+                    function emitEvent() public {
+                        emit AnEvent();
+                    }
+                ",
             )
             .unwrap();
             let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
             let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
-            let contract_address = receipt.contract_address.unwrap();
+            let block = AtBlock::from(receipt.block_hash);
+            let transactions = client.block_transactions(block).await.unwrap();
+            let tx = transactions
+                .iter()
+                .find(|tx| {
+                    tx.transaction_identifier.hash == format!("{:?}", receipt.transaction_hash)
+                })
+                .unwrap();
+            assert!(tx.success);
+            assert_eq!(tx.index, receipt.transaction_index);
 
-            let response = {
-                let call = TestContract::identityCall { a: true };
-                wallet
-                    .eth_view_call(contract_address.0, call.abi_encode(), AtBlock::Latest)
+            let fee = receipt.gas_used.unwrap() * receipt.effective_gas_price.unwrap();
+            let fee_op = tx.operations.iter().find(|op| op.r#type == "FEE").unwrap();
+            assert_eq!(fee_op.amount.as_ref().unwrap().value, format!("-{fee}"));
+            assert_eq!(fee_op.status.as_deref(), Some(OPERATION_STATUS_SUCCESS));
+            assert!(fee_op
+                .account
+                .as_ref()
+                .unwrap()
+                .address
+                .eq_ignore_ascii_case(&wallet.account().address));
+
+            // The dev chain is post london, so part of the fee is burnt
+            let burn_op = tx.operations.iter().find(|op| op.r#type == "FEE_BURN").unwrap();
+            let burnt: u128 = burn_op.amount.as_ref().unwrap().value.parse().unwrap();
+            assert!(burnt > 0 && burnt <= fee.as_u128());
+            assert!(burn_op.account.is_none());
+
+            // The tip is credited to the block's fee recipient, balancing the fee
+            let reward_op = tx.operations.iter().find(|op| op.r#type == "FEE_REWARD").unwrap();
+            let reward: u128 = reward_op.amount.as_ref().unwrap().value.parse().unwrap();
+            assert_eq!(burnt + reward, fee.as_u128());
+            assert_eq!(reward_op.status.as_deref(), Some(OPERATION_STATUS_SUCCESS));
+            let sealed = wallet.query(GetBlock(block)).await.unwrap().unwrap();
+            assert_eq!(
+                reward_op.account.as_ref().unwrap().address,
+                format!("{:?}", sealed.header().header().beneficiary)
+            );
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block_receipts() -> Result<()> {
+        use crate::utils::EthereumRpcExt;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_config_ethereum::ext::types::{Bytes, H160};
+        use rosetta_ethereum_backend::{
+            jsonrpsee::core::{client::ClientT, params::ArrayParams},
+            EthereumRpc,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-block-receipts", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(faucet, None).await.unwrap();
+            let address: H160 = wallet.account().address.parse().unwrap();
+            let nonce =
+                client.backend.get_transaction_count(address, AtBlock::Latest).await.unwrap();
+
+            // Pause the block production, so all the transactions are included in the same block
+            let _: serde_json::Value =
+                client.backend.0.request("miner_stop", ArrayParams::new()).await.unwrap();
+            let mut tx_hashes = Vec::new();
+            for i in 0..3 {
+                let params = GenericMetadataParams::Ethereum(EthereumMetadataParams {
+                    destination: Some(H160::repeat_byte(0x42).0),
+                    amount: [1, 0, 0, 0],
+                    data: Vec::new(),
+                    nonce: Some(nonce + i),
+                    gas_limit: None,
+                    auto_access_list: false,
+                });
+                let transaction = wallet.sign_transaction(&params).await.unwrap();
+                let tx_hash =
+                    client.backend.send_raw_transaction(Bytes::from(transaction)).await.unwrap();
+                tx_hashes.push(tx_hash);
+            }
+            let _: serde_json::Value =
+                client.backend.0.request("miner_start", ArrayParams::new()).await.unwrap();
+
+            let mut blocks = Vec::new();
+            for tx_hash in &tx_hashes {
+                let receipt = client
+                    .backend
+                    .wait_for_transaction_receipt(*tx_hash, DEFAULT_SUBMIT_TIMEOUT, None)
                     .await
-                    .unwrap()
-            };
+                    .unwrap();
+                blocks.push(receipt.block_hash);
+            }
+            blocks.dedup();
+            assert_eq!(blocks.len(), 1, "transactions were included in different blocks");
+            let at = AtBlock::At(blocks[0].into());
+
+            let receipts = client.block_receipts(at).await.unwrap();
+            let hashes =
+                receipts.iter().map(|receipt| receipt.transaction_hash).collect::<Vec<_>>();
+            assert_eq!(hashes, tx_hashes);
+
+            // The fallback returns the same receipts
+            assert_eq!(client.batched_block_receipts(at).await.unwrap(), receipts);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_effective_gas_price() -> Result<()> {
+        use rosetta_config_ethereum::ext::types::U256;
+        use rosetta_ethereum_backend::EthereumRpc;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-effective-gas-price", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let client = ws_client(env);
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            let result = alice.transfer(bob.account(), value, None, None).await.unwrap();
+            let effective_gas_price = result.effective_gas_price().unwrap();
+
+            // The transfer is an EIP-1559 transaction, so it never pays more than the fee cap
+            let tx = client.backend.transaction_by_hash(result.tx_hash()).await.unwrap().unwrap();
+            let max_fee_per_gas = tx.max_fee_per_gas.unwrap();
+            assert!(effective_gas_price > U256::zero());
+            assert!(effective_gas_price <= max_fee_per_gas);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_block() -> Result<()> {
+        use rosetta_core::BlockTimeout;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-wait-for-block", config, client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let head = client.current_block().await.unwrap().index;
+            let block = client.wait_for_block(head + 3, Duration::from_secs(30)).await.unwrap();
+            assert_eq!(block.index, head + 3);
+
+            // Blocks already produced are returned immediately
+            let block = client.wait_for_block(head, Duration::from_secs(1)).await.unwrap();
+            assert!(block.index >= head + 3);
+
+            let error =
+                client.wait_for_block(head + 1000, Duration::from_secs(1)).await.unwrap_err();
+            let timeout = error.downcast_ref::<BlockTimeout>().unwrap();
+            assert_eq!(timeout.height, head + 1000);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_genesis_accounts() -> Result<()> {
+        use rosetta_config_ethereum::{ext::types::H160, GenesisAccount};
+
+        let balance = 42 * u128::pow(10, 18);
+        let account = GenesisAccount::new(H160::repeat_byte(0x42), balance);
+        let config = rosetta_config_ethereum::dev_config_with_genesis_accounts(&[account]);
+        let env = Env::new("ethereum-genesis-accounts", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let address = Address::new(
+                config.address_format,
+                "0x4242424242424242424242424242424242424242".into(),
+            );
+            let genesis = PartialBlockIdentifier::by_number(0);
+            let actual = env.node().balance(&address, &genesis).await.unwrap();
+            assert_eq!(actual, balance);
+            let earliest = PartialBlockIdentifier::earliest();
+            assert_eq!(env.node().balance(&address, &earliest).await.unwrap(), balance);
+
+            // The dev signer still funds the ephemeral wallets
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(u128::pow(10, 18), None).await.unwrap();
+            assert_eq!(wallet.balance().await.unwrap(), u128::pow(10, 18));
+
+            // The funds didn't exist at the earliest block
+            let address = Address::new(config.address_format, wallet.account().address.clone());
+            assert_eq!(env.node().balance(&address, &earliest).await.unwrap(), 0);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_verify_signature_1271() -> Result<()> {
+        use rosetta_client::crypto::{Algorithm, SecretKey};
+        use rosetta_config_ethereum::ext::types::H160;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-verify-1271", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            // Mock contract wallet which only accepts the signature `0x01`
+            let bytes = compile_snippet(
+                r"
+                    function isValidSignature(bytes32, bytes memory signature) public pure returns (bytes4) {
+                        if (signature.length == 1 && signature[0] == 0x01) {
+                            return 0x1626ba7e;
+                        }
+                        return 0xffffffff;
+                    }
+                ",
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(bytes).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let contract = receipt.contract_address.unwrap();
+
+            let hash = H256(sha3::Keccak256::digest("hello").into());
+            assert!(wallet.verify_signature_1271(contract, hash, &[0x01]).await.unwrap());
+            assert!(!wallet.verify_signature_1271(contract, hash, &[0x02]).await.unwrap());
+
+            // Falls back to ecrecover when the signer has no code
+            let secret_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let signer: H160 = secret_key
+                .public_key()
+                .to_address(config.address_format)
+                .address()
+                .parse()
+                .unwrap();
+            let signature = secret_key.sign_prehashed(hash.as_bytes()).unwrap().to_bytes();
+            assert!(wallet.verify_signature_1271(signer, hash, &signature).await.unwrap());
+            assert!(!wallet.verify_signature_1271(contract, hash, &signature).await.unwrap());
+            let other = H256(sha3::Keccak256::digest("world").into());
+            assert!(!wallet.verify_signature_1271(signer, other, &signature).await.unwrap());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_resolve_proxy_implementation() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-proxy", config.clone(), client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            let implementation = compile_snippet(
+                r"
+                    event AnEvent();
+                    function emitEvent() public {
+                        emit AnEvent();
+                    }
+                ",
+            )
+            .unwrap();
+            // Minimal proxy which only stores the implementation in the EIP-1967 slot
+            let proxy = compile_snippet(
+                r"
+                    bytes32 constant IMPLEMENTATION_SLOT =
+                        0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+                    function upgradeTo(address implementation) public {
+                        bytes32 slot = IMPLEMENTATION_SLOT;
+                        assembly {
+                            sstore(slot, implementation)
+                        }
+                    }
+                ",
+            )
+            .unwrap();
+            let mut addresses = Vec::with_capacity(2);
+            for bytecode in [implementation, proxy] {
+                let tx_hash = wallet.eth_deploy_contract(bytecode).await.unwrap().tx_hash().0;
+                let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+                addresses.push(receipt.contract_address.unwrap());
+            }
+            let (implementation, proxy) = (addresses[0], addresses[1]);
+
+            assert!(!wallet.get_code(implementation).await.unwrap().is_empty());
+            assert!(wallet
+                .get_code(rosetta_config_ethereum::Address::repeat_byte(0x42))
+                .await
+                .unwrap()
+                .is_empty());
+            assert_eq!(wallet.resolve_proxy_implementation(proxy).await.unwrap(), None);
+
+            let call = Proxy::upgradeToCall { implementation: implementation.0.into() };
+            wallet.eth_send_call(proxy.0, call.abi_encode(), 0, None, None).await.unwrap();
             assert_eq!(
-                response,
-                CallResult::Success(
-                    [
-                        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                        0, 0, 0, 0, 0, 0, 1
-                    ]
-                    .to_vec()
-                )
+                wallet.resolve_proxy_implementation(proxy).await.unwrap(),
+                Some(implementation)
+            );
+            assert_eq!(wallet.resolve_proxy_implementation(implementation).await.unwrap(), None);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(clippy::needless_raw_string_hashes)]
+    async fn test_resolve_proxy_admin() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-proxy-admin", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            wallet
+                .faucet(100 * u128::pow(10, config.currency_decimals), None)
+                .await
+                .unwrap();
+
+            // Transparent proxy administrated by its deployer
+            let proxy = compile_snippet(
+                r"
+                    bytes32 constant ADMIN_SLOT =
+                        0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103;
+                    constructor() {
+                        bytes32 slot = ADMIN_SLOT;
+                        address admin = msg.sender;
+                        assembly {
+                            sstore(slot, admin)
+                        }
+                    }
+                ",
+            )
+            .unwrap();
+            let tx_hash = wallet.eth_deploy_contract(proxy).await.unwrap().tx_hash().0;
+            let receipt = wallet.eth_transaction_receipt(tx_hash).await.unwrap().unwrap();
+            let proxy = receipt.contract_address.unwrap();
+
+            let admin: rosetta_config_ethereum::Address = wallet.account().address.parse().unwrap();
+            assert_eq!(wallet.resolve_proxy_admin(proxy).await.unwrap(), Some(admin));
+            assert_eq!(wallet.resolve_beacon(proxy).await.unwrap(), None);
+            assert_eq!(wallet.resolve_proxy_implementation(proxy).await.unwrap(), None);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multi_chain_balances() -> Result<()> {
+        use rosetta_client::{
+            client::GenericClient,
+            crypto::bip39::{Language, Mnemonic},
+            Blockchain, MultiChainWallet, Wallet,
+        };
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-multi-chain", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let polygon_config = rosetta_config_ethereum::polygon_config("dev").unwrap();
+            let polygon_env =
+                Env::new("ethereum-multi-chain-polygon", polygon_config, client_from_config)
+                    .await
+                    .unwrap();
+
+            // Both wallets are derived from the same mnemonic
+            let mnemonic = Mnemonic::from_entropy_in(Language::English, &[0x42; 32]).unwrap();
+            let mut wallets = Vec::with_capacity(2);
+            for (blockchain, env) in
+                [(Blockchain::Ethereum, &*env), (Blockchain::Polygon, &polygon_env)]
+            {
+                let config = env.node().config().clone();
+                let url = config.node_uri.to_string();
+                let client = GenericClient::from_config(config, &url, None).await.unwrap();
+                wallets.push((blockchain, Wallet::from_mnemonic(client, &mnemonic).unwrap()));
+            }
+            assert_eq!(wallets[0].1.account().address, wallets[1].1.account().address);
+            let eth_value = u128::pow(10, 18);
+            let polygon_value = 2 * eth_value;
+            wallets[0].1.faucet(eth_value, None).await.unwrap();
+            wallets[1].1.faucet(polygon_value, None).await.unwrap();
+
+            let wallet = MultiChainWallet::from_wallets(wallets);
+            let balances = wallet
+                .balances()
+                .await
+                .into_iter()
+                .map(|(blockchain, balance)| (blockchain, balance.unwrap()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                balances,
+                vec![(Blockchain::Ethereum, eth_value), (Blockchain::Polygon, polygon_value)]
             );
+
+            polygon_env.shutdown().await.unwrap();
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer() -> Result<()> {
+        use rosetta_client::{
+            client::GenericClient,
+            crypto::{Algorithm, PublicKey, SecretKey, Signature},
+            RemoteSigner, Wallet,
+        };
+        use rosetta_config_ethereum::ext::types::H160;
+
+        /// Signer which never exposes its secret key to the wallet, like a hardware wallet.
+        struct MockSigner {
+            secret_key: SecretKey,
+        }
+
+        #[async_trait::async_trait]
+        impl RemoteSigner for MockSigner {
+            async fn sign(&self, payload: &[u8], algorithm: Algorithm) -> Result<Signature> {
+                assert_eq!(algorithm, Algorithm::EcdsaRecoverableSecp256k1);
+                let signature = self.secret_key.sign_prehashed(payload)?;
+                assert_eq!(signature.recover_prehashed(payload)?, Some(self.public_key()));
+                Ok(signature)
+            }
+
+            fn public_key(&self) -> PublicKey {
+                self.secret_key.public_key()
+            }
+        }
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-remote-signer", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let url = config.node_uri.to_string();
+            let client = GenericClient::from_config(config.clone(), &url, None).await.unwrap();
+            let secret_key =
+                SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+            let alice = Wallet::from_signer(client, MockSigner { secret_key }).unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            let result = alice.transfer(bob.account(), value, None, None).await.unwrap();
+            let receipt = result.receipt().unwrap();
+            let alice_address: H160 = alice.account().address.parse().unwrap();
+            assert_eq!(receipt.from, Some(alice_address));
+            assert_eq!(bob.balance().await.unwrap(), value);
         })
         .await;
         Ok(())
@@ -427,8 +3630,6 @@ mod tests {
 
     #[tokio::test]
     async fn test_subscription() -> Result<()> {
-        use futures_util::StreamExt;
-        use rosetta_core::{BlockOrIdentifier, ClientEvent};
         let config = rosetta_config_ethereum::config("dev").unwrap();
         let env = Env::new("ethereum-subscription", config.clone(), client_from_config)
             .await
@@ -464,4 +3665,62 @@ mod tests {
         .await;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_listen_finalized() -> Result<()> {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-listen-finalized", config.clone(), client_from_config)
+            .await
+            .unwrap();
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let mut stream = std::pin::pin!(wallet.listen_finalized().await.unwrap());
+
+            let mut last_finalized: Option<u64> = None;
+            for _ in 0..5 {
+                let finalized = stream.next().await.unwrap();
+                if let Some(block_number) = last_finalized {
+                    assert!(finalized.index > block_number);
+                }
+                // Never yields a head which isn't finalized yet
+                assert!(finalized.index <= wallet.status().await.unwrap().index);
+                last_finalized = Some(finalized.index);
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_force_ws_transport() -> Result<()> {
+        use rosetta_core::ClientEvent;
+
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let env = Env::new("ethereum-force-ws", config.clone(), client_from_config).await.unwrap();
+
+        run_test(env, |env| async move {
+            let config = env.node().config().clone();
+            let url = config.node_uri.with_scheme("http").to_string();
+
+            // The transport is detected from the url scheme by default
+            let client =
+                MaybeWsEthereumClient::from_config(config.clone(), &url, None, Transport::Auto)
+                    .await
+                    .unwrap();
+            assert!(matches!(client, MaybeWsEthereumClient::Http(_)));
+            assert!(client.listen().await.unwrap().is_none());
+
+            // The same endpoint also accepts websocket connections
+            let client = MaybeWsEthereumClient::from_config(config, &url, None, Transport::Ws)
+                .await
+                .unwrap();
+            assert!(matches!(client, MaybeWsEthereumClient::Ws(_)));
+            let mut stream = client.listen().await.unwrap().unwrap();
+            let event = stream.next().await.unwrap();
+            assert!(matches!(event, ClientEvent::NewHead(_) | ClientEvent::NewFinalized(_)));
+        })
+        .await;
+        Ok(())
+    }
 }
@@ -0,0 +1,164 @@
+use futures_util::{StreamExt, TryStreamExt};
+use rosetta_config_ethereum::ext::types::{Bloom, BloomInput, H160, H256};
+use std::{future::Future, ops::RangeInclusive};
+
+/// Maximum number of block headers fetched concurrently while prefiltering a range.
+pub const BLOOM_FETCH_CONCURRENCY: usize = 16;
+
+/// Returns `false` if the `logsBloom` of a block proves it has no log emitted by one of
+/// `contracts` containing all `topics`, an empty `contracts` matches any address.
+///
+/// Bloom filters have false positives, so `true` only means the block may contain a match.
+pub fn bloom_may_contain(bloom: &Bloom, contracts: &[H160], topics: &[H256]) -> bool {
+    let has_contract = contracts.is_empty() ||
+        contracts
+            .iter()
+            .any(|contract| bloom.contains_input(BloomInput::Raw(contract.as_bytes())));
+    has_contract &&
+        topics
+            .iter()
+            .all(|topic| bloom.contains_input(BloomInput::Raw(topic.as_bytes())))
+}
+
+/// Scans the blocks between `from` and `to` (inclusive) for logs matching `contracts` and
+/// `topics`, the `logsBloom` of each block is fetched with `get_bloom` first, and `get_logs` is
+/// only called for the contiguous ranges of blocks that may contain a match.
+pub async fn scan_logs<T, E, B, BFut, L, LFut>(
+    from: u64,
+    to: u64,
+    contracts: &[H160],
+    topics: &[H256],
+    get_bloom: B,
+    get_logs: L,
+) -> Result<Vec<T>, E>
+where
+    B: Fn(u64) -> BFut,
+    BFut: Future<Output = Result<Bloom, E>>,
+    L: Fn(u64, u64) -> LFut,
+    LFut: Future<Output = Result<Vec<T>, E>>,
+{
+    let candidates = futures_util::stream::iter(from..=to)
+        .map(|number| {
+            let bloom = get_bloom(number);
+            async move { Ok::<_, E>((number, bloom.await?)) }
+        })
+        .buffered(BLOOM_FETCH_CONCURRENCY)
+        .try_filter_map(|(number, bloom)| async move {
+            Ok(bloom_may_contain(&bloom, contracts, topics).then_some(number))
+        })
+        .try_collect::<Vec<u64>>()
+        .await?;
+
+    let mut logs = Vec::new();
+    for range in candidate_ranges(candidates) {
+        logs.extend(get_logs(*range.start(), *range.end()).await?);
+    }
+    Ok(logs)
+}
+
+/// Merges sorted block numbers into contiguous ranges, ex: `[1, 2, 3, 7]` into `[1..=3, 7..=7]`.
+fn candidate_ranges(blocks: Vec<u64>) -> Vec<RangeInclusive<u64>> {
+    let mut ranges: Vec<RangeInclusive<u64>> = Vec::new();
+    for number in blocks {
+        match ranges.last_mut() {
+            Some(range) if range.end().checked_add(1) == Some(number) => {
+                *range = *range.start()..=number;
+            },
+            _ => ranges.push(number..=number),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hashbrown::HashMap;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    struct CountingBackend {
+        blooms: HashMap<u64, Bloom>,
+        header_calls: AtomicUsize,
+        logs_calls: Mutex<Vec<(u64, u64)>>,
+    }
+
+    impl CountingBackend {
+        #[allow(clippy::unused_async)]
+        async fn bloom(&self, number: u64) -> Result<Bloom, ()> {
+            self.header_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.blooms.get(&number).copied().unwrap_or_default())
+        }
+
+        #[allow(clippy::unused_async)]
+        async fn logs(&self, from: u64, to: u64) -> Result<Vec<u64>, ()> {
+            self.logs_calls.lock().unwrap().push((from, to));
+            Ok((from..=to).collect())
+        }
+    }
+
+    #[test]
+    fn candidate_ranges_are_merged() {
+        assert!(candidate_ranges(Vec::new()).is_empty());
+        assert_eq!(candidate_ranges(vec![1, 2, 3, 7, 9, 10]), vec![1..=3, 7..=7, 9..=10]);
+    }
+
+    #[tokio::test]
+    async fn scan_skips_blocks_without_matching_logs() {
+        let contract = H160::repeat_byte(0x01);
+        let topic = H256::repeat_byte(0x02);
+
+        // Only block 42 contains a log emitted by `contract` with `topic`
+        let mut matching = Bloom::default();
+        matching.accrue(BloomInput::Raw(contract.as_bytes()));
+        matching.accrue(BloomInput::Raw(topic.as_bytes()));
+        // Block 43 has a log from `contract`, but with a different topic
+        let mut other_topic = Bloom::default();
+        other_topic.accrue(BloomInput::Raw(contract.as_bytes()));
+        other_topic.accrue(BloomInput::Raw(H256::repeat_byte(0x03).as_bytes()));
+
+        let backend = CountingBackend {
+            blooms: [(42, matching), (43, other_topic)].into_iter().collect(),
+            header_calls: AtomicUsize::new(0),
+            logs_calls: Mutex::new(Vec::new()),
+        };
+        let logs = scan_logs(
+            0,
+            99,
+            &[contract],
+            &[topic],
+            |number| backend.bloom(number),
+            |from, to| backend.logs(from, to),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(logs, vec![42]);
+        assert_eq!(backend.header_calls.load(Ordering::SeqCst), 100);
+        assert_eq!(*backend.logs_calls.lock().unwrap(), vec![(42, 42)]);
+    }
+
+    #[tokio::test]
+    async fn empty_range_queries_nothing() {
+        let backend = CountingBackend {
+            blooms: HashMap::new(),
+            header_calls: AtomicUsize::new(0),
+            logs_calls: Mutex::new(Vec::new()),
+        };
+        let logs = scan_logs(
+            10,
+            9,
+            &[],
+            &[],
+            |number| backend.bloom(number),
+            |from, to| backend.logs(from, to),
+        )
+        .await
+        .unwrap();
+        assert!(logs.is_empty());
+        assert_eq!(backend.header_calls.load(Ordering::SeqCst), 0);
+        assert!(backend.logs_calls.lock().unwrap().is_empty());
+    }
+}
@@ -256,7 +256,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::MaybeWsEthereumClient;
+    use crate::{MaybeWsEthereumClient, Transport};
     use futures_util::StreamExt;
     use rosetta_core::BlockchainConfig;
     use rosetta_docker::{run_test, Env};
@@ -266,7 +266,7 @@ mod tests {
         config: BlockchainConfig,
     ) -> anyhow::Result<MaybeWsEthereumClient> {
         let url = config.node_uri.to_string();
-        MaybeWsEthereumClient::from_config(config, url.as_str(), None).await
+        MaybeWsEthereumClient::from_config(config, url.as_str(), None, Transport::Auto).await
     }
 
     struct TestSubscriber<RPC>(RPC);
@@ -0,0 +1,146 @@
+use rosetta_ethereum_backend::jsonrpsee::core::ClientError;
+use std::{future::Future, time::Duration};
+use tokio_retry::{strategy::jitter, RetryIf};
+
+/// Default number of attempts of a json-rpc call, including the first one.
+pub const DEFAULT_RETRY_ATTEMPTS: usize = 3;
+
+/// Default delay before the first retry of a json-rpc call.
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Error code returned by hosted providers when the request rate limit is exceeded.
+const LIMIT_EXCEEDED_ERROR_CODE: i32 = -32005;
+
+/// Policy to retry the json-rpc calls failing with a transient transport error, ex: a connection
+/// reset or a HTTP 429 response. The delay doubles after each retry. Other errors, ex: reverts or
+/// invalid params, are returned immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one. Zero and one disable the retries.
+    pub max_attempts: usize,
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Picks each delay at random below its nominal value, so the clients which failed together
+    /// don't retry together.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RETRY_ATTEMPTS,
+            base_delay: DEFAULT_RETRY_BASE_DELAY,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the first error as is, without retrying.
+    #[must_use]
+    pub const fn fail_fast() -> Self {
+        Self { max_attempts: 1, base_delay: Duration::ZERO, jitter: false }
+    }
+
+    /// Returns the delays before each retry.
+    fn delays(self) -> impl Iterator<Item = Duration> {
+        (0..self.max_attempts.saturating_sub(1)).map(move |retry| {
+            let delay = self.base_delay.saturating_mul(1 << retry.min(16));
+            if self.jitter {
+                jitter(delay)
+            } else {
+                delay
+            }
+        })
+    }
+
+    /// Calls `action` until it succeeds, fails with an error which isn't retryable, or the
+    /// attempts are exhausted.
+    pub(crate) async fn retry<T, F, Fut>(self, action: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        RetryIf::spawn(self.delays(), action, |error: &anyhow::Error| {
+            let retryable = is_retryable(error);
+            if retryable {
+                tracing::warn!("retrying json-rpc call after a transient error: {error:#}");
+            }
+            retryable
+        })
+        .await
+    }
+}
+
+/// Returns `true` if `error` is caused by a transient transport error.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|error| error.downcast_ref::<ClientError>())
+        .any(|error| match error {
+            ClientError::Transport(_) |
+            ClientError::RestartNeeded(_) |
+            ClientError::RequestTimeout => true,
+            ClientError::Call(error) => error.code() == LIMIT_EXCEEDED_ERROR_CODE,
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const POLICY: RetryPolicy =
+        RetryPolicy { max_attempts: 3, base_delay: Duration::from_millis(1), jitter: false };
+
+    #[test]
+    fn delays_double_after_each_retry() {
+        let policy = RetryPolicy { base_delay: Duration::from_millis(100), ..POLICY };
+        assert_eq!(
+            policy.delays().collect::<Vec<_>>(),
+            vec![Duration::from_millis(100), Duration::from_millis(200)]
+        );
+        assert_eq!(RetryPolicy::fail_fast().delays().count(), 0);
+        assert_eq!(RetryPolicy { max_attempts: 0, ..POLICY }.delays().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let attempts = AtomicUsize::new(0);
+        let result = POLICY
+            .retry(|| async {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(anyhow::Error::from(ClientError::RequestTimeout));
+                }
+                Ok(42)
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        // The last error is returned once the attempts are exhausted
+        let attempts = AtomicUsize::new(0);
+        let result = POLICY
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), anyhow::Error>(ClientError::RequestTimeout.into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_other_errors_immediately() {
+        let attempts = AtomicUsize::new(0);
+        let result = POLICY
+            .retry(|| async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), anyhow::Error>(ClientError::Custom("execution reverted".into()).into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
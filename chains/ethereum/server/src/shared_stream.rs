@@ -1,3 +1,4 @@
+use crate::event_stream::PauseHandle;
 use futures_util::{future::Shared, Future, FutureExt, Stream, StreamExt};
 use std::{
     pin::Pin,
@@ -13,6 +14,8 @@ where
 {
     inner: Inner<T>,
     stream: Option<BroadcastStream<<T as Stream>::Item>>,
+    /// Pauses the upstream stream, if it supports it
+    pause: Option<PauseHandle>,
 }
 
 impl<T> SharedStream<T>
@@ -24,7 +27,34 @@ where
     pub fn new(stream: T, capacity: usize) -> Self {
         let (tx, rx) = tokio::sync::broadcast::channel::<<T as Stream>::Item>(capacity);
         let inner = Inner::new(stream, tx);
-        Self { inner, stream: Some(BroadcastStream::new(rx)) }
+        Self { inner, stream: Some(BroadcastStream::new(rx)), pause: None }
+    }
+
+    /// Allows pausing the upstream stream with [`Self::pause`].
+    #[must_use]
+    pub(crate) fn with_pause_handle(mut self, pause: PauseHandle) -> Self {
+        self.pause = Some(pause);
+        self
+    }
+
+    /// Pause the upstream stream, so it isn't polled until [`Self::resume`] is called. The
+    /// upstream is shared, so all the clones of this stream are paused.
+    pub fn pause(&self) {
+        if let Some(pause) = self.pause.as_ref() {
+            pause.pause();
+        }
+    }
+
+    /// Resume the upstream stream.
+    pub fn resume(&self) {
+        if let Some(pause) = self.pause.as_ref() {
+            pause.resume();
+        }
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.pause.as_ref().is_some_and(PauseHandle::is_paused)
     }
 }
 
@@ -84,6 +114,7 @@ where
                 let receiver = channel.subscribe();
                 BroadcastStream::new(receiver)
             }),
+            pause: self.pause.clone(),
         }
     }
 }
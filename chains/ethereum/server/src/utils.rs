@@ -5,9 +5,9 @@ use rosetta_config_ethereum::{
     },
     AtBlock, CallResult, SubmitResult,
 };
-use rosetta_core::types::PartialBlockIdentifier;
+use rosetta_core::types::{BlockTag, PartialBlockIdentifier};
 use rosetta_ethereum_backend::{jsonrpsee::core::ClientError, EthereumRpc, ExitReason};
-use std::string::ToString;
+use std::{string::ToString, time::Duration};
 
 pub type FullBlock = SealedBlock<SignedTransaction<TypedTransaction>, SealedHeader>;
 pub type PartialBlock = SealedBlock<H256, H256>;
@@ -42,10 +42,12 @@ pub trait AtBlockExt {
 
 impl AtBlockExt for AtBlock {
     fn from_partial_identifier(block_identifier: &PartialBlockIdentifier) -> Self {
-        match (block_identifier.index, block_identifier.hash) {
-            (_, Some(hash)) => Self::from(hash),
-            (Some(index), None) => Self::from(index),
-            (None, None) => Self::Latest,
+        match (block_identifier.tag, block_identifier.index, block_identifier.hash) {
+            (Some(BlockTag::Finalized), _, _) => Self::Finalized,
+            (Some(BlockTag::Earliest), _, _) => Self::Earliest,
+            (None, _, Some(hash)) => Self::from(hash),
+            (None, Some(index), None) => Self::from(index),
+            (None, None, None) => Self::Latest,
         }
     }
 }
@@ -176,11 +178,116 @@ pub fn eip1559_default_estimator<F: FeeEstimatorConfig>(
     (max_fee_per_gas, max_priority_fee_per_gas)
 }
 
+/// Bound divisor of the base fee, which limits the amount the base fee can change between blocks.
+const EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+/// Elasticity multiplier, the gas target of a block is `gas_limit / EIP1559_ELASTICITY_MULTIPLIER`.
+const EIP1559_ELASTICITY_MULTIPLIER: u64 = 2;
+
+/// Computes the base fee of the next block as specified by EIP-1559, given the base fee, gas used
+/// and gas limit of the parent block.
+pub fn next_block_base_fee(base_fee_per_gas: U256, gas_used: u64, gas_limit: u64) -> U256 {
+    let gas_target = gas_limit / EIP1559_ELASTICITY_MULTIPLIER;
+    if gas_target == 0 || gas_used == gas_target {
+        return base_fee_per_gas;
+    }
+    let denominator = U256::from(gas_target) * EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR;
+    if gas_used > gas_target {
+        let gas_used_delta = U256::from(gas_used - gas_target);
+        let base_fee_delta =
+            std::cmp::max(base_fee_per_gas * gas_used_delta / denominator, U256::one());
+        base_fee_per_gas.saturating_add(base_fee_delta)
+    } else {
+        let gas_used_delta = U256::from(gas_target - gas_used);
+        let base_fee_delta = base_fee_per_gas * gas_used_delta / denominator;
+        base_fee_per_gas.saturating_sub(base_fee_delta)
+    }
+}
+
+/// Default time to wait for a transaction to be included in a block.
+pub const DEFAULT_SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time without new blocks after which a dev chain is considered stalled. The stall detection is
+/// disabled by default, because a few missed slots on a live chain would abort the submission of
+/// a transaction which is already broadcast, see [`EthereumClient::with_stall_timeout`].
+///
+/// [`EthereumClient::with_stall_timeout`]: crate::EthereumClient::with_stall_timeout
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Returned when the chain stops producing blocks while waiting for a transaction to be
+/// included, can be retrieved with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainStalled {
+    /// Hash of the transaction waiting for inclusion.
+    pub tx_hash: H256,
+    /// Latest block number observed.
+    pub block_number: u64,
+    /// Time elapsed without new blocks.
+    pub timeout: Duration,
+}
+
+impl std::fmt::Display for ChainStalled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chain stalled at block {} for more than {} seconds while waiting for {:?}",
+            self.block_number,
+            self.timeout.as_secs(),
+            self.tx_hash
+        )
+    }
+}
+
+impl std::error::Error for ChainStalled {}
+
+/// Returned when the gas estimation fails because the transaction would revert, the reason is
+/// retrieved by replaying the transaction with `eth_call`. Can be retrieved with
+/// [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WouldRevert {
+    /// Revert message, `None` if the revert data is not encoded as `Error(string)`.
+    pub reason: Option<String>,
+}
+
+impl std::fmt::Display for WouldRevert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "transaction would revert: {reason}"),
+            None => f.write_str("transaction would revert"),
+        }
+    }
+}
+
+impl std::error::Error for WouldRevert {}
+
+/// Maximum size of the code of a contract, defined by EIP-170.
+pub const MAX_CODE_SIZE: usize = 0x6000;
+
+/// Returned when a deployment is rejected before broadcasting because the contract code is
+/// larger than the limit, can be retrieved with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeSizeExceeded {
+    /// Size of the code the deployment would store.
+    pub size: usize,
+    /// Maximum code size accepted by the chain.
+    pub limit: usize,
+}
+
+impl std::fmt::Display for CodeSizeExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "contract code size {} exceeds the limit of {} bytes", self.size, self.limit)
+    }
+}
+
+impl std::error::Error for CodeSizeExceeded {}
+
 #[async_trait::async_trait]
 pub trait EthereumRpcExt {
     async fn wait_for_transaction_receipt(
         &self,
         tx_hash: H256,
+        timeout: Duration,
+        stall_timeout: Option<Duration>,
     ) -> anyhow::Result<TransactionReceipt>;
 
     async fn get_call_result(
@@ -203,23 +310,38 @@ where
     T: EthereumRpc<Error = ClientError> + Send + Sync + 'static,
 {
     // Wait for the transaction to be included in a block by polling the transaction receipt every 2
-    // seconds
+    // seconds, fails after `timeout`, or with `ChainStalled` if no new block is produced within
+    // `stall_timeout`.
     async fn wait_for_transaction_receipt(
         &self,
         tx_hash: H256,
+        timeout: Duration,
+        stall_timeout: Option<Duration>,
     ) -> anyhow::Result<TransactionReceipt> {
         let now = std::time::Instant::now();
-        let timeout = std::time::Duration::from_secs(30);
+        let mut best_block = <T as EthereumRpc>::block_number(self).await?;
+        let mut last_progress = now;
         let receipt = loop {
             let Some(receipt) = <T as EthereumRpc>::transaction_receipt(self, tx_hash).await?
             else {
-                if now.elapsed() > timeout {
+                if let Some(stall_timeout) = stall_timeout {
+                    let block_number = <T as EthereumRpc>::block_number(self).await?;
+                    if block_number > best_block {
+                        best_block = block_number;
+                        last_progress = std::time::Instant::now();
+                    } else if last_progress.elapsed() > stall_timeout {
+                        return Err(
+                            ChainStalled { tx_hash, block_number, timeout: stall_timeout }.into()
+                        );
+                    }
+                }
+                let Some(remaining) = timeout.checked_sub(now.elapsed()) else {
                     anyhow::bail!(
                         "Transaction not included in a block after {} seconds",
                         timeout.as_secs()
                     );
-                }
-                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                };
+                tokio::time::sleep(remaining.min(Duration::from_secs(2))).await;
                 continue;
             };
             break receipt;
@@ -368,4 +490,25 @@ mod tests {
 
         assert_eq!(actual, expect);
     }
+
+    #[test]
+    fn next_block_base_fee_follows_gas_usage() {
+        use super::next_block_base_fee;
+        use rosetta_config_ethereum::ext::types::U256;
+
+        let base_fee = U256::from(1_000_000_000u64);
+        let gas_limit = 30_000_000;
+
+        // Full block, base fee increases by 12.5%
+        assert_eq!(
+            next_block_base_fee(base_fee, gas_limit, gas_limit),
+            U256::from(1_125_000_000u64)
+        );
+        // Empty block, base fee decreases by 12.5%
+        assert_eq!(next_block_base_fee(base_fee, 0, gas_limit), U256::from(875_000_000u64));
+        // Gas used equals the target, base fee doesn't change
+        assert_eq!(next_block_base_fee(base_fee, gas_limit / 2, gas_limit), base_fee);
+        // Base fee increases by at least 1 wei when above the target
+        assert_eq!(next_block_base_fee(U256::one(), 15_000_001, gas_limit), U256::from(2));
+    }
 }
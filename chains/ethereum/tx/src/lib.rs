@@ -1,18 +1,128 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use rosetta_config_ethereum::{
     ext::types::{
         crypto::{Keypair, Signer},
-        transactions::Eip1559Transaction,
-        AccessList, TransactionT, H160, U256,
+        ext::rlp::Rlp,
+        rlp_utils::RlpDecodableTransaction,
+        transactions::{
+            signature::{RecoveryId, Signature as EthSignature},
+            Eip1559Transaction, Eip2930Transaction, LegacyTransaction,
+        },
+        TransactionT, TypedTransaction, H160, U256,
     },
     EthereumMetadata, EthereumMetadataParams,
 };
 use rosetta_core::{
-    crypto::{address::Address, SecretKey},
+    crypto::{address::Address, PublicKey, SecretKey, Signature},
     BlockchainConfig, TransactionBuilder,
 };
 
-#[derive(Default)]
+fn build_transaction(
+    metadata_params: &EthereumMetadataParams,
+    metadata: &EthereumMetadata,
+) -> TypedTransaction {
+    let to = metadata_params.destination.map(H160);
+    let value = U256(metadata_params.amount);
+    let data = metadata_params.data.iter().collect();
+    match metadata.gas_price.map(U256) {
+        // Chains without base fee don't support EIP-1559 transactions
+        Some(gas_price) if metadata.access_list.is_empty() => {
+            TypedTransaction::Legacy(LegacyTransaction {
+                nonce: metadata.nonce,
+                gas_price,
+                gas_limit: metadata.gas_limit,
+                to,
+                value,
+                data,
+                chain_id: Some(metadata.chain_id),
+            })
+        },
+        Some(gas_price) => TypedTransaction::Eip2930(Eip2930Transaction {
+            chain_id: metadata.chain_id,
+            nonce: metadata.nonce,
+            gas_price,
+            gas_limit: metadata.gas_limit,
+            to,
+            value,
+            data,
+            access_list: metadata.access_list.clone(),
+        }),
+        None => TypedTransaction::Eip1559(Eip1559Transaction {
+            to,
+            gas_limit: metadata.gas_limit,
+            value,
+            data,
+            nonce: metadata.nonce,
+            access_list: metadata.access_list.clone(),
+            max_priority_fee_per_gas: U256(metadata.max_priority_fee_per_gas),
+            max_fee_per_gas: U256(metadata.max_fee_per_gas),
+            chain_id: metadata.chain_id,
+        }),
+    }
+}
+
+/// [EIP-2718] transaction type.
+///
+/// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TxType {
+    /// Pre EIP-2718 transaction, with an optional [EIP-155] chain id.
+    ///
+    /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+    Legacy,
+    /// [EIP-2930] transaction with an access list, type `0x01`.
+    ///
+    /// [EIP-2930]: https://eips.ethereum.org/EIPS/eip-2930
+    Eip2930,
+    /// [EIP-1559] transaction with fee caps, type `0x02`.
+    ///
+    /// [EIP-1559]: https://eips.ethereum.org/EIPS/eip-1559
+    Eip1559,
+}
+
+impl TxType {
+    /// Returns the transaction type of an encoded transaction from its first byte.
+    ///
+    /// # Errors
+    /// Returns `Err` if the type is unknown or not supported.
+    pub fn from_first_byte(first: u8) -> Result<Self> {
+        match first {
+            0x01 => Ok(Self::Eip2930),
+            0x02 => Ok(Self::Eip1559),
+            0x03 => bail!("unsupported transaction type 0x03, EIP-4844 blob transactions"),
+            // legacy transactions are a RLP list, which always start with a byte >= 0xc0
+            first if first >= 0xc0 => Ok(Self::Legacy),
+            first => bail!("unknown transaction type 0x{first:02x}"),
+        }
+    }
+}
+
+/// A raw transaction decoded by [`decode_transaction`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedTransaction {
+    pub tx_type: TxType,
+    /// The transaction, [`TransactionT`] exposes the type specific fields: the access list and
+    /// the gas price or fee caps.
+    pub transaction: TypedTransaction,
+    /// `None` if the transaction isn't signed.
+    pub signature: Option<EthSignature>,
+}
+
+/// Decodes a raw signed or unsigned ethereum transaction.
+///
+/// # Errors
+/// Returns `Err` if the transaction type is unknown or the transaction is malformed.
+pub fn decode_transaction(raw: &[u8]) -> Result<DecodedTransaction> {
+    let Some(first) = raw.first() else {
+        bail!("empty transaction");
+    };
+    let tx_type = TxType::from_first_byte(*first)?;
+    let (transaction, signature) = TypedTransaction::rlp_decode(&Rlp::new(raw), true)
+        .map_err(|error| anyhow::anyhow!("invalid {tx_type:?} transaction: {error}"))?;
+    Ok(DecodedTransaction { tx_type, transaction, signature })
+}
+
+#[derive(Clone, Default)]
 pub struct EthereumTransactionBuilder;
 
 impl TransactionBuilder for EthereumTransactionBuilder {
@@ -28,6 +138,7 @@ impl TransactionBuilder for EthereumTransactionBuilder {
             data: Vec::new(),
             nonce: None,
             gas_limit: None,
+            auto_access_list: false,
         })
     }
 
@@ -45,6 +156,7 @@ impl TransactionBuilder for EthereumTransactionBuilder {
             data: data.to_vec(),
             nonce: None,
             gas_limit: None,
+            auto_access_list: false,
         })
     }
 
@@ -55,6 +167,7 @@ impl TransactionBuilder for EthereumTransactionBuilder {
             data: contract_binary,
             nonce: None,
             gas_limit: None,
+            auto_access_list: false,
         })
     }
 
@@ -65,18 +178,7 @@ impl TransactionBuilder for EthereumTransactionBuilder {
         metadata: &Self::Metadata,
         secret_key: &SecretKey,
     ) -> Vec<u8> {
-        let to = metadata_params.destination.map(H160);
-        let tx = Eip1559Transaction {
-            to,
-            gas_limit: metadata.gas_limit,
-            value: U256(metadata_params.amount),
-            data: metadata_params.data.iter().collect(),
-            nonce: metadata.nonce,
-            access_list: AccessList::default(),
-            max_priority_fee_per_gas: U256(metadata.max_priority_fee_per_gas),
-            max_fee_per_gas: U256(metadata.max_fee_per_gas),
-            chain_id: metadata.chain_id,
-        };
+        let tx = build_transaction(metadata_params, metadata);
         let sighash = tx.sighash();
         #[allow(clippy::expect_used)]
         let signature = {
@@ -88,4 +190,198 @@ impl TransactionBuilder for EthereumTransactionBuilder {
         };
         tx.encode(Some(&signature)).0.to_vec()
     }
+
+    fn signing_payload(
+        &self,
+        _config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+    ) -> Result<Vec<u8>> {
+        Ok(build_transaction(metadata_params, metadata).sighash().0.to_vec())
+    }
+
+    fn encode_signed(
+        &self,
+        _config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+        _public_key: &PublicKey,
+        signature: &Signature,
+    ) -> Result<Vec<u8>> {
+        let Signature::EcdsaRecoverableSecp256k1(signature, recovery_id) = signature else {
+            bail!("unsupported signature, expected a recoverable secp256k1 signature");
+        };
+        // All transaction signatures whose s-value is greater than secp256k1n/2 are invalid,
+        // normalizing s flips the parity of the y-coordinate.
+        // - https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2.md
+        let mut y_parity = u64::from(recovery_id.is_y_odd());
+        let signature = signature.normalize_s().map_or(*signature, |normalized| {
+            y_parity ^= 1;
+            normalized
+        });
+        let (r, s) = signature.split_bytes();
+        let signature = EthSignature {
+            v: RecoveryId::new(RecoveryId::new(y_parity).as_eip155(metadata.chain_id)),
+            r: U256::from_big_endian(&r),
+            s: U256::from_big_endian(&s),
+        };
+        let tx = build_transaction(metadata_params, metadata);
+        Ok(tx.encode(Some(&signature)).0.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosetta_config_ethereum::ext::types::{
+        transactions::{access_list::AccessListItem, GasPrice},
+        AccessList, Bytes, H256,
+    };
+    use rosetta_core::crypto::{Algorithm, SecretKey};
+
+    fn sign<T: TransactionT>(tx: &T) -> Vec<u8> {
+        let keypair = Keypair::from_bytes([0x42; 32]).unwrap();
+        let signature = keypair.sign_prehash(tx.sighash(), tx.chain_id()).unwrap();
+        tx.encode(Some(&signature)).0.to_vec()
+    }
+
+    fn access_list() -> AccessList {
+        AccessList(vec![AccessListItem {
+            address: H160::repeat_byte(0x11),
+            storage_keys: vec![H256::repeat_byte(0x22)],
+        }])
+    }
+
+    #[test]
+    fn decodes_legacy_transaction() {
+        let tx = LegacyTransaction {
+            nonce: 1,
+            gas_price: U256::from(10),
+            gas_limit: 21000,
+            to: Some(H160::repeat_byte(0x33)),
+            value: U256::from(100),
+            data: Bytes::default(),
+            chain_id: Some(1337),
+        };
+        let decoded = decode_transaction(&sign(&tx)).unwrap();
+        assert_eq!(decoded.tx_type, TxType::Legacy);
+        assert_eq!(decoded.transaction, TypedTransaction::Legacy(tx));
+        assert_eq!(decoded.transaction.gas_price(), GasPrice::Legacy(U256::from(10)));
+        assert!(decoded.transaction.access_list().is_none());
+        assert!(decoded.signature.is_some());
+    }
+
+    #[test]
+    fn decodes_eip2930_transaction() {
+        let tx = Eip2930Transaction {
+            chain_id: 1337,
+            nonce: 2,
+            gas_price: U256::from(10),
+            gas_limit: 50000,
+            to: Some(H160::repeat_byte(0x33)),
+            value: U256::zero(),
+            data: Bytes::from(vec![0xaa, 0xbb]),
+            access_list: access_list(),
+        };
+        let decoded = decode_transaction(&sign(&tx)).unwrap();
+        assert_eq!(decoded.tx_type, TxType::Eip2930);
+        assert_eq!(decoded.transaction.access_list(), Some(&access_list()));
+        assert_eq!(decoded.transaction.gas_price(), GasPrice::Legacy(U256::from(10)));
+        assert_eq!(decoded.transaction, TypedTransaction::Eip2930(tx));
+    }
+
+    #[test]
+    fn decodes_eip1559_transaction() {
+        let tx = Eip1559Transaction {
+            chain_id: 1337,
+            nonce: 3,
+            max_priority_fee_per_gas: U256::from(2),
+            max_fee_per_gas: U256::from(20),
+            gas_limit: 50000,
+            to: None,
+            value: U256::zero(),
+            data: Bytes::from(vec![0x60, 0x00]),
+            access_list: access_list(),
+        };
+        let decoded = decode_transaction(&sign(&tx)).unwrap();
+        assert_eq!(decoded.tx_type, TxType::Eip1559);
+        assert_eq!(
+            decoded.transaction.gas_price(),
+            GasPrice::Eip1559 {
+                max_priority_fee_per_gas: U256::from(2),
+                max_fee_per_gas: U256::from(20),
+            }
+        );
+        assert_eq!(decoded.transaction.access_list(), Some(&access_list()));
+        assert_eq!(decoded.transaction, TypedTransaction::Eip1559(tx.clone()));
+
+        // Unsigned transactions are decoded too
+        let decoded = decode_transaction(&tx.encode(None).0).unwrap();
+        assert_eq!(decoded.tx_type, TxType::Eip1559);
+        assert!(decoded.signature.is_none());
+    }
+
+    fn metadata(gas_price: Option<u64>, access_list: AccessList) -> EthereumMetadata {
+        EthereumMetadata {
+            chain_id: 1337,
+            nonce: 4,
+            max_priority_fee_per_gas: U256::from(2).0,
+            max_fee_per_gas: U256::from(20).0,
+            gas_limit: 50000,
+            min_gas_price: U256::zero().0,
+            gas_price: gas_price.map(|gas_price| U256::from(gas_price).0),
+            access_list,
+        }
+    }
+
+    #[test]
+    fn falls_back_to_gas_price_without_base_fee() {
+        let config = rosetta_config_ethereum::config("dev").unwrap();
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let builder = EthereumTransactionBuilder;
+        let params = EthereumMetadataParams {
+            destination: Some([0x33; 20]),
+            amount: U256::from(100).0,
+            data: Vec::new(),
+            nonce: None,
+            gas_limit: None,
+            auto_access_list: false,
+        };
+        let sign = |metadata: &EthereumMetadata| {
+            let signed = builder.create_and_sign(&config, &params, metadata, &secret_key);
+            decode_transaction(&signed).unwrap()
+        };
+
+        let decoded = sign(&metadata(None, AccessList::default()));
+        assert_eq!(decoded.tx_type, TxType::Eip1559);
+        assert_eq!(
+            decoded.transaction.gas_price(),
+            GasPrice::Eip1559 {
+                max_priority_fee_per_gas: U256::from(2),
+                max_fee_per_gas: U256::from(20),
+            }
+        );
+
+        let decoded = sign(&metadata(Some(10), AccessList::default()));
+        assert_eq!(decoded.tx_type, TxType::Legacy);
+        assert_eq!(decoded.transaction.gas_price(), GasPrice::Legacy(U256::from(10)));
+        assert_eq!(decoded.transaction.chain_id(), Some(1337));
+
+        // The access list is kept in an EIP-2930 transaction
+        let decoded = sign(&metadata(Some(10), access_list()));
+        assert_eq!(decoded.tx_type, TxType::Eip2930);
+        assert_eq!(decoded.transaction.gas_price(), GasPrice::Legacy(U256::from(10)));
+        assert_eq!(decoded.transaction.access_list(), Some(&access_list()));
+    }
+
+    #[test]
+    fn rejects_unknown_transaction_types() {
+        let error = decode_transaction(&[0x03, 0xc0]).unwrap_err();
+        assert!(error.to_string().contains("EIP-4844"));
+        let error = decode_transaction(&[0x7f, 0xc0]).unwrap_err();
+        assert_eq!(error.to_string(), "unknown transaction type 0x7f");
+        assert!(decode_transaction(&[]).is_err());
+        assert!(decode_transaction(&[0x02, 0xc0]).is_err());
+    }
 }
@@ -155,6 +155,7 @@ pub fn config(network: &str) -> Result<BlockchainConfig> {
         currency_decimals: properties.decimals,
         node_uri: NodeUri::parse("ws://127.0.0.1:9944")?,
         node_image: "parity/polkadot:v1.5.0",
+        node_entrypoint: None,
         node_command: Arc::new(move |network, port| {
             let chain = if network == "mainnet" {
                 blockchain.to_string()
@@ -168,6 +169,8 @@ pub fn config(network: &str) -> Result<BlockchainConfig> {
                     "--rpc-external".into(),
                     "--force-authoring".into(),
                     "--rpc-cors=all".into(),
+                    // Exposes `system_peers`
+                    "--rpc-methods=unsafe".into(),
                     "--alice".into(),
                     "--tmp".into(),
                     "--allow-private-ip".into(),
@@ -184,15 +187,23 @@ pub fn config(network: &str) -> Result<BlockchainConfig> {
         node_additional_ports: &[],
         connector_port: 8082,
         testnet: properties.is_testnet(),
+        block_explorer_url: None,
     })
 }
 
-#[derive(Clone, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PolkadotMetadataParams {
     pub nonce: Option<u32>,
     pub pallet_name: String,
     pub call_name: String,
     pub call_args: Vec<u8>,
+    /// Tip paid to the block author to prioritize the inclusion of the extrinsic.
+    #[serde(default)]
+    pub tip: Option<u128>,
+    /// Number of blocks the extrinsic stays valid for, rounded up to a power of two. `None`
+    /// builds an immortal extrinsic.
+    #[serde(default)]
+    pub mortality: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -204,4 +215,89 @@ pub struct PolkadotMetadata {
     pub pallet_index: u8,
     pub call_index: u8,
     pub call_hash: [u8; 32],
+    #[serde(default)]
+    pub tip: u128,
+    /// Preview of the call being signed, `None` if the arguments couldn't be decoded.
+    #[serde(default)]
+    pub decoded_call: Option<DecodedCall>,
+    /// Era of a mortal extrinsic, `None` if the extrinsic is immortal.
+    #[serde(default)]
+    pub era: Option<MortalEra>,
+    /// Identifiers of the chain signed extensions, in the order they are encoded in the signed
+    /// extra, ex: `["CheckNonZeroSender", "CheckSpecVersion", ..., "ChargeTransactionPayment"]`.
+    #[serde(default)]
+    pub signed_extensions: Vec<String>,
+}
+
+impl PolkadotMetadata {
+    /// Returns the signed extra the extrinsic is built with.
+    #[must_use]
+    pub fn signed_extra(&self) -> SignedExtra {
+        SignedExtra {
+            extensions: self.signed_extensions.clone(),
+            nonce: self.nonce,
+            tip: self.tip,
+            mortality: self.era.as_ref().map(MortalEra::mortality),
+        }
+    }
+}
+
+/// A mortal extrinsic is valid from the `block_number` checkpoint until `period` blocks later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MortalEra {
+    /// Validity period, a power of two between 4 and 65536.
+    pub period: u64,
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+}
+
+impl MortalEra {
+    /// Returns the period and phase encoded in the `CheckMortality` signed extension, same as
+    /// `sp_runtime::generic::Era::mortal`.
+    #[must_use]
+    pub fn mortality(&self) -> Mortality {
+        let period = self.period.checked_next_power_of_two().unwrap_or(1 << 16).clamp(4, 1 << 16);
+        let quantize_factor = (period >> 12).max(1);
+        let phase = self.block_number % period / quantize_factor * quantize_factor;
+        Mortality { period, phase }
+    }
+
+    /// Returns the first block in which the extrinsic is no longer valid, same as
+    /// `sp_runtime::generic::Era::death` at the checkpoint block.
+    #[must_use]
+    pub fn expiry_block(&self) -> u64 {
+        let Mortality { period, phase } = self.mortality();
+        let birth = (self.block_number.max(phase) - phase) / period * period + phase;
+        birth + period
+    }
+}
+
+/// Period and phase of a mortal extrinsic, the extrinsic is valid during the `period` blocks
+/// starting at the block whose number modulo `period` is `phase`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Mortality {
+    pub period: u64,
+    pub phase: u64,
+}
+
+/// Typed view of the signed extra of an extrinsic, which is opaque once encoded.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SignedExtra {
+    /// Identifiers of the chain signed extensions, see [`PolkadotMetadata::signed_extensions`].
+    pub extensions: Vec<String>,
+    /// `CheckNonce` account nonce.
+    pub nonce: u32,
+    /// `ChargeTransactionPayment` tip.
+    pub tip: u128,
+    /// `CheckMortality` era, `None` if the extrinsic is immortal.
+    pub mortality: Option<Mortality>,
+}
+
+/// Human readable call, decoded from the SCALE encoded arguments using the chain metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DecodedCall {
+    pub pallet: String,
+    pub call: String,
+    /// Call arguments keyed by name, ex: `{ "dest": { "Id": [...] }, "value": 1000 }`.
+    pub args: serde_json::Value,
 }
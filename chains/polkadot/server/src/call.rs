@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use scale_info::{
     form::PortableForm, PortableRegistry, TypeDef, TypeDefArray, TypeDefBitSequence,
     TypeDefCompact, TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple,
-    TypeDefVariant,
+    TypeDefVariant, Variant,
 };
 use serde_json::{Map, Value, Value as SerdeValue};
 use subxt::{
@@ -30,6 +30,39 @@ pub async fn dynamic_storage_req<T: subxt::Config>(
     storage_name: &str,
     params: Value,
 ) -> Result<Value> {
+    let params = dynamic_storage_params(subxt, pallet_name, storage_name, params)?;
+    let storage_address = subxt::dynamic::storage(pallet_name, storage_name, params);
+
+    let data = subxt.storage().at_latest().await?.fetch_or_default(&storage_address).await?;
+
+    let serde_val = if data.encoded() == [0] {
+        Value::Null
+    } else {
+        let abc = data.to_value()?;
+        scale_to_serde_json(abc.value)?
+    };
+
+    Ok(serde_val)
+}
+
+/// Returns the storage key of the `pallet_name` `storage_name` item with the map keys `params`.
+pub fn dynamic_storage_key<T: subxt::Config>(
+    subxt: &OnlineClient<T>,
+    pallet_name: &str,
+    storage_name: &str,
+    params: Value,
+) -> Result<Vec<u8>> {
+    let params = dynamic_storage_params(subxt, pallet_name, storage_name, params)?;
+    let storage_address = subxt::dynamic::storage(pallet_name, storage_name, params);
+    Ok(subxt.storage().address_bytes(&storage_address)?)
+}
+
+fn dynamic_storage_params<T: subxt::Config>(
+    subxt: &OnlineClient<T>,
+    pallet_name: &str,
+    storage_name: &str,
+    params: Value,
+) -> Result<Vec<SubxtValue>> {
     let metadata = subxt.metadata();
     let types = metadata.types();
     let pallet = metadata
@@ -58,20 +91,26 @@ pub async fn dynamic_storage_req<T: subxt::Config>(
         vec![]
     };
 
-    let params = set_params_acc_to_storage(params);
-
-    let storage_address = subxt::dynamic::storage(pallet_name, storage_name, params);
-
-    let data = subxt.storage().at_latest().await?.fetch_or_default(&storage_address).await?;
-
-    let serde_val = if data.encoded() == [0] {
-        Value::Null
-    } else {
-        let abc = data.to_value()?;
-        scale_to_serde_json(abc.value)?
-    };
+    Ok(set_params_acc_to_storage(params))
+}
 
-    Ok(serde_val)
+/// Decodes the SCALE encoded arguments of `call`, returns a JSON object keyed by argument name.
+pub fn decode_call_args(
+    call: &Variant<PortableForm>,
+    mut call_args: &[u8],
+    types: &PortableRegistry,
+) -> Result<Value> {
+    let mut args = Map::new();
+    for (index, field) in call.fields.iter().enumerate() {
+        let value = scale_value::scale::decode_as_type(&mut call_args, field.ty.id, types)
+            .map_err(|err| anyhow::anyhow!("failed to decode call argument: {err}"))?;
+        let name = field.name.clone().unwrap_or_else(|| index.to_string());
+        args.insert(name, scale_to_serde_json(value.value)?);
+    }
+    if !call_args.is_empty() {
+        anyhow::bail!("{} trailing bytes after the call arguments", call_args.len());
+    }
+    Ok(Value::Object(args))
 }
 
 fn set_params_acc_to_storage(values: Vec<SubxtValue>) -> Vec<SubxtValue> {
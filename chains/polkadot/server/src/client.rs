@@ -1,12 +1,27 @@
-use crate::types::{BlockIdentifier, ClientConfig, SubxtConfigAdapter};
+use crate::{
+    finality::GrandpaAuthority,
+    proof::ReadProof,
+    types::{BlockIdentifier, ClientConfig, SubxtConfigAdapter},
+};
 use anyhow::Context;
+use parity_scale_codec::{Compact, Decode, Encode};
 use std::{borrow::Borrow, future::Future, sync::Arc};
 use subxt::{
-    backend::rpc::{RpcClient, RpcClientT},
+    backend::{
+        legacy::rpc_methods::Bytes,
+        rpc::{rpc_params, RpcClient, RpcClientT},
+    },
     blocks::BlockRef,
     client::RuntimeVersion,
+    config::Hasher,
+    dynamic::Value as SubxtValue,
+    events::Phase,
+    ext::{
+        scale_value::{At, ValueDef},
+        sp_runtime::transaction_validity::{TransactionSource, TransactionValidity},
+    },
     metadata::Metadata,
-    utils::AccountId32,
+    utils::{AccountId32, MultiAddress},
 };
 
 type Config<T> = SubxtConfigAdapter<T>;
@@ -14,11 +29,105 @@ type OnlineClient<T> = subxt::OnlineClient<Config<T>>;
 type LegacyRpcMethods<T> = subxt::backend::legacy::LegacyRpcMethods<Config<T>>;
 type BlockDetails<T> = subxt::backend::legacy::rpc_methods::BlockDetails<Config<T>>;
 
+/// Balance of an account in the `Assets` pallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetBalance {
+    pub balance: u128,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// An extrinsic included in a block, decoded using the events it emitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtrinsicInfo {
+    /// Index of the extrinsic in the block.
+    pub index: u32,
+    /// Hash of the extrinsic.
+    pub hash: Vec<u8>,
+    /// Account which signed the extrinsic, `None` for unsigned extrinsics like inherents.
+    pub signer: Option<AccountId32>,
+    /// `true` if the extrinsic emitted `System.ExtrinsicSuccess`.
+    pub success: bool,
+    /// Fee paid by the signer, from the `TransactionPayment.TransactionFeePaid` event.
+    pub fee: Option<u128>,
+    /// Tip paid by the signer, from the `TransactionPayment.TransactionFeePaid` event.
+    pub tip: Option<u128>,
+    /// Destination and amount of a `Balances` transfer call.
+    pub transfer: Option<(AccountId32, u128)>,
+    /// `Balances.Transfer` events emitted by the extrinsic, this includes the transfers
+    /// dispatched by nested calls, like `utility.batch` or `proxy.proxy`.
+    pub transfers: Vec<BalanceTransfer>,
+    /// `Balances.Deposit` events crediting the fee paid by the signer, ex: to the block author
+    /// or the treasury.
+    pub fee_deposits: Vec<BalanceDeposit>,
+    /// `Staking.Rewarded` events, the staking rewards paid out by the extrinsic.
+    pub rewards: Vec<BalanceDeposit>,
+}
+
+/// An extrinsic pending in the transaction pool of the node, it emitted no events yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingExtrinsicInfo {
+    /// Hash of the extrinsic.
+    pub hash: Vec<u8>,
+    /// Account which signed the extrinsic, `None` for unsigned extrinsics.
+    pub signer: Option<AccountId32>,
+    /// Destination and amount of a `Balances` transfer call.
+    pub transfer: Option<(AccountId32, u128)>,
+}
+
+/// A `Balances.Transfer` event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceTransfer {
+    pub from: AccountId32,
+    pub to: AccountId32,
+    pub amount: u128,
+}
+
+/// A credit of `amount` to the account `who`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDeposit {
+    pub who: AccountId32,
+    pub amount: u128,
+}
+
+/// An event emitted in a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventInfo {
+    /// Index of the event in the block.
+    pub index: u32,
+    /// Index of the extrinsic which emitted the event, `None` for the events emitted during the
+    /// block initialization or finalization.
+    pub extrinsic_index: Option<u32>,
+    pub pallet: String,
+    pub variant: String,
+    /// SCALE encoded fields of the event.
+    pub fields: Vec<u8>,
+}
+
+/// `pallet_transaction_payment::RuntimeDispatchInfo`
+#[derive(Decode)]
+struct RuntimeDispatchInfo {
+    _weight: (Compact<u64>, Compact<u64>),
+    _class: u8,
+    partial_fee: u128,
+}
+
 pub struct SubstrateClient<T: ClientConfig> {
     client: OnlineClient<T>,
+    rpc_client: RpcClient,
     rpc_methods: LegacyRpcMethods<T>,
 }
 
+impl<T: ClientConfig> Clone for SubstrateClient<T> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            rpc_client: self.rpc_client.clone(),
+            rpc_methods: self.rpc_methods.clone(),
+        }
+    }
+}
+
 impl<T: ClientConfig> SubstrateClient<T> {
     /// Creates a new polkadot client using the provided `config` and connects to `addr`
     ///
@@ -27,9 +136,9 @@ impl<T: ClientConfig> SubstrateClient<T> {
     pub async fn from_client<C: RpcClientT>(client: C) -> anyhow::Result<Self> {
         let rpc_client = RpcClient::new(client);
         let rpc_methods = LegacyRpcMethods::<T>::new(rpc_client.clone());
-        let backend = subxt::backend::legacy::LegacyBackendBuilder::new().build(rpc_client);
+        let backend = subxt::backend::legacy::LegacyBackendBuilder::new().build(rpc_client.clone());
         let client = OnlineClient::<T>::from_backend(Arc::new(backend)).await?;
-        Ok(Self { client, rpc_methods })
+        Ok(Self { client, rpc_client, rpc_methods })
     }
 
     pub const fn client(&self) -> &OnlineClient<T> {
@@ -59,10 +168,53 @@ impl<T: ClientConfig> SubstrateClient<T> {
                 .await?
                 .context("latest block not found")?,
             BlockIdentifier::Finalized => self.rpc_methods.chain_get_finalized_head().await?,
+            BlockIdentifier::Earliest => self.earliest_block_hash().await?,
         };
         Ok(block_hash)
     }
 
+    /// Returns the hash of the earliest block whose state is available. The nodes pruning the
+    /// state only keep the state of the recent blocks, the lowest one is found by bisection
+    /// between the genesis and the finalized head, whose state is always kept.
+    async fn earliest_block_hash(&self) -> anyhow::Result<T::Hash> {
+        use subxt::{backend::legacy::rpc_methods::BlockNumber, config::Header};
+        let block_hash = |number: u64| async move {
+            self.rpc_methods
+                .chain_get_block_hash(Some(BlockNumber::Number(number)))
+                .await?
+                .with_context(|| format!("block {number} not found"))
+        };
+        let genesis_hash = block_hash(0).await?;
+        if self.has_state(genesis_hash).await {
+            return Ok(genesis_hash);
+        }
+        let finalized_hash = self.rpc_methods.chain_get_finalized_head().await?;
+        let finalized = self
+            .rpc_methods
+            .chain_get_header(Some(finalized_hash))
+            .await?
+            .context("finalized header not found")?;
+        let (mut low, mut high) = (1, finalized.number().into());
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.has_state(block_hash(middle).await?).await {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        block_hash(high).await
+    }
+
+    /// Returns `true` if the node still has the state of the block `block_hash`, the pruned
+    /// states fail with `State already discarded`.
+    async fn has_state(&self, block_hash: T::Hash) -> bool {
+        use subxt::ext::sp_core::hashing::twox_128;
+        // `System.Number` is defined by every runtime
+        let key = [twox_128(b"System"), twox_128(b"Number")].concat();
+        self.rpc_methods.state_get_storage(&key, Some(block_hash)).await.is_ok()
+    }
+
     pub fn account_info(
         &self,
         account: impl Borrow<AccountId32>,
@@ -83,6 +235,104 @@ impl<T: ClientConfig> SubstrateClient<T> {
         }
     }
 
+    /// Reads the `asset_id` balance of `account` from the `Assets.Account` storage, the symbol
+    /// and decimals are read from `Assets.Metadata`.
+    pub fn asset_balance(
+        &self,
+        asset_id: u32,
+        account: impl Borrow<AccountId32>,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>>,
+    ) -> impl Future<Output = anyhow::Result<AssetBalance>> + Sized + Send + '_ {
+        let account = account.borrow().clone();
+        let block_identifier = block_identifier.into();
+        async move {
+            if self.client.metadata().pallet_by_name("Assets").is_none() {
+                anyhow::bail!("the Assets pallet is not available in this chain");
+            }
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let storage = self.client.storage().at(BlockRef::from_hash(block_hash));
+
+            // Assets.Account(asset_id, account)
+            let address = subxt::dynamic::storage(
+                "Assets",
+                "Account",
+                vec![SubxtValue::u128(u128::from(asset_id)), SubxtValue::from_bytes(account.0)],
+            );
+            let balance = match storage.fetch(&address).await? {
+                Some(asset_account) => asset_account
+                    .to_value()?
+                    .at("balance")
+                    .and_then(subxt::ext::scale_value::Value::as_u128)
+                    .context("invalid Assets.Account entry")?,
+                None => 0,
+            };
+
+            // Assets.Metadata(asset_id)
+            let address = subxt::dynamic::storage(
+                "Assets",
+                "Metadata",
+                vec![SubxtValue::u128(u128::from(asset_id))],
+            );
+            let metadata = storage
+                .fetch(&address)
+                .await?
+                .with_context(|| format!("asset {asset_id} metadata not found"))?
+                .to_value()?;
+            let symbol = metadata
+                .at("symbol")
+                .and_then(value_to_bytes)
+                .context("invalid Assets.Metadata symbol")?;
+            let decimals = metadata
+                .at("decimals")
+                .and_then(subxt::ext::scale_value::Value::as_u128)
+                .and_then(|decimals| u8::try_from(decimals).ok())
+                .context("invalid Assets.Metadata decimals")?;
+            Ok(AssetBalance {
+                balance,
+                symbol: String::from_utf8_lossy(&symbol).into_owned(),
+                decimals,
+            })
+        }
+    }
+
+    /// Returns the validators of the current session, from the `Session.Validators` storage.
+    pub fn session_validators(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>>,
+    ) -> impl Future<Output = anyhow::Result<Vec<AccountId32>>> + Sized + Send + '_ {
+        let block_identifier = block_identifier.into();
+        async move {
+            if self.client.metadata().pallet_by_name("Session").is_none() {
+                anyhow::bail!("the Session pallet is not available in this chain");
+            }
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let address =
+                subxt::dynamic::storage("Session", "Validators", Vec::<SubxtValue>::new());
+            let Some(validators) = self
+                .client
+                .storage()
+                .at(BlockRef::from_hash(block_hash))
+                .fetch(&address)
+                .await?
+            else {
+                return Ok(Vec::new());
+            };
+            let validators = validators.to_value()?;
+            let ValueDef::Composite(validators) = &validators.value else {
+                anyhow::bail!("invalid Session.Validators entry");
+            };
+            validators
+                .values()
+                .map(|validator| {
+                    value_to_bytes(validator)
+                        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                        .map(AccountId32)
+                        .context("invalid Session.Validators account")
+                })
+                .collect()
+        }
+    }
+
     // pub fn block(
     //     &self,
     //     block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
@@ -109,6 +359,351 @@ impl<T: ClientConfig> SubstrateClient<T> {
         }
     }
 
+    /// Estimates the fee of the signed `extrinsic`, excluding the tip, using the
+    /// `TransactionPaymentApi_query_info` runtime api.
+    pub async fn estimate_extrinsic_fee(&self, extrinsic: &[u8]) -> anyhow::Result<u128> {
+        let len = u32::try_from(extrinsic.len()).context("extrinsic too long")?;
+        let mut params = extrinsic.to_vec();
+        len.encode_to(&mut params);
+        let info = self
+            .rpc_methods
+            .state_call("TransactionPaymentApi_query_info", Some(&params), None)
+            .await?;
+        let info = RuntimeDispatchInfo::decode(&mut info.as_slice())?;
+        Ok(info.partial_fee)
+    }
+
+    /// Validates the signed `extrinsic` against the state of the given block, like the
+    /// transaction pool does before accepting it, using the
+    /// `TaggedTransactionQueue_validate_transaction` runtime api.
+    pub fn validate_transaction<'a>(
+        &'a self,
+        extrinsic: &'a [u8],
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<TransactionValidity>> + Sized + Send + 'a {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            // The extrinsic is already SCALE encoded, with its length prefix
+            let mut params = TransactionSource::External.encode();
+            params.extend_from_slice(extrinsic);
+            block_hash.encode_to(&mut params);
+            let validity = self
+                .rpc_methods
+                .state_call(
+                    "TaggedTransactionQueue_validate_transaction",
+                    Some(&params),
+                    Some(block_hash),
+                )
+                .await?;
+            Ok(TransactionValidity::decode(&mut validity.as_slice())?)
+        }
+    }
+
+    /// Returns the `Balances.ExistentialDeposit` constant, the minimum balance of an account.
+    pub fn existential_deposit(&self) -> anyhow::Result<u128> {
+        let metadata = self.client.metadata();
+        let constant = metadata
+            .pallet_by_name("Balances")
+            .and_then(|pallet| pallet.constant_by_name("ExistentialDeposit"))
+            .context("Balances.ExistentialDeposit constant not found")?;
+        Ok(u128::decode(&mut constant.value())?)
+    }
+
+    /// Returns the SCALE encoded GRANDPA finality proof of the finalized block `number`, from the
+    /// `grandpa_proveFinality` rpc. `None` when the block isn't finalized yet.
+    pub async fn prove_finality(&self, number: u32) -> anyhow::Result<Option<Vec<u8>>> {
+        let proof: Option<String> =
+            self.rpc_client.request("grandpa_proveFinality", rpc_params![number]).await?;
+        proof
+            .map(|proof| {
+                hex::decode(proof.trim_start_matches("0x")).context("invalid finality proof hex")
+            })
+            .transpose()
+    }
+
+    /// Returns the GRANDPA authority set id and authorities at the given block, using the
+    /// `GrandpaApi_current_set_id` and `GrandpaApi_grandpa_authorities` runtime apis.
+    pub fn grandpa_authorities(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<(u64, Vec<GrandpaAuthority>)>> + Sized + Send + '_
+    {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let set_id = self
+                .rpc_methods
+                .state_call("GrandpaApi_current_set_id", None, Some(block_hash))
+                .await?;
+            let set_id = u64::decode(&mut set_id.as_slice())?;
+            let authorities = self
+                .rpc_methods
+                .state_call("GrandpaApi_grandpa_authorities", None, Some(block_hash))
+                .await?;
+            let authorities = Vec::<GrandpaAuthority>::decode(&mut authorities.as_slice())?;
+            Ok((set_id, authorities))
+        }
+    }
+
+    /// Returns the header of the given block.
+    pub fn block_header(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<T::Header>> + Sized + Send + '_ {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            self.rpc_methods
+                .chain_get_header(Some(block_hash))
+                .await?
+                .with_context(|| format!("header not found: {block_hash:?}"))
+        }
+    }
+
+    /// Returns the proof of the storage `keys` at the given block, from the `state_getReadProof`
+    /// rpc.
+    pub fn read_proof<'a>(
+        &'a self,
+        keys: &'a [Vec<u8>],
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<ReadProof<T::Hash>>> + Sized + Send + 'a {
+        #[derive(serde::Deserialize)]
+        struct RpcReadProof<Hash> {
+            at: Hash,
+            proof: Vec<String>,
+        }
+
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let keys = keys.iter().map(|key| format!("0x{}", hex::encode(key))).collect::<Vec<_>>();
+            let RpcReadProof { at, proof }: RpcReadProof<T::Hash> = self
+                .rpc_client
+                .request("state_getReadProof", rpc_params![keys, block_hash])
+                .await?;
+            let proof = proof
+                .iter()
+                .map(|node| {
+                    hex::decode(node.trim_start_matches("0x")).context("invalid read proof hex")
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(ReadProof { at, proof })
+        }
+    }
+
+    /// Returns the extrinsics waiting in the transaction pool of the node, with their hash.
+    pub async fn pending_extrinsics(&self) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let pending: Vec<Bytes> =
+            self.rpc_client.request("author_pendingExtrinsics", rpc_params![]).await?;
+        Ok(pending
+            .into_iter()
+            .map(|extrinsic| {
+                let hash = <T::Hasher as Hasher>::hash(&extrinsic.0).as_ref().to_vec();
+                (hash, extrinsic.0)
+            })
+            .collect())
+    }
+
+    /// Decodes an extrinsic returned by [`Self::pending_extrinsics`], like the extrinsics of
+    /// [`Self::extrinsics`].
+    pub fn decode_pending_extrinsic(
+        &self,
+        hash: Vec<u8>,
+        extrinsic: Vec<u8>,
+    ) -> anyhow::Result<PendingExtrinsicInfo> {
+        let extrinsics = subxt::ext::subxt_core::blocks::decode_from::<Config<T>>(
+            vec![extrinsic],
+            self.client.metadata(),
+        )?;
+        let extrinsic =
+            extrinsics.iter().next().context("the pending extrinsic cannot be decoded")??;
+        Ok(PendingExtrinsicInfo {
+            hash,
+            signer: extrinsic_signer(extrinsic.address_bytes())?,
+            transfer: transfer_call(
+                extrinsic.pallet_name()?,
+                extrinsic.variant_name()?,
+                &extrinsic.field_values()?,
+            )?,
+        })
+    }
+
+    /// Returns the number of extrinsics waiting in the transaction pool of the node.
+    pub async fn pending_extrinsics_count(&self) -> anyhow::Result<usize> {
+        let pending: Vec<Bytes> =
+            self.rpc_client.request("author_pendingExtrinsics", rpc_params![]).await?;
+        Ok(pending.len())
+    }
+
+    /// Returns the number of signed extrinsics included in the block, inherents are not counted.
+    pub fn signed_extrinsics_count(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<usize>> + Sized + Send + '_ {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let block = self.client.blocks().at(BlockRef::from_hash(block_hash)).await?;
+            let mut count = 0;
+            for extrinsic in block.extrinsics().await?.iter() {
+                if extrinsic?.is_signed() {
+                    count += 1;
+                }
+            }
+            Ok(count)
+        }
+    }
+
+    /// Returns the peers the node is connected to, from the unsafe `system_peers` rpc, ex:
+    /// `{ "peerId": "12D3KooW...", "roles": "FULL", "bestHash": "0x...", "bestNumber": 42 }`.
+    pub async fn system_peers(&self) -> anyhow::Result<Vec<serde_json::Value>> {
+        let peers = self.rpc_client.request("system_peers", rpc_params![]).await?;
+        Ok(peers)
+    }
+
+    /// Returns the properties of the chain from the `system_properties` rpc, ex:
+    /// `{ "ss58Format": 42, "tokenDecimals": 12, "tokenSymbol": "WND" }`.
+    pub async fn system_properties(
+        &self,
+    ) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+        let properties = self.rpc_methods.system_properties().await?;
+        Ok(properties)
+    }
+
+    /// Returns the sync state of the node from the `system_syncState` rpc, ex:
+    /// `{ "startingBlock": 0, "currentBlock": 42, "highestBlock": 42 }`.
+    pub async fn system_sync_state(&self) -> anyhow::Result<serde_json::Value> {
+        let state = self.rpc_client.request("system_syncState", rpc_params![]).await?;
+        Ok(state)
+    }
+
+    /// Exports the raw chain spec of the running chain, from the unsafe `sync_state_genSyncSpec`
+    /// rpc. The chain spec can be used to start other nodes of the same chain.
+    pub async fn chain_spec(&self) -> anyhow::Result<serde_json::Value> {
+        let spec = self.rpc_client.request("sync_state_genSyncSpec", rpc_params![true]).await?;
+        Ok(spec)
+    }
+
+    /// Returns all extrinsics included in the block, with their success status derived from the
+    /// `System.ExtrinsicSuccess` and `System.ExtrinsicFailed` events. Events are associated with
+    /// the extrinsic by their `ApplyExtrinsic` phase.
+    pub fn extrinsics(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<Vec<ExtrinsicInfo>>> + Sized + Send + '_ {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let block = self.client.blocks().at(BlockRef::from_hash(block_hash)).await?;
+            let mut extrinsics = Vec::new();
+            for extrinsic in block.extrinsics().await?.iter() {
+                let extrinsic = extrinsic?;
+                let signer = extrinsic_signer(extrinsic.address_bytes())?;
+                let transfer = transfer_call(
+                    extrinsic.pallet_name()?,
+                    extrinsic.variant_name()?,
+                    &extrinsic.field_values()?,
+                )?;
+
+                let events = extrinsic.events().await?;
+                let mut success = false;
+                let mut fee = None;
+                let mut tip = None;
+                let mut transfers = Vec::new();
+                let mut deposits = Vec::new();
+                let mut fee_deposits = Vec::new();
+                let mut rewards = Vec::new();
+                for event in events.iter() {
+                    let event = event?;
+                    match (event.pallet_name(), event.variant_name()) {
+                        ("System", "ExtrinsicSuccess") => success = true,
+                        ("TransactionPayment", "TransactionFeePaid") => {
+                            let fields = event.field_values()?;
+                            fee = fields
+                                .at("actual_fee")
+                                .and_then(subxt::ext::scale_value::Value::as_u128);
+                            tip =
+                                fields.at("tip").and_then(subxt::ext::scale_value::Value::as_u128);
+                            fee_deposits =
+                                take_fee_deposits(&mut deposits, fee.unwrap_or_default());
+                        },
+                        // The fee is credited right before `TransactionFeePaid`, after the
+                        // refund of the signer
+                        ("Balances", "Deposit") => {
+                            let fields = event.field_values()?;
+                            deposits.push(BalanceDeposit {
+                                who: event_account(&fields, "who")?,
+                                amount: event_amount(&fields, "amount")?,
+                            });
+                            continue;
+                        },
+                        ("Treasury", "Deposit") => continue,
+                        ("Staking", "Rewarded") => {
+                            let fields = event.field_values()?;
+                            rewards.push(BalanceDeposit {
+                                who: event_account(&fields, "stash")?,
+                                amount: event_amount(&fields, "amount")?,
+                            });
+                        },
+                        ("Balances", "Transfer") => {
+                            let fields = event.field_values()?;
+                            transfers.push(BalanceTransfer {
+                                from: event_account(&fields, "from")?,
+                                to: event_account(&fields, "to")?,
+                                amount: event_amount(&fields, "amount")?,
+                            });
+                        },
+                        _ => {},
+                    }
+                    // Only the deposits right before `TransactionFeePaid` credit the fee
+                    deposits.clear();
+                }
+                extrinsics.push(ExtrinsicInfo {
+                    index: extrinsic.index(),
+                    hash: events.extrinsic_hash().as_ref().to_vec(),
+                    signer,
+                    success,
+                    fee,
+                    tip,
+                    transfer,
+                    transfers,
+                    fee_deposits,
+                    rewards,
+                });
+            }
+            Ok(extrinsics)
+        }
+    }
+
+    /// Returns the hash of the block and the events it emitted, in order.
+    pub fn events(
+        &self,
+        block_identifier: impl Into<BlockIdentifier<T::Hash>> + Send,
+    ) -> impl Future<Output = anyhow::Result<(T::Hash, Vec<EventInfo>)>> + Sized + Send + '_ {
+        let block_identifier = block_identifier.into();
+        async move {
+            let block_hash = self.block_identifier_to_hash(block_identifier).await?;
+            let block = self.client.blocks().at(BlockRef::from_hash(block_hash)).await?;
+            let mut events = Vec::new();
+            for event in block.events().await?.iter() {
+                let event = event?;
+                let extrinsic_index = match event.phase() {
+                    Phase::ApplyExtrinsic(index) => Some(index),
+                    Phase::Initialization | Phase::Finalization => None,
+                };
+                events.push(EventInfo {
+                    index: event.index(),
+                    extrinsic_index,
+                    pallet: event.pallet_name().into(),
+                    variant: event.variant_name().into(),
+                    fields: event.field_bytes().to_vec(),
+                });
+            }
+            Ok((block_hash, events))
+        }
+    }
+
     pub async fn faucet(
         &self,
         signer: T::Pair,
@@ -139,3 +734,115 @@ impl<T: ClientConfig> SubstrateClient<T> {
         self.client.genesis_hash()
     }
 }
+
+/// Decodes the account which signed an extrinsic from its address, `None` for unsigned extrinsics
+/// or addresses which aren't an account id.
+fn extrinsic_signer(address_bytes: Option<&[u8]>) -> anyhow::Result<Option<AccountId32>> {
+    let Some(mut bytes) = address_bytes else {
+        return Ok(None);
+    };
+    match MultiAddress::<AccountId32, u32>::decode(&mut bytes)? {
+        MultiAddress::Id(account) => Ok(Some(account)),
+        _ => Ok(None),
+    }
+}
+
+/// Decodes the destination and amount of a `Balances` transfer call, `None` for other calls.
+fn transfer_call<C>(
+    pallet_name: &str,
+    variant_name: &str,
+    args: &impl At<C>,
+) -> anyhow::Result<Option<(AccountId32, u128)>> {
+    if pallet_name != "Balances" || !variant_name.starts_with("transfer") {
+        return Ok(None);
+    }
+    let dest = args.at("dest").and_then(|dest| dest.at(0)).and_then(value_to_bytes);
+    let value = args.at("value").and_then(subxt::ext::scale_value::Value::as_u128);
+    let (Some(dest), Some(value)) = (dest, value) else {
+        return Ok(None);
+    };
+    let dest =
+        <[u8; 32]>::try_from(dest).map_err(|_| anyhow::anyhow!("invalid transfer destination"))?;
+    Ok(Some((AccountId32(dest), value)))
+}
+
+/// Reads the account in the field `name` of an event.
+fn event_account<C>(fields: &impl At<C>, name: &str) -> anyhow::Result<AccountId32> {
+    fields
+        .at(name)
+        .and_then(value_to_bytes)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(AccountId32)
+        .with_context(|| format!("invalid event {name} account"))
+}
+
+/// Reads the balance in the field `name` of an event.
+fn event_amount<C>(fields: &impl At<C>, name: &str) -> anyhow::Result<u128> {
+    fields
+        .at(name)
+        .and_then(subxt::ext::scale_value::Value::as_u128)
+        .with_context(|| format!("invalid event {name}"))
+}
+
+/// Removes the last `deposits` which sum to the `fee`, the ones before are refunds of the
+/// signer or credits of the call. Returns nothing if no trailing deposits sum to the fee, ex:
+/// when the chain burns the fee.
+fn take_fee_deposits(deposits: &mut Vec<BalanceDeposit>, fee: u128) -> Vec<BalanceDeposit> {
+    let mut remaining = fee;
+    let mut split = deposits.len();
+    while split > 0 && remaining > 0 && deposits[split - 1].amount <= remaining {
+        split -= 1;
+        remaining -= deposits[split].amount;
+    }
+    if remaining > 0 || split == deposits.len() {
+        return Vec::new();
+    }
+    deposits.split_off(split)
+}
+
+/// Converts a dynamic value into bytes, unwrapping newtypes like `BoundedVec<u8>`.
+fn value_to_bytes<C>(value: &subxt::ext::scale_value::Value<C>) -> Option<Vec<u8>> {
+    let ValueDef::Composite(composite) = &value.value else {
+        return None;
+    };
+    let values = composite.values().collect::<Vec<_>>();
+    if let [inner] = values.as_slice() {
+        if matches!(inner.value, ValueDef::Composite(_)) {
+            return value_to_bytes(inner);
+        }
+    }
+    values
+        .into_iter()
+        .map(|byte| byte.as_u128().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_deposits_are_the_last_ones() {
+        let deposit =
+            |byte: u8, amount: u128| BalanceDeposit { who: AccountId32([byte; 32]), amount };
+        // Refund of the signer, then the fee split between the treasury and the author
+        let mut deposits = vec![deposit(1, 50), deposit(2, 80), deposit(3, 20)];
+        assert_eq!(take_fee_deposits(&mut deposits, 100), vec![deposit(2, 80), deposit(3, 20)]);
+        assert_eq!(deposits, vec![deposit(1, 50)]);
+
+        // The fee may be credited to the signer itself
+        let mut deposits = vec![deposit(1, 50), deposit(1, 100)];
+        assert_eq!(take_fee_deposits(&mut deposits, 100), vec![deposit(1, 100)]);
+
+        let mut deposits = vec![deposit(1, 50)];
+        assert!(take_fee_deposits(&mut deposits, 0).is_empty());
+        assert_eq!(deposits.len(), 1);
+
+        // Deposits which don't sum to the fee aren't fee credits, ex: when the fee is burnt
+        let mut deposits = vec![deposit(1, 50), deposit(2, 30)];
+        assert!(take_fee_deposits(&mut deposits, 100).is_empty());
+        assert_eq!(deposits.len(), 2);
+        let mut deposits = vec![deposit(1, 150)];
+        assert!(take_fee_deposits(&mut deposits, 100).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use subxt::error::{DispatchError, Error as SubxtError};
+
+/// A runtime [`DispatchError`] decoded against the chain metadata, so clients can react to
+/// specific pallet errors instead of parsing strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DispatchErrorDetails {
+    /// Name of the pallet which returned the error, `None` if it isn't a module error.
+    pub pallet: Option<String>,
+    /// Name of the error, ex: `InsufficientBalance` or `Token(BelowMinimum)`.
+    pub error: String,
+    /// Documentation of the error, extracted from the metadata.
+    pub docs: Vec<String>,
+}
+
+impl From<&DispatchError> for DispatchErrorDetails {
+    fn from(error: &DispatchError) -> Self {
+        match error {
+            DispatchError::Module(module_error) => match module_error.details() {
+                Ok(details) => Self {
+                    pallet: Some(details.pallet.name().to_string()),
+                    error: details.variant.name.clone(),
+                    docs: details.variant.docs.clone(),
+                },
+                // The error is not present in the metadata, fallback to the raw indexes
+                Err(_) => Self {
+                    pallet: Some(format!("#{}", module_error.pallet_index())),
+                    error: format!("#{}", module_error.error_index()),
+                    docs: Vec::new(),
+                },
+            },
+            error => {
+                Self { pallet: None, error: format!("{error:?}"), docs: vec![error.to_string()] }
+            },
+        }
+    }
+}
+
+impl fmt::Display for DispatchErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.pallet {
+            Some(pallet) => write!(f, "{pallet}::{}", self.error)?,
+            None => write!(f, "{}", self.error)?,
+        }
+        if !self.docs.is_empty() {
+            write!(f, ": {}", self.docs.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DispatchErrorDetails {}
+
+/// Returned by `/call` when the request is rejected, ex: the storage or constant read isn't
+/// allowed by [`PolkadotClient::with_call_allowlist`](crate::PolkadotClient::with_call_allowlist).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidParams {
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid params: {}", self.reason)
+    }
+}
+
+impl std::error::Error for InvalidParams {}
+
+/// Converts a subxt error into an [`anyhow::Error`], runtime errors are decoded into
+/// [`DispatchErrorDetails`] which can be retrieved with [`anyhow::Error::downcast_ref`].
+pub fn decode_runtime_error(error: SubxtError) -> anyhow::Error {
+    match error {
+        SubxtError::Runtime(dispatch_error) => DispatchErrorDetails::from(&dispatch_error).into(),
+        error => error.into(),
+    }
+}
+
+/// Returns `true` if the transaction pool rejected the extrinsic because it was built with stale
+/// metadata, ex: an outdated nonce or a signature made over an old payload (bad proof). Those
+/// errors are recoverable by rebuilding the extrinsic with fresh metadata.
+pub fn is_stale_transaction_error(error: &anyhow::Error) -> bool {
+    const PATTERNS: [&str; 3] = ["bad proof", "bad signature", "transaction is outdated"];
+    error.chain().any(|cause| {
+        let message = format!("{cause} {cause:?}").to_lowercase();
+        PATTERNS.iter().any(|pattern| message.contains(pattern))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_stale_transaction_errors() {
+        let stale = anyhow::anyhow!("1010: Invalid Transaction: Transaction is outdated");
+        assert!(is_stale_transaction_error(&stale));
+        let bad_proof = anyhow::anyhow!("Invalid Transaction: Transaction has a bad signature")
+            .context("failed to submit extrinsic");
+        assert!(is_stale_transaction_error(&bad_proof));
+        let payment = anyhow::anyhow!("1010: Invalid Transaction: Inability to pay some fees");
+        assert!(!is_stale_transaction_error(&payment));
+    }
+}
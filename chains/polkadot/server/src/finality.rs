@@ -0,0 +1,252 @@
+use anyhow::Context;
+use parity_scale_codec::{Decode, Encode};
+use std::collections::{BTreeSet, HashMap};
+use subxt::{
+    config::{
+        substrate::{BlakeTwo256, SubstrateHeader},
+        Header,
+    },
+    ext::sp_core::{ed25519, Pair},
+    utils::H256,
+};
+
+type BlockHeader = SubstrateHeader<u32, BlakeTwo256>;
+
+/// A GRANDPA authority, its ed25519 public key and its voting weight.
+pub type GrandpaAuthority = ([u8; 32], u64);
+
+/// Proof of finality returned by the `grandpa_proveFinality` rpc.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct FinalityProof {
+    /// Hash of the block finalized by the justification.
+    pub block: H256,
+    /// SCALE encoded [`GrandpaJustification`].
+    pub justification: Vec<u8>,
+    /// Headers from the requested block to the finalized block.
+    pub unknown_headers: Vec<BlockHeader>,
+}
+
+/// Commit of a GRANDPA round, with the ancestry needed to check the precommits.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct GrandpaJustification {
+    pub round: u64,
+    pub commit: Commit,
+    /// Headers from the precommit targets to the commit target.
+    pub votes_ancestries: Vec<BlockHeader>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Commit {
+    pub target_hash: H256,
+    pub target_number: u32,
+    pub precommits: Vec<SignedPrecommit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SignedPrecommit {
+    pub precommit: Precommit,
+    pub signature: [u8; 64],
+    pub id: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Precommit {
+    pub target_hash: H256,
+    pub target_number: u32,
+}
+
+/// Payload signed by the authorities, the precommit variant of the GRANDPA message, the round
+/// and the authority set id.
+fn precommit_payload(precommit: &Precommit, round: u64, set_id: u64) -> Vec<u8> {
+    // `Message::Precommit` is the second variant of the GRANDPA message
+    (1u8, precommit, round, set_id).encode()
+}
+
+/// Verifies the SCALE encoded `grandpa_proveFinality` `proof` against the authority set
+/// `set_id`, returns the hash and number of the finalized block.
+///
+/// # Errors
+/// Returns `Err` if the proof cannot be decoded or the justification doesn't verify.
+pub fn verify_finality_proof(
+    proof: &[u8],
+    set_id: u64,
+    authorities: &[GrandpaAuthority],
+) -> anyhow::Result<(H256, u32)> {
+    let proof = FinalityProof::decode(&mut &*proof).context("invalid finality proof")?;
+    let (hash, number) = verify_justification(&proof.justification, set_id, authorities)?;
+    if hash != proof.block {
+        anyhow::bail!("justification finalizes {hash:?}, the proof is for {:?}", proof.block);
+    }
+    Ok((hash, number))
+}
+
+/// Verifies the SCALE encoded GRANDPA `justification` against the authority set `set_id`: the
+/// precommits for the commit target or its descendants must be signed by authorities holding
+/// at least the supermajority of the weight. Returns the hash and number of the finalized block.
+///
+/// # Errors
+/// Returns `Err` if the justification cannot be decoded or doesn't verify.
+pub fn verify_justification(
+    justification: &[u8],
+    set_id: u64,
+    authorities: &[GrandpaAuthority],
+) -> anyhow::Result<(H256, u32)> {
+    let justification =
+        GrandpaJustification::decode(&mut &*justification).context("invalid justification")?;
+    let commit = &justification.commit;
+    let ancestries = justification
+        .votes_ancestries
+        .iter()
+        .map(|header| (header.hash(), header))
+        .collect::<HashMap<_, _>>();
+    let weights = authorities.iter().copied().collect::<HashMap<_, _>>();
+
+    let mut signers = BTreeSet::new();
+    let mut signed_weight = 0u64;
+    for signed in &commit.precommits {
+        let Some(weight) = weights.get(&signed.id) else {
+            anyhow::bail!("precommit signed by 0x{}, not an authority", hex::encode(signed.id));
+        };
+        let payload = precommit_payload(&signed.precommit, justification.round, set_id);
+        let signature = ed25519::Signature::from_raw(signed.signature);
+        if !ed25519::Pair::verify(&signature, payload, &ed25519::Public::from_raw(signed.id)) {
+            anyhow::bail!("invalid precommit signature of 0x{}", hex::encode(signed.id));
+        }
+        if !is_descendant(&ancestries, signed.precommit.target_hash, commit.target_hash) {
+            anyhow::bail!(
+                "precommit target {:?} isn't a descendant of the commit target {:?}",
+                signed.precommit.target_hash,
+                commit.target_hash
+            );
+        }
+        // Equivocations are only counted once
+        if signers.insert(signed.id) {
+            signed_weight = signed_weight.saturating_add(*weight);
+        }
+    }
+
+    let total_weight = authorities.iter().map(|(_, weight)| weight).sum::<u64>();
+    let threshold = total_weight - total_weight.saturating_sub(1) / 3;
+    if signed_weight < threshold {
+        anyhow::bail!("justification signed by {signed_weight} of {threshold} required weight");
+    }
+    Ok((commit.target_hash, commit.target_number))
+}
+
+/// Returns `true` if `block` is `ancestor` or one of its descendants, following the parents in
+/// `ancestries`.
+fn is_descendant(ancestries: &HashMap<H256, &BlockHeader>, block: H256, ancestor: H256) -> bool {
+    let mut current = block;
+    loop {
+        if current == ancestor {
+            return true;
+        }
+        match ancestries.get(&current) {
+            Some(header) => current = header.parent_hash,
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::config::substrate::Digest;
+
+    const SET_ID: u64 = 3;
+    const ROUND: u64 = 7;
+
+    fn pair(seed: u8) -> ed25519::Pair {
+        ed25519::Pair::from_seed(&[seed; 32])
+    }
+
+    fn header(parent_hash: H256, number: u32) -> BlockHeader {
+        BlockHeader {
+            parent_hash,
+            number,
+            state_root: H256::zero(),
+            extrinsics_root: H256::zero(),
+            digest: Digest::default(),
+        }
+    }
+
+    fn sign(pair: &ed25519::Pair, target_hash: H256, target_number: u32) -> SignedPrecommit {
+        let precommit = Precommit { target_hash, target_number };
+        let signature = pair.sign(&precommit_payload(&precommit, ROUND, SET_ID));
+        SignedPrecommit { precommit, signature: signature.0, id: pair.public().0 }
+    }
+
+    fn justification(
+        target: &BlockHeader,
+        precommits: Vec<SignedPrecommit>,
+        votes_ancestries: Vec<BlockHeader>,
+    ) -> Vec<u8> {
+        GrandpaJustification {
+            round: ROUND,
+            commit: Commit { target_hash: target.hash(), target_number: target.number, precommits },
+            votes_ancestries,
+        }
+        .encode()
+    }
+
+    fn authorities() -> Vec<GrandpaAuthority> {
+        (1..=4).map(|seed| (pair(seed).public().0, 1)).collect()
+    }
+
+    #[test]
+    fn verifies_justification() {
+        let target = header(H256::repeat_byte(1), 10);
+        let child = header(target.hash(), 11);
+        // One authority voted for a descendant of the target
+        let mut precommits =
+            (1..=2).map(|seed| sign(&pair(seed), target.hash(), 10)).collect::<Vec<_>>();
+        precommits.push(sign(&pair(3), child.hash(), 11));
+        let justification = justification(&target, precommits, vec![child]);
+        assert_eq!(
+            verify_justification(&justification, SET_ID, &authorities()).unwrap(),
+            (target.hash(), 10)
+        );
+
+        let proof =
+            FinalityProof { block: target.hash(), justification, unknown_headers: Vec::new() };
+        assert_eq!(
+            verify_finality_proof(&proof.encode(), SET_ID, &authorities()).unwrap(),
+            (target.hash(), 10)
+        );
+    }
+
+    #[test]
+    fn rejects_insufficient_weight() {
+        let target = header(H256::repeat_byte(1), 10);
+        // Equivocations don't count twice
+        let precommits = vec![
+            sign(&pair(1), target.hash(), 10),
+            sign(&pair(2), target.hash(), 10),
+            sign(&pair(2), target.hash(), 10),
+        ];
+        let justification = justification(&target, precommits, Vec::new());
+        let error = verify_justification(&justification, SET_ID, &authorities()).unwrap_err();
+        assert_eq!(error.to_string(), "justification signed by 2 of 3 required weight");
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let target = header(H256::repeat_byte(1), 10);
+        let precommits = (1..=4).map(|seed| sign(&pair(seed), target.hash(), 10)).collect();
+        let justification = justification(&target, precommits, Vec::new());
+        // Signed for another authority set
+        assert!(verify_justification(&justification, SET_ID + 1, &authorities()).is_err());
+        // Signed by unknown authorities
+        let others = (5..=8).map(|seed| (pair(seed).public().0, 1)).collect::<Vec<_>>();
+        assert!(verify_justification(&justification, SET_ID, &others).is_err());
+    }
+
+    #[test]
+    fn rejects_votes_outside_the_ancestry() {
+        let target = header(H256::repeat_byte(1), 10);
+        let fork = header(H256::repeat_byte(2), 11);
+        let precommits = (1..=3).map(|seed| sign(&pair(seed), fork.hash(), 11)).collect();
+        let justification = justification(&target, precommits, vec![fork]);
+        assert!(verify_justification(&justification, SET_ID, &authorities()).is_err());
+    }
+}
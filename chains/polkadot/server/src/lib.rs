@@ -1,30 +1,97 @@
 use anyhow::{Context, Result};
 use chains::WestendDevConfig;
-use parity_scale_codec::{Decode, Encode};
-pub use rosetta_config_polkadot::{PolkadotMetadata, PolkadotMetadataParams};
+use parity_scale_codec::{Compact, Decode, Encode};
+pub use rosetta_config_polkadot::{
+    DecodedCall, MortalEra, PolkadotMetadata, PolkadotMetadataParams,
+};
+pub use rosetta_core::types::{
+    BlockTransaction, OPERATION_STATUS_FAILURE, OPERATION_STATUS_SUCCESS,
+};
 use rosetta_core::{
+    check_synced,
     crypto::{address::Address, PublicKey},
-    types::{BlockIdentifier, CallRequest, PartialBlockIdentifier},
-    BlockchainClient, BlockchainConfig, EmptyEventStream,
+    types::{
+        operation_identifier, AccountIdentifier, Amount, BlockIdentifier, CallRequest, Currency,
+        Operation, PartialBlockIdentifier, Peer, TransactionIdentifier,
+    },
+    BlockchainClient, BlockchainConfig, EmptyEventStream, InclusionEstimate,
 };
-use rosetta_server::ws::default_client;
+pub use rosetta_server::ws::ConnectionStatus;
+use rosetta_server::ws::{default_client, DefaultClient};
+use rosetta_types::Transaction;
 use serde_json::Value;
 use sp_keyring::AccountKeyring;
 use subxt::{
     config::Header,
     tx::{PairSigner, SubmittableExtrinsic},
-    utils::{AccountId32, MultiAddress},
+    utils::{AccountId32, MultiAddress, H256},
 };
 
 mod call;
 mod chains;
 mod client;
+mod error;
+mod finality;
+mod proof;
 mod types;
 
+pub use error::{is_stale_transaction_error, DispatchErrorDetails, InvalidParams};
+pub use finality::{
+    verify_finality_proof, verify_justification, Commit, FinalityProof, GrandpaAuthority,
+    GrandpaJustification, Precommit, SignedPrecommit,
+};
+pub use proof::{verify_read_proof, ReadProof};
+pub use subxt::ext::sp_runtime::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, UnknownTransaction,
+    ValidTransaction,
+};
+
+/// Number of recent blocks used to estimate how many extrinsics are included per block, see
+/// [`PolkadotClient::inclusion_estimate`].
+pub const INCLUSION_ESTIMATE_BLOCKS: u64 = 10;
+
+/// Default maximum number of operations of a transfer, see [`PolkadotClient::transfer_params`].
+pub const DEFAULT_MAX_OPERATIONS: usize = 16;
+
+/// Genesis hash of the well known public chains, verified by [`PolkadotClient::preflight`].
+fn known_genesis_hash(config: &BlockchainConfig) -> Option<&'static str> {
+    let genesis_hash = match (config.blockchain, config.network) {
+        ("polkadot", "mainnet") => {
+            "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3"
+        },
+        ("kusama", "mainnet") => "b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe",
+        ("westend", "mainnet") => {
+            "e143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e"
+        },
+        _ => return None,
+    };
+    Some(genesis_hash)
+}
+
+/// An event emitted in a block, returned by [`PolkadotClient::events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord {
+    pub block_identifier: BlockIdentifier,
+    /// Index of the event in the block.
+    pub event_index: u32,
+    /// Index of the extrinsic which emitted the event, `None` for the events emitted during the
+    /// block initialization or finalization.
+    pub extrinsic_index: Option<u32>,
+    pub pallet: String,
+    pub event: String,
+    /// SCALE encoded fields of the event.
+    pub fields: Vec<u8>,
+}
+
+#[derive(Clone)]
 pub struct PolkadotClient {
     config: BlockchainConfig,
     client: client::SubstrateClient<chains::WestendDevConfig>,
+    ws_client: DefaultClient,
+    currency: Currency,
     genesis_block: BlockIdentifier,
+    call_allowlist: Option<Vec<String>>,
+    max_operations: usize,
 }
 
 impl PolkadotClient {
@@ -43,13 +110,644 @@ impl PolkadotClient {
     /// Will return `Err` when the network is invalid, or when the provided `addr` is unreacheable.
     pub async fn from_config(config: BlockchainConfig, addr: &str) -> Result<Self> {
         let ws_client = default_client(addr, None).await?;
-        let client = client::SubstrateClient::<WestendDevConfig>::from_client(ws_client).await?;
+        Self::from_jsonrpsee(config, ws_client).await
+    }
+
+    /// Creates a new substrate client using the provided `config` and `JsonRpsee` client.
+    ///
+    /// # Errors
+    /// Will return `Err` when the network is invalid, or when the node is unreacheable.
+    pub async fn from_jsonrpsee(
+        config: BlockchainConfig,
+        ws_client: DefaultClient,
+    ) -> Result<Self> {
+        let client =
+            client::SubstrateClient::<WestendDevConfig>::from_client(ws_client.clone()).await?;
         let genesis = client.genesis_hash();
         let genesis_block = BlockIdentifier { index: 0, hash: genesis.0 };
-        Ok(Self { config, client, genesis_block })
+        let properties = client.system_properties().await?;
+        let currency = native_currency(&config, &properties);
+        Ok(Self {
+            config,
+            client,
+            ws_client,
+            currency,
+            genesis_block,
+            call_allowlist: None,
+            max_operations: DEFAULT_MAX_OPERATIONS,
+        })
+    }
+
+    /// Returns the native currency of the chain, the symbol and decimals reported by the node
+    /// `system_properties` take precedence over the configured ones.
+    #[must_use]
+    pub const fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// Returns the status of the websocket connection, without making any request.
+    #[must_use]
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.ws_client.connection_status()
+    }
+
+    /// Restricts the storage and constant reads allowed by `/call`, each entry is either
+    /// `<pallet>-<item>`, ex: `System-Account`, or `<pallet>-*` to allow every item of the
+    /// pallet. Disallowed reads fail with [`InvalidParams`]. By default all reads are allowed.
+    #[must_use]
+    pub fn with_call_allowlist<I, S>(mut self, allowlist: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.call_allowlist = Some(allowlist.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Limits the number of operations accepted by [`Self::transfer_params`], the default is
+    /// [`DEFAULT_MAX_OPERATIONS`].
+    #[must_use]
+    pub const fn with_max_operations(mut self, max_operations: usize) -> Self {
+        self.max_operations = max_operations;
+        self
+    }
+
+    fn is_call_allowed(&self, pallet_name: &str, item_name: &str) -> bool {
+        let Some(allowlist) = &self.call_allowlist else {
+            return true;
+        };
+        allowlist.iter().any(|entry| {
+            entry.split_once('-').is_some_and(|(pallet, item)| {
+                pallet.eq_ignore_ascii_case(pallet_name) &&
+                    (item == "*" || item.eq_ignore_ascii_case(item_name))
+            })
+        })
+    }
+
+    /// Returns the balance of `address`, when `asset_id` is provided the balance is read from
+    /// the `Assets` pallet instead of `Balances`, and the currency reflects the asset metadata.
+    ///
+    /// # Errors
+    /// Will return `Err` when the address is invalid, or the asset doesn't exist.
+    pub async fn account_balance(
+        &self,
+        address: &Address,
+        asset_id: Option<u32>,
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<Amount> {
+        let Some(asset_id) = asset_id else {
+            let balance = self.balance(address, block_identifier).await?;
+            return Ok(Amount::new(balance.to_string(), self.currency.clone()));
+        };
+        let account: AccountId32 = address
+            .address()
+            .parse()
+            .map_err(|err| anyhow::anyhow!("{}", err))
+            .context("invalid address")?;
+        let asset = self.client.asset_balance(asset_id, account, block_identifier).await?;
+        let currency = Currency {
+            symbol: asset.symbol,
+            decimals: u32::from(asset.decimals),
+            metadata: Some(serde_json::json!({ "asset_id": asset_id })),
+        };
+        Ok(Amount::new(asset.balance.to_string(), currency))
+    }
+
+    /// Returns the nonce of `address` at `block_identifier`, read from the `System.Account`
+    /// storage.
+    ///
+    /// # Errors
+    /// Will return `Err` when the address is invalid, or the block doesn't exist.
+    pub async fn nonce(
+        &self,
+        address: &Address,
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<u64> {
+        let account: AccountId32 = address
+            .address()
+            .parse()
+            .map_err(|err| anyhow::anyhow!("{}", err))
+            .context("invalid address")?;
+        let account_info = self.client.account_info(account, block_identifier).await?;
+        Ok(u64::from(account_info.nonce))
+    }
+
+    /// Returns the peers the node is connected to, from `system_peers`. The node must expose the
+    /// unsafe rpc methods.
+    ///
+    /// # Errors
+    /// Will return `Err` when `system_peers` is not exposed by the node.
+    pub async fn peers(&self) -> Result<Vec<Peer>> {
+        let peers = self.client.system_peers().await?;
+        peers
+            .into_iter()
+            .map(|peer| {
+                let peer_id = peer
+                    .get("peerId")
+                    .and_then(Value::as_str)
+                    .context("system_peers returned a peer without peerId")?
+                    .to_string();
+                Ok(Peer { peer_id, metadata: Some(peer) })
+            })
+            .collect()
+    }
+
+    /// Returns the active validators at `block_identifier`, from the `Session.Validators`
+    /// storage, encoded in the configured address format.
+    ///
+    /// # Errors
+    /// Will return `Err` when the block doesn't exist, or the chain has no `Session` pallet.
+    pub async fn validators(
+        &self,
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<Vec<Address>> {
+        let validators = self.client.session_validators(block_identifier).await?;
+        Ok(validators
+            .iter()
+            .map(|validator| {
+                Address::from_public_key_bytes(self.config.address_format, &validator.0)
+            })
+            .collect())
+    }
+
+    /// Returns all extrinsics in the block, the operations of failed extrinsics are marked as
+    /// [`OPERATION_STATUS_FAILURE`] and have no effect on balances, except for the fee payment.
+    /// Transfers are decoded from the `Balances.Transfer` events, including the ones performed
+    /// by nested calls like `utility.batch`. The `Balances.Deposit` events crediting the fee to
+    /// the block author are reported as `FEE_REWARD` operations, which balance the `FEE`, and
+    /// the staking rewards paid out by an extrinsic as `REWARD` operations. Rewards deposited
+    /// outside of extrinsics, ex: on block initialization, are not reported.
+    ///
+    /// # Errors
+    /// Will return `Err` when the block doesn't exist or cannot be decoded.
+    pub async fn block_transactions(
+        &self,
+        block_identifier: &PartialBlockIdentifier,
+    ) -> Result<Vec<BlockTransaction>> {
+        let extrinsics = self.client.extrinsics(block_identifier).await?;
+        let operation =
+            |index: usize, account: &AccountId32, value: String, status: &str| Operation {
+                status: Some(status.into()),
+                ..self.balance_operation(index, account, value)
+            };
+
+        let mut transactions = Vec::with_capacity(extrinsics.len());
+        for extrinsic in extrinsics {
+            let status =
+                if extrinsic.success { OPERATION_STATUS_SUCCESS } else { OPERATION_STATUS_FAILURE };
+            let mut operations = Vec::new();
+            if let Some(signer) = &extrinsic.signer {
+                // The fee is paid even if the extrinsic failed
+                if let Some(fee) = extrinsic.fee {
+                    operations.push(Operation {
+                        r#type: "FEE".into(),
+                        ..operation(
+                            operations.len(),
+                            signer,
+                            format!("-{fee}"),
+                            OPERATION_STATUS_SUCCESS,
+                        )
+                    });
+                    // The fee is credited to the block author, and the treasury on some chains
+                    for deposit in &extrinsic.fee_deposits {
+                        operations.push(Operation {
+                            r#type: "FEE_REWARD".into(),
+                            related_operations: Some(vec![operation_identifier(0)]),
+                            ..operation(
+                                operations.len(),
+                                &deposit.who,
+                                deposit.amount.to_string(),
+                                OPERATION_STATUS_SUCCESS,
+                            )
+                        });
+                    }
+                }
+                // Failed transfers have no effect on balances and emit no events
+                if let (false, Some((dest, _))) = (extrinsic.success, &extrinsic.transfer) {
+                    let debit_index = operations.len();
+                    operations.push(operation(debit_index, signer, "0".into(), status));
+                    operations.push(Operation {
+                        related_operations: Some(vec![operation_identifier(debit_index)]),
+                        ..operation(operations.len(), dest, "0".into(), status)
+                    });
+                }
+            }
+            // Transfers are read from the events, so the ones dispatched by nested calls are
+            // included
+            for transfer in &extrinsic.transfers {
+                let debit_index = operations.len();
+                let debit = format!("-{}", transfer.amount);
+                operations.push(operation(debit_index, &transfer.from, debit, status));
+                operations.push(Operation {
+                    related_operations: Some(vec![operation_identifier(debit_index)]),
+                    ..operation(operations.len(), &transfer.to, transfer.amount.to_string(), status)
+                });
+            }
+            for reward in &extrinsic.rewards {
+                operations.push(Operation {
+                    r#type: "REWARD".into(),
+                    ..operation(
+                        operations.len(),
+                        &reward.who,
+                        reward.amount.to_string(),
+                        OPERATION_STATUS_SUCCESS,
+                    )
+                });
+            }
+            transactions.push(BlockTransaction {
+                transaction_identifier: TransactionIdentifier::new(format!(
+                    "0x{}",
+                    hex::encode(extrinsic.hash)
+                )),
+                index: u64::from(extrinsic.index),
+                success: extrinsic.success,
+                operations,
+            });
+        }
+        Ok(transactions)
+    }
+
+    /// Maps the `TRANSFER` operations of a transaction to the call which performs them, the
+    /// operations are the same reported by [`Self::block_transactions`]:
+    /// - a debit of the sender followed by a credit of the receiver is a
+    ///   `Balances.transfer_keep_alive`.
+    /// - a debit of the sender followed by many credits is a multi-output transfer, dispatched as
+    ///   `Utility.batch_all` of `Balances.transfer_keep_alive`, the credits must sum to the debit.
+    ///
+    /// The debited account must sign the transaction, the returned params have no nonce, tip or
+    /// mortality.
+    ///
+    /// # Errors
+    /// Will return [`InvalidParams`] when the operations have another shape, or there are more
+    /// than the maximum set by [`Self::with_max_operations`].
+    pub fn transfer_params(&self, operations: &[Operation]) -> Result<PolkadotMetadataParams> {
+        let invalid = |reason: String| anyhow::Error::from(InvalidParams { reason });
+        if operations.len() > self.max_operations {
+            return Err(invalid(format!(
+                "{} operations exceed the maximum of {}",
+                operations.len(),
+                self.max_operations
+            )));
+        }
+        let mut transfers = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.iter().enumerate() {
+            if operation.r#type != "TRANSFER" {
+                return Err(invalid(format!(
+                    "operation {index} is a {}, expected a TRANSFER",
+                    operation.r#type
+                )));
+            }
+            let (Some(account), Some(amount)) = (&operation.account, &operation.amount) else {
+                return Err(invalid(format!("operation {index} has no account or amount")));
+            };
+            if amount.currency != self.currency {
+                return Err(invalid(format!(
+                    "operation {index} transfers {}, expected {}",
+                    amount.currency.symbol, self.currency.symbol
+                )));
+            }
+            let account = account.address.parse::<AccountId32>().map_err(|err| {
+                invalid(format!("operation {index} has an invalid account: {err}"))
+            })?;
+            let value = amount.value.parse::<i128>().map_err(|err| {
+                invalid(format!("operation {index} has an invalid amount: {err}"))
+            })?;
+            transfers.push((account, value));
+        }
+
+        let [(_, debit), credits @ ..] = transfers.as_slice() else {
+            return Err(invalid("expected a debit and at least one credit operation".into()));
+        };
+        if credits.is_empty() {
+            return Err(invalid("expected a debit and at least one credit operation".into()));
+        }
+        if *debit >= 0 || credits.iter().any(|(_, credit)| *credit <= 0) {
+            return Err(invalid("expected a debit followed by credit operations".into()));
+        }
+        let total = credits
+            .iter()
+            .try_fold(0i128, |total, (_, credit)| total.checked_add(*credit))
+            .ok_or_else(|| invalid("the credits overflow".into()))?;
+        if total.checked_neg() != Some(*debit) {
+            return Err(invalid(format!("the credits sum to {total}, the debit is {debit}")));
+        }
+
+        let transfer_args = |dest: &AccountId32, value: i128| {
+            let value = value.unsigned_abs();
+            (MultiAddress::<AccountId32, ()>::Id(dest.clone()), Compact(value)).encode()
+        };
+        let (pallet_name, call_name, call_args) = if let [(dest, value)] = credits {
+            ("Balances", "transfer_keep_alive", transfer_args(dest, *value))
+        } else {
+            let metadata = self.client.client().metadata();
+            let pallet =
+                metadata.pallet_by_name("Balances").context("Balances pallet not found")?;
+            let call_index = pallet
+                .call_variant_by_name("transfer_keep_alive")
+                .context("Balances.transfer_keep_alive call not found")?
+                .index;
+            let len = u32::try_from(credits.len()).context("too many credits")?;
+            let mut call_args = Compact(len).encode();
+            for (dest, value) in credits {
+                call_args.extend([pallet.index(), call_index]);
+                call_args.extend(transfer_args(dest, *value));
+            }
+            ("Utility", "batch_all", call_args)
+        };
+        Ok(PolkadotMetadataParams {
+            nonce: None,
+            pallet_name: pallet_name.into(),
+            call_name: call_name.into(),
+            call_args,
+            tip: None,
+            mortality: None,
+        })
+    }
+
+    /// Verifies the node is reachable, serves the chain of the config when its genesis is well
+    /// known, and is at most `max_blocks_behind` blocks behind the highest block announced by
+    /// its peers, from `system_syncState`.
+    ///
+    /// # Errors
+    /// Will return `Err` when the node is unreachable, serves another chain, or isn't synced.
+    pub async fn preflight(&self, max_blocks_behind: u64) -> Result<()> {
+        if let Some(expected) = known_genesis_hash(&self.config) {
+            let genesis_hash = hex::encode(self.genesis_block.hash);
+            if genesis_hash != expected {
+                anyhow::bail!(
+                    "node serves the chain with genesis 0x{genesis_hash}, expected 0x{expected} \
+                     for {} {}",
+                    self.config.blockchain,
+                    self.config.network
+                );
+            }
+        }
+        let state = self.client.system_sync_state().await.context("node is unreachable")?;
+        let current_block = state
+            .get("currentBlock")
+            .and_then(Value::as_u64)
+            .context("system_syncState returned an invalid currentBlock")?;
+        let highest_block =
+            state.get("highestBlock").and_then(Value::as_u64).unwrap_or(current_block);
+        check_synced(current_block, highest_block, max_blocks_behind)
+    }
+
+    /// Returns the `pallet` events emitted from block `from` to block `to` included, in order,
+    /// optionally only the ones named `event`. Names are case sensitive, ex: `Balances` and
+    /// `Transfer`.
+    ///
+    /// # Errors
+    /// Will return `Err` when a block of the range doesn't exist or its events cannot be decoded.
+    pub async fn events(
+        &self,
+        from: u64,
+        to: u64,
+        pallet: &str,
+        event: Option<&str>,
+    ) -> Result<Vec<EventRecord>> {
+        let mut records = Vec::new();
+        for index in from..=to {
+            let block = PartialBlockIdentifier::by_number(index);
+            let (hash, events) = self.client.events(&block).await?;
+            let block_identifier = BlockIdentifier { index, hash: hash.0 };
+            records.extend(
+                events
+                    .into_iter()
+                    .filter(|info| {
+                        info.pallet == pallet &&
+                            (event.is_none() || event == Some(info.variant.as_str()))
+                    })
+                    .map(|info| EventRecord {
+                        block_identifier: block_identifier.clone(),
+                        event_index: info.index,
+                        extrinsic_index: info.extrinsic_index,
+                        pallet: info.pallet,
+                        event: info.variant,
+                        fields: info.fields,
+                    }),
+            );
+        }
+        Ok(records)
+    }
+
+    /// Estimates the fee of the signed `extrinsic`, excluding the tip.
+    ///
+    /// # Errors
+    /// Will return `Err` when the extrinsic is invalid.
+    pub async fn estimate_extrinsic_fee(&self, extrinsic: &[u8]) -> Result<u128> {
+        self.client.estimate_extrinsic_fee(extrinsic).await
+    }
+
+    /// Dry-runs the signed `extrinsic` against the latest block, returns its [`ValidTransaction`]
+    /// priority, tags and longevity, or why the transaction pool would reject it, ex: an
+    /// [`InvalidTransaction::Stale`] nonce or an [`InvalidTransaction::Payment`] failure.
+    ///
+    /// # Errors
+    /// Will return `Err` when the runtime api cannot be called.
+    pub async fn validate_transaction(&self, extrinsic: &[u8]) -> Result<TransactionValidity> {
+        self.client
+            .validate_transaction(extrinsic, types::BlockIdentifier::<_>::Latest)
+            .await
+    }
+
+    /// Returns the existential deposit, the minimum balance an account must keep to stay alive.
+    ///
+    /// # Errors
+    /// Will return `Err` when the chain has no `Balances` pallet.
+    pub fn existential_deposit(&self) -> Result<u128> {
+        self.client.existential_deposit()
+    }
+
+    /// Exports the raw chain spec of the running chain, which can be used to start other nodes
+    /// of the same chain.
+    ///
+    /// # Errors
+    /// Will return `Err` when the node doesn't expose the unsafe `sync_state_genSyncSpec` rpc.
+    pub async fn chain_spec(&self) -> Result<Value> {
+        self.client.chain_spec().await
+    }
+
+    /// Returns the SCALE encoded GRANDPA [`FinalityProof`] of the finalized block `index`, `None`
+    /// when the block isn't finalized yet.
+    ///
+    /// # Errors
+    /// Will return `Err` when the node doesn't run GRANDPA or the proof cannot be generated.
+    pub async fn finality_proof(&self, index: u64) -> Result<Option<Vec<u8>>> {
+        let number = u32::try_from(index).context("block number overflow")?;
+        self.client.prove_finality(number).await
+    }
+
+    /// Verifies a [`FinalityProof`] against the GRANDPA authority set of the latest finalized
+    /// block, returns the block finalized by the proof.
+    ///
+    /// # Errors
+    /// Will return `Err` when the authority set cannot be read, or the proof doesn't verify.
+    pub async fn verify_finality_proof(&self, proof: &[u8]) -> Result<BlockIdentifier> {
+        let (set_id, authorities) =
+            self.client.grandpa_authorities(types::BlockIdentifier::<_>::Finalized).await?;
+        let (hash, number) = finality::verify_finality_proof(proof, set_id, &authorities)?;
+        Ok(BlockIdentifier { index: u64::from(number), hash: hash.0 })
+    }
+
+    /// Returns the read proof of the storage `key` at the latest block.
+    ///
+    /// # Errors
+    /// Will return `Err` when the node cannot generate the proof.
+    pub async fn read_proof(&self, key: &[u8]) -> Result<ReadProof<H256>> {
+        let keys = [key.to_vec()];
+        self.client.read_proof(&keys, types::BlockIdentifier::<_>::Latest).await
+    }
+
+    /// Verifies the read `proof` of the storage `key` against the state root of the block `at`,
+    /// returns the proven value, `None` when the key is absent.
+    ///
+    /// # Errors
+    /// Will return `Err` when the block header cannot be read, or the proof doesn't verify.
+    pub async fn verify_read_proof(
+        &self,
+        at: H256,
+        key: &[u8],
+        proof: &[Vec<u8>],
+    ) -> Result<Option<Vec<u8>>> {
+        let header = self.client.block_header(types::BlockIdentifier::Hash(at)).await?;
+        proof::verify_read_proof(header.state_root, proof, key)
+    }
+
+    /// Returns the identifiers of the extrinsics pending in the pool of the node, empty when the
+    /// pool is empty.
+    ///
+    /// # Errors
+    /// Will return `Err` when the pool cannot be read.
+    pub async fn mempool(&self) -> Result<Vec<TransactionIdentifier>> {
+        let pending = self.client.pending_extrinsics().await?;
+        Ok(pending
+            .into_iter()
+            .map(|(hash, _)| TransactionIdentifier::new(format!("0x{}", hex::encode(hash))))
+            .collect())
+    }
+
+    /// Returns the extrinsic `transaction` while it is pending in the pool of the node, `None`
+    /// once it left the pool. The call is decoded like the ones of [`Self::block_transactions`],
+    /// but pending extrinsics emitted no events yet, so only the debit of the signer and the
+    /// credit of the destination of a `Balances` transfer call are reported, without status.
+    ///
+    /// # Errors
+    /// Will return `Err` when the pool cannot be read or the extrinsic cannot be decoded.
+    pub async fn mempool_transaction(
+        &self,
+        transaction: &TransactionIdentifier,
+    ) -> Result<Option<Transaction>> {
+        let pending = self.client.pending_extrinsics().await?;
+        let Some((hash, extrinsic)) = pending.into_iter().find(|(hash, _)| {
+            format!("0x{}", hex::encode(hash)).eq_ignore_ascii_case(&transaction.hash)
+        }) else {
+            return Ok(None);
+        };
+        let extrinsic = self.client.decode_pending_extrinsic(hash, extrinsic)?;
+        let mut operations = Vec::new();
+        if let (Some(signer), Some((dest, value))) = (&extrinsic.signer, &extrinsic.transfer) {
+            operations.push(self.balance_operation(0, signer, format!("-{value}")));
+            operations.push(Operation {
+                related_operations: Some(vec![operation_identifier(0)]),
+                ..self.balance_operation(1, dest, value.to_string())
+            });
+        }
+        let transaction_identifier =
+            TransactionIdentifier::new(format!("0x{}", hex::encode(extrinsic.hash)));
+        Ok(Some(Transaction::new(transaction_identifier, operations)))
+    }
+
+    /// Returns the `TRANSFER` operation at `index` changing the balance of `account` by `value`.
+    fn balance_operation(&self, index: usize, account: &AccountId32, value: String) -> Operation {
+        let address = Address::from_public_key_bytes(self.config.address_format, &account.0);
+        Operation {
+            operation_identifier: operation_identifier(index),
+            r#type: "TRANSFER".into(),
+            account: Some(AccountIdentifier {
+                address: address.address().into(),
+                sub_account: None,
+                metadata: None,
+            }),
+            amount: Some(Amount::new(value, self.currency.clone())),
+            ..Operation::default()
+        }
+    }
+
+    /// Estimates whether an extrinsic of the mortal `era` is included before the era ends, from
+    /// the extrinsics pending in the pool of the node and the most signed extrinsics included in
+    /// one of the last [`INCLUSION_ESTIMATE_BLOCKS`] blocks.
+    ///
+    /// # Errors
+    /// Will return `Err` when the pool or the recent blocks cannot be read.
+    pub async fn inclusion_estimate(&self, era: &MortalEra) -> Result<InclusionEstimate> {
+        let best_block = self.current_block().await?.index;
+        // The extrinsic can be included from the next block until the era ends
+        let remaining_blocks = era.expiry_block().saturating_sub(best_block + 1);
+        let pending = self.client.pending_extrinsics_count().await?;
+        let mut per_block = 0;
+        for index in best_block.saturating_sub(INCLUSION_ESTIMATE_BLOCKS - 1)..=best_block {
+            let block = PartialBlockIdentifier::by_number(index);
+            per_block = per_block.max(self.client.signed_extrinsics_count(&block).await?);
+        }
+        Ok(InclusionEstimate::new(pending as u64, per_block as u64, remaining_blocks))
+    }
+
+    /// Returns the era of an extrinsic valid for `mortality` blocks, checkpointed at the latest
+    /// finalized block. The period must fit in the block hashes kept by the chain.
+    ///
+    /// The era isn't checkpointed at the best block: the checkpoint hash is part of the signed
+    /// payload, so an extrinsic checkpointed at a best block which gets reorganized is invalid.
+    /// Checkpointing at the finalized block shortens the validity by the finality lag instead.
+    async fn mortal_era(&self, mortality: u64) -> Result<MortalEra> {
+        let block_hash_count = self
+            .client
+            .metadata()
+            .pallet_by_name("System")
+            .and_then(|pallet| pallet.constant_by_name("BlockHashCount"))
+            .map(|constant| u32::decode(&mut constant.value()))
+            .context("System.BlockHashCount not found")??;
+        let limit = u64::from(block_hash_count).min(1 << 16);
+        let period = mortality.checked_next_power_of_two().unwrap_or(u64::MAX).max(4);
+        if mortality == 0 || period > limit {
+            anyhow::bail!(
+                "invalid mortality {mortality}, the period must be between 1 and {limit} blocks"
+            );
+        }
+        let block = self.finalized_block().await?;
+        let era = MortalEra { period, block_number: block.index, block_hash: block.hash };
+        let best_block = self.current_block().await?.index;
+        if era.expiry_block() <= best_block + 1 {
+            anyhow::bail!(
+                "invalid mortality {mortality}, the finalized block {} is too far behind the best block {best_block}",
+                block.index
+            );
+        }
+        Ok(era)
     }
 }
 
+/// Returns the native currency of the chain, from the `tokenSymbol` and `tokenDecimals`
+/// `system_properties` when present, or from the `config` otherwise. Chains with multiple tokens
+/// report arrays, the first entry is the native token.
+fn native_currency(
+    config: &BlockchainConfig,
+    properties: &serde_json::Map<String, Value>,
+) -> Currency {
+    let first = |key: &str| match properties.get(key) {
+        Some(Value::Array(values)) => values.first().cloned(),
+        value => value.cloned(),
+    };
+    let symbol = first("tokenSymbol")
+        .and_then(|symbol| symbol.as_str().map(str::to_string))
+        .unwrap_or_else(|| config.currency_symbol.to_string());
+    let decimals = first("tokenDecimals")
+        .as_ref()
+        .and_then(Value::as_u64)
+        .and_then(|decimals| u32::try_from(decimals).ok())
+        .unwrap_or(config.currency_decimals);
+    Currency { symbol, decimals, metadata: None }
+}
+
 #[async_trait::async_trait]
 impl BlockchainClient for PolkadotClient {
     type MetadataParams = PolkadotMetadataParams;
@@ -164,10 +862,32 @@ impl BlockchainClient for PolkadotClient {
             .call_variant_by_name(&params.call_name)
             .ok_or_else(|| anyhow::anyhow!("call name not found"))?;
         let call_index = call_variant.index;
+        let decoded_call =
+            crate::call::decode_call_args(call_variant, &params.call_args, metadata.types())
+                .map(|args| DecodedCall {
+                    pallet: params.pallet_name.clone(),
+                    call: params.call_name.clone(),
+                    args,
+                })
+                .ok();
         let call_hash = pallet
             .call_hash(&params.call_name)
             .ok_or_else(|| anyhow::anyhow!("call hash not found"))?;
         let genesis_hash = self.client.genesis_hash().0;
+        let signed_extensions = metadata
+            .extrinsic()
+            .signed_extensions()
+            .iter()
+            .map(|extension| extension.identifier().to_string())
+            .collect();
+
+        let tip = params.tip.unwrap_or_default();
+
+        let era = match params.mortality {
+            Some(mortality) => Some(self.mortal_era(mortality).await?),
+            None => None,
+        };
+
         Ok(PolkadotMetadata {
             nonce,
             spec_version: runtime.spec_version,
@@ -176,6 +896,10 @@ impl BlockchainClient for PolkadotClient {
             pallet_index,
             call_index,
             call_hash,
+            tip,
+            decoded_call,
+            era,
+            signed_extensions,
         })
     }
 
@@ -185,7 +909,8 @@ impl BlockchainClient for PolkadotClient {
                 .submit_and_watch()
                 .await?
                 .wait_for_finalized_success()
-                .await?
+                .await
+                .map_err(error::decode_runtime_error)?
                 .extrinsic_hash();
         Ok(hash.0.to_vec())
     }
@@ -198,6 +923,12 @@ impl BlockchainClient for PolkadotClient {
         let pallet_name = call_details[0];
         let call_name = call_details[1];
         let query_type = call_details[2];
+        if !self.is_call_allowed(pallet_name, call_name) {
+            return Err(InvalidParams {
+                reason: format!("{pallet_name}::{call_name} reads are not allowed"),
+            }
+            .into());
+        }
         match query_type.to_lowercase().as_str() {
             "constant" => {
                 crate::call::dynamic_constant_req(self.client.client(), pallet_name, call_name)
@@ -211,6 +942,34 @@ impl BlockchainClient for PolkadotClient {
                 )
                 .await
             },
+            "justification" => {
+                if !pallet_name.eq_ignore_ascii_case("Grandpa") ||
+                    !call_name.eq_ignore_ascii_case("proveFinality")
+                {
+                    anyhow::bail!("expected Grandpa-proveFinality-justification");
+                }
+                let index = request
+                    .parameters
+                    .get(0)
+                    .and_then(Value::as_u64)
+                    .context("expected the block number as first parameter")?;
+                let proof = self.finality_proof(index).await?;
+                Ok(proof.map_or(Value::Null, |proof| Value::String(hex::encode(proof))))
+            },
+            "proof" => {
+                let key = crate::call::dynamic_storage_key(
+                    self.client.client(),
+                    pallet_name,
+                    call_name,
+                    request.parameters.clone(),
+                )?;
+                let ReadProof { at, proof } = self.read_proof(&key).await?;
+                Ok(serde_json::json!({
+                    "at": hex::encode(at),
+                    "key": hex::encode(key),
+                    "proof": proof.iter().map(hex::encode).collect::<Vec<_>>(),
+                }))
+            },
             _ => {
                 anyhow::bail!("invalid query type");
             },
@@ -248,12 +1007,29 @@ pub struct Transfer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     pub async fn client_from_config(config: BlockchainConfig) -> Result<PolkadotClient> {
         let url = config.node_uri.to_string();
         PolkadotClient::from_config(config, url.as_str()).await
     }
 
+    /// Searches the 10 latest blocks, from the most recent one, for the first item returned by
+    /// `find`, ex: a transaction which was just included.
+    async fn find_in_latest_blocks<T, Fut, F>(client: &PolkadotClient, find: F) -> Option<T>
+    where
+        Fut: std::future::Future<Output = Option<T>>,
+        F: Fn(PartialBlockIdentifier) -> Fut,
+    {
+        let latest = client.current_block().await.unwrap().index;
+        for index in (0..=latest).rev().take(10) {
+            if let Some(found) = find(PartialBlockIdentifier::by_number(index)).await {
+                return Some(found);
+            }
+        }
+        None
+    }
+
     #[tokio::test]
     async fn test_network_status() -> Result<()> {
         let config = rosetta_config_polkadot::config("westend-dev")?;
@@ -261,6 +1037,170 @@ mod tests {
             .await
     }
 
+    #[tokio::test]
+    async fn test_peers() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-peers", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            // The dev node is isolated, but `system_peers` must still be exposed
+            let peers = env.node().peers().await.unwrap();
+            assert!(peers.iter().all(|peer| !peer.peer_id.is_empty()));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connection_status() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+        use rosetta_server::ws::{RetryStrategyConfig, RpcClientConfig};
+        use std::{num::NonZeroU32, time::Duration};
+
+        // Give up quickly once the node is gone
+        let rpc_config = RpcClientConfig {
+            retry_strategy: RetryStrategyConfig::FixedInterval(Duration::from_millis(100)),
+            max_reconnect_attempts: NonZeroU32::new(3),
+            ..RpcClientConfig::default()
+        };
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-connection-status", config, move |config| {
+            let rpc_config = rpc_config.clone();
+            async move {
+                let url = config.node_uri.to_string();
+                let client = default_client(url.as_str(), Some(rpc_config)).await?;
+                PolkadotClient::from_jsonrpsee(config, client).await
+            }
+        })
+        .await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            assert_eq!(client.connection_status(), ConnectionStatus::Connected);
+
+            env.stop_node().await.unwrap();
+            let mut status = client.connection_status();
+            for _ in 0..100 {
+                if status != ConnectionStatus::Connected {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                status = client.connection_status();
+            }
+            assert_eq!(status, ConnectionStatus::Reconnecting);
+
+            // The next request reconnects, and gives up after the max attempts
+            client.current_block().await.unwrap_err();
+            assert_eq!(client.connection_status(), ConnectionStatus::Closed);
+            client.current_block().await.unwrap_err();
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validators() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-validators", config.clone(), client_from_config).await?;
+
+        run_test(env, move |env| async move {
+            let latest = PartialBlockIdentifier::latest();
+            let validators = env.node().validators(&latest).await.unwrap();
+            assert!(!validators.is_empty());
+            for validator in &validators {
+                validator.address().parse::<AccountId32>().unwrap();
+            }
+
+            // Alice validates the dev chain
+            let alice = Address::from_public_key_bytes(
+                config.address_format,
+                &AccountKeyring::Alice.public().0,
+            );
+            assert!(validators.contains(&alice));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_estimate() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-inclusion-estimate", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            client.wait_for_block(4, std::time::Duration::from_secs(60)).await.unwrap();
+
+            // The pool is empty, a long era leaves plenty of margin
+            let era = client.mortal_era(64).await.unwrap();
+            let estimate = client.inclusion_estimate(&era).await.unwrap();
+            assert!(estimate.likely, "{estimate:?}");
+            assert!(estimate.margin > 0, "{estimate:?}");
+
+            // An era which already ended is never included
+            let expired = MortalEra { period: 4, block_number: 0, ..era };
+            let estimate = client.inclusion_estimate(&expired).await.unwrap();
+            assert!(!estimate.likely, "{estimate:?}");
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mounted_chain_spec() -> Result<()> {
+        use rosetta_docker::{run_test, Env, EnvBuilder};
+
+        // Export the chain spec of a dev node
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-export-spec", config.clone(), client_from_config).await?;
+        let client = env.node();
+        let genesis = client.genesis_block();
+        let spec = client.chain_spec().await;
+        env.shutdown().await?;
+        let specs = std::env::temp_dir().join("rosetta-polkadot-chain-spec");
+        std::fs::create_dir_all(&specs)?;
+        std::fs::write(specs.join("westend-dev.json"), serde_json::to_vec(&spec?)?)?;
+
+        // And boot a node from the mounted chain spec
+        let node_command = config.node_command.clone();
+        let mut spec_config = config;
+        spec_config.node_command = std::sync::Arc::new(move |network, port| {
+            node_command(network, port)
+                .into_iter()
+                .map(|arg| {
+                    if arg.starts_with("--chain=") {
+                        "--chain=/specs/westend-dev.json".into()
+                    } else {
+                        arg
+                    }
+                })
+                .collect()
+        });
+        let mounts = vec![(specs, "/specs".to_string())];
+        let env = EnvBuilder::new("polkadot-chain-spec")?
+            .mounts(mounts)
+            .build(spec_config, client_from_config)
+            .await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            assert_eq!(client.genesis_block(), genesis);
+            let current = client.current_block().await.unwrap().index;
+            client
+                .wait_for_block(current + 1, std::time::Duration::from_secs(60))
+                .await
+                .unwrap();
+        })
+        .await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_account() -> Result<()> {
         let config = rosetta_config_polkadot::config("westend-dev")?;
@@ -273,4 +1213,1165 @@ mod tests {
         rosetta_docker::tests::construction::<PolkadotClient, _, _>(client_from_config, config)
             .await
     }
+
+    #[tokio::test]
+    async fn test_account_balance() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-account-balance", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let value = 100 * u128::pow(10, config.currency_decimals);
+            wallet.faucet(value, None).await.unwrap();
+
+            let address = Address::new(config.address_format, wallet.account().address.clone());
+            let block = PartialBlockIdentifier::latest();
+            let amount = client.account_balance(&address, None, &block).await.unwrap();
+            assert_eq!(amount.value, value.to_string());
+            assert_eq!(amount.currency.symbol, config.currency_symbol);
+            assert_eq!(amount.currency.decimals, config.currency_decimals);
+
+            // Westend doesn't include the `Assets` pallet
+            assert!(client.account_balance(&address, Some(1), &block).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_asset_balance() -> Result<()> {
+        use crate::types::ClientConfig;
+        use rosetta_core::crypto::address::{AddressFormat, Ss58AddressFormatRegistry};
+        use rosetta_docker::{run_test, Env};
+        use subxt::dynamic::Value as SubxtValue;
+
+        // Westend doesn't include the `Assets` pallet, the astar dev node does
+        let config = rosetta_config_astar::config("dev")?;
+        let env = Env::new("polkadot-asset-balance", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            const ASSET_ID: u32 = 1337;
+            let client = env.node();
+            let alice = AccountId32::from(AccountKeyring::Alice.public().0);
+            let bob = AccountId32::from(AccountKeyring::Bob.public().0);
+            let asset_id = || SubxtValue::u128(u128::from(ASSET_ID));
+            let account = |account: &AccountId32| {
+                SubxtValue::unnamed_variant("Id", [SubxtValue::from_bytes(account.0)])
+            };
+
+            // Create the asset, then mint some units to Bob
+            let calls = [
+                ("create", vec![asset_id(), account(&alice), SubxtValue::u128(1)]),
+                (
+                    "set_metadata",
+                    vec![
+                        asset_id(),
+                        SubxtValue::from_bytes("Test Token"),
+                        SubxtValue::from_bytes("TST"),
+                        SubxtValue::u128(6),
+                    ],
+                ),
+                ("mint", vec![asset_id(), account(&bob), SubxtValue::u128(5_000_000)]),
+            ];
+            let signer = PairSigner::<_, _>::new(AccountKeyring::Alice.pair());
+            for (call_name, call_args) in calls {
+                let tx = subxt::dynamic::tx("Assets", call_name, call_args);
+                client
+                    .client
+                    .client()
+                    .tx()
+                    .sign_and_submit_then_watch(&tx, &signer, WestendDevConfig::other_params())
+                    .await
+                    .unwrap()
+                    .wait_for_finalized_success()
+                    .await
+                    .unwrap();
+            }
+
+            let format = AddressFormat::from(Ss58AddressFormatRegistry::SubstrateAccount);
+            let address = Address::new(format, bob.to_string());
+            let block = PartialBlockIdentifier::latest();
+            let amount = client.account_balance(&address, Some(ASSET_ID), &block).await.unwrap();
+            assert_eq!(amount.value, "5000000");
+            assert_eq!(amount.currency.symbol, "TST");
+            assert_eq!(amount.currency.decimals, 6);
+            assert_eq!(amount.currency.metadata, Some(json!({ "asset_id": ASSET_ID })));
+
+            // Accounts without the asset have no balance
+            let address = Address::new(format, alice.to_string());
+            let amount = client.account_balance(&address, Some(ASSET_ID), &block).await.unwrap();
+            assert_eq!(amount.value, "0");
+
+            // The asset must exist
+            assert!(client.account_balance(&address, Some(ASSET_ID + 1), &block).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[test]
+    fn native_currency_from_system_properties() {
+        let config = rosetta_config_polkadot::config("westend-dev").unwrap();
+        let properties = |json: Value| json.as_object().cloned().unwrap();
+
+        let currency = native_currency(
+            &config,
+            &properties(json!({"tokenDecimals": 10, "tokenSymbol": "DOT"})),
+        );
+        assert_eq!((currency.symbol.as_str(), currency.decimals), ("DOT", 10));
+
+        // The first token is the native one
+        let currency = native_currency(
+            &config,
+            &properties(json!({"tokenDecimals": [18, 6], "tokenSymbol": ["ABC", "USDT"]})),
+        );
+        assert_eq!((currency.symbol.as_str(), currency.decimals), ("ABC", 18));
+
+        // Missing properties fall back to the config
+        let currency = native_currency(&config, &properties(json!({})));
+        assert_eq!(currency.symbol, config.currency_symbol);
+        assert_eq!(currency.decimals, config.currency_decimals);
+    }
+
+    #[tokio::test]
+    async fn test_account_balance_currency() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-balance-currency", config.clone(), client_from_config).await?;
+
+        run_test(env, move |env| async move {
+            let client = env.node();
+            let properties = client.client.system_properties().await.unwrap();
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let address = Address::new(config.address_format, wallet.account().address.clone());
+            let block = PartialBlockIdentifier::latest();
+            let amount = client.account_balance(&address, None, &block).await.unwrap();
+            assert_eq!(Some(&json!(amount.currency.symbol)), properties.get("tokenSymbol"));
+            assert_eq!(Some(&json!(amount.currency.decimals)), properties.get("tokenDecimals"));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_dispatch_error() -> Result<()> {
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-dispatch-error", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            // Transfer below the existential deposit to a new account
+            let error = alice.transfer(bob.account(), 1, None, None).await.unwrap_err();
+            let details = error.downcast_ref::<DispatchErrorDetails>().unwrap();
+            assert_eq!(details.pallet, None);
+            assert_eq!(details.error, "Token(BelowMinimum)");
+
+            // Alice is not a staking controller
+            let params = GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                nonce: None,
+                pallet_name: "Staking".into(),
+                call_name: "chill".into(),
+                call_args: Vec::new(),
+                tip: None,
+                mortality: None,
+            });
+            let error = alice.construct(&params).await.unwrap_err();
+            let details = error.downcast_ref::<DispatchErrorDetails>().unwrap();
+            assert_eq!(details.pallet.as_deref(), Some("Staking"));
+            assert_eq!(details.error, "NotController");
+            assert!(!details.docs.is_empty());
+            assert!(error.to_string().starts_with("Staking::NotController"));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block_transactions_status() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env =
+            Env::new("polkadot-block-transactions", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            // Transfer below the existential deposit to a new account
+            alice.transfer(bob.account(), 1, None, None).await.unwrap_err();
+
+            // Find the failed extrinsic in the latest blocks
+            let address = &alice.account().address;
+            let failed = find_in_latest_blocks(&client, |block| {
+                let client = &client;
+                async move {
+                    let transactions = client.block_transactions(&block).await.unwrap();
+                    transactions.into_iter().find(|tx| {
+                        !tx.success &&
+                            tx.operations.iter().any(|op| {
+                                op.account.as_ref().map(|account| &account.address) == Some(address)
+                            })
+                    })
+                }
+            })
+            .await
+            .expect("failed extrinsic not found");
+
+            // The fee is paid, but the transfer has no effect
+            let operations = failed
+                .operations
+                .iter()
+                .filter(|op| op.r#type != "FEE_REWARD")
+                .collect::<Vec<_>>();
+            let [fee, debit, credit] = operations.as_slice() else {
+                panic!("expected fee and transfer operations, got {:?}", failed.operations);
+            };
+            assert_eq!(fee.r#type, "FEE");
+            assert_eq!(fee.status.as_deref(), Some(OPERATION_STATUS_SUCCESS));
+            assert!(fee.amount.as_ref().unwrap().value.starts_with('-'));
+            for op in [debit, credit] {
+                assert_eq!(op.r#type, "TRANSFER");
+                assert_eq!(op.status.as_deref(), Some(OPERATION_STATUS_FAILURE));
+                assert_eq!(op.amount.as_ref().unwrap().value, "0");
+            }
+            assert_eq!(credit.account.as_ref().unwrap().address, bob.account().address);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block_transactions_fee_reward() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-fee-reward", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            let tip = u128::pow(10, config.currency_decimals - 3);
+            let result = alice.transfer_with_tip(bob.account(), value, tip, None).await.unwrap();
+            let tx_hash = format!("0x{}", hex::encode(result.tx_hash().0));
+
+            let transaction = find_in_latest_blocks(&client, |block| {
+                let (client, tx_hash) = (&client, &tx_hash);
+                async move {
+                    let transactions = client.block_transactions(&block).await.unwrap();
+                    transactions.into_iter().find(|tx| &tx.transaction_identifier.hash == tx_hash)
+                }
+            })
+            .await
+            .expect("extrinsic not found");
+
+            // The fee paid by alice is credited to other accounts, like the block author
+            let amount =
+                |op: &Operation| -> i128 { op.amount.as_ref().unwrap().value.parse().unwrap() };
+            let fee = transaction.operations.iter().find(|op| op.r#type == "FEE").unwrap();
+            let rewards = transaction
+                .operations
+                .iter()
+                .filter(|op| op.r#type == "FEE_REWARD")
+                .collect::<Vec<_>>();
+            assert!(!rewards.is_empty());
+            assert_eq!(rewards.iter().map(|op| amount(op)).sum::<i128>(), -amount(fee));
+            for reward in rewards {
+                assert_eq!(reward.status.as_deref(), Some(OPERATION_STATUS_SUCCESS));
+                assert_eq!(reward.related_operations, Some(vec![fee.operation_identifier.clone()]));
+                assert_ne!(reward.account.as_ref().unwrap().address, alice.account().address);
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transfer_with_tip() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-transfer-tip", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            // Confirmations aren't supported, the transfer is rejected before being submitted
+            let result = alice.transfer_with_confirmations(bob.account(), value, None, None, 1);
+            assert!(result.await.is_err());
+
+            let tip = u128::pow(10, config.currency_decimals - 3);
+            let result = alice.transfer_with_tip(bob.account(), value, tip, None).await.unwrap();
+            let tx_hash = result.tx_hash();
+
+            // The tip is reported by the `TransactionPayment.TransactionFeePaid` event
+            let extrinsic = find_in_latest_blocks(&client, |block| {
+                let client = &client;
+                async move {
+                    let extrinsics = client.client.extrinsics(&block).await.unwrap();
+                    extrinsics.into_iter().find(|ext| ext.hash == tx_hash.0)
+                }
+            })
+            .await
+            .expect("extrinsic not found");
+            assert!(extrinsic.success);
+            assert_eq!(extrinsic.tip, Some(tip));
+            assert_eq!(bob.balance().await.unwrap(), value);
+
+            // The amount, the tip and the fee must fit the free balance, checked before submitting
+            let balance = alice.balance().await.unwrap();
+            let error = alice.transfer_with_tip(bob.account(), 1, balance - 1, None).await;
+            assert!(error.unwrap_err().to_string().contains("insufficient balance"));
+            assert_eq!(alice.balance().await.unwrap(), balance);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_events() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-events", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            // Emit a `Balances.Transfer` event in several blocks
+            let from = client.current_block().await.unwrap().index + 1;
+            let value = u128::pow(10, config.currency_decimals);
+            let mut tx_hashes = Vec::new();
+            for _ in 0..3 {
+                let result = alice.transfer(bob.account(), value, None, None).await.unwrap();
+                tx_hashes.push(result.tx_hash().0.to_vec());
+            }
+            let to = client.current_block().await.unwrap().index;
+
+            let events = client.events(from, to, "Balances", Some("Transfer")).await.unwrap();
+            assert_eq!(events.len(), 3);
+            for (event, tx_hash) in events.iter().zip(&tx_hashes) {
+                assert_eq!((event.pallet.as_str(), event.event.as_str()), ("Balances", "Transfer"));
+                let block = PartialBlockIdentifier::from(event.block_identifier.clone());
+                let extrinsics = client.client.extrinsics(&block).await.unwrap();
+                let extrinsic = extrinsics
+                    .iter()
+                    .find(|extrinsic| Some(extrinsic.index) == event.extrinsic_index)
+                    .unwrap();
+                assert_eq!(&extrinsic.hash, tx_hash);
+                let (_, block_events) = client.client.events(&block).await.unwrap();
+                let block_event =
+                    block_events.iter().find(|info| info.index == event.event_index).unwrap();
+                assert_eq!(block_event.variant, "Transfer");
+            }
+
+            // Other events of the range are filtered out
+            let all = client.events(from, to, "Balances", None).await.unwrap();
+            assert!(all.len() > events.len());
+            assert!(all.iter().all(|event| event.pallet == "Balances"));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stale_explicit_nonce() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-stale-nonce", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            alice.transfer(bob.account(), value, Some(0), None).await.unwrap();
+
+            // The nonce 0 is now outdated, an explicit nonce is never replaced by the retry
+            let error = alice.transfer(bob.account(), value, Some(0), None).await.unwrap_err();
+            assert!(is_stale_transaction_error(&error), "{error:#}");
+            assert_eq!(bob.balance().await.unwrap(), value);
+
+            // Without an explicit nonce, the nonce is fetched from the chain
+            alice.transfer(bob.account(), value, None, None).await.unwrap();
+            assert_eq!(bob.balance().await.unwrap(), 2 * value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_finalized_block() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env =
+            Env::new("polkadot-resolve-finalized", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let finalized = client
+                .client
+                .block_details(&PartialBlockIdentifier::finalized())
+                .await
+                .unwrap()
+                .expect("finalized block not found");
+            let best = client.current_block().await.unwrap();
+            assert!(u64::from(finalized.block.header.number) <= best.index);
+
+            // The sentinel resolves to the same block as `chain_getFinalizedHead`
+            let expected = client.finalized_block().await.unwrap();
+            let number = u64::from(finalized.block.header.number);
+            assert!(number <= expected.index);
+            let block = PartialBlockIdentifier::by_number(number);
+            let block = client.client.block_details(&block).await.unwrap().unwrap();
+            assert_eq!(block.block.header.hash(), finalized.block.header.hash());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_finalized_balance() -> Result<()> {
+        use crate::types::ClientConfig;
+        use rosetta_docker::{run_test, Env};
+        use subxt::tx::TxStatus;
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env =
+            Env::new("polkadot-finalized-balance", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let address = Address::new(config.address_format, bob.account().address.clone());
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+
+            // Only wait for the transfer to be included in the best block, the finalized head
+            // lags behind it until GRANDPA finalizes the block
+            let tx = WestendDevConfig::transfer_keep_alive(dest.into(), value);
+            let signer = PairSigner::<_, _>::new(AccountKeyring::Alice.pair());
+            let mut progress = client
+                .client
+                .client()
+                .tx()
+                .sign_and_submit_then_watch(&tx, &signer, WestendDevConfig::other_params())
+                .await
+                .unwrap();
+            let in_block = loop {
+                match progress.next().await.unwrap().unwrap() {
+                    TxStatus::InBestBlock(in_block) => break in_block,
+                    TxStatus::Validated |
+                    TxStatus::Broadcasted { .. } |
+                    TxStatus::NoLongerInBestBlock => {},
+                    _ => panic!("transfer not included in a block"),
+                }
+            };
+            let included = PartialBlockIdentifier::by_hash(in_block.block_hash().0);
+            let included = client.client.block_details(&included).await.unwrap().unwrap();
+            let included = u64::from(included.block.header.number);
+
+            let latest = client.balance(&address, &PartialBlockIdentifier::new()).await.unwrap();
+            let finalized =
+                client.balance(&address, &PartialBlockIdentifier::finalized()).await.unwrap();
+            let finalized_block = client.finalized_block().await.unwrap();
+            assert!(
+                finalized_block.index < included,
+                "the finalized head reached the transfer before the balances were read"
+            );
+            assert_eq!(latest, value);
+            assert_eq!(finalized, 0);
+            assert_ne!(finalized, latest);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_earliest_block() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-earliest-block", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let earliest = PartialBlockIdentifier::earliest();
+            let block = client.client.block_details(&earliest).await.unwrap().unwrap();
+            assert_eq!(block.block.header.number, 0);
+            assert_eq!(block.block.header.hash().0, client.genesis_block().hash);
+
+            // The state of the earliest block predates the transfer
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            bob.faucet(value, None).await.unwrap();
+            let address = Address::new(config.address_format, bob.account().address.clone());
+            assert_eq!(
+                client.balance(&address, &PartialBlockIdentifier::new()).await.unwrap(),
+                value
+            );
+            assert_eq!(client.balance(&address, &earliest).await.unwrap(), 0);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_decoded_call() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::{GenericMetadata, GenericMetadataParams};
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-decoded-call", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+
+            let params = GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                nonce: None,
+                pallet_name: "Balances".into(),
+                call_name: "transfer_keep_alive".into(),
+                call_args: (MultiAddress::<AccountId32, ()>::Id(dest.clone()), Compact(value))
+                    .encode(),
+                tip: None,
+                mortality: None,
+            });
+            let GenericMetadata::Polkadot(metadata) = alice.metadata(&params).await.unwrap() else {
+                panic!("expected polkadot metadata");
+            };
+            let decoded = metadata.decoded_call.unwrap();
+            assert_eq!(decoded.pallet, "Balances");
+            assert_eq!(decoded.call, "transfer_keep_alive");
+            assert_eq!(decoded.args["dest"], serde_json::json!({ "Id": [[dest.0]] }));
+            assert_eq!(decoded.args["value"], serde_json::json!(value));
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mortal_transaction() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-mortal-tx", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            let params = |mortality| {
+                GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                    nonce: None,
+                    pallet_name: "Balances".into(),
+                    call_name: "transfer_keep_alive".into(),
+                    call_args: (MultiAddress::<AccountId32, ()>::Id(dest.clone()), Compact(value))
+                        .encode(),
+                    tip: None,
+                    mortality: Some(mortality),
+                })
+            };
+
+            // The period must fit in `System.BlockHashCount`
+            assert!(alice.sign_transaction(&params(1 << 20)).await.is_err());
+
+            // A mortal transaction is accepted within its era
+            let transaction = alice.sign_transaction(&params(4)).await.unwrap();
+            alice.submit(&transaction).await.unwrap();
+            assert_eq!(bob.balance().await.unwrap(), value);
+
+            // And rejected once the era has passed
+            let transaction = alice.sign_transaction(&params(4)).await.unwrap();
+            let checkpoint = client.finalized_block().await.unwrap().index;
+            while client.current_block().await.unwrap().index <= checkpoint + 4 {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+            assert!(alice.submit(&transaction).await.is_err());
+            assert_eq!(bob.balance().await.unwrap(), value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_signed_extra() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::{GenericMetadata, GenericMetadataParams};
+        use rosetta_core::{
+            crypto::{Algorithm, SecretKey},
+            TransactionBuilder,
+        };
+        use rosetta_docker::{run_test, Env};
+        use rosetta_tx_polkadot::{
+            decode_signed_extra, verify_signed_extra, PolkadotTransactionBuilder,
+        };
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-signed-extra", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            let tip = u128::pow(10, config.currency_decimals - 3);
+            let metadata_params = PolkadotMetadataParams {
+                nonce: Some(7),
+                pallet_name: "Balances".into(),
+                call_name: "transfer_keep_alive".into(),
+                call_args: (MultiAddress::<AccountId32, ()>::Id(dest.clone()), Compact(value))
+                    .encode(),
+                tip: Some(tip),
+                mortality: Some(64),
+            };
+            let params = GenericMetadataParams::Polkadot(metadata_params.clone());
+            let GenericMetadata::Polkadot(metadata) = alice.metadata(&params).await.unwrap() else {
+                panic!("expected polkadot metadata");
+            };
+            for extension in ["CheckMortality", "CheckNonce", "ChargeTransactionPayment"] {
+                assert!(metadata.signed_extensions.iter().any(|ext| ext == extension));
+            }
+
+            // The signed extra decodes back to the metadata the extrinsic was built with
+            let secret_key = SecretKey::from_bytes(Algorithm::Sr25519, &[0x42; 32]).unwrap();
+            let extrinsic = PolkadotTransactionBuilder.create_and_sign(
+                &config,
+                &metadata_params,
+                &metadata,
+                &secret_key,
+            );
+            let signed_extra = verify_signed_extra(&extrinsic, &metadata).unwrap();
+            assert_eq!(signed_extra.nonce, 7);
+            assert_eq!(signed_extra.tip, tip);
+            let mortality = signed_extra.mortality.unwrap();
+            assert_eq!(mortality.period, 64);
+            assert!(mortality.phase < 64);
+
+            // And is rejected if it doesn't match
+            let extensions = metadata.signed_extensions.clone();
+            let mut metadata = metadata;
+            metadata.nonce = 8;
+            assert!(verify_signed_extra(&extrinsic, &metadata).is_err());
+
+            // Extrinsics signed by the wallet carry the requested signed extra too
+            let extrinsic = alice.sign_transaction(&params).await.unwrap();
+            let signed_extra = decode_signed_extra(&extrinsic, extensions).unwrap();
+            assert_eq!(signed_extra.nonce, 7);
+            assert_eq!(signed_extra.tip, tip);
+            assert_eq!(signed_extra.mortality.unwrap().period, 64);
+            assert!(decode_signed_extra(&extrinsic[..extrinsic.len() - 1], vec![]).is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_transfer_operations() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-batch-transfer", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let charlie = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            // Encode `utility.batch([transfer_keep_alive(bob), transfer_keep_alive(charlie)])`
+            let metadata = client.client.metadata();
+            let pallet = metadata.pallet_by_name("Balances").unwrap();
+            let call_index = pallet.call_variant_by_name("transfer_keep_alive").unwrap().index;
+            let value = u128::pow(10, config.currency_decimals);
+            let mut call_args = Compact(2u32).encode();
+            for wallet in [&bob, &charlie] {
+                let dest: AccountId32 = wallet.account().address.parse().unwrap();
+                call_args.extend([pallet.index(), call_index]);
+                call_args
+                    .extend((MultiAddress::<AccountId32, ()>::Id(dest), Compact(value)).encode());
+            }
+            let params = GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                nonce: None,
+                pallet_name: "Utility".into(),
+                call_name: "batch".into(),
+                call_args,
+                tip: None,
+                mortality: None,
+            });
+            let tx_hash = alice.construct(&params).await.unwrap().tx_hash();
+            let tx_hash = format!("0x{}", hex::encode(tx_hash.0));
+
+            let batch = find_in_latest_blocks(&client, |block| {
+                let (client, tx_hash) = (&client, &tx_hash);
+                async move {
+                    let transactions = client.block_transactions(&block).await.unwrap();
+                    transactions.into_iter().find(|tx| &tx.transaction_identifier.hash == tx_hash)
+                }
+            })
+            .await
+            .expect("batch extrinsic not found");
+            assert!(batch.success);
+
+            // Both legs of each nested transfer are reported
+            let transfers = batch
+                .operations
+                .iter()
+                .filter(|op| op.r#type == "TRANSFER")
+                .map(|op| {
+                    (
+                        op.account.as_ref().unwrap().address.clone(),
+                        op.amount.as_ref().unwrap().value.clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let expected = [&bob, &charlie]
+                .into_iter()
+                .flat_map(|wallet| {
+                    [
+                        (alice.account().address.clone(), format!("-{value}")),
+                        (wallet.account().address.clone(), value.to_string()),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(transfers, expected);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_raw() -> Result<()> {
+        use rosetta_client::{
+            client::GenericClient,
+            crypto::{Algorithm, PublicKey, SecretKey},
+            Wallet,
+        };
+        use rosetta_docker::{run_test, Env};
+        use subxt::ext::sp_core::{sr25519, Pair};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-sign-raw", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let wallet = env.ephemeral_wallet().await.unwrap();
+            let message = b"hello polkadot";
+            let signature = wallet.sign_raw(message).await.unwrap();
+            assert_eq!(signature.len(), 64);
+
+            let public_key = hex::decode(&wallet.public_key().hex_bytes).unwrap();
+            let public_key = PublicKey::from_bytes(config.algorithm, &public_key).unwrap();
+            rosetta_client::verify_raw(&public_key, message, &signature).unwrap();
+            assert!(rosetta_client::verify_raw(&public_key, b"hello kusama", &signature).is_err());
+
+            // Same check done by polkadot-js `signatureVerify` on the `<Bytes>` wrapped message
+            let wrapped = [b"<Bytes>".as_slice(), message, b"</Bytes>"].concat();
+            let sr_signature = sr25519::Signature::from_raw(signature.try_into().unwrap());
+            let sr_public = sr25519::Public::from_raw(public_key.to_bytes().try_into().unwrap());
+            assert!(sr25519::Pair::verify(&sr_signature, wrapped, &sr_public));
+
+            // sr25519 keys can't sign prehashed digests
+            assert!(wallet.sign_digest(&[0x42; 32]).await.is_err());
+
+            // ed25519 signatures are deterministic (RFC 8032), so polkadot-js `signRaw` with the
+            // `ed25519PairFromSeed` of the RFC 8032 test 1 secret key gives this exact signature
+            // of `u8aWrapBytes("hello polkadot")`
+            let expected = hex::decode(
+                "96d06a26e0818dcbbdb80f415938d4001268da74faf12f96b33436b5db91f3fe\
+                 2a094836812e8fce95ff794e9e0e3acd5e8a3581536e259b95d427f0eb2ce701",
+            )
+            .unwrap();
+            let config = BlockchainConfig { algorithm: Algorithm::Ed25519, ..config };
+            let client =
+                GenericClient::from_config(config.clone(), &config.node_uri.to_string(), None)
+                    .await
+                    .unwrap();
+            let secret_key = SecretKey::from_bytes(
+                Algorithm::Ed25519,
+                &hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                    .unwrap(),
+            )
+            .unwrap();
+            let wallet = Wallet::from_signer(client, secret_key).unwrap();
+            assert_eq!(
+                wallet.public_key().hex_bytes,
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+            );
+            let signature = wallet.sign_raw(message).await.unwrap();
+            assert_eq!(signature, expected);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_allowlist() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        async fn client_with_allowlist(config: BlockchainConfig) -> Result<PolkadotClient> {
+            let client = client_from_config(config).await?;
+            Ok(client.with_call_allowlist(["System-BlockHashCount", "Timestamp-*"]))
+        }
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-call-allowlist", config, client_with_allowlist).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let call =
+                |method: &str| CallRequest::new(method.into(), Value::Array(Vec::new()), None);
+
+            // Allowed by name and by the pallet wildcard
+            client.call(&call("System-BlockHashCount-constant")).await.unwrap();
+            client.call(&call("Timestamp-Now-storage")).await.unwrap();
+
+            let error =
+                client.call(&call("Balances-ExistentialDeposit-constant")).await.unwrap_err();
+            assert!(error.downcast_ref::<InvalidParams>().is_some());
+            let error = client.call(&call("System-Account-storage")).await.unwrap_err();
+            assert!(error.downcast_ref::<InvalidParams>().is_some());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_transferable() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-max-transferable", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let faucet = 100 * u128::pow(10, config.currency_decimals);
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let charlie = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(faucet, None).await.unwrap();
+            charlie.faucet(faucet, None).await.unwrap();
+
+            // The existential deposit and the fee are reserved
+            let existential_deposit = env.node().existential_deposit().unwrap();
+            let amount = alice.max_transferable().await.unwrap();
+            assert!(amount < faucet - existential_deposit);
+            assert_eq!(charlie.max_transferable().await.unwrap(), amount);
+
+            // One unit more would reap the account, failed extrinsics still pay the fee so it is
+            // attempted from another account with the same balance
+            assert!(charlie.transfer(bob.account(), amount + 1, None, None).await.is_err());
+            alice.transfer(bob.account(), amount, None, None).await.unwrap();
+            assert_eq!(bob.balance().await.unwrap(), amount);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_finality_proof() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-finality-proof", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            // Waits until the transfer is finalized
+            alice.faucet(u128::pow(10, config.currency_decimals), None).await.unwrap();
+            let finalized = client.finalized_block().await.unwrap();
+            assert!(finalized.index > 0);
+
+            let request = CallRequest::new(
+                "Grandpa-proveFinality-justification".into(),
+                json!([finalized.index]),
+                None,
+            );
+            let proof = client.call(&request).await.unwrap();
+            let proof = hex::decode(proof.as_str().unwrap()).unwrap();
+            assert!(!proof.is_empty());
+
+            // The proof finalizes the requested block or one of its descendants
+            let block = client.verify_finality_proof(&proof).await.unwrap();
+            assert!(block.index >= finalized.index);
+            let proof = FinalityProof::decode(&mut proof.as_slice()).unwrap();
+            assert_eq!(proof.block.0, block.hash);
+
+            // A tampered signature doesn't verify
+            let mut justification =
+                GrandpaJustification::decode(&mut proof.justification.as_slice()).unwrap();
+            justification.commit.precommits[0].signature[0] ^= 1;
+            let tampered = FinalityProof { justification: justification.encode(), ..proof };
+            assert!(client.verify_finality_proof(&tampered.encode()).await.is_err());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_proof() -> Result<()> {
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-read-proof", config, client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = AccountKeyring::Alice.public().0;
+            let request = CallRequest::new("System-Account-proof".into(), json!([alice]), None);
+            let response = client.call(&request).await.unwrap();
+            let decode = |value: &Value| hex::decode(value.as_str().unwrap()).unwrap();
+            let at = H256::from_slice(&decode(&response["at"]));
+            let key = decode(&response["key"]);
+            let proof =
+                response["proof"].as_array().unwrap().iter().map(decode).collect::<Vec<_>>();
+
+            // The proven value is the balance of alice at the proof block
+            let value = client.verify_read_proof(at, &key, &proof).await.unwrap().unwrap();
+            let proven = AccountInfo::<u32, AccountData>::decode(&mut value.as_slice()).unwrap();
+            let account_info = client
+                .client
+                .account_info(AccountId32(alice), types::BlockIdentifier::Hash(at))
+                .await
+                .unwrap();
+            assert!(proven.data.free > 0);
+            assert_eq!(proven.data.free, account_info.data.free);
+
+            // A tampered trie node doesn't verify
+            for index in 0..proof.len() {
+                let mut tampered = proof.clone();
+                let node = &mut tampered[index];
+                let last = node.len() - 1;
+                node[last] ^= 1;
+                assert!(client.verify_read_proof(at, &key, &tampered).await.is_err());
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_transaction() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env =
+            Env::new("polkadot-validate-transaction", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            let params = |nonce| {
+                GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                    nonce: Some(nonce),
+                    pallet_name: "Balances".into(),
+                    call_name: "transfer_keep_alive".into(),
+                    call_args: (MultiAddress::<AccountId32, ()>::Id(dest.clone()), Compact(value))
+                        .encode(),
+                    tip: None,
+                    mortality: None,
+                })
+            };
+
+            // The next nonce is valid, and provides the tag of its nonce
+            let transaction = alice.sign_transaction(&params(0)).await.unwrap();
+            let valid = client.validate_transaction(&transaction).await.unwrap().unwrap();
+            assert!(valid.requires.is_empty());
+            assert_eq!(valid.provides.len(), 1);
+            assert!(valid.longevity > 0);
+
+            // A future nonce requires the previous one
+            let future = alice.sign_transaction(&params(1)).await.unwrap();
+            let valid = client.validate_transaction(&future).await.unwrap().unwrap();
+            assert_eq!(valid.requires.len(), 1);
+
+            // Once the nonce is used, the transaction is stale
+            alice.submit(&transaction).await.unwrap();
+            assert_eq!(
+                client.validate_transaction(&transaction).await.unwrap(),
+                Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
+            );
+            assert_eq!(bob.balance().await.unwrap(), value);
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mempool() -> Result<()> {
+        use parity_scale_codec::Compact;
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env = Env::new("polkadot-mempool", config.clone(), client_from_config).await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+            assert!(client.mempool().await.unwrap().is_empty());
+
+            let dest: AccountId32 = bob.account().address.parse().unwrap();
+            let value = u128::pow(10, config.currency_decimals);
+            let params = GenericMetadataParams::Polkadot(PolkadotMetadataParams {
+                nonce: None,
+                pallet_name: "Balances".into(),
+                call_name: "transfer_keep_alive".into(),
+                call_args: (MultiAddress::<AccountId32, ()>::Id(dest), Compact(value)).encode(),
+                tip: None,
+                mortality: None,
+            });
+            let transaction = alice.sign_transaction(&params).await.unwrap();
+            let hash =
+                SubmittableExtrinsic::from_bytes(client.client.client().clone(), transaction)
+                    .submit()
+                    .await
+                    .unwrap();
+            let id = TransactionIdentifier::new(format!("0x{}", hex::encode(hash)));
+
+            // The extrinsic is pending until it is included in the next block
+            assert_eq!(client.mempool().await.unwrap(), vec![id.clone()]);
+            let pending = client.mempool_transaction(&id).await.unwrap().unwrap();
+            assert_eq!(pending.transaction_identifier, id);
+            let transfers = pending
+                .operations
+                .iter()
+                .map(|operation| {
+                    assert_eq!(operation.status, None);
+                    let account = operation.account.as_ref().unwrap().address.clone();
+                    (account, operation.amount.as_ref().unwrap().value.clone())
+                })
+                .collect::<Vec<_>>();
+            let expected = vec![
+                (alice.account().address.clone(), format!("-{value}")),
+                (bob.account().address.clone(), value.to_string()),
+            ];
+            assert_eq!(transfers, expected);
+
+            tokio::time::timeout(std::time::Duration::from_secs(60), async {
+                while client.mempool_transaction(&id).await.unwrap().is_some() {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            })
+            .await
+            .unwrap();
+            assert!(client.mempool().await.unwrap().is_empty());
+        })
+        .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transfer_operations() -> Result<()> {
+        use rosetta_client::client::GenericMetadataParams;
+        use rosetta_docker::{run_test, Env};
+
+        async fn client_with_max_operations(config: BlockchainConfig) -> Result<PolkadotClient> {
+            let client = client_from_config(config).await?;
+            Ok(client.with_max_operations(3))
+        }
+
+        let config = rosetta_config_polkadot::config("westend-dev")?;
+        let env =
+            Env::new("polkadot-transfer-operations", config.clone(), client_with_max_operations)
+                .await?;
+
+        run_test(env, |env| async move {
+            let client = env.node();
+            let alice = env.ephemeral_wallet().await.unwrap();
+            let bob = env.ephemeral_wallet().await.unwrap();
+            let charlie = env.ephemeral_wallet().await.unwrap();
+            let dave = env.ephemeral_wallet().await.unwrap();
+            alice.faucet(100 * u128::pow(10, config.currency_decimals), None).await.unwrap();
+
+            let value = u128::pow(10, config.currency_decimals);
+            let transfer = |account: &AccountIdentifier, value: String| Operation {
+                r#type: "TRANSFER".into(),
+                account: Some(account.clone()),
+                amount: Some(Amount::new(value, client.currency().clone())),
+                ..Operation::default()
+            };
+            let debit = |value: u128| transfer(alice.account(), format!("-{value}"));
+            let credit =
+                |wallet: &rosetta_client::Wallet| transfer(wallet.account(), value.to_string());
+
+            // A debit and a credit are a plain transfer
+            let params = client.transfer_params(&[debit(value), credit(&bob)]).unwrap();
+            assert_eq!(params.pallet_name, "Balances");
+            assert_eq!(params.call_name, "transfer_keep_alive");
+            alice.construct(&GenericMetadataParams::Polkadot(params)).await.unwrap();
+            assert_eq!(bob.balance().await.unwrap(), value);
+
+            // Many credits are a batch of transfers
+            let operations = [debit(2 * value), credit(&charlie), credit(&dave)];
+            let params = client.transfer_params(&operations).unwrap();
+            assert_eq!(params.pallet_name, "Utility");
+            assert_eq!(params.call_name, "batch_all");
+            alice.construct(&GenericMetadataParams::Polkadot(params)).await.unwrap();
+            assert_eq!(charlie.balance().await.unwrap(), value);
+            assert_eq!(dave.balance().await.unwrap(), value);
+
+            // The credits must match the debit
+            let error = client.transfer_params(&[debit(value), credit(&bob), credit(&charlie)]);
+            assert!(error.unwrap_err().downcast_ref::<InvalidParams>().is_some());
+
+            // And the operations can't exceed the maximum
+            let operations = [debit(3 * value), credit(&bob), credit(&charlie), credit(&dave)];
+            let error = client.transfer_params(&operations).unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<InvalidParams>().unwrap().reason,
+                "4 operations exceed the maximum of 3"
+            );
+        })
+        .await;
+        Ok(())
+    }
 }
@@ -0,0 +1,72 @@
+use sp_trie::{read_trie_value, LayoutV1, StorageProof};
+use subxt::{ext::sp_core::Blake2Hasher, utils::H256};
+
+/// Storage proof returned by the `state_getReadProof` rpc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadProof<Hash> {
+    /// Hash of the block the proof was generated at.
+    pub at: Hash,
+    /// Trie nodes from the state root to the proven values.
+    pub proof: Vec<Vec<u8>>,
+}
+
+/// Verifies the read `proof` of the storage `key` against the `state_root` of a block, returns
+/// the proven value, `None` when the proof shows the key is absent.
+///
+/// # Errors
+/// Returns `Err` if the proof misses trie nodes needed to read `key`, or a node doesn't match
+/// the hash referencing it.
+pub fn verify_read_proof(
+    state_root: H256,
+    proof: &[Vec<u8>],
+    key: &[u8],
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let db = StorageProof::new(proof.iter().cloned()).into_memory_db::<Blake2Hasher>();
+    let root = state_root.0.into();
+    read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, &root, key, None, None)
+        .map_err(|err| anyhow::anyhow!("invalid read proof: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_trie::{trie_types::TrieDBMutBuilderV1, MemoryDB, TrieMut};
+
+    /// Builds a trie of `entries`, returns its root and every node as proof.
+    fn trie(entries: &[(&[u8], &[u8])]) -> (H256, Vec<Vec<u8>>) {
+        let mut db = MemoryDB::<Blake2Hasher>::default();
+        let mut root = Default::default();
+        {
+            let mut trie = TrieDBMutBuilderV1::new(&mut db, &mut root).build();
+            for (key, value) in entries {
+                trie.insert(key, value).unwrap();
+            }
+        }
+        let proof = db.drain().into_values().map(|(node, _)| node).collect();
+        (H256(root.0), proof)
+    }
+
+    #[test]
+    fn verifies_read_proof() {
+        // Values above 32 bytes are stored in their own node
+        let long_value = [7u8; 80];
+        let (root, proof) = trie(&[(b"alice", &long_value), (b"bob", b"short")]);
+        assert_eq!(verify_read_proof(root, &proof, b"alice").unwrap(), Some(long_value.to_vec()));
+        assert_eq!(verify_read_proof(root, &proof, b"bob").unwrap(), Some(b"short".to_vec()));
+        assert_eq!(verify_read_proof(root, &proof, b"charlie").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_tampered_proof() {
+        let (root, proof) = trie(&[(b"alice", &[7u8; 80]), (b"bob", b"short")]);
+        for index in 0..proof.len() {
+            let mut tampered = proof.clone();
+            let node = &mut tampered[index];
+            let last = node.len() - 1;
+            node[last] ^= 1;
+            assert!(verify_read_proof(root, &tampered, b"alice").is_err());
+        }
+        // Another state root
+        assert!(verify_read_proof(H256::repeat_byte(1), &proof, b"alice").is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use rosetta_core::{traits::Member, types::PartialBlockIdentifier};
+use rosetta_core::{
+    traits::Member,
+    types::{BlockTag, PartialBlockIdentifier},
+};
 use std::{borrow::Borrow, fmt::Debug, marker::PhantomData};
 use subxt::{
     blocks::StaticExtrinsic,
@@ -96,6 +99,9 @@ pub enum BlockIdentifier<BlockHash> {
     Hash(BlockHash),
     Latest,
     Finalized,
+    /// The earliest block whose state is available, the genesis block unless the node prunes
+    /// the state.
+    Earliest,
 }
 
 impl<T> From<rosetta_core::types::BlockIdentifier> for BlockIdentifier<T>
@@ -113,11 +119,13 @@ where
 {
     fn from(block_identifier: PartialBlockIdentifier) -> Self {
         match block_identifier {
+            PartialBlockIdentifier { tag: Some(BlockTag::Finalized), .. } => Self::Finalized,
+            PartialBlockIdentifier { tag: Some(BlockTag::Earliest), .. } => Self::Earliest,
             PartialBlockIdentifier { hash: Some(block_hash), .. } => {
                 Self::Hash((block_hash).into())
             },
             PartialBlockIdentifier { index: Some(block_number), .. } => Self::Number(block_number),
-            PartialBlockIdentifier { hash: None, index: None } => Self::Latest,
+            PartialBlockIdentifier { hash: None, index: None, tag: None } => Self::Latest,
         }
     }
 }
@@ -128,11 +136,13 @@ where
 {
     fn from(block_identifier: &PartialBlockIdentifier) -> Self {
         match block_identifier {
+            PartialBlockIdentifier { tag: Some(BlockTag::Finalized), .. } => Self::Finalized,
+            PartialBlockIdentifier { tag: Some(BlockTag::Earliest), .. } => Self::Earliest,
             PartialBlockIdentifier { hash: Some(block_hash), .. } => {
                 Self::Hash((*block_hash).into())
             },
             PartialBlockIdentifier { index: Some(block_number), .. } => Self::Number(*block_number),
-            PartialBlockIdentifier { hash: None, index: None } => Self::Latest,
+            PartialBlockIdentifier { hash: None, index: None, tag: None } => Self::Latest,
         }
     }
 }
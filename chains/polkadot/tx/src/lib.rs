@@ -1,8 +1,8 @@
 use anyhow::{bail, Context, Result};
-use parity_scale_codec::{Compact, Decode, Encode};
-use rosetta_config_polkadot::{PolkadotMetadata, PolkadotMetadataParams};
+use parity_scale_codec::{Compact, Decode, Encode, Error as CodecError, Input, Output};
+use rosetta_config_polkadot::{Mortality, PolkadotMetadata, PolkadotMetadataParams, SignedExtra};
 use rosetta_core::{
-    crypto::{address::Address, SecretKey},
+    crypto::{address::Address, PublicKey, SecretKey, Signature},
     BlockchainConfig, TransactionBuilder,
 };
 
@@ -14,16 +14,66 @@ enum MultiAddress {
     Id(AccountId32),
 }
 
-#[derive(Encode)]
+#[derive(Decode, Encode)]
 enum MultiSignature {
-    #[allow(unused)]
     Ed25519([u8; 64]),
     Sr25519([u8; 64]),
 }
 
-#[derive(Encode)]
 enum Era {
     Immortal,
+    Mortal { period: u64, phase: u64 },
+}
+
+impl Era {
+    fn from_metadata(metadata: &PolkadotMetadata) -> Self {
+        metadata.era.map_or(Self::Immortal, |era| {
+            let Mortality { period, phase } = era.mortality();
+            Self::Mortal { period, phase }
+        })
+    }
+
+    const fn mortality(&self) -> Option<Mortality> {
+        match self {
+            Self::Immortal => None,
+            Self::Mortal { period, phase } => Some(Mortality { period: *period, phase: *phase }),
+        }
+    }
+}
+
+impl Encode for Era {
+    #[allow(clippy::cast_possible_truncation)]
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        match self {
+            Self::Immortal => dest.push_byte(0),
+            Self::Mortal { period, phase } => {
+                // Same encoding as `sp_runtime::generic::Era`
+                let quantize_factor = (*period >> 12).max(1);
+                let encoded = (period.trailing_zeros() - 1).clamp(1, 15) as u16 |
+                    ((phase / quantize_factor) << 4) as u16;
+                encoded.encode_to(dest);
+            },
+        }
+    }
+}
+
+impl Decode for Era {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        // Same decoding as `sp_runtime::generic::Era`
+        let first = input.read_byte()?;
+        if first == 0 {
+            return Ok(Self::Immortal);
+        }
+        let encoded = u64::from(first) | (u64::from(input.read_byte()?) << 8);
+        let period = 2 << (encoded % (1 << 4));
+        let quantize_factor = (period >> 12).max(1);
+        let phase = (encoded >> 4) * quantize_factor;
+        if period >= 4 && phase < period {
+            Ok(Self::Mortal { period, phase })
+        } else {
+            Err("invalid period and phase".into())
+        }
+    }
 }
 
 fn parse_address(address: &Address) -> Result<AccountId32> {
@@ -74,7 +124,114 @@ fn ss58hash(data: &[u8]) -> blake2_rfc::blake2b::Blake2bResult {
     context.finalize()
 }
 
-#[derive(Default)]
+fn extra_parameters(metadata: &PolkadotMetadata) -> (Era, Compact<u64>, Compact<u128>) {
+    (
+        Era::from_metadata(metadata),
+        Compact(u64::from(metadata.nonce)),
+        // plain tip
+        Compact(metadata.tip),
+    )
+}
+
+/// Returns the payload signed by the sender, the payload is hashed if longer than 256 bytes.
+fn signing_payload(
+    metadata_params: &PolkadotMetadataParams,
+    metadata: &PolkadotMetadata,
+) -> Vec<u8> {
+    // Mortal extrinsics are checkpointed at the era's birth block
+    let checkpoint = metadata.era.map_or(metadata.genesis_hash, |era| era.block_hash);
+    let additional_parameters =
+        (metadata.spec_version, metadata.transaction_version, metadata.genesis_hash, checkpoint);
+
+    // construct payload
+    let mut payload = vec![];
+    metadata.pallet_index.encode_to(&mut payload);
+    metadata.call_index.encode_to(&mut payload);
+    payload.extend(&metadata_params.call_args);
+    extra_parameters(metadata).encode_to(&mut payload);
+    additional_parameters.encode_to(&mut payload);
+
+    if payload.len() > 256 {
+        blake2_rfc::blake2b::blake2b(64, &[], &payload).as_bytes().to_vec()
+    } else {
+        payload
+    }
+}
+
+/// Encodes the extrinsic signed by `address`.
+fn encode_signed(
+    metadata_params: &PolkadotMetadataParams,
+    metadata: &PolkadotMetadata,
+    address: AccountId32,
+    signature: &MultiSignature,
+) -> Vec<u8> {
+    let address = MultiAddress::Id(address);
+
+    // encode transaction
+    let mut encoded = vec![];
+    // "is signed" + transaction protocol version (4)
+    (0b1000_0000 + 4u8).encode_to(&mut encoded);
+    // from address for signature
+    address.encode_to(&mut encoded);
+    // signature encode pending to vector
+    signature.encode_to(&mut encoded);
+    // attach custom extra params
+    extra_parameters(metadata).encode_to(&mut encoded);
+    // and now, call data
+    metadata.pallet_index.encode_to(&mut encoded);
+    metadata.call_index.encode_to(&mut encoded);
+    encoded.extend(&metadata_params.call_args);
+
+    // now, prefix byte length:
+    #[allow(clippy::expect_used)]
+    let len = Compact(u32::try_from(encoded.len()).expect("tx cannot have more than 32 bits"));
+    let mut transaction = vec![];
+    len.encode_to(&mut transaction);
+    transaction.extend(encoded);
+    transaction
+}
+
+/// Decodes the signed extra of an `extrinsic` built by [`PolkadotTransactionBuilder`],
+/// `extensions` are the chain signed extensions, see [`PolkadotMetadata::signed_extensions`].
+///
+/// # Errors
+/// Returns `Err` if `extrinsic` isn't a signed extrinsic.
+pub fn decode_signed_extra(extrinsic: &[u8], extensions: Vec<String>) -> Result<SignedExtra> {
+    let input = &mut &extrinsic[..];
+    let Compact(len) = Compact::<u32>::decode(input)?;
+    if usize::try_from(len)? != input.len() {
+        bail!("invalid extrinsic length, expected {len} got {}", input.len());
+    }
+    // "is signed" + transaction protocol version (4)
+    if u8::decode(input)? != 0b1000_0000 + 4 {
+        bail!("not a signed extrinsic");
+    }
+    MultiAddress::decode(input)?;
+    MultiSignature::decode(input)?;
+    let (era, Compact(nonce), Compact(tip)) = <(Era, Compact<u64>, Compact<u128>)>::decode(input)?;
+    Ok(SignedExtra {
+        extensions,
+        nonce: u32::try_from(nonce).context("invalid nonce")?,
+        tip,
+        mortality: era.mortality(),
+    })
+}
+
+/// Decodes the signed extra of an `extrinsic` and checks it matches the `metadata` the
+/// extrinsic was built with.
+///
+/// # Errors
+/// Returns `Err` if `extrinsic` isn't a signed extrinsic, or its signed extra doesn't match.
+pub fn verify_signed_extra(extrinsic: &[u8], metadata: &PolkadotMetadata) -> Result<SignedExtra> {
+    let signed_extra = decode_signed_extra(extrinsic, metadata.signed_extensions.clone())?;
+    let expected = metadata.signed_extra();
+    if signed_extra != expected {
+        bail!("signed extra mismatch, expected {expected:?} got {signed_extra:?}");
+    }
+    Ok(signed_extra)
+}
+
+#[derive(Clone, Default)]
 pub struct PolkadotTransactionBuilder;
 
 #[derive(Debug, Decode, Encode)]
@@ -96,6 +253,8 @@ impl TransactionBuilder for PolkadotTransactionBuilder {
             pallet_name: "Balances".into(),
             call_name: "transfer_keep_alive".into(),
             call_args: Transfer { dest, amount }.encode(),
+            tip: None,
+            mortality: None,
         })
     }
 
@@ -117,61 +276,45 @@ impl TransactionBuilder for PolkadotTransactionBuilder {
     ) -> Vec<u8> {
         #[allow(clippy::unwrap_used)]
         let address = AccountId32(secret_key.public_key().to_bytes().try_into().unwrap());
-        let address = MultiAddress::Id(address);
-        let extra_parameters = (
-            Era::Immortal,
-            Compact(u64::from(metadata.nonce)),
-            // plain tip
-            Compact(0u128),
-        );
-        let additional_parameters = (
-            metadata.spec_version,
-            metadata.transaction_version,
-            metadata.genesis_hash,
-            metadata.genesis_hash,
-        );
-
-        // construct payload
-        let mut payload = vec![];
-        metadata.pallet_index.encode_to(&mut payload);
-        metadata.call_index.encode_to(&mut payload);
-        payload.extend(&metadata_params.call_args);
-        extra_parameters.encode_to(&mut payload);
-        additional_parameters.encode_to(&mut payload);
 
         // sign payload
-        let signature = if payload.len() > 256 {
-            let hash = blake2_rfc::blake2b::blake2b(64, &[], &payload);
-            secret_key.sign(hash.as_bytes(), "substrate")
-        } else {
-            secret_key.sign(&payload, "substrate")
-        };
+        let payload = signing_payload(metadata_params, metadata);
+        let signature = secret_key.sign(&payload, "substrate");
         #[allow(clippy::unwrap_used)]
         let signature =
             MultiSignature::Sr25519(signature.to_bytes().as_slice().try_into().unwrap());
+        encode_signed(metadata_params, metadata, address, &signature)
+    }
 
-        // encode transaction
-        let mut encoded = vec![];
-        // "is signed" + transaction protocol version (4)
-        (0b1000_0000 + 4u8).encode_to(&mut encoded);
-        // from address for signature
-        address.encode_to(&mut encoded);
-        // signature encode pending to vector
-        signature.encode_to(&mut encoded);
-        // attach custom extra params
-        extra_parameters.encode_to(&mut encoded);
-        // and now, call data
-        metadata.pallet_index.encode_to(&mut encoded);
-        metadata.call_index.encode_to(&mut encoded);
-        encoded.extend(&metadata_params.call_args);
-
-        // now, prefix byte length:
-        #[allow(clippy::expect_used)]
-        let len = Compact(u32::try_from(encoded.len()).expect("tx cannot have more than 32 bits"));
-        let mut transaction = vec![];
-        len.encode_to(&mut transaction);
-        transaction.extend(encoded);
-        transaction
+    fn signing_payload(
+        &self,
+        _config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+    ) -> Result<Vec<u8>> {
+        Ok(signing_payload(metadata_params, metadata))
+    }
+
+    fn encode_signed(
+        &self,
+        _config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> Result<Vec<u8>> {
+        let address = AccountId32(
+            public_key
+                .to_bytes()
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("invalid public key"))?,
+        );
+        let signature = match signature {
+            Signature::Sr25519(signature) => MultiSignature::Sr25519(signature.to_bytes()),
+            Signature::Ed25519(signature) => MultiSignature::Ed25519(signature.to_bytes()),
+            _ => bail!("unsupported signature, expected a sr25519 or ed25519 signature"),
+        };
+        Ok(encode_signed(metadata_params, metadata, address, &signature))
     }
 
     fn deploy_contract(&self, _contract_binary: Vec<u8>) -> Result<Self::MetadataParams> {
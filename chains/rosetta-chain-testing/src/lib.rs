@@ -1,33 +1,249 @@
-use std::future::Future;
+use anyhow::Result;
+use rand_core::{OsRng, RngCore};
+use rosetta_client::{
+    client::GenericClient,
+    crypto::{
+        bip32::DerivedSecretKey,
+        bip39::{Language, Mnemonic},
+        Algorithm, SecretKey,
+    },
+    Wallet,
+};
+use rosetta_core::BlockchainClient;
+use rosetta_server_ethereum::{EthereumClient, MaybeWsEthereumClient};
+use std::{future::Future, pin::Pin, time::Duration};
 
-/// Run the test in another thread while sending txs
+/// Run the test in another thread while sending txs, guarantee that only one test is running
+/// at a time, including the tests using [`serialized_block_producer`].
 /// # Panic
 /// Panics if the future panics
 pub async fn run_test<Fut: Future<Output = ()> + Send + 'static>(future: Fut) {
-    // Guarantee that only one test is incrementing blocks at a time
-    static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+    serialized_block_producer(future, BlockProducerConfig::without_producer()).await;
+}
 
-    // Run the test in another thread
-    let test_handler = tokio::spawn(future);
+/// Produces new blocks on chains which only mine blocks when there are pending transactions.
+pub trait BlockProducer: Send + 'static {
+    /// Produces a new block, called repeatedly while a serialized test is running.
+    fn produce_block(&mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>>;
+}
+
+/// No-op block producer, for chains which produce blocks by themselves.
+impl BlockProducer for () {
+    fn produce_block(&mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Configuration of [`serialized_block_producer`].
+pub struct BlockProducerConfig<P> {
+    /// Block producer, `None` if the chain produces blocks by itself.
+    pub producer: Option<P>,
+    /// Time to wait between produced blocks.
+    pub interval: Duration,
+}
+
+impl BlockProducerConfig<()> {
+    /// Serialize the tests without producing blocks.
+    #[must_use]
+    pub const fn without_producer() -> Self {
+        Self { producer: None, interval: Duration::ZERO }
+    }
+}
+
+impl<P: BlockProducer> BlockProducerConfig<P> {
+    #[must_use]
+    pub const fn new(producer: P, interval: Duration) -> Self {
+        Self { producer: Some(producer), interval }
+    }
+}
+
+/// Run the test in another thread while producing new blocks, guarantee that only one test
+/// is running at a time.
+/// # Panic
+/// Panics if the future or the block producer panics
+pub async fn serialized_block_producer<Fut, P>(future: Fut, config: BlockProducerConfig<P>)
+where
+    Fut: Future<Output = ()> + Send + 'static,
+    P: BlockProducer,
+{
+    // Guarantee that only one test is running and producing blocks at a time
+    static LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
 
     // Acquire Lock
     let guard = LOCK.lock().await;
 
-    // Check if the test is finished after acquiring the lock
-    if test_handler.is_finished() {
-        // Release lock
-        drop(guard);
+    // Run the test in another thread
+    let test_handler = tokio::spawn(future);
 
-        // Now is safe to panic
-        if let Err(err) = test_handler.await {
-            std::panic::resume_unwind(err.into_panic());
+    // Produce new blocks until the test finishes
+    if let Some(mut producer) = config.producer {
+        while !test_handler.is_finished() {
+            if let Err(err) = producer.produce_block().await {
+                test_handler.abort();
+                drop(guard);
+                panic!("failed to produce block: {err:?}");
+            }
+            tokio::time::sleep(config.interval).await;
         }
-        return;
     }
+    let result = test_handler.await;
+
+    // Release lock
+    drop(guard);
 
     // Now is safe to panic
-    if let Err(err) = test_handler.await {
+    if let Err(err) = result {
         // Resume the panic on the main task
         std::panic::resume_unwind(err.into_panic());
     }
 }
+
+/// Generates a new account, funds it with `balance` using the `client` faucet and returns its
+/// private key plus a [`Wallet`] of this account, whose balance is `balance`. The account is
+/// also the funding account of the wallet's client.
+///
+/// This function can be called concurrently, the calls to the faucet are serialized to
+/// guarantee the funding account nonce is incremented atomically.
+///
+/// # Errors
+/// Returns `Err` if the faucet fails or the wallet cannot be created.
+pub async fn funded_account(
+    client: &MaybeWsEthereumClient,
+    balance: u128,
+) -> Result<([u8; 32], Wallet<SecretKey>)> {
+    // Guarantee the funding account nonce is incremented atomically
+    static NONCE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    let config = client.config().clone();
+
+    // Generate a random secp256k1 key
+    let (private_key, secret_key) = loop {
+        let mut private_key = [0u8; 32];
+        OsRng.fill_bytes(&mut private_key);
+        if let Ok(secret_key) =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &private_key)
+        {
+            break (private_key, secret_key);
+        }
+    };
+    let address = secret_key.public_key().to_address(config.address_format);
+
+    // Send funds to the new account
+    let nonce_lock = NONCE.lock().await;
+    client.faucet(&address, balance, None).await?;
+    drop(nonce_lock);
+
+    // Reuse the same rpc connection
+    let funded_client = match client {
+        MaybeWsEthereumClient::Http(client) => MaybeWsEthereumClient::Http(
+            EthereumClient::new(config, client.backend.0.clone(), Some(private_key)).await?,
+        ),
+        MaybeWsEthereumClient::Ws(client) => {
+            MaybeWsEthereumClient::from_jsonrpsee(
+                config,
+                client.backend.0.clone(),
+                Some(private_key),
+            )
+            .await?
+        },
+    };
+    let wallet = Wallet::from_signer(GenericClient::Ethereum(funded_client), secret_key)?;
+    Ok((private_key, wallet))
+}
+
+/// Well-known substrate development phrase, the dev accounts are derived from it.
+pub const DEV_PHRASE: &str =
+    "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+
+/// Names of the well-known substrate development accounts.
+pub const DEV_ACCOUNTS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Returns the sr25519 key of the well-known substrate development account `name`, which is
+/// the `//<name>` hard junction of [`DEV_PHRASE`], ex: `dev_account("Alice")` is `//Alice`.
+///
+/// # Errors
+/// Returns `Err` if `name` isn't one of [`DEV_ACCOUNTS`].
+pub fn dev_account(name: &str) -> Result<SecretKey> {
+    let Some(name) = DEV_ACCOUNTS.iter().find(|account| account.eq_ignore_ascii_case(name)) else {
+        anyhow::bail!("unknown dev account {name:?}, expected one of {DEV_ACCOUNTS:?}");
+    };
+    let mnemonic = Mnemonic::parse_in(Language::English, DEV_PHRASE)?;
+    let master = DerivedSecretKey::substrate(&mnemonic, "", Algorithm::Sr25519)?;
+    Ok(master.derive_hard_junction(name)?.secret_key().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosetta_client::crypto::address::{AddressFormat, Ss58AddressFormatRegistry};
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    struct CountingProducer(Arc<AtomicUsize>);
+
+    impl BlockProducer for CountingProducer {
+        fn produce_block(&mut self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn serialized_tests_dont_interleave() {
+        let running = Arc::new(AtomicBool::new(false));
+        let blocks = Arc::new(AtomicUsize::new(0));
+
+        let serialized_test = |running: Arc<AtomicBool>, blocks: Arc<AtomicUsize>| async move {
+            let config =
+                BlockProducerConfig::new(CountingProducer(blocks), Duration::from_millis(10));
+            serialized_block_producer(
+                async move {
+                    assert!(!running.swap(true, Ordering::SeqCst), "tests interleaved");
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    assert!(running.swap(false, Ordering::SeqCst), "tests interleaved");
+                },
+                config,
+            )
+            .await;
+        };
+
+        tokio::join!(
+            serialized_test(running.clone(), blocks.clone()),
+            serialized_test(running.clone(), blocks.clone()),
+        );
+        assert!(!running.load(Ordering::SeqCst));
+        assert!(blocks.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[tokio::test]
+    async fn run_test_is_serialized_with_block_producers() {
+        let running = Arc::new(AtomicBool::new(false));
+        let blocks = Arc::new(AtomicUsize::new(0));
+
+        let test = |running: Arc<AtomicBool>| async move {
+            assert!(!running.swap(true, Ordering::SeqCst), "tests interleaved");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            assert!(running.swap(false, Ordering::SeqCst), "tests interleaved");
+        };
+        let config =
+            BlockProducerConfig::new(CountingProducer(blocks.clone()), Duration::from_millis(10));
+
+        tokio::join!(
+            run_test(test(running.clone())),
+            serialized_block_producer(test(running.clone()), config),
+        );
+        assert!(!running.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn dev_accounts_match_the_well_known_addresses() {
+        let format = AddressFormat::from(Ss58AddressFormatRegistry::SubstrateAccount);
+        let address = |name| dev_account(name).unwrap().public_key().to_address(format);
+        assert_eq!(address("Alice").address(), "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+        assert_eq!(address("Bob").address(), "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty");
+        assert_eq!(address("alice"), address("Alice"));
+        assert!(dev_account("Mallory").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::types::{Amount as RosettaAmount, Currency};
+use anyhow::{Context, Result};
+use fraction::BigUint;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// An amount of a [`Currency`], stored in base units, e.g. wei for ETH or planck for DOT.
+///
+/// The arithmetic is checked, operations between amounts of different currencies or resulting
+/// in a negative amount fail instead of silently losing precision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Amount {
+    value: BigUint,
+    currency: Currency,
+}
+
+impl Amount {
+    /// Creates an amount of `value` base units of `currency`.
+    #[must_use]
+    pub fn new(value: impl Into<BigUint>, currency: Currency) -> Self {
+        Self { value: value.into(), currency }
+    }
+
+    /// Creates an empty amount of `currency`.
+    #[must_use]
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0u8, currency)
+    }
+
+    /// Parses an amount in standard units, e.g. `"1.5"` ETH is 1.5 * 10^18 wei.
+    ///
+    /// # Errors
+    /// Returns `Err` if `value` is not a positive decimal number, or has more decimal places than
+    /// the currency.
+    pub fn from_decimal_str(value: &str, currency: Currency) -> Result<Self> {
+        let (integer, fractional) = value.split_once('.').unwrap_or((value, ""));
+        let decimals = usize::try_from(currency.decimals)?;
+        if integer.is_empty() && fractional.is_empty() {
+            anyhow::bail!("invalid amount {value:?}");
+        }
+        if !integer.chars().chain(fractional.chars()).all(|c| c.is_ascii_digit()) {
+            anyhow::bail!("invalid amount {value:?}");
+        }
+        if fractional.len() > decimals {
+            anyhow::bail!(
+                "invalid amount {value:?}, {} supports at most {decimals} decimal places",
+                currency.symbol
+            );
+        }
+        let digits = format!("{integer}{fractional:0<decimals$}");
+        let value =
+            digits.parse::<BigUint>().with_context(|| format!("invalid amount {value:?}"))?;
+        Ok(Self { value, currency })
+    }
+
+    /// Returns the amount in base units.
+    #[must_use]
+    pub const fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    /// Returns the currency of the amount.
+    #[must_use]
+    pub const fn currency(&self) -> &Currency {
+        &self.currency
+    }
+
+    /// Returns the amount in standard units, without trailing zeros, e.g. `"1.5"`.
+    #[must_use]
+    pub fn to_decimal_string(&self) -> String {
+        let decimals = usize::try_from(self.currency.decimals).unwrap_or(usize::MAX);
+        let digits = format!("{:0>width$}", self.value, width = decimals.saturating_add(1));
+        let (integer, fractional) = digits.split_at(digits.len() - decimals);
+        let fractional = fractional.trim_end_matches('0');
+        if fractional.is_empty() {
+            integer.to_string()
+        } else {
+            format!("{integer}.{fractional}")
+        }
+    }
+
+    /// Adds two amounts of the same currency.
+    ///
+    /// # Errors
+    /// Returns `Err` if the currencies are different.
+    pub fn checked_add(&self, other: &Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+        Ok(Self { value: &self.value + &other.value, currency: self.currency.clone() })
+    }
+
+    /// Subtracts `other` from this amount.
+    ///
+    /// # Errors
+    /// Returns `Err` if the currencies are different, or `other` is greater than this amount.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+        if other.value > self.value {
+            anyhow::bail!("cannot subtract {other} from {self}");
+        }
+        Ok(Self { value: &self.value - &other.value, currency: self.currency.clone() })
+    }
+
+    fn ensure_same_currency(&self, other: &Self) -> Result<()> {
+        if self.currency != other.currency {
+            anyhow::bail!(
+                "currency mismatch, expected {} got {}",
+                self.currency.symbol,
+                other.currency.symbol
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} {}", self.to_decimal_string(), self.currency.symbol)
+    }
+}
+
+impl TryFrom<RosettaAmount> for Amount {
+    type Error = anyhow::Error;
+
+    fn try_from(amount: RosettaAmount) -> Result<Self> {
+        let value = amount
+            .value
+            .parse::<BigUint>()
+            .with_context(|| format!("invalid amount value {:?}", amount.value))?;
+        Ok(Self { value, currency: amount.currency })
+    }
+}
+
+impl From<Amount> for RosettaAmount {
+    fn from(amount: Amount) -> Self {
+        Self::new(amount.value.to_string(), amount.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(symbol: &str, decimals: u32) -> Currency {
+        Currency { symbol: symbol.into(), decimals, metadata: None }
+    }
+
+    #[test]
+    fn add_amounts() {
+        let eth = currency("ETH", 18);
+        let a = Amount::from_decimal_str("1.5", eth.clone()).unwrap();
+        let b = Amount::new(250_000_000_000_000_000u128, eth);
+        let sum = a.checked_add(&b).unwrap();
+        assert_eq!(sum.value(), &BigUint::from(1_750_000_000_000_000_000u128));
+        assert_eq!(sum.to_decimal_string(), "1.75");
+        assert_eq!(sum.to_string(), "1.75 ETH");
+    }
+
+    #[test]
+    fn sub_underflow_fails() {
+        let dot = currency("DOT", 10);
+        let a = Amount::from_decimal_str("1", dot.clone()).unwrap();
+        let b = Amount::from_decimal_str("0.0000000001", dot.clone()).unwrap();
+        assert_eq!(a.checked_sub(&b).unwrap().to_decimal_string(), "0.9999999999");
+        assert!(b.checked_sub(&a).is_err());
+        assert_eq!(a.checked_sub(&a).unwrap(), Amount::zero(dot));
+    }
+
+    #[test]
+    fn cross_currency_fails() {
+        let eth = Amount::new(1u8, currency("ETH", 18));
+        let dot = Amount::new(1u8, currency("DOT", 10));
+        assert!(eth.checked_add(&dot).is_err());
+        assert!(eth.checked_sub(&dot).is_err());
+        // Same symbol with a different precision is a different currency
+        let eth6 = Amount::new(1u8, currency("ETH", 6));
+        assert!(eth.checked_add(&eth6).is_err());
+    }
+
+    #[test]
+    fn decimal_conversion() {
+        let eth = currency("ETH", 18);
+        assert!(Amount::from_decimal_str("0.0000000000000000001", eth.clone()).is_err());
+        assert!(Amount::from_decimal_str("-1", eth.clone()).is_err());
+        assert!(Amount::from_decimal_str(".", eth.clone()).is_err());
+        let amount = Amount::from_decimal_str("0.000000000000000001", eth.clone()).unwrap();
+        assert_eq!(amount.value(), &BigUint::from(1u8));
+        assert_eq!(amount.to_decimal_string(), "0.000000000000000001");
+        assert_eq!(Amount::zero(eth).to_decimal_string(), "0");
+
+        let rosetta = RosettaAmount::from(amount.clone());
+        assert_eq!(rosetta.value, "1");
+        assert_eq!(Amount::try_from(rosetta).unwrap(), amount);
+    }
+}
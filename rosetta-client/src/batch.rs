@@ -0,0 +1,130 @@
+use anyhow::Result;
+use rosetta_server_ethereum::config::ext::types::Address as EthAddress;
+
+const OP_STOP: u8 = 0x00;
+const OP_ISZERO: u8 = 0x15;
+const OP_RETURNDATASIZE: u8 = 0x3d;
+const OP_RETURNDATACOPY: u8 = 0x3e;
+const OP_CODECOPY: u8 = 0x39;
+const OP_JUMPI: u8 = 0x57;
+const OP_GAS: u8 = 0x5a;
+const OP_JUMPDEST: u8 = 0x5b;
+const OP_PUSH1: u8 = 0x60;
+const OP_PUSH4: u8 = 0x63;
+const OP_PUSH16: u8 = 0x6f;
+const OP_PUSH20: u8 = 0x73;
+const OP_CALL: u8 = 0xf1;
+const OP_REVERT: u8 = 0xfd;
+
+/// Size of the code executing a single call, all the operands use fixed size pushes.
+const CALL_CODE_LEN: usize = 71;
+
+/// Size of the code after the calls: `STOP` plus the revert handler.
+const TRAILER_LEN: usize = 12;
+
+/// Builds the init code of a disposable contract which executes `calls` in order from its
+/// constructor, and reverts the whole creation with the revert data of the first failed call.
+///
+/// The constructor returns no runtime code, so no contract is left on chain. The calldata of each
+/// call is appended after the code and copied to memory with `CODECOPY`. The creation
+/// transaction must carry the sum of the calls value, which is forwarded by each `CALL`.
+pub fn atomic_batch_init_code(calls: &[(EthAddress, Vec<u8>, u128)]) -> Result<Vec<u8>> {
+    let data_len = calls.iter().map(|(_, data, _)| data.len()).sum::<usize>();
+    let code_len = calls.len() * CALL_CODE_LEN + TRAILER_LEN;
+    let revert_label = u32::try_from(code_len - TRAILER_LEN + 1)?;
+
+    let mut code = Vec::with_capacity(code_len + data_len);
+    let mut data_offset = code_len;
+    for (address, data, value) in calls {
+        let len = u32::try_from(data.len())?.to_be_bytes();
+        let offset = u32::try_from(data_offset)?.to_be_bytes();
+        data_offset += data.len();
+
+        // CODECOPY(destOffset = 0, offset, size)
+        code.push(OP_PUSH4);
+        code.extend_from_slice(&len);
+        code.push(OP_PUSH4);
+        code.extend_from_slice(&offset);
+        code.extend_from_slice(&[OP_PUSH1, 0, OP_CODECOPY]);
+
+        // CALL(gas, address, value, argsOffset = 0, argsSize, retOffset = 0, retSize = 0)
+        code.extend_from_slice(&[OP_PUSH1, 0, OP_PUSH1, 0]);
+        code.push(OP_PUSH4);
+        code.extend_from_slice(&len);
+        code.extend_from_slice(&[OP_PUSH1, 0]);
+        code.push(OP_PUSH16);
+        code.extend_from_slice(&value.to_be_bytes());
+        code.push(OP_PUSH20);
+        code.extend_from_slice(address.as_bytes());
+        code.extend_from_slice(&[OP_GAS, OP_CALL]);
+
+        // Jump to the revert handler if the call failed
+        code.extend_from_slice(&[OP_ISZERO, OP_PUSH4]);
+        code.extend_from_slice(&revert_label.to_be_bytes());
+        code.push(OP_JUMPI);
+    }
+    code.push(OP_STOP);
+
+    // RETURNDATACOPY(destOffset = 0, offset = 0, size) then REVERT(offset = 0, size)
+    code.extend_from_slice(&[
+        OP_JUMPDEST,
+        OP_RETURNDATASIZE,
+        OP_PUSH1,
+        0,
+        OP_PUSH1,
+        0,
+        OP_RETURNDATACOPY,
+        OP_RETURNDATASIZE,
+        OP_PUSH1,
+        0,
+        OP_REVERT,
+    ]);
+    debug_assert_eq!(code.len(), code_len);
+
+    for (_, data, _) in calls {
+        code.extend_from_slice(data);
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_batch_only_stops() {
+        let code = atomic_batch_init_code(&[]).unwrap();
+        assert_eq!(code.len(), TRAILER_LEN);
+        assert_eq!(code[0], OP_STOP);
+        assert_eq!(code[1], OP_JUMPDEST);
+    }
+
+    #[test]
+    fn calldata_is_appended_after_the_code() {
+        let first = EthAddress::repeat_byte(0x11);
+        let second = EthAddress::repeat_byte(0x22);
+        let calls = vec![(first, vec![0xaa, 0xbb], 0), (second, vec![0xcc], 1_000)];
+        let code = atomic_batch_init_code(&calls).unwrap();
+
+        let code_len = 2 * CALL_CODE_LEN + TRAILER_LEN;
+        assert_eq!(code.len(), code_len + 3);
+        assert_eq!(&code[code_len..], &[0xaa, 0xbb, 0xcc]);
+
+        // The second call copies its calldata from right after the first one
+        let second_call = &code[CALL_CODE_LEN..];
+        assert_eq!(second_call[0], OP_PUSH4);
+        assert_eq!(second_call[1..5], 1u32.to_be_bytes());
+        assert_eq!(second_call[5], OP_PUSH4);
+        assert_eq!(second_call[6..10], u32::try_from(code_len + 2).unwrap().to_be_bytes());
+        assert_eq!(second_call[25..41], 1_000u128.to_be_bytes());
+        assert_eq!(&second_call[42..62], second.as_bytes());
+
+        // Every call jumps to the revert handler on failure
+        let revert_label = 2 * CALL_CODE_LEN + 1;
+        for call in code[..2 * CALL_CODE_LEN].chunks(CALL_CODE_LEN) {
+            assert_eq!(call[66..70], u32::try_from(revert_label).unwrap().to_be_bytes());
+            assert_eq!(call[70], OP_JUMPI);
+        }
+        assert_eq!(code[revert_label], OP_JUMPDEST);
+    }
+}
@@ -4,13 +4,13 @@ use crate::{
     types::CallRequest,
     Blockchain, BlockchainConfig,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use derive_more::From;
 use futures::Stream;
 use futures_util::StreamExt;
 use rosetta_core::{
     types::{BlockIdentifier, PartialBlockIdentifier},
-    BlockchainClient, ClientEvent,
+    BlockchainClient, ClientEvent, PREFLIGHT_MAX_BLOCKS_BEHIND,
 };
 use rosetta_server_astar::{AstarClient, AstarMetadata, AstarMetadataParams};
 use rosetta_server_ethereum::{
@@ -18,15 +18,16 @@ use rosetta_server_ethereum::{
         CallResult, Query as EthQuery, QueryResult as EthQueryResult, TransactionReceipt, H256,
     },
     EthereumMetadata, EthereumMetadataParams, MaybeWsEthereumClient as EthereumClient,
-    SubmitResult,
+    SubmitResult, Transport,
 };
 use rosetta_server_polkadot::{PolkadotClient, PolkadotMetadata, PolkadotMetadataParams};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{pin::Pin, str::FromStr, task::Poll};
+use std::{pin::Pin, str::FromStr, task::Poll, time::Duration};
 
 /// Generic Client
 #[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
 pub enum GenericClient {
     Ethereum(EthereumClient),
     Astar(AstarClient),
@@ -35,13 +36,27 @@ pub enum GenericClient {
 
 #[allow(clippy::missing_errors_doc)]
 impl GenericClient {
+    /// Creates a client for `network` of `blockchain` connected to `url`, the node is verified by
+    /// [`Self::preflight`] before the client is returned.
     pub async fn new(
         blockchain: Blockchain,
         network: &str,
         url: &str,
         private_key: Option<[u8; 32]>,
     ) -> Result<Self> {
-        Ok(match blockchain {
+        Self::new_with_preflight(blockchain, network, url, private_key, true).await
+    }
+
+    /// Same as [`Self::new`], the [`Self::preflight`] check is skipped when `preflight` is
+    /// `false`, ex: to connect to a node which is still syncing.
+    pub async fn new_with_preflight(
+        blockchain: Blockchain,
+        network: &str,
+        url: &str,
+        private_key: Option<[u8; 32]>,
+        preflight: bool,
+    ) -> Result<Self> {
+        let client = match blockchain {
             Blockchain::Ethereum => {
                 let client = EthereumClient::new("ethereum", network, url, private_key).await?;
                 Self::Ethereum(client)
@@ -77,23 +92,42 @@ impl GenericClient {
             Blockchain::Kusama | Blockchain::Wococo => {
                 anyhow::bail!("unsupported blockchain: {blockchain:?}")
             },
-        })
+        };
+        if preflight {
+            client.preflight(PREFLIGHT_MAX_BLOCKS_BEHIND).await
+        } else {
+            Ok(client)
+        }
     }
 
+    /// Creates a client from `config` connected to `url`, the node is verified by
+    /// [`Self::preflight`] before the client is returned.
     pub async fn from_config(
         config: BlockchainConfig,
         url: &str,
         private_key: Option<[u8; 32]>,
+    ) -> Result<Self> {
+        Self::from_config_with_preflight(config, url, private_key, true).await
+    }
+
+    /// Same as [`Self::from_config`], the [`Self::preflight`] check is skipped when `preflight`
+    /// is `false`, ex: to connect to a node which is still syncing.
+    pub async fn from_config_with_preflight(
+        config: BlockchainConfig,
+        url: &str,
+        private_key: Option<[u8; 32]>,
+        preflight: bool,
     ) -> Result<Self> {
         let blockchain = Blockchain::from_str(config.blockchain)?;
-        Ok(match blockchain {
+        let client = match blockchain {
             Blockchain::Ethereum |
             Blockchain::Polygon |
             Blockchain::Arbitrum |
             Blockchain::Binance |
             Blockchain::Base |
             Blockchain::Avalanche => {
-                let client = EthereumClient::from_config(config, url, private_key).await?;
+                let client =
+                    EthereumClient::from_config(config, url, private_key, Transport::Auto).await?;
                 Self::Ethereum(client)
             },
             Blockchain::Astar => {
@@ -107,12 +141,31 @@ impl GenericClient {
             Blockchain::Kusama | Blockchain::Wococo => {
                 anyhow::bail!("unsupported blockchain: {blockchain:?}")
             },
-        })
+        };
+        if preflight {
+            client.preflight(PREFLIGHT_MAX_BLOCKS_BEHIND).await
+        } else {
+            Ok(client)
+        }
+    }
+
+    /// Verifies the node is reachable, serves the configured chain and is at most
+    /// `max_blocks_behind` blocks behind the chain tip, so a wrong or unsynced node fails on
+    /// construction instead of on the first call. Returns the client on success.
+    pub async fn preflight(self, max_blocks_behind: u64) -> Result<Self> {
+        let result = match &self {
+            Self::Ethereum(client) => client.preflight(max_blocks_behind).await,
+            Self::Astar(client) => client.preflight(max_blocks_behind).await,
+            Self::Polkadot(client) => client.preflight(max_blocks_behind).await,
+        };
+        result
+            .with_context(|| format!("preflight check of {} failed", self.config().blockchain))?;
+        Ok(self)
     }
 }
 
 /// Generic Blockchain Params
-#[derive(Deserialize, Serialize, From)]
+#[derive(Clone, Deserialize, Serialize, From)]
 pub enum GenericMetadataParams {
     Ethereum(EthereumMetadataParams),
     Astar(AstarMetadataParams),
@@ -217,6 +270,14 @@ impl BlockchainClient for GenericClient {
         }
     }
 
+    async fn balances(&self, addresses: &[Address], block: &Self::AtBlock) -> Result<Vec<u128>> {
+        match self {
+            Self::Ethereum(client) => client.balances(addresses, block).await,
+            Self::Astar(client) => client.balances(addresses, block).await,
+            Self::Polkadot(client) => client.balances(addresses, block).await,
+        }
+    }
+
     async fn faucet(
         &self,
         address: &Address,
@@ -313,6 +374,14 @@ impl BlockchainClient for GenericClient {
         }
     }
 
+    async fn wait_for_block(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<Self::BlockIdentifier> {
+        dispatch!(self.wait_for_block(height, timeout).await)
+    }
+
     async fn subscribe(&self, sub: &Self::Subscription) -> Result<u32> {
         match self {
             Self::Ethereum(client) => match sub {
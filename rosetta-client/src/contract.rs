@@ -0,0 +1,66 @@
+//! ABI decoding of contract call results and event data.
+use anyhow::{Context, Result};
+use ethabi::param_type::Reader;
+pub use ethabi::{ParamType, Token};
+
+/// Decodes each payload of `data` against the schema at the same index of `schemas`, ex: the
+/// return data of the calls of a multicall. A schema is a single type, ex: `bool`, or a tuple of
+/// types, ex: `(uint256,address)`, decoded as one token per tuple member.
+///
+/// # Errors
+/// Returns `Err` with the index of the first schema which can't be parsed or payload which
+/// doesn't match its schema, or if `schemas` and `data` have different lengths.
+pub fn decode_many(schemas: &[&str], data: &[&[u8]]) -> Result<Vec<Vec<Token>>> {
+    if schemas.len() != data.len() {
+        anyhow::bail!("expected {} payloads, got {}", schemas.len(), data.len());
+    }
+    schemas
+        .iter()
+        .zip(data)
+        .enumerate()
+        .map(|(index, (schema, data))| {
+            let types = match Reader::read(schema)
+                .with_context(|| format!("invalid schema {schema:?} at index {index}"))?
+            {
+                ParamType::Tuple(types) => types,
+                param => vec![param],
+            };
+            ethabi::decode(&types, data)
+                .with_context(|| format!("failed to decode payload at index {index} as {schema}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::{Address, Uint};
+
+    #[test]
+    fn decodes_many_schemas() {
+        let address = Address::repeat_byte(0x42);
+        let first = ethabi::encode(&[Token::Uint(Uint::from(1000)), Token::Address(address)]);
+        let second = ethabi::encode(&[Token::Bool(true)]);
+        let tokens =
+            decode_many(&["(uint256,address)", "(bool)"], &[first.as_slice(), &second]).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                vec![Token::Uint(Uint::from(1000)), Token::Address(address)],
+                vec![Token::Bool(true)]
+            ]
+        );
+        // A single type isn't required to be wrapped in a tuple
+        assert_eq!(decode_many(&["bool"], &[&second]).unwrap(), vec![vec![Token::Bool(true)]]);
+    }
+
+    #[test]
+    fn reports_the_failing_index() {
+        let valid = ethabi::encode(&[Token::Bool(true)]);
+        let error = decode_many(&["(bool)", "(uint256,address)"], &[&valid, &valid]).unwrap_err();
+        assert!(error.to_string().contains("index 1"), "{error}");
+        let error = decode_many(&["(bool)", "(uintx)"], &[&valid, &valid]).unwrap_err();
+        assert!(error.to_string().contains("index 1"), "{error}");
+        assert!(decode_many(&["(bool)"], &[]).is_err());
+    }
+}
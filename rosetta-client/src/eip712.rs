@@ -0,0 +1,154 @@
+//! [EIP-712](https://eips.ethereum.org/EIPS/eip-712) typed structured data hashing.
+use rosetta_server_ethereum::config::ext::types::{
+    crypto::{Crypto, DefaultCrypto},
+    Address as EthAddress, H256, U256,
+};
+use std::cell::RefCell;
+
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address
+/// verifyingContract)")`
+const EIP712_DOMAIN_TYPEHASH: [u8; 32] = [
+    0x8b, 0x73, 0xc3, 0xc6, 0x9b, 0xb8, 0xfe, 0x3d, 0x51, 0x2e, 0xcc, 0x4c, 0xf7, 0x59, 0xcc, 0x79,
+    0x23, 0x9f, 0x7b, 0x17, 0x9b, 0x0f, 0xfa, 0xca, 0xa9, 0xa7, 0x5d, 0x52, 0x2b, 0x39, 0x40, 0x0f,
+];
+
+thread_local! {
+    /// Separator of the last domain used on this thread, bulk signing messages of the same
+    /// domain computes it only once.
+    static DOMAIN_SEPARATOR: RefCell<Option<(Eip712Domain, H256)>> = const { RefCell::new(None) };
+}
+
+#[cfg(test)]
+thread_local! {
+    static SEPARATORS_COMPUTED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// EIP-712 signing domain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Eip712Domain {
+    /// Name of the signing domain, ex: the token name.
+    pub name: String,
+    /// Current major version of the signing domain.
+    pub version: String,
+    /// Chain id of the chain the signature is valid on.
+    pub chain_id: u64,
+    /// Address of the contract which verifies the signature.
+    pub verifying_contract: EthAddress,
+}
+
+impl Eip712Domain {
+    /// Returns the domain separator, the result is cached until a different domain is used on
+    /// the same thread.
+    #[must_use]
+    pub fn separator(&self) -> H256 {
+        DOMAIN_SEPARATOR.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            match cache.as_ref() {
+                Some((domain, separator)) if domain == self => *separator,
+                _ => {
+                    let separator = self.compute_separator();
+                    *cache = Some((self.clone(), separator));
+                    separator
+                },
+            }
+        })
+    }
+
+    /// `keccak256(abi.encode(EIP712_DOMAIN_TYPEHASH, keccak256(name), keccak256(version),
+    /// chainId, verifyingContract))`
+    fn compute_separator(&self) -> H256 {
+        #[cfg(test)]
+        SEPARATORS_COMPUTED.with(|computed| computed.set(computed.get() + 1));
+
+        let mut encoded = Vec::with_capacity(5 * 32);
+        encoded.extend_from_slice(&EIP712_DOMAIN_TYPEHASH);
+        encoded.extend_from_slice(DefaultCrypto::keccak256(&self.name).as_bytes());
+        encoded.extend_from_slice(DefaultCrypto::keccak256(&self.version).as_bytes());
+        let mut chain_id = [0u8; 32];
+        U256::from(self.chain_id).to_big_endian(&mut chain_id);
+        encoded.extend_from_slice(&chain_id);
+        encoded.extend_from_slice(&[0u8; 12]);
+        encoded.extend_from_slice(self.verifying_contract.as_bytes());
+        DefaultCrypto::keccak256(encoded)
+    }
+}
+
+/// Returns the digest to sign for the message with the given `struct_hash` in `domain`:
+/// `keccak256("\x19\x01" || domainSeparator || structHash)`.
+#[must_use]
+pub fn eip712_digest(domain: &Eip712Domain, struct_hash: H256) -> H256 {
+    let mut encoded = [0u8; 66];
+    encoded[0..2].copy_from_slice(&[0x19, 0x01]);
+    encoded[2..34].copy_from_slice(domain.separator().as_bytes());
+    encoded[34..66].copy_from_slice(struct_hash.as_bytes());
+    DefaultCrypto::keccak256(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{address::AddressFormat, Algorithm, SecretKey};
+    use rosetta_server_ethereum::config::ext::types::crypto::{RecoveryId, Signature};
+
+    fn domain(chain_id: u64) -> Eip712Domain {
+        Eip712Domain {
+            name: "Token".into(),
+            version: "1".into(),
+            chain_id,
+            verifying_contract: EthAddress::repeat_byte(0x42),
+        }
+    }
+
+    fn computed() -> usize {
+        SEPARATORS_COMPUTED.with(std::cell::Cell::get)
+    }
+
+    #[test]
+    fn domain_typehash() {
+        let typehash = DefaultCrypto::keccak256(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+        );
+        assert_eq!(typehash.0, EIP712_DOMAIN_TYPEHASH);
+    }
+
+    #[test]
+    fn bulk_signing_computes_the_separator_once() {
+        let secret_key =
+            SecretKey::from_bytes(Algorithm::EcdsaRecoverableSecp256k1, &[0x42; 32]).unwrap();
+        let signer: EthAddress = secret_key
+            .public_key()
+            .to_address(AddressFormat::Eip55)
+            .address()
+            .parse()
+            .unwrap();
+        let domain = domain(1337);
+
+        let before = computed();
+        for i in 0..1000u64 {
+            let struct_hash = DefaultCrypto::keccak256(i.to_be_bytes());
+            let digest = eip712_digest(&domain, struct_hash);
+            let signature = secret_key.sign_prehashed(digest.as_bytes()).unwrap().to_bytes();
+            let signature = Signature {
+                v: RecoveryId::new(u64::from(signature[64])),
+                r: U256::from_big_endian(&signature[0..32]),
+                s: U256::from_big_endian(&signature[32..64]),
+            };
+            let recovered = DefaultCrypto::secp256k1_ecdsa_recover(&signature, digest).unwrap();
+            assert_eq!(recovered, signer);
+        }
+        assert_eq!(computed() - before, 1);
+    }
+
+    #[test]
+    fn domain_change_invalidates_the_separator() {
+        let first = domain(1);
+        let second = domain(2);
+        let separator = first.separator();
+        assert_eq!(separator, first.compute_separator());
+
+        let before = computed();
+        assert_ne!(second.separator(), separator);
+        assert_eq!(first.separator(), separator);
+        assert_eq!(computed() - before, 2);
+    }
+}
@@ -2,17 +2,37 @@
 #![deny(missing_docs)]
 use anyhow::Result;
 
-pub use crate::wallet::Wallet;
+pub use crate::{
+    amount::Amount,
+    multi_chain::MultiChainWallet,
+    nonce::{
+        NonceGap, NonceGapStrategy, NonceManager, NonceManagerConfig, DEFAULT_NONCE_GAP_TOLERANCE,
+    },
+    submission::{SubmissionLimit, DEFAULT_MAX_QUEUED_SUBMISSIONS},
+    wallet::{
+        FaucetOptions, LabeledSubmitResult, Permit, TxProgress, Wallet,
+        DEFAULT_BALANCES_CONCURRENCY, FAUCET_WAIT_TIMEOUT,
+    },
+    watch_only::{AccountDescriptor, WatchOnly},
+};
 pub use rosetta_core::{crypto, types, BlockchainConfig};
 
+mod amount;
+mod batch;
 /// Clients that communicates to different blockchains
 pub mod client;
+pub mod contract;
+pub mod eip712;
 mod mnemonic;
+mod multi_chain;
+mod nonce;
 mod signer;
+mod submission;
 mod tx_builder;
 mod wallet;
+mod watch_only;
 
-pub use signer::Signer;
+pub use signer::{verify_raw, wrap_bytes, RemoteSigner, Signer};
 
 /// Re-exports libraries to not require any additional
 /// dependencies to be explicitly added on the client side.
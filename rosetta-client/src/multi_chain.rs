@@ -0,0 +1,49 @@
+use crate::{client::GenericClient, mnemonic::MnemonicStore, Blockchain, Wallet};
+use anyhow::Result;
+use std::path::Path;
+
+/// Wallets derived from the same mnemonic on multiple chains.
+pub struct MultiChainWallet {
+    wallets: Vec<(Blockchain, Wallet)>,
+}
+
+impl MultiChainWallet {
+    /// Connects to each `(blockchain, network, url)` and derives the wallets from the mnemonic
+    /// stored in `keyfile`, a new mnemonic is generated when no keyfile is provided.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn new(chains: &[(Blockchain, &str, &str)], keyfile: Option<&Path>) -> Result<Self> {
+        let store = MnemonicStore::new(keyfile)?;
+        let mnemonic = match keyfile {
+            Some(_) => store.get_or_generate_mnemonic()?,
+            None => store.generate()?,
+        };
+        let mut wallets = Vec::with_capacity(chains.len());
+        for (blockchain, network, url) in chains {
+            let client = GenericClient::new(*blockchain, network, url, None).await?;
+            wallets.push((*blockchain, Wallet::from_mnemonic(client, &mnemonic)?));
+        }
+        Ok(Self { wallets })
+    }
+
+    /// Creates a multi-chain wallet from existing wallets.
+    #[must_use]
+    pub const fn from_wallets(wallets: Vec<(Blockchain, Wallet)>) -> Self {
+        Self { wallets }
+    }
+
+    /// Returns the wallet of each chain.
+    #[must_use]
+    pub fn wallets(&self) -> &[(Blockchain, Wallet)] {
+        &self.wallets
+    }
+
+    /// Returns the balance of the wallet on each chain, the balances are fetched concurrently
+    /// and a failure on one chain doesn't affect the others.
+    pub async fn balances(&self) -> Vec<(Blockchain, Result<u128>)> {
+        let balances = self
+            .wallets
+            .iter()
+            .map(|(blockchain, wallet)| async move { (*blockchain, wallet.balance().await) });
+        futures_util::future::join_all(balances).await
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Default number of consecutive checks a nonce gap is tolerated before it is recovered.
+pub const DEFAULT_NONCE_GAP_TOLERANCE: u32 = 3;
+
+/// How a [`NonceManager`] recovers from a nonce gap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NonceGapStrategy {
+    /// Broadcasts the missing transaction once again, falls back to [`Self::Reset`] if it isn't
+    /// known by the manager.
+    #[default]
+    Rebroadcast,
+    /// Resets the counter to the on-chain nonce, the transactions dispatched after the gap must
+    /// be signed again.
+    Reset,
+}
+
+/// Configuration of the [`NonceManager`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceManagerConfig {
+    /// Number of consecutive checks where the on-chain nonce doesn't move while transactions are
+    /// in flight, before the next transaction is considered dropped.
+    pub gap_tolerance: u32,
+    /// How the gap is recovered.
+    pub strategy: NonceGapStrategy,
+}
+
+impl Default for NonceManagerConfig {
+    fn default() -> Self {
+        Self { gap_tolerance: DEFAULT_NONCE_GAP_TOLERANCE, strategy: NonceGapStrategy::default() }
+    }
+}
+
+/// A nonce gap detected by the [`NonceManager`], and the action taken to recover it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NonceGap {
+    /// The transaction with `nonce` must be broadcasted once again.
+    Rebroadcast {
+        /// Nonce of the missing transaction.
+        nonce: u64,
+        /// Signed transaction to broadcast.
+        transaction: Vec<u8>,
+    },
+    /// The counter was reset to `nonce`, the transactions dispatched with `nonce` or above were
+    /// forgotten.
+    Reset {
+        /// Nonce of the missing transaction, and the next nonce allocated.
+        nonce: u64,
+        /// Number of dispatched transactions forgotten.
+        dropped: usize,
+    },
+}
+
+/// Allocates the nonces of concurrent transactions without waiting for their inclusion, and
+/// detects the gaps left by dropped transactions: the on-chain nonce staying below the highest
+/// dispatched nonce.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    config: NonceManagerConfig,
+    /// Next nonce to allocate, `None` until the first allocation.
+    next: Option<u64>,
+    /// Last on-chain nonce seen by [`Self::check`].
+    on_chain: u64,
+    /// Consecutive checks without progress of the on-chain nonce.
+    stalled: u32,
+    /// Signed transactions which aren't included yet, by nonce.
+    dispatched: BTreeMap<u64, Vec<u8>>,
+    /// Allocated nonces whose transaction was never dispatched, allocated again first.
+    released: BTreeSet<u64>,
+}
+
+impl NonceManager {
+    /// Creates a nonce manager with the given configuration.
+    #[must_use]
+    pub const fn new(config: NonceManagerConfig) -> Self {
+        Self {
+            config,
+            next: None,
+            on_chain: 0,
+            stalled: 0,
+            dispatched: BTreeMap::new(),
+            released: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the configuration of the manager.
+    #[must_use]
+    pub const fn config(&self) -> &NonceManagerConfig {
+        &self.config
+    }
+
+    /// Allocates the next nonce, never below the `on_chain` nonce. The released nonces are
+    /// allocated first, so they don't leave a gap.
+    pub fn next_nonce(&mut self, on_chain: u64) -> u64 {
+        self.released = self.released.split_off(&on_chain);
+        if let Some(nonce) = self.released.pop_first() {
+            return nonce;
+        }
+        let nonce = self.next.map_or(on_chain, |next| next.max(on_chain));
+        self.next = Some(nonce + 1);
+        nonce
+    }
+
+    /// Releases a `nonce` allocated by [`Self::next_nonce`] whose transaction wasn't signed or
+    /// was rejected by the node, so it is allocated again instead of leaving a gap.
+    pub fn release(&mut self, nonce: u64) {
+        if self.next.map_or(true, |next| nonce >= next) {
+            return;
+        }
+        self.dispatched.remove(&nonce);
+        self.released.insert(nonce);
+        // Give back the released nonces at the end of the allocated range
+        while let Some(next) =
+            self.next.filter(|&next| next > 0 && self.released.remove(&(next - 1)))
+        {
+            self.next = Some(next - 1);
+        }
+    }
+
+    /// Records the signed `transaction` dispatched with `nonce`, so it can be broadcasted again.
+    pub fn dispatched(&mut self, nonce: u64, transaction: Vec<u8>) {
+        self.dispatched.insert(nonce, transaction);
+    }
+
+    /// Checks for a nonce gap given the current `on_chain` nonce, returns the action taken when
+    /// the gap lasted more than [`NonceManagerConfig::gap_tolerance`] checks.
+    pub fn check(&mut self, on_chain: u64) -> Option<NonceGap> {
+        // Forget the included transactions
+        self.dispatched = self.dispatched.split_off(&on_chain);
+        if on_chain > self.on_chain || !self.next.is_some_and(|next| next > on_chain) {
+            self.on_chain = on_chain;
+            self.stalled = 0;
+            return None;
+        }
+        self.stalled += 1;
+        if self.stalled <= self.config.gap_tolerance {
+            return None;
+        }
+        self.stalled = 0;
+        if self.config.strategy == NonceGapStrategy::Rebroadcast {
+            if let Some(transaction) = self.dispatched.get(&on_chain) {
+                return Some(NonceGap::Rebroadcast {
+                    nonce: on_chain,
+                    transaction: transaction.clone(),
+                });
+            }
+        }
+        let dropped = self.dispatched.len();
+        self.dispatched.clear();
+        self.released.clear();
+        self.next = Some(on_chain);
+        Some(NonceGap::Reset { nonce: on_chain, dropped })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dispatches three transactions starting at nonce 5, then drops the middle one.
+    fn dispatch_with_gap(strategy: NonceGapStrategy) -> NonceManager {
+        let mut manager = NonceManager::new(NonceManagerConfig { gap_tolerance: 2, strategy });
+        for expected in 5..8 {
+            let nonce = manager.next_nonce(5);
+            assert_eq!(nonce, expected);
+            manager.dispatched(nonce, vec![u8::try_from(nonce).unwrap()]);
+        }
+        // The first transaction is included, the second is dropped
+        assert_eq!(manager.check(6), None);
+        manager
+    }
+
+    #[test]
+    fn allocates_from_the_on_chain_nonce() {
+        let mut manager = NonceManager::default();
+        assert_eq!(manager.next_nonce(3), 3);
+        assert_eq!(manager.next_nonce(3), 4);
+        // Transactions were sent outside of the manager
+        assert_eq!(manager.next_nonce(10), 10);
+    }
+
+    #[test]
+    fn tolerates_in_flight_transactions() {
+        let mut manager = dispatch_with_gap(NonceGapStrategy::Rebroadcast);
+        assert_eq!(manager.check(6), None);
+        assert_eq!(manager.check(6), None);
+        // Progress resets the tolerance
+        assert_eq!(manager.check(7), None);
+        assert_eq!(manager.check(7), None);
+        assert_eq!(manager.check(8), None);
+        // Nothing in flight
+        for _ in 0..5 {
+            assert_eq!(manager.check(8), None);
+        }
+    }
+
+    #[test]
+    fn rebroadcasts_the_missing_transaction() {
+        let mut manager = dispatch_with_gap(NonceGapStrategy::Rebroadcast);
+        assert_eq!(manager.check(6), None);
+        assert_eq!(manager.check(6), None);
+        assert_eq!(
+            manager.check(6),
+            Some(NonceGap::Rebroadcast { nonce: 6, transaction: vec![6] })
+        );
+        // Once recovered, the next allocation continues after the last dispatched nonce
+        assert_eq!(manager.check(8), None);
+        assert_eq!(manager.next_nonce(8), 8);
+    }
+
+    #[test]
+    fn resets_the_counter() {
+        let mut manager = dispatch_with_gap(NonceGapStrategy::Reset);
+        assert_eq!(manager.check(6), None);
+        assert_eq!(manager.check(6), None);
+        assert_eq!(manager.check(6), Some(NonceGap::Reset { nonce: 6, dropped: 2 }));
+        assert_eq!(manager.next_nonce(6), 6);
+    }
+
+    #[test]
+    fn reallocates_the_released_nonces() {
+        let mut manager = NonceManager::default();
+        for expected in 0..3 {
+            assert_eq!(manager.next_nonce(0), expected);
+        }
+        // A nonce in the middle of the allocated range is allocated again first
+        manager.release(1);
+        assert_eq!(manager.next_nonce(0), 1);
+        assert_eq!(manager.next_nonce(0), 3);
+
+        // The nonces at the end of the range are given back to the counter
+        manager.release(2);
+        manager.release(3);
+        assert_eq!(manager.next_nonce(0), 2);
+        assert_eq!(manager.next_nonce(0), 3);
+
+        // Released nonces below the on-chain nonce were used by another transaction
+        manager.release(0);
+        assert_eq!(manager.next_nonce(1), 4);
+
+        // Nonces which weren't allocated are ignored
+        manager.release(10);
+        assert_eq!(manager.next_nonce(0), 5);
+
+        // The whole range can be given back
+        let mut manager = NonceManager::default();
+        assert_eq!(manager.next_nonce(0), 0);
+        manager.release(0);
+        assert_eq!(manager.next_nonce(0), 0);
+    }
+
+    #[test]
+    fn resets_when_the_missing_transaction_is_unknown() {
+        let mut manager =
+            NonceManager::new(NonceManagerConfig { gap_tolerance: 0, ..Default::default() });
+        assert_eq!(manager.next_nonce(0), 0);
+        assert_eq!(manager.next_nonce(0), 1);
+        manager.dispatched(1, vec![1]);
+        assert_eq!(manager.check(0), Some(NonceGap::Reset { nonce: 0, dropped: 1 }));
+    }
+}
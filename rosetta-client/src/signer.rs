@@ -4,7 +4,7 @@ use crate::{
         bip32::{DerivedPublicKey, DerivedSecretKey},
         bip39::Mnemonic,
         bip44::ChildNumber,
-        Algorithm,
+        Algorithm, PublicKey as CryptoPublicKey, SecretKey, Signature,
     },
     types::{AccountIdentifier, CurveType, PublicKey},
 };
@@ -73,10 +73,10 @@ pub trait RosettaPublicKey {
     fn to_rosetta(&self) -> PublicKey;
 }
 
-impl RosettaPublicKey for DerivedPublicKey {
+impl RosettaPublicKey for CryptoPublicKey {
     fn to_rosetta(&self) -> PublicKey {
         PublicKey {
-            curve_type: match self.public_key().algorithm() {
+            curve_type: match self.algorithm() {
                 Algorithm::EcdsaSecp256k1 | Algorithm::EcdsaRecoverableSecp256k1 => {
                     CurveType::Secp256k1
                 },
@@ -84,11 +84,17 @@ impl RosettaPublicKey for DerivedPublicKey {
                 Algorithm::Ed25519 => CurveType::Edwards25519,
                 Algorithm::Sr25519 => CurveType::Schnorrkel,
             },
-            hex_bytes: hex::encode(self.public_key().to_bytes()),
+            hex_bytes: hex::encode(self.to_bytes()),
         }
     }
 }
 
+impl RosettaPublicKey for DerivedPublicKey {
+    fn to_rosetta(&self) -> PublicKey {
+        self.public_key().to_rosetta()
+    }
+}
+
 /// Conversion trait for account identifiers.
 pub trait RosettaAccount {
     /// Returns a rosetta account identifier.
@@ -100,3 +106,130 @@ impl RosettaAccount for Address {
         AccountIdentifier { address: self.address().into(), sub_account: None, metadata: None }
     }
 }
+
+/// Signs transaction payloads on behalf of a [`Wallet`](crate::Wallet), implement this trait to
+/// sign with keys which never leave an external device, like hardware wallets.
+#[async_trait::async_trait]
+pub trait RemoteSigner: Send + Sync {
+    /// Signs the `payload` using the signing `algorithm`, recoverable ECDSA payloads are
+    /// already hashed and must be signed as is.
+    ///
+    /// # Errors
+    /// Returns `Err` if the signer doesn't support `algorithm` or the signing failed.
+    async fn sign(&self, payload: &[u8], algorithm: Algorithm) -> Result<Signature>;
+
+    /// Returns the public key used to verify the signatures.
+    fn public_key(&self) -> CryptoPublicKey;
+}
+
+/// Signs using a local secret key derived from the wallet mnemonic.
+#[async_trait::async_trait]
+impl RemoteSigner for DerivedSecretKey {
+    async fn sign(&self, payload: &[u8], algorithm: Algorithm) -> Result<Signature> {
+        RemoteSigner::sign(self.secret_key(), payload, algorithm).await
+    }
+
+    fn public_key(&self) -> CryptoPublicKey {
+        self.secret_key().public_key()
+    }
+}
+
+/// Signs using a local secret key, ex: an account funded by a raw private key.
+#[async_trait::async_trait]
+impl RemoteSigner for SecretKey {
+    async fn sign(&self, payload: &[u8], algorithm: Algorithm) -> Result<Signature> {
+        if self.algorithm() != algorithm {
+            anyhow::bail!("unsupported algorithm {algorithm:?}, expected {:?}", self.algorithm());
+        }
+        if algorithm.is_recoverable() {
+            self.sign_prehashed(payload)
+        } else {
+            Ok(Self::sign(self, payload, "substrate"))
+        }
+    }
+
+    fn public_key(&self) -> CryptoPublicKey {
+        Self::public_key(self)
+    }
+}
+
+const BYTES_PREFIX: &[u8] = b"<Bytes>";
+const BYTES_POSTFIX: &[u8] = b"</Bytes>";
+
+/// Wraps `data` in `<Bytes>...</Bytes>`, like polkadot-js `signRaw` does before signing, so the
+/// signed message can never be a valid extrinsic. Already wrapped data is returned as is.
+#[must_use]
+pub fn wrap_bytes(data: &[u8]) -> Vec<u8> {
+    if data.starts_with(BYTES_PREFIX) && data.ends_with(BYTES_POSTFIX) {
+        return data.to_vec();
+    }
+    [BYTES_PREFIX, data, BYTES_POSTFIX].concat()
+}
+
+/// Verifies a `signature` of `data` made with polkadot-js `signRaw` or
+/// [`Wallet::sign_raw`](crate::Wallet::sign_raw).
+///
+/// # Errors
+/// Returns `Err` if the signature is malformed or invalid.
+pub fn verify_raw(public_key: &CryptoPublicKey, data: &[u8], signature: &[u8]) -> Result<()> {
+    let signature = Signature::from_bytes(public_key.algorithm(), signature)?;
+    public_key.verify_with_context(&wrap_bytes(data), &signature, "substrate")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_bytes_matches_polkadot_js() {
+        assert_eq!(wrap_bytes(b"hello"), b"<Bytes>hello</Bytes>");
+        assert_eq!(wrap_bytes(b"<Bytes>hello</Bytes>"), b"<Bytes>hello</Bytes>");
+        assert_eq!(wrap_bytes(b""), b"<Bytes></Bytes>");
+    }
+
+    #[test]
+    fn sign_raw_round_trip() {
+        let signer = Signer::generate().unwrap();
+        for algorithm in [Algorithm::Sr25519, Algorithm::Ed25519] {
+            let secret_key = signer.master_key(algorithm);
+            let public_key = RemoteSigner::public_key(secret_key);
+            let payload = wrap_bytes(b"hello");
+            let signature =
+                futures::executor::block_on(secret_key.sign(&payload, algorithm)).unwrap();
+            let signature = signature.to_bytes();
+            verify_raw(&public_key, b"hello", &signature).unwrap();
+            verify_raw(&public_key, b"<Bytes>hello</Bytes>", &signature).unwrap();
+            assert!(verify_raw(&public_key, b"hello!", &signature).is_err());
+        }
+    }
+
+    #[test]
+    fn sign_raw_matches_polkadot_js() {
+        // ed25519 signatures are deterministic, the RFC 8032 test 1 secret key signs the
+        // `<Bytes>` wrapped message like polkadot-js `signRaw` does
+        let secret_key = SecretKey::from_bytes(
+            Algorithm::Ed25519,
+            &hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .unwrap(),
+        )
+        .unwrap();
+        let payload = wrap_bytes(b"hello polkadot");
+        let signature = futures::executor::block_on(RemoteSigner::sign(
+            &secret_key,
+            &payload,
+            Algorithm::Ed25519,
+        ))
+        .unwrap();
+        assert_eq!(
+            hex::encode(signature.to_bytes()),
+            "96d06a26e0818dcbbdb80f415938d4001268da74faf12f96b33436b5db91f3fe\
+             2a094836812e8fce95ff794e9e0e3acd5e8a3581536e259b95d427f0eb2ce701"
+        );
+        verify_raw(
+            &RemoteSigner::public_key(&secret_key),
+            b"hello polkadot",
+            &signature.to_bytes(),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use async_lock::{Semaphore, SemaphoreGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default number of submissions waiting for a slot before new ones are rejected.
+pub const DEFAULT_MAX_QUEUED_SUBMISSIONS: usize = 64;
+
+/// Limits of the concurrent submissions of a wallet, see [`crate::Wallet::with_submission_limit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmissionLimit {
+    /// Number of submissions in flight at the same time, a submission is in flight until the
+    /// transaction is included or rejected. Zero is treated as one.
+    pub max_in_flight: usize,
+    /// Number of submissions waiting for a slot, the submissions above are rejected.
+    pub max_queued: usize,
+}
+
+impl SubmissionLimit {
+    /// Allows `max_in_flight` concurrent submissions, with the default queue bound.
+    #[must_use]
+    pub const fn new(max_in_flight: usize) -> Self {
+        Self { max_in_flight, max_queued: DEFAULT_MAX_QUEUED_SUBMISSIONS }
+    }
+}
+
+/// Semaphore which queues the submissions above [`SubmissionLimit::max_in_flight`].
+#[derive(Debug)]
+pub(crate) struct SubmissionLimiter {
+    limit: SubmissionLimit,
+    semaphore: Semaphore,
+    queued: AtomicUsize,
+}
+
+impl SubmissionLimiter {
+    pub(crate) fn new(limit: SubmissionLimit) -> Self {
+        let semaphore = Semaphore::new(limit.max_in_flight.max(1));
+        Self { limit, semaphore, queued: AtomicUsize::new(0) }
+    }
+
+    /// Waits for a free slot, the slot is released when the guard is dropped.
+    pub(crate) async fn acquire(&self) -> anyhow::Result<SemaphoreGuard<'_>> {
+        if let Some(guard) = self.semaphore.try_acquire() {
+            return Ok(guard);
+        }
+        let _queued = QueuedGuard::enter(&self.queued, self.limit.max_queued)?;
+        Ok(self.semaphore.acquire().await)
+    }
+}
+
+/// Counts a submission waiting for a slot, also when the waiting future is dropped.
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn enter(queued: &'a AtomicUsize, max_queued: usize) -> anyhow::Result<Self> {
+        if queued.fetch_add(1, Ordering::SeqCst) >= max_queued {
+            queued.fetch_sub(1, Ordering::SeqCst);
+            anyhow::bail!("submission queue is full, {max_queued} submissions already waiting");
+        }
+        Ok(Self(queued))
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{future::poll_fn, FutureExt};
+    use std::task::Poll;
+
+    #[test]
+    fn queues_above_the_limit() {
+        futures::executor::block_on(async {
+            let limiter =
+                SubmissionLimiter::new(SubmissionLimit { max_in_flight: 1, max_queued: 1 });
+            let first = limiter.acquire().await.unwrap();
+
+            let mut second = Box::pin(limiter.acquire());
+            // The second submission waits for the first one
+            poll_fn(|cx| {
+                assert!(second.poll_unpin(cx).is_pending());
+                Poll::Ready(())
+            })
+            .await;
+            let error = limiter.acquire().await.unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "submission queue is full, 1 submissions already waiting"
+            );
+
+            drop(first);
+            let second = second.await.unwrap();
+            drop(second);
+            // The queue is empty again
+            assert!(limiter.acquire().await.is_ok());
+        });
+    }
+
+    #[test]
+    fn dropped_waiters_leave_the_queue() {
+        futures::executor::block_on(async {
+            let limiter =
+                SubmissionLimiter::new(SubmissionLimit { max_in_flight: 1, max_queued: 1 });
+            let _first = limiter.acquire().await.unwrap();
+            for _ in 0..3 {
+                let mut waiting = Box::pin(limiter.acquire());
+                poll_fn(|cx| {
+                    assert!(waiting.poll_unpin(cx).is_pending());
+                    Poll::Ready(())
+                })
+                .await;
+            }
+            assert_eq!(limiter.queued.load(Ordering::SeqCst), 0);
+        });
+    }
+}
@@ -1,12 +1,13 @@
 use crate::{
     client::{GenericMetadata, GenericMetadataParams},
-    crypto::{address::Address, SecretKey},
+    crypto::{address::Address, PublicKey, Signature},
     BlockchainConfig,
 };
 use anyhow::Result;
 use rosetta_core::TransactionBuilder;
 use rosetta_server_astar::AstarMetadataParams;
 
+#[derive(Clone)]
 pub enum GenericTransactionBuilder {
     Astar(rosetta_tx_ethereum::EthereumTransactionBuilder),
     Ethereum(rosetta_tx_ethereum::EthereumTransactionBuilder),
@@ -56,30 +57,57 @@ impl GenericTransactionBuilder {
         })
     }
 
-    pub fn create_and_sign(
+    pub fn signing_payload(
         &self,
         config: &BlockchainConfig,
         params: &GenericMetadataParams,
         metadata: &GenericMetadata,
-        secret_key: &SecretKey,
     ) -> Result<Vec<u8>> {
-        Ok(match (self, params, metadata) {
+        match (self, params, metadata) {
             (
                 Self::Astar(tx),
                 GenericMetadataParams::Astar(params),
                 GenericMetadata::Astar(metadata),
-            ) => tx.create_and_sign(config, &params.0, &metadata.0, secret_key),
+            ) => tx.signing_payload(config, &params.0, &metadata.0),
             (
                 Self::Ethereum(tx),
                 GenericMetadataParams::Ethereum(params),
                 GenericMetadata::Ethereum(metadata),
-            ) => tx.create_and_sign(config, params, metadata, secret_key),
+            ) => tx.signing_payload(config, params, metadata),
             (
                 Self::Polkadot(tx),
                 GenericMetadataParams::Polkadot(params),
                 GenericMetadata::Polkadot(metadata),
-            ) => tx.create_and_sign(config, params, metadata, secret_key),
+            ) => tx.signing_payload(config, params, metadata),
             _ => anyhow::bail!("invalid params"),
-        })
+        }
+    }
+
+    pub fn encode_signed(
+        &self,
+        config: &BlockchainConfig,
+        params: &GenericMetadataParams,
+        metadata: &GenericMetadata,
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> Result<Vec<u8>> {
+        match (self, params, metadata) {
+            (
+                Self::Astar(tx),
+                GenericMetadataParams::Astar(params),
+                GenericMetadata::Astar(metadata),
+            ) => tx.encode_signed(config, &params.0, &metadata.0, public_key, signature),
+            (
+                Self::Ethereum(tx),
+                GenericMetadataParams::Ethereum(params),
+                GenericMetadata::Ethereum(metadata),
+            ) => tx.encode_signed(config, params, metadata, public_key, signature),
+            (
+                Self::Polkadot(tx),
+                GenericMetadataParams::Polkadot(params),
+                GenericMetadata::Polkadot(metadata),
+            ) => tx.encode_signed(config, params, metadata, public_key, signature),
+            _ => anyhow::bail!("invalid params"),
+        }
     }
 }
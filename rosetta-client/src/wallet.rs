@@ -1,33 +1,217 @@
 use crate::{
+    batch::atomic_batch_init_code,
     client::{GenericClient, GenericMetadata, GenericMetadataParams},
-    crypto::{address::Address, bip32::DerivedSecretKey, bip44::ChildNumber},
+    crypto::{
+        address::Address, bip32::DerivedSecretKey, bip39::Mnemonic, bip44::ChildNumber, Algorithm,
+    },
+    eip712::{eip712_digest, Eip712Domain},
     mnemonic::MnemonicStore,
-    signer::{RosettaAccount, RosettaPublicKey, Signer},
+    nonce::{NonceGap, NonceManager, NonceManagerConfig},
+    signer::{wrap_bytes, RemoteSigner, RosettaAccount, RosettaPublicKey, Signer},
+    submission::{SubmissionLimit, SubmissionLimiter},
     tx_builder::GenericTransactionBuilder,
     types::{AccountIdentifier, BlockIdentifier, PublicKey},
     Blockchain, BlockchainConfig,
 };
 use anyhow::Result;
-use rosetta_core::{types::PartialBlockIdentifier, BlockchainClient, RosettaAlgorithm};
+use async_lock::SemaphoreGuard;
+use futures_util::{future, Stream, StreamExt};
+use rosetta_core::{
+    types::PartialBlockIdentifier, BlockOrIdentifier, BlockchainClient, ClientEvent,
+    RosettaAlgorithm,
+};
 use rosetta_server_ethereum::{
     config::{
-        ext::types::{self as ethereum_types, Address as EthAddress, H256, U256},
-        AtBlock, CallContract, CallResult, EIP1186ProofResponse, GetProof, GetStorageAt,
-        GetTransactionReceipt, Query as EthQuery, QueryResult as EthQueryResult,
-        TransactionReceipt,
+        ext::types::{
+            self as ethereum_types,
+            crypto::{Crypto, DefaultCrypto, RecoveryId, Signature as EthSignature},
+            Address as EthAddress, H256, U256,
+        },
+        query::GetBlock,
+        AtBlock, Bytes, CallContract, CallResult, EIP1186ProofResponse, GetCode, GetProof,
+        GetStorageAt, GetTransactionCount, GetTransactionReceipt, Query as EthQuery,
+        QueryResult as EthQueryResult, TransactionReceipt,
     },
-    SubmitResult,
+    ChainStalled, CodeSizeExceeded, SubmitResult, MAX_CODE_SIZE,
 };
-use std::path::Path;
+use rosetta_server_polkadot::{is_stale_transaction_error, PolkadotMetadataParams};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::Instrument;
+
+/// Default number of balances fetched concurrently by [`Wallet::balances_snapshot`].
+pub const DEFAULT_BALANCES_CONCURRENCY: usize = 8;
+
+/// Maximum time [`Wallet::faucet`] waits for the faucet transaction to be included.
+pub const FAUCET_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval at which [`Wallet::faucet`] polls the receipt of the faucet transaction.
+const FAUCET_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Selector of `isValidSignature(bytes32,bytes)`, also the magic value returned by EIP-1271
+/// contracts when the signature is valid.
+const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// EIP-1967 implementation slot:
+/// `bytes32(uint256(keccak256('eip1967.proxy.implementation')) - 1)`.
+const EIP1967_IMPLEMENTATION_SLOT: [u8; 32] = [
+    0x36, 0x08, 0x94, 0xa1, 0x3b, 0xa1, 0xa3, 0x21, 0x06, 0x67, 0xc8, 0x28, 0x49, 0x2d, 0xb9, 0x8d,
+    0xca, 0x3e, 0x20, 0x76, 0xcc, 0x37, 0x35, 0xa9, 0x20, 0xa3, 0xca, 0x50, 0x5d, 0x38, 0x2b, 0xbc,
+];
+
+/// EIP-1967 admin slot: `bytes32(uint256(keccak256('eip1967.proxy.admin')) - 1)`.
+const EIP1967_ADMIN_SLOT: [u8; 32] = [
+    0xb5, 0x31, 0x27, 0x68, 0x4a, 0x56, 0x8b, 0x31, 0x73, 0xae, 0x13, 0xb9, 0xf8, 0xa6, 0x01, 0x6e,
+    0x24, 0x3e, 0x63, 0xb6, 0xe8, 0xee, 0x11, 0x78, 0xd6, 0xa7, 0x17, 0x85, 0x0b, 0x5d, 0x61, 0x03,
+];
+
+/// EIP-1967 beacon slot: `bytes32(uint256(keccak256('eip1967.proxy.beacon')) - 1)`.
+const EIP1967_BEACON_SLOT: [u8; 32] = [
+    0xa3, 0xf0, 0xad, 0x74, 0xe5, 0x42, 0x3a, 0xeb, 0xfd, 0x80, 0xd3, 0xef, 0x43, 0x46, 0x57, 0x83,
+    0x35, 0xa9, 0xa7, 0x2a, 0xea, 0xee, 0x59, 0xff, 0x6c, 0xb3, 0x58, 0x2b, 0x35, 0x13, 0x3d, 0x50,
+];
+
+/// Selector of `permit(address,address,uint256,uint256,uint8,bytes32,bytes32)`.
+const PERMIT_SELECTOR: [u8; 4] = [0xd5, 0x05, 0xac, 0xcf];
+
+/// Selector of the ERC-20 `allowance(address,address)`.
+const ERC20_ALLOWANCE_SELECTOR: [u8; 4] = [0xdd, 0x62, 0xed, 0x3e];
+
+/// Selector of the ERC-20 `approve(address,uint256)`.
+const ERC20_APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+
+/// Result of a labeled submission, see [`Wallet::transfer_labeled`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabeledSubmitResult {
+    /// Opaque label given by the caller, it isn't part of the transaction.
+    pub label: String,
+    /// Result of the submission.
+    pub result: SubmitResult,
+}
+
+/// Lifecycle stage of a transaction, see [`Wallet::transfer_with_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxProgress {
+    /// The signed transaction was accepted by the node.
+    Broadcast {
+        /// Hash of the transaction.
+        tx_hash: H256,
+    },
+    /// The transaction was included in a block, emitted again if a chain reorganization moves
+    /// the transaction to another block before it is finalized.
+    Included {
+        /// Hash of the transaction.
+        tx_hash: H256,
+        /// Block including the transaction.
+        block: BlockIdentifier,
+    },
+    /// The block including the transaction was finalized.
+    Finalized {
+        /// Hash of the transaction.
+        tx_hash: H256,
+        /// Finalized block including the transaction.
+        block: BlockIdentifier,
+    },
+}
+
+/// Options of [`Wallet::faucet_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaucetOptions {
+    /// Gas price of the faucet transaction, on chains where it can be set.
+    pub high_gas_price: Option<u128>,
+    /// Waits up to [`FAUCET_WAIT_TIMEOUT`] for the faucet transaction to be included.
+    pub wait: bool,
+}
+
+impl Default for FaucetOptions {
+    fn default() -> Self {
+        Self { high_gas_price: None, wait: true }
+    }
+}
+
+/// An EIP-2612 `permit` message, signed off-chain by the token `owner`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permit {
+    /// Account which owns the tokens and signed the permit.
+    pub owner: EthAddress,
+    /// Account allowed to spend the tokens.
+    pub spender: EthAddress,
+    /// Allowance granted to the `spender`.
+    pub value: U256,
+    /// Timestamp after which the permit is no longer valid.
+    pub deadline: U256,
+}
+
+impl Permit {
+    /// Encodes the `permit(owner,spender,value,deadline,v,r,s)` call for the given
+    /// 65 bytes `r || s || v` signature.
+    fn encode_call(&self, signature: &[u8]) -> Result<Vec<u8>> {
+        let Ok(signature) = <[u8; 65]>::try_from(signature) else {
+            anyhow::bail!("invalid permit signature, expected 65 bytes, got {}", signature.len());
+        };
+        // Accept both the raw recovery id and the `27 + recovery_id` encoding
+        let v = match signature[64] {
+            v @ (0 | 1) => v + 27,
+            v @ (27 | 28) => v,
+            v => anyhow::bail!("invalid permit signature, unexpected v = {v}"),
+        };
+        let mut data = Vec::with_capacity(4 + 7 * 32);
+        data.extend_from_slice(&PERMIT_SELECTOR);
+        for address in [self.owner, self.spender] {
+            data.extend_from_slice(&[0u8; 12]);
+            data.extend_from_slice(address.as_bytes());
+        }
+        for value in [self.value, self.deadline] {
+            let mut word = [0u8; 32];
+            value.to_big_endian(&mut word);
+            data.extend_from_slice(&word);
+        }
+        data.extend_from_slice(&[0u8; 31]);
+        data.push(v);
+        data.extend_from_slice(&signature[0..64]);
+        Ok(data)
+    }
+}
 
 /// The wallet provides the main entry point to this crate.
-pub struct Wallet {
+///
+/// Transactions are signed by `S`, which defaults to a local key derived from a mnemonic.
+///
+/// Cloning a wallet is cheap, the clones share the connection, the nonce manager and the
+/// submission limit. By default every transaction reads its nonce from the node, once the nonce
+/// manager is enabled with [`Self::with_nonce_manager`], tasks submitting from clones of the same
+/// wallet never reuse a nonce. Nonces aren't coordinated across processes, processes submitting
+/// from the same account must use explicit nonces.
+pub struct Wallet<S = DerivedSecretKey> {
     /// `GenericClient` instance
     pub client: GenericClient,
     account: AccountIdentifier,
-    secret_key: DerivedSecretKey,
+    signer: S,
     public_key: PublicKey,
     tx: GenericTransactionBuilder,
+    nonce_manager: Option<Arc<Mutex<NonceManager>>>,
+    submission_limiter: Option<Arc<SubmissionLimiter>>,
+    max_code_size: usize,
+    unchecked_code_size: bool,
+}
+
+impl<S: Clone> Clone for Wallet<S> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            account: self.account.clone(),
+            signer: self.signer.clone(),
+            public_key: self.public_key.clone(),
+            tx: self.tx.clone(),
+            nonce_manager: self.nonce_manager.clone(),
+            submission_limiter: self.submission_limiter.clone(),
+            max_code_size: self.max_code_size,
+            unchecked_code_size: self.unchecked_code_size,
+        }
+    }
 }
 
 impl Wallet {
@@ -64,8 +248,13 @@ impl Wallet {
             Some(_) => store.get_or_generate_mnemonic()?,
             None => store.generate()?,
         };
-        let signer = Signer::new(&mnemonic, "")?;
-        let tx = GenericTransactionBuilder::new(client.config())?;
+        Self::from_mnemonic(client, &mnemonic)
+    }
+
+    /// Creates a new wallet from a client, deriving the key from `mnemonic`.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_mnemonic(client: GenericClient, mnemonic: &Mnemonic) -> Result<Self> {
+        let signer = Signer::new(mnemonic, "")?;
         let secret_key = if client.config().bip44 {
             signer
                 .bip44_account(client.config().algorithm, client.config().coin, 0)?
@@ -73,7 +262,16 @@ impl Wallet {
         } else {
             signer.master_key(client.config().algorithm).clone()
         };
-        let public_key = secret_key.public_key();
+        Self::from_signer(client, secret_key)
+    }
+}
+
+impl<S: RemoteSigner> Wallet<S> {
+    /// Creates a new wallet from a client and a signer, ex: a hardware wallet.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_signer(client: GenericClient, signer: S) -> Result<Self> {
+        let tx = GenericTransactionBuilder::new(client.config())?;
+        let public_key = signer.public_key();
         let account = public_key.to_address(client.config().address_format).to_rosetta();
         let public_key = public_key.to_rosetta();
 
@@ -81,7 +279,56 @@ impl Wallet {
             anyhow::bail!("The signer and client curve type aren't compatible.")
         }
 
-        Ok(Self { client, account, secret_key, public_key, tx })
+        Ok(Self {
+            client,
+            account,
+            signer,
+            public_key,
+            tx,
+            nonce_manager: None,
+            submission_limiter: None,
+            max_code_size: MAX_CODE_SIZE,
+            unchecked_code_size: false,
+        })
+    }
+
+    /// Enables a nonce manager using `config`, shared with the wallets cloned afterwards, the
+    /// wallets cloned before don't share the new manager.
+    ///
+    /// The transactions submitted without an explicit nonce, ex: by [`Self::transfer`], use the
+    /// next nonce of the manager, see [`Self::sign_with_auto_nonce`]. The manager allocates
+    /// nonces ahead of the chain, a dropped transaction leaves a gap until it is recovered by
+    /// [`Self::check_nonce_gap`].
+    #[must_use]
+    pub fn with_nonce_manager(mut self, config: NonceManagerConfig) -> Self {
+        self.nonce_manager = Some(Arc::new(Mutex::new(NonceManager::new(config))));
+        self
+    }
+
+    /// Limits the concurrent submissions of the account, the submissions above
+    /// [`SubmissionLimit::max_in_flight`] wait until a previous one is included, and are
+    /// rejected once [`SubmissionLimit::max_queued`] are already waiting.
+    #[must_use]
+    pub fn with_submission_limit(mut self, limit: SubmissionLimit) -> Self {
+        self.submission_limiter = Some(Arc::new(SubmissionLimiter::new(limit)));
+        self
+    }
+
+    /// Overrides the maximum contract code size checked by [`Self::eth_deploy_contract`], for
+    /// chains with a higher limit than EIP-170.
+    #[must_use]
+    pub const fn with_max_code_size(mut self, max_code_size: usize) -> Self {
+        self.max_code_size = max_code_size;
+        self
+    }
+
+    /// Deploys the contracts whose code size can't be checked by [`Self::eth_deploy_contract`],
+    /// ex: when the node doesn't support the state overrides of `eth_call`, a warning is logged
+    /// instead of failing the deployment.
+    #[must_use]
+    pub const fn with_unchecked_code_size(mut self) -> Self {
+        self.unchecked_code_size = true;
+        self
     }
 
     /// Returns the blockchain config.
@@ -113,21 +360,215 @@ impl Wallet {
     /// Returns the balance of the wallet.
     #[allow(clippy::missing_errors_doc)]
     pub async fn balance(&self) -> Result<u128> {
-        let block = self.client.current_block().await?;
+        let block = PartialBlockIdentifier::from(self.client.current_block().await?);
+        self.balance_of(&self.account, &block).await
+    }
+
+    /// Returns the largest amount the wallet can transfer after reserving the estimated fee, and
+    /// the existential deposit on substrate chains, as transfers keep the account alive.
+    ///
+    /// The fee is estimated for the transfer of the whole available balance, then estimated
+    /// again for the reduced amount, ex: substrate encodes smaller amounts in fewer bytes.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn max_transferable(&self) -> Result<u128> {
+        let reserved = match &self.client {
+            GenericClient::Polkadot(client) => client.existential_deposit()?,
+            GenericClient::Ethereum(_) | GenericClient::Astar(_) => 0,
+        };
+        let available = self.balance().await?.saturating_sub(reserved);
+        let mut amount = available.saturating_sub(self.transfer_fee(available).await?);
+        // The fee of a smaller amount is never higher, so `amount` can be transferred, but a
+        // lower fee may leave room for a larger amount
+        let candidate = available.saturating_sub(self.transfer_fee(amount).await?);
+        if candidate > amount &&
+            candidate.saturating_add(self.transfer_fee(candidate).await?) <= available
+        {
+            amount = candidate;
+        }
+        Ok(amount)
+    }
+
+    /// Estimates the fee of transferring `amount` to the wallet itself. EVM transfers reserve
+    /// `gas_limit * max_fee_per_gas` whatever the amount, substrate fees are estimated from the
+    /// signed extrinsic.
+    async fn transfer_fee(&self, amount: u128) -> Result<u128> {
         let address =
             Address::new(self.client.config().address_format, self.account.address.clone());
-        let balance = match &self.client {
-            GenericClient::Astar(client) => {
-                client.balance(&address, &PartialBlockIdentifier::from(block)).await?
+        if let GenericClient::Polkadot(client) = &self.client {
+            let params = self.tx.transfer(&address, amount)?;
+            let transaction = self.sign_transaction(&params).await?;
+            return client.estimate_extrinsic_fee(&transaction).await;
+        }
+        // The gas estimation fails when the balance can't pay the value and the fee
+        let params = self.tx.transfer(&address, 0)?;
+        let metadata = match self.metadata(&params).await? {
+            GenericMetadata::Ethereum(metadata) => metadata,
+            GenericMetadata::Astar(metadata) => metadata.0,
+            GenericMetadata::Polkadot(_) => anyhow::bail!("[this is a bug] unexpected metadata"),
+        };
+        let fee = U256(metadata.max_fee_per_gas).saturating_mul(U256::from(metadata.gas_limit));
+        u128::try_from(fee).map_err(|_| anyhow::anyhow!("fee overflow: {fee}"))
+    }
+
+    /// Returns a stream of the wallet balance, starting with the current balance and re-read at
+    /// every new head. A chain reorganization is reported as a new head, so the balance is
+    /// re-read at the new canonical head and corrected. Consecutive equal balances are only
+    /// yielded once, and the stream ends after the new heads subscription is closed.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn balance_stream(&self) -> Result<impl Stream<Item = Result<u128>> + '_> {
+        let Some(events) = self.listen().await? else {
+            anyhow::bail!("blockchain doesn't support new heads subscription");
+        };
+        let balance = self.balance().await?;
+        let initial = futures_util::stream::once(async move { Ok(balance) });
+        let updates = futures_util::stream::unfold(
+            (Some(events), balance),
+            move |(events, mut last)| async move {
+                let mut events = events?;
+                loop {
+                    let block = match events.next().await {
+                        Some(ClientEvent::NewHead(BlockOrIdentifier::Identifier(block))) => block,
+                        Some(ClientEvent::NewHead(BlockOrIdentifier::Block(block))) => {
+                            block.block_identifier
+                        },
+                        Some(ClientEvent::Close(reason)) => {
+                            let error = anyhow::anyhow!("new heads stream closed: {reason}");
+                            return Some((Err(error), (None, last)));
+                        },
+                        Some(_) => continue,
+                        None => return None,
+                    };
+                    let block = PartialBlockIdentifier::from(block);
+                    match self.balance_of(&self.account, &block).await {
+                        Ok(balance) if balance == last => {},
+                        Ok(balance) => {
+                            last = balance;
+                            return Some((Ok(balance), (Some(events), last)));
+                        },
+                        Err(error) => return Some((Err(error), (Some(events), last))),
+                    }
+                }
             },
-            GenericClient::Ethereum(client) => {
-                client.balance(&address, &PartialBlockIdentifier::from(block)).await?
+        );
+        Ok(initial.chain(updates))
+    }
+
+    /// Returns a stream of the wallet nonce, starting with the current nonce and re-read at every
+    /// new head, so transactions sent by other processes with the same account are noticed.
+    /// Unchanged nonces are only yielded once, a nonce which can't be read is logged and read
+    /// again at the next head, and the stream ends after the new heads subscription is closed.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn nonce_stream(&self) -> Result<impl Stream<Item = u64> + '_> {
+        let Some(events) = self.listen().await? else {
+            anyhow::bail!("blockchain doesn't support new heads subscription");
+        };
+        let nonce = self.nonce_at(AtBlock::Latest).await?;
+        let initial = futures_util::stream::once(async move { nonce });
+        let updates = futures_util::stream::unfold(
+            (events, nonce),
+            move |(mut events, mut last)| async move {
+                loop {
+                    let block = match events.next().await {
+                        Some(ClientEvent::NewHead(BlockOrIdentifier::Identifier(block))) => block,
+                        Some(ClientEvent::NewHead(BlockOrIdentifier::Block(block))) => {
+                            block.block_identifier
+                        },
+                        Some(ClientEvent::Close(reason)) => {
+                            tracing::warn!("nonce stream closed: {reason}");
+                            return None;
+                        },
+                        Some(_) => continue,
+                        None => return None,
+                    };
+                    let at = AtBlock::At(ethereum_types::BlockIdentifier::Hash(H256(block.hash)));
+                    match self.nonce_at(at).await {
+                        Ok(nonce) if nonce == last => {},
+                        Ok(nonce) => {
+                            last = nonce;
+                            return Some((nonce, (events, last)));
+                        },
+                        Err(error) => {
+                            tracing::warn!("failed to read the nonce at {block}: {error:?}")
+                        },
+                    }
+                }
+            },
+        );
+        Ok(initial.chain(updates))
+    }
+
+    /// Returns the balance of each address at the same block, fetching at most
+    /// [`DEFAULT_BALANCES_CONCURRENCY`] balances concurrently. Errors are returned per address.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn balances_snapshot(
+        &self,
+        addresses: &[AccountIdentifier],
+    ) -> Result<Vec<(AccountIdentifier, Result<u128>)>> {
+        self.balances_snapshot_with_concurrency(addresses, DEFAULT_BALANCES_CONCURRENCY)
+            .await
+    }
+
+    /// Same as [`Wallet::balances_snapshot`], fetching at most `concurrency` balances
+    /// concurrently.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn balances_snapshot_with_concurrency(
+        &self,
+        addresses: &[AccountIdentifier],
+        concurrency: usize,
+    ) -> Result<Vec<(AccountIdentifier, Result<u128>)>> {
+        let block = PartialBlockIdentifier::from(self.client.current_block().await?);
+        let block = &block;
+        let balances = futures_util::stream::iter(addresses.iter().cloned())
+            .map(|account| async move {
+                let balance = self.balance_of(&account, block).await;
+                (account, balance)
+            })
+            .buffered(concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        Ok(balances)
+    }
+
+    async fn balance_of(
+        &self,
+        account: &AccountIdentifier,
+        block: &PartialBlockIdentifier,
+    ) -> Result<u128> {
+        let address = Address::new(self.client.config().address_format, account.address.clone());
+        match &self.client {
+            GenericClient::Astar(client) => client.balance(&address, block).await,
+            GenericClient::Ethereum(client) => client.balance(&address, block).await,
+            GenericClient::Polkadot(client) => client.balance(&address, block).await,
+        }
+    }
+
+    /// Returns the nonce of the wallet at `block`, useful to reconstruct historical account state.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn nonce_at(&self, block: AtBlock) -> Result<u64> {
+        match &self.client {
+            GenericClient::Ethereum(_) | GenericClient::Astar(_) => {
+                let address: EthAddress = self.account.address.parse()?;
+                self.query(GetTransactionCount { address, block }).await
             },
             GenericClient::Polkadot(client) => {
-                client.balance(&address, &PartialBlockIdentifier::from(block)).await?
+                let block = match block {
+                    AtBlock::Latest | AtBlock::Pending => PartialBlockIdentifier::latest(),
+                    AtBlock::Finalized | AtBlock::Safe => {
+                        PartialBlockIdentifier::from(client.finalized_block().await?)
+                    },
+                    AtBlock::Earliest => PartialBlockIdentifier::earliest(),
+                    AtBlock::At(ethereum_types::BlockIdentifier::Hash(hash)) => {
+                        PartialBlockIdentifier::by_hash(hash.0)
+                    },
+                    AtBlock::At(ethereum_types::BlockIdentifier::Number(number)) => {
+                        PartialBlockIdentifier::by_number(number)
+                    },
+                };
+                let address =
+                    Address::new(self.client.config().address_format, self.account.address.clone());
+                client.nonce(&address, &block).await
             },
-        };
-        Ok(balance)
+        }
     }
 
     /// Return a stream of events, return None if the blockchain doesn't support events.
@@ -138,6 +579,38 @@ impl Wallet {
         self.client.listen().await
     }
 
+    /// Returns a stream of the finalized blocks, in increasing order. New heads and blockchain
+    /// specific events are skipped, and the stream ends after the subscription is closed.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn listen_finalized(&self) -> Result<impl Stream<Item = BlockIdentifier> + '_> {
+        let Some(events) = self.listen().await? else {
+            anyhow::bail!("blockchain doesn't support finalized blocks subscription");
+        };
+        let finalized =
+            futures_util::stream::unfold((events, None::<u64>), |(mut events, last)| async move {
+                loop {
+                    let block = match events.next().await? {
+                        ClientEvent::NewFinalized(BlockOrIdentifier::Identifier(block)) => block,
+                        ClientEvent::NewFinalized(BlockOrIdentifier::Block(block)) => {
+                            block.block_identifier
+                        },
+                        ClientEvent::Close(reason) => {
+                            tracing::warn!("finalized blocks stream closed: {reason}");
+                            return None;
+                        },
+                        _ => continue,
+                    };
+                    // A finalized block is only yielded once
+                    if last.is_some_and(|last| block.index <= last) {
+                        continue;
+                    }
+                    let index = block.index;
+                    return Some((block, (events, Some(index))));
+                }
+            });
+        Ok(finalized)
+    }
+
     /// Returns the on chain metadata.
     /// Parameters:
     /// - `metadata_params`: the metadata parameters which we got from transaction builder.
@@ -159,20 +632,213 @@ impl Wallet {
     /// - transaction: the transaction bytes to submit
     #[allow(clippy::missing_errors_doc)]
     pub async fn submit(&self, transaction: &[u8]) -> Result<SubmitResult> {
+        let _slot = self.acquire_submission_slot().await?;
         self.client.submit(transaction).await
     }
 
     /// Creates, signs and submits a transaction.
+    ///
+    /// On substrate chains, if the extrinsic is rejected because it was built with stale
+    /// metadata (outdated nonce or bad proof), it is rebuilt with a fresh nonce and submitted
+    /// once again. Extrinsics with an explicit nonce aren't retried, the rejection is returned.
     #[allow(clippy::missing_errors_doc)]
     pub async fn construct(&self, params: &GenericMetadataParams) -> Result<SubmitResult> {
+        // The slot is acquired before fetching the nonce, so queued transactions get fresh nonces
+        let _slot = self.acquire_submission_slot().await?;
+        match (self.sign_and_submit(params).await, params) {
+            (
+                Err(error),
+                GenericMetadataParams::Polkadot(PolkadotMetadataParams { nonce: None, .. }),
+            ) if is_stale_transaction_error(&error) => {
+                tracing::warn!("retrying stale transaction with fresh metadata: {error:#}");
+                self.sign_and_submit(params).await
+            },
+            (result, _) => result,
+        }
+    }
+
+    /// Signs a transaction with the next nonce of the nonce manager, without waiting for the
+    /// previous transactions to be included, so many transactions can be submitted concurrently.
+    /// The nonce manager is shared with the clones of the wallet.
+    ///
+    /// # Errors
+    /// Returns `Err` if the nonce manager isn't enabled, see [`Self::with_nonce_manager`], or the
+    /// signing failed, the nonce is then allocated to the next transaction.
+    pub async fn sign_with_auto_nonce(&self, params: GenericMetadataParams) -> Result<Vec<u8>> {
+        let (transaction, _) = self.sign_with_managed_nonce(params).await?;
+        Ok(transaction)
+    }
+
+    /// Same as [`Self::sign_with_auto_nonce`], also returns the allocated nonce.
+    async fn sign_with_managed_nonce(
+        &self,
+        mut params: GenericMetadataParams,
+    ) -> Result<(Vec<u8>, u64)> {
+        let on_chain = self.nonce_at(AtBlock::Latest).await?;
+        let Some(nonce) = self.with_nonce_manager_lock(|manager| manager.next_nonce(on_chain))?
+        else {
+            anyhow::bail!("the nonce manager isn't enabled, see Wallet::with_nonce_manager");
+        };
+        let signed = match update_metadata_params(&mut params, Some(nonce), None) {
+            Ok(()) => self.sign_transaction(&params).await,
+            Err(error) => Err(error),
+        };
+        let transaction = match signed {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                self.with_nonce_manager_lock(|manager| manager.release(nonce))?;
+                return Err(error);
+            },
+        };
+        self.with_nonce_manager_lock(|manager| manager.dispatched(nonce, transaction.clone()))?;
+        Ok((transaction, nonce))
+    }
+
+    /// Checks for a nonce gap left by a dropped transaction, should be called periodically, ex:
+    /// on every new head. Once the gap lasted more than the configured tolerance, the missing
+    /// transaction is broadcasted once again, or the nonce counter is reset.
+    ///
+    /// Always returns `None` when the nonce manager isn't enabled.
+    ///
+    /// # Errors
+    /// Returns `Err` if the on-chain nonce can't be fetched, or the rebroadcast failed.
+    pub async fn check_nonce_gap(&self) -> Result<Option<NonceGap>> {
+        if self.nonce_manager.is_none() {
+            return Ok(None);
+        }
+        let on_chain = self.nonce_at(AtBlock::Latest).await?;
+        let gap = self.with_nonce_manager_lock(|manager| manager.check(on_chain))?.flatten();
+        match &gap {
+            Some(NonceGap::Rebroadcast { nonce, transaction }) => {
+                tracing::warn!("nonce gap at {nonce}, broadcasting the missing transaction again");
+                self.submit(transaction).await?;
+            },
+            Some(NonceGap::Reset { nonce, dropped }) => {
+                tracing::warn!(
+                    "nonce gap at {nonce}, reset the nonce counter, dropped {dropped} txs"
+                );
+            },
+            None => {},
+        }
+        Ok(gap)
+    }
+
+    /// Calls `f` with the nonce manager locked, returns `None` if the nonce manager isn't enabled.
+    fn with_nonce_manager_lock<T>(
+        &self,
+        f: impl FnOnce(&mut NonceManager) -> T,
+    ) -> Result<Option<T>> {
+        let Some(nonce_manager) = self.nonce_manager.as_ref() else {
+            return Ok(None);
+        };
+        let Ok(mut nonce_manager) = nonce_manager.lock() else {
+            anyhow::bail!("Fatal error: nonce manager lock is poisoned");
+        };
+        Ok(Some(f(&mut nonce_manager)))
+    }
+
+    /// Signs and submits a transaction, the nonce allocated by the nonce manager is released if
+    /// the transaction is rejected.
+    async fn sign_and_submit(&self, params: &GenericMetadataParams) -> Result<SubmitResult> {
+        let (transaction, managed_nonce) = self.sign_for_submission(params).await?;
+        let result = self.client.submit(&transaction).await;
+        self.release_if_rejected(result, managed_nonce)
+    }
+
+    /// Releases the `managed_nonce` if the submission `result` is an error, the rejected
+    /// transaction didn't use its nonce, unlike a stalled one which may still be pending.
+    fn release_if_rejected<T>(&self, result: Result<T>, managed_nonce: Option<u64>) -> Result<T> {
+        if let (Err(error), Some(nonce)) = (&result, managed_nonce) {
+            if !error.is::<ChainStalled>() {
+                self.with_nonce_manager_lock(|manager| manager.release(nonce))?;
+            }
+        }
+        result
+    }
+
+    /// Signs a transaction about to be submitted, the nonce is assigned by the nonce manager
+    /// unless it isn't enabled or the params have an explicit nonce. Returns the nonce allocated
+    /// by the nonce manager, if any.
+    async fn sign_for_submission(
+        &self,
+        params: &GenericMetadataParams,
+    ) -> Result<(Vec<u8>, Option<u64>)> {
+        if self.nonce_manager.is_some() && !has_explicit_nonce(params) {
+            let (transaction, nonce) = self.sign_with_managed_nonce(params.clone()).await?;
+            Ok((transaction, Some(nonce)))
+        } else {
+            Ok((self.sign_transaction(params).await?, None))
+        }
+    }
+
+    /// Waits for a free submission slot when the submissions are limited.
+    async fn acquire_submission_slot(&self) -> Result<Option<SemaphoreGuard<'_>>> {
+        match self.submission_limiter.as_ref() {
+            Some(limiter) => limiter.acquire().await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Creates and signs a transaction without submitting it, returns the encoded transaction.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn sign_transaction(&self, params: &GenericMetadataParams) -> Result<Vec<u8>> {
+        let config = self.client.config();
         let metadata = self.metadata(params).await?;
-        let transaction = self.tx.create_and_sign(
-            self.client.config(),
-            params,
-            &metadata,
-            self.secret_key.secret_key(),
-        )?;
-        self.submit(&transaction).await
+        let payload = self.tx.signing_payload(config, params, &metadata)?;
+        let signature = self.signer.sign(&payload, config.algorithm).await?;
+        self.tx
+            .encode_signed(config, params, &metadata, &self.signer.public_key(), &signature)
+    }
+
+    /// Signs arbitrary `data` like polkadot-js `signRaw`, the data is wrapped in
+    /// `<Bytes>...</Bytes>` before signing, returns the raw signature bytes.
+    ///
+    /// The signature can be verified with [`verify_raw`](crate::verify_raw).
+    ///
+    /// # Errors
+    /// Returns `Err` if the wallet isn't connected to a substrate chain, or the signing failed.
+    pub async fn sign_raw(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !matches!(self.client, GenericClient::Polkadot(_)) {
+            anyhow::bail!("sign_raw is only supported on substrate chains");
+        }
+        let algorithm = self.client.config().algorithm;
+        if !matches!(algorithm, Algorithm::Sr25519 | Algorithm::Ed25519) {
+            anyhow::bail!("sign_raw doesn't support {algorithm:?} keys");
+        }
+        let signature = self.signer.sign(&wrap_bytes(data), algorithm).await?;
+        Ok(signature.to_bytes())
+    }
+
+    /// Signs the EIP-712 typed data with the given `struct_hash` in `domain`, returns the 65 bytes
+    /// `r || s || v` signature.
+    ///
+    /// The domain separator is cached, signing many messages of the same domain computes it once.
+    ///
+    /// # Errors
+    /// Returns `Err` if the wallet doesn't use recoverable ECDSA keys, or the signing failed.
+    pub async fn sign_eip712(&self, domain: &Eip712Domain, struct_hash: H256) -> Result<Vec<u8>> {
+        let algorithm = self.client.config().algorithm;
+        if !algorithm.is_recoverable() {
+            anyhow::bail!("sign_eip712 doesn't support {algorithm:?} keys");
+        }
+        let digest = eip712_digest(domain, struct_hash);
+        let signature = self.signer.sign(digest.as_bytes(), algorithm).await?;
+        Ok(signature.to_bytes())
+    }
+
+    /// Signs an arbitrary 32 bytes `digest` as is, ex: the hash of an order, returns the 65 bytes
+    /// `r || s || v` signature.
+    ///
+    /// # Errors
+    /// Returns `Err` if the wallet doesn't use recoverable ECDSA keys, ed25519 and sr25519 keys
+    /// can't sign prehashed digests, or the signing failed.
+    pub async fn sign_digest(&self, digest: &[u8; 32]) -> Result<Vec<u8>> {
+        let algorithm = self.client.config().algorithm;
+        if !algorithm.is_recoverable() {
+            anyhow::bail!("sign_digest doesn't support {algorithm:?} keys");
+        }
+        let signature = self.signer.sign(digest, algorithm).await?;
+        Ok(signature.to_bytes())
     }
 
     /// Makes a transfer.
@@ -193,7 +859,196 @@ impl Wallet {
         self.construct(&metadata_params).await
     }
 
-    /// Uses the faucet on dev chains to seed the account with funds.
+    /// Makes a transfer like [`Self::transfer`], and waits until the transaction is buried under
+    /// `confirmations` blocks. Only supported by EVM chains.
+    ///
+    /// # Errors
+    /// Returns `Err` before submitting anything if the chain isn't an EVM chain.
+    pub async fn transfer_with_confirmations(
+        &self,
+        account: &AccountIdentifier,
+        amount: u128,
+        nonce: Option<u64>,
+        gas_limit: Option<u64>,
+        confirmations: u32,
+    ) -> Result<SubmitResult> {
+        self.ensure_confirmations_supported()?;
+        let result = self.transfer(account, amount, nonce, gas_limit).await?;
+        self.wait_for_confirmations(result, confirmations).await
+    }
+
+    /// Makes a transfer like [`Self::transfer`], the opaque `label` is attached to the `transfer`
+    /// tracing span and returned with the result, to correlate the submission in the logs. The
+    /// label is never included in the transaction.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn transfer_labeled(
+        &self,
+        label: &str,
+        account: &AccountIdentifier,
+        amount: u128,
+        nonce: Option<u64>,
+        gas_limit: Option<u64>,
+    ) -> Result<LabeledSubmitResult> {
+        let span = tracing::info_span!("transfer", label, from = %self.account.address);
+        let result = self.transfer(account, amount, nonce, gas_limit).instrument(span).await?;
+        Ok(LabeledSubmitResult { label: label.to_owned(), result })
+    }
+
+    /// Makes a transfer like [`Self::transfer`], `on_progress` is called with the [`TxProgress`]
+    /// of the transaction as it is broadcast, included in a block and finalized. The finalization
+    /// is tracked with the finalized blocks subscription, so a websocket client is required.
+    /// Only supported by EVM chains.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn transfer_with_progress(
+        &self,
+        account: &AccountIdentifier,
+        amount: u128,
+        nonce: Option<u64>,
+        gas_limit: Option<u64>,
+        mut on_progress: impl FnMut(TxProgress) + Send,
+    ) -> Result<SubmitResult> {
+        let client = match &self.client {
+            GenericClient::Ethereum(client) => client,
+            GenericClient::Astar(client) => client.ethereum_client(),
+            GenericClient::Polkadot(_) => {
+                anyhow::bail!("transaction progress is only supported by EVM chains")
+            },
+        };
+        let address = Address::new(self.client.config().address_format, account.address.clone());
+        let mut metadata_params = self.tx.transfer(&address, amount)?;
+        update_metadata_params(&mut metadata_params, nonce, gas_limit)?;
+
+        // Subscribed before broadcasting, so the finalized blocks are never missed
+        let mut finalized = std::pin::pin!(self.listen_finalized().await?);
+        let result = {
+            let _slot = self.acquire_submission_slot().await?;
+            let (transaction, managed_nonce) = self.sign_for_submission(&metadata_params).await?;
+            let on_broadcast = |tx_hash| on_progress(TxProgress::Broadcast { tx_hash });
+            let result = client.submit_with_broadcast_callback(&transaction, on_broadcast).await;
+            self.release_if_rejected(result, managed_nonce)?
+        };
+        let SubmitResult::Executed { tx_hash, result: call_result, mut receipt } = result else {
+            return Ok(result);
+        };
+
+        let mut finalized_index = self.client.finalized_block().await?.index;
+        loop {
+            let Some(index) = receipt.block_number else {
+                anyhow::bail!("[this is a bug] receipt of {tx_hash:?} has no block number");
+            };
+            let block = BlockIdentifier::new(index, receipt.block_hash.0);
+            on_progress(TxProgress::Included { tx_hash, block: block.clone() });
+            while finalized_index < index {
+                let Some(finalized_block) = finalized.next().await else {
+                    anyhow::bail!("finalized blocks stream closed");
+                };
+                finalized_index = finalized_block.index;
+            }
+            // The receipt is fetched again, the transaction may have moved to another block
+            let Some(finalized_receipt) = self.eth_transaction_receipt(tx_hash.0).await? else {
+                anyhow::bail!("transaction {tx_hash:?} was dropped by a chain reorganization");
+            };
+            receipt = finalized_receipt;
+            if receipt.block_number == Some(index) && receipt.block_hash.0 == block.hash {
+                on_progress(TxProgress::Finalized { tx_hash, block });
+                return Ok(SubmitResult::Executed { tx_hash, result: call_result, receipt });
+            }
+        }
+    }
+
+    /// Makes a transfer paying a `tip` to the block author to prioritize its inclusion, only
+    /// supported by substrate chains. Fails before submitting when the free balance can't pay
+    /// the amount, the tip and the fee estimated from the signed extrinsic.
+    /// Parameters:
+    /// - account: the account to transfer to
+    /// - amount: the amount to transfer
+    /// - tip: the tip paid on top of the fee
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn transfer_with_tip(
+        &self,
+        account: &AccountIdentifier,
+        amount: u128,
+        tip: u128,
+        nonce: Option<u64>,
+    ) -> Result<SubmitResult> {
+        let GenericClient::Polkadot(client) = &self.client else {
+            anyhow::bail!("tips are only supported by substrate chains");
+        };
+        let address = Address::new(self.client.config().address_format, account.address.clone());
+        let mut metadata_params = self.tx.transfer(&address, amount)?;
+        update_metadata_params(&mut metadata_params, nonce, None)?;
+        if let GenericMetadataParams::Polkadot(params) = &mut metadata_params {
+            params.tip = Some(tip);
+        }
+
+        // The fee depends on the length of the signed extrinsic
+        let transaction = self.sign_transaction(&metadata_params).await?;
+        let fee = client.estimate_extrinsic_fee(&transaction).await?;
+        let free = self.balance().await?;
+        if free < amount.saturating_add(fee).saturating_add(tip) {
+            anyhow::bail!(
+                "insufficient balance to transfer {amount} and pay the tip {tip} and the fee {fee}, free balance is {free}"
+            );
+        }
+        self.construct(&metadata_params).await
+    }
+
+    /// Confirmations are tracked with the receipts, which are only available on EVM chains.
+    fn ensure_confirmations_supported(&self) -> Result<()> {
+        if matches!(self.client, GenericClient::Polkadot(_)) {
+            anyhow::bail!("confirmations are only supported by EVM chains");
+        }
+        Ok(())
+    }
+
+    /// Waits until the transaction is buried under `confirmations` blocks, the receipt is fetched
+    /// again on every new head, so the returned receipt reflects any chain reorganization.
+    async fn wait_for_confirmations(
+        &self,
+        result: SubmitResult,
+        confirmations: u32,
+    ) -> Result<SubmitResult> {
+        if confirmations == 0 {
+            return Ok(result);
+        }
+        let confirmations = u64::from(confirmations);
+        let SubmitResult::Executed { tx_hash, result: call_result, .. } = result else {
+            return Ok(result);
+        };
+        let Some(mut stream) = self.listen().await? else {
+            anyhow::bail!("blockchain doesn't support new heads subscription");
+        };
+        let mut best_block = self.client.current_block().await?.index;
+        loop {
+            let Some(receipt) = self.eth_transaction_receipt(tx_hash.0).await? else {
+                anyhow::bail!("transaction {tx_hash:?} was dropped by a chain reorganization");
+            };
+            let Some(block_number) = receipt.block_number else {
+                anyhow::bail!("[this is a bug] receipt of {tx_hash:?} has no block number");
+            };
+            if best_block >= block_number.saturating_add(confirmations) {
+                return Ok(SubmitResult::Executed { tx_hash, result: call_result, receipt });
+            }
+            best_block = loop {
+                match stream.next().await {
+                    Some(ClientEvent::NewHead(BlockOrIdentifier::Identifier(block))) => {
+                        break block.index
+                    },
+                    Some(ClientEvent::NewHead(BlockOrIdentifier::Block(block))) => {
+                        break block.block_identifier.index
+                    },
+                    Some(ClientEvent::Close(reason)) => {
+                        anyhow::bail!("new heads stream closed: {reason}")
+                    },
+                    Some(_) => {},
+                    None => anyhow::bail!("new heads stream closed"),
+                }
+            };
+        }
+    }
+
+    /// Uses the faucet on dev chains to seed the account with funds, and waits until the faucet
+    /// transaction is included.
     /// Parameters:
     /// - `faucet_parameter`: the amount to seed the account with
     #[allow(clippy::missing_errors_doc)]
@@ -201,19 +1056,117 @@ impl Wallet {
         &self,
         faucet_parameter: u128,
         high_gas_price: Option<u128>,
+    ) -> Result<Vec<u8>> {
+        self.faucet_with_options(faucet_parameter, FaucetOptions { high_gas_price, wait: true })
+            .await
+    }
+
+    /// Uses the faucet on dev chains to seed the account with funds.
+    /// Parameters:
+    /// - `faucet_parameter`: the amount to seed the account with
+    /// - `options`: see [`FaucetOptions`]
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn faucet_with_options(
+        &self,
+        faucet_parameter: u128,
+        options: FaucetOptions,
     ) -> Result<Vec<u8>> {
         let address =
             Address::new(self.client.config().address_format, self.account.address.clone());
-        self.client.faucet(&address, faucet_parameter, high_gas_price).await
+        let tx_hash =
+            self.client.faucet(&address, faucet_parameter, options.high_gas_price).await?;
+        if options.wait {
+            self.wait_for_faucet(&tx_hash).await?;
+        }
+        Ok(tx_hash)
     }
 
-    /// deploys contract to chain
+    /// Waits until the faucet transaction `tx_hash` is included. The receipt is checked instead
+    /// of the balance, which doesn't move when the wallet is its own funding account.
+    ///
+    /// The substrate faucets only return once the transfer is finalized, on ethereum the receipt
+    /// is polled every [`FAUCET_POLL_INTERVAL`], or sooner when a new head arrives, for up to
+    /// [`FAUCET_WAIT_TIMEOUT`].
+    async fn wait_for_faucet(&self, tx_hash: &[u8]) -> Result<()> {
+        if !matches!(self.client, GenericClient::Ethereum(_)) {
+            return Ok(());
+        }
+        let tx_hash = <[u8; 32]>::try_from(tx_hash)
+            .map_err(|_| anyhow::anyhow!("invalid faucet transaction hash"))?;
+        let poll = async {
+            // The new heads only wake up the polling, chains without subscription are polled
+            let mut heads = self.listen().await.ok().flatten();
+            loop {
+                if let Some(receipt) = self.eth_transaction_receipt(tx_hash).await? {
+                    if receipt.status_code != Some(1) {
+                        anyhow::bail!("faucet transaction reverted: {}", H256(tx_hash));
+                    }
+                    return Ok(());
+                }
+                let delay = futures_timer::Delay::new(FAUCET_POLL_INTERVAL);
+                let closed = match heads.as_mut() {
+                    Some(stream) => match future::select(stream.next(), delay).await {
+                        future::Either::Left((event, _)) => {
+                            matches!(event, None | Some(ClientEvent::Close(_)))
+                        },
+                        future::Either::Right(_) => false,
+                    },
+                    None => {
+                        delay.await;
+                        false
+                    },
+                };
+                if closed {
+                    heads = None;
+                }
+            }
+        };
+        let poll = std::pin::pin!(poll);
+        match future::select(poll, futures_timer::Delay::new(FAUCET_WAIT_TIMEOUT)).await {
+            future::Either::Left((result, _)) => result,
+            future::Either::Right(_) => {
+                anyhow::bail!("faucet transaction not included after {FAUCET_WAIT_TIMEOUT:?}")
+            },
+        }
+    }
+
+    /// Deploys a contract to the chain, fails with [`CodeSizeExceeded`] before broadcasting when
+    /// the deployed code would be larger than the maximum code size, see
+    /// [`Self::with_max_code_size`].
     #[allow(clippy::missing_errors_doc)]
     pub async fn eth_deploy_contract(&self, bytecode: Vec<u8>) -> Result<SubmitResult> {
+        self.check_code_size(&bytecode).await?;
         let metadata_params = self.tx.deploy_contract(bytecode)?;
         self.construct(&metadata_params).await
     }
 
+    /// Simulates the `init_code` to check the size of the code it deploys, fails when the
+    /// simulation fails unless enabled by [`Self::with_unchecked_code_size`].
+    async fn check_code_size(&self, init_code: &[u8]) -> Result<()> {
+        let size = match &self.client {
+            GenericClient::Ethereum(client) => {
+                let from: EthAddress = self.account.address.parse()?;
+                client.deployed_code_size(from.0, init_code).await
+            },
+            GenericClient::Astar(client) => {
+                let from: EthAddress = self.account.address.parse()?;
+                client.deployed_code_size(from.0, init_code).await
+            },
+            GenericClient::Polkadot(_) => return Ok(()),
+        };
+        match size {
+            Ok(size) if size > self.max_code_size => {
+                Err(CodeSizeExceeded { size, limit: self.max_code_size }.into())
+            },
+            Ok(_) => Ok(()),
+            Err(error) if self.unchecked_code_size => {
+                tracing::warn!("skipping the contract code size check: {error:#}");
+                Ok(())
+            },
+            Err(error) => Err(error.context("failed to check the contract code size")),
+        }
+    }
+
     /// calls contract send call function
     #[allow(clippy::missing_errors_doc)]
     pub async fn eth_send_call(
@@ -229,6 +1182,202 @@ impl Wallet {
         self.construct(&metadata_params).await
     }
 
+    /// Calls a contract like [`Self::eth_send_call`], and waits until the transaction is buried
+    /// under `confirmations` blocks.
+    ///
+    /// # Errors
+    /// Returns `Err` before submitting anything if the chain isn't an EVM chain.
+    pub async fn eth_send_call_with_confirmations(
+        &self,
+        contract_address: [u8; 20],
+        data: Vec<u8>,
+        amount: u128,
+        nonce: Option<u64>,
+        gas_limit: Option<u64>,
+        confirmations: u32,
+    ) -> Result<SubmitResult> {
+        self.ensure_confirmations_supported()?;
+        let result = self.eth_send_call(contract_address, data, amount, nonce, gas_limit).await?;
+        self.wait_for_confirmations(result, confirmations).await
+    }
+
+    /// Calls a contract like [`Self::eth_send_call`], the opaque `label` is attached to the
+    /// `eth_send_call` tracing span and returned with the result, see [`Self::transfer_labeled`].
+    #[allow(clippy::missing_errors_doc, clippy::too_many_arguments)]
+    pub async fn eth_send_call_labeled(
+        &self,
+        label: &str,
+        contract_address: [u8; 20],
+        data: Vec<u8>,
+        amount: u128,
+        nonce: Option<u64>,
+        gas_limit: Option<u64>,
+    ) -> Result<LabeledSubmitResult> {
+        let span = tracing::info_span!("eth_send_call", label, from = %self.account.address);
+        let result = self
+            .eth_send_call(contract_address, data, amount, nonce, gas_limit)
+            .instrument(span)
+            .await?;
+        Ok(LabeledSubmitResult { label: label.to_owned(), result })
+    }
+
+    /// Executes `calls` as `(contract, data, value)` atomically in a single transaction, if any
+    /// call reverts none of the effects of the other calls persist.
+    ///
+    /// The calls are made from the constructor of a disposable contract, which returns no code so
+    /// nothing is left on chain. Notice the `msg.sender` of each call is the disposable contract,
+    /// not this wallet, and the transaction value is the sum of the calls value.
+    ///
+    /// Gas cost: on top of the calls themselves, the batch pays the contract creation cost of
+    /// 53000 gas (instead of 21000 for a regular transaction), the calldata cost of the init
+    /// code, which is 71 bytes per call plus the call data, and ~120 gas of opcodes per call.
+    /// No code deposit cost is paid.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, the sum of the values
+    /// overflows, or the batch reverts during gas estimation.
+    pub async fn atomic_batch(
+        &self,
+        calls: Vec<(EthAddress, Vec<u8>, u128)>,
+    ) -> Result<SubmitResult> {
+        let mut value = 0u128;
+        for (_, _, call_value) in &calls {
+            let Some(total) = value.checked_add(*call_value) else {
+                anyhow::bail!("atomic batch value overflows");
+            };
+            value = total;
+        }
+        let init_code = atomic_batch_init_code(&calls)?;
+        let mut metadata_params = self.tx.deploy_contract(init_code)?;
+        match &mut metadata_params {
+            GenericMetadataParams::Ethereum(params) => params.amount = U256::from(value).0,
+            GenericMetadataParams::Astar(params) => params.0.amount = U256::from(value).0,
+            GenericMetadataParams::Polkadot(_) => {
+                anyhow::bail!("polkadot doesn't support atomic_batch")
+            },
+        }
+        self.construct(&metadata_params).await
+    }
+
+    /// Submits a signed EIP-2612 `permit` to the `token` contract, paying the gas fees from
+    /// this wallet on behalf of the `permit.owner`.
+    /// Parameters:
+    /// - token: the ERC-20 contract which implements EIP-2612
+    /// - permit: the permit message signed by the owner
+    /// - signature: the owner's 65 bytes `r || s || v` signature of the permit
+    ///
+    /// # Errors
+    /// Returns `Err` if the permit deadline has passed, the signature is malformed, or the
+    /// blockchain doesn't support EVM calls.
+    pub async fn submit_permit(
+        &self,
+        token: EthAddress,
+        permit: Permit,
+        signature: &[u8],
+    ) -> Result<SubmitResult> {
+        let data = permit.encode_call(signature)?;
+
+        // Check the deadline against the latest block, otherwise the transaction will revert
+        let Some(latest_block) = self.query(GetBlock(AtBlock::Latest)).await? else {
+            anyhow::bail!("latest block not found");
+        };
+        let timestamp = latest_block.header().header().timestamp;
+        if permit.deadline < U256::from(timestamp) {
+            anyhow::bail!(
+                "permit expired, deadline {} is before the latest block timestamp {timestamp}",
+                permit.deadline
+            );
+        }
+        self.eth_send_call(token.0, data, 0, None, None).await
+    }
+
+    /// Returns the amount of `token` the `spender` is allowed to spend on behalf of the `owner`,
+    /// from the ERC-20 `allowance(owner,spender)` at the latest block.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the call reverts.
+    pub async fn erc20_allowance(
+        &self,
+        token: EthAddress,
+        owner: EthAddress,
+        spender: EthAddress,
+    ) -> Result<U256> {
+        let mut data = Vec::with_capacity(4 + 2 * 32);
+        data.extend_from_slice(&ERC20_ALLOWANCE_SELECTOR);
+        for address in [owner, spender] {
+            data.extend_from_slice(&[0u8; 12]);
+            data.extend_from_slice(address.as_bytes());
+        }
+        match self.eth_view_call(token.0, data, AtBlock::Latest).await? {
+            CallResult::Success(result) if result.len() >= 32 => {
+                Ok(U256::from_big_endian(&result[..32]))
+            },
+            CallResult::Success(result) => {
+                anyhow::bail!("invalid allowance, expected 32 bytes, got {}", result.len())
+            },
+            CallResult::Revert(_) | CallResult::Error => {
+                anyhow::bail!("allowance call to {token:?} failed")
+            },
+        }
+    }
+
+    /// Allows the `spender` to spend `amount` of `token` on behalf of this wallet, with the
+    /// ERC-20 `approve(spender,amount)`.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the call reverts.
+    pub async fn erc20_approve(
+        &self,
+        token: EthAddress,
+        spender: EthAddress,
+        amount: U256,
+    ) -> Result<SubmitResult> {
+        let mut data = Vec::with_capacity(4 + 2 * 32);
+        data.extend_from_slice(&ERC20_APPROVE_SELECTOR);
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(spender.as_bytes());
+        let mut word = [0u8; 32];
+        amount.to_big_endian(&mut word);
+        data.extend_from_slice(&word);
+        self.eth_send_call(token.0, data, 0, None, None).await
+    }
+
+    /// Verifies the `signature` of `hash` made by `signer`, which can be either an EOA or a
+    /// smart contract wallet implementing [EIP-1271](https://eips.ethereum.org/EIPS/eip-1271).
+    ///
+    /// If `signer` has code, its `isValidSignature(bytes32,bytes)` must return the magic value,
+    /// otherwise the 65 bytes `r || s || v` signature must recover to `signer`.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls or the client connection failed.
+    pub async fn verify_signature_1271(
+        &self,
+        signer: EthAddress,
+        hash: H256,
+        signature: &[u8],
+    ) -> Result<bool> {
+        if self.get_code(signer).await?.is_empty() {
+            return Ok(recover_eoa_signature(hash, signature) == Some(signer));
+        }
+
+        let padded_len = signature.len().div_ceil(32) * 32;
+        let mut data = Vec::with_capacity(4 + 3 * 32 + padded_len);
+        data.extend_from_slice(&EIP1271_MAGIC_VALUE);
+        data.extend_from_slice(hash.as_bytes());
+        for value in [U256::from(64), U256::from(signature.len())] {
+            let mut word = [0u8; 32];
+            value.to_big_endian(&mut word);
+            data.extend_from_slice(&word);
+        }
+        data.extend_from_slice(signature);
+        data.resize(4 + 3 * 32 + padded_len, 0);
+
+        match self.eth_view_call(signer.0, data, AtBlock::Latest).await? {
+            CallResult::Success(result) => Ok(result.get(..4) == Some(&EIP1271_MAGIC_VALUE)),
+            CallResult::Revert(_) | CallResult::Error => Ok(false),
+        }
+    }
+
     /// estimates gas of send call
     #[allow(clippy::missing_errors_doc)]
     pub async fn eth_send_call_estimate_gas(
@@ -319,6 +1468,66 @@ impl Wallet {
         Ok(value)
     }
 
+    /// Returns the code deployed at `address`, empty if the address is not a contract.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the client connection failed.
+    pub async fn get_code(&self, address: EthAddress) -> Result<Bytes> {
+        let get_code = GetCode { address, block: AtBlock::Latest };
+        let result = match &self.client {
+            GenericClient::Ethereum(client) => client.call(&EthQuery::GetCode(get_code)).await?,
+            GenericClient::Astar(client) => client.call(&EthQuery::GetCode(get_code)).await?,
+            GenericClient::Polkadot(_) => anyhow::bail!("polkadot doesn't support get_code"),
+        };
+        let EthQueryResult::GetCode(code) = result else {
+            anyhow::bail!("[this is a bug] invalid result type");
+        };
+        Ok(code)
+    }
+
+    /// Returns the implementation of an EIP-1967 `proxy`, read from the implementation storage
+    /// slot. Returns `None` if the address is not a proxy.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the client connection failed.
+    pub async fn resolve_proxy_implementation(
+        &self,
+        proxy: EthAddress,
+    ) -> Result<Option<EthAddress>> {
+        self.eip1967_address(proxy, EIP1967_IMPLEMENTATION_SLOT).await
+    }
+
+    /// Returns the admin of an EIP-1967 transparent `proxy`, read from the admin storage slot.
+    /// Returns `None` if the slot is empty.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the client connection failed.
+    pub async fn resolve_proxy_admin(&self, proxy: EthAddress) -> Result<Option<EthAddress>> {
+        self.eip1967_address(proxy, EIP1967_ADMIN_SLOT).await
+    }
+
+    /// Returns the beacon of an EIP-1967 beacon `proxy`, read from the beacon storage slot, the
+    /// implementation is provided by the beacon. Returns `None` if the slot is empty.
+    ///
+    /// # Errors
+    /// Returns `Err` if the blockchain doesn't support EVM calls, or the client connection failed.
+    pub async fn resolve_beacon(&self, proxy: EthAddress) -> Result<Option<EthAddress>> {
+        self.eip1967_address(proxy, EIP1967_BEACON_SLOT).await
+    }
+
+    /// Reads the address stored in the EIP-1967 storage `slot` of `proxy`.
+    async fn eip1967_address(
+        &self,
+        proxy: EthAddress,
+        slot: [u8; 32],
+    ) -> Result<Option<EthAddress>> {
+        let value = self.eth_storage(proxy.0, slot, AtBlock::Latest).await?;
+        if value.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(EthAddress::from_slice(&value[12..])))
+    }
+
     /// gets storage proof from ethereum contract
     #[allow(clippy::missing_errors_doc)]
     pub async fn eth_storage_proof<I: Iterator<Item = ethereum_types::H256> + Send + Sync>(
@@ -386,6 +1595,33 @@ impl Wallet {
     }
 }
 
+/// Recovers the signer of a 65 bytes `r || s || v` signature of `hash`, returns `None` if the
+/// signature is malformed or invalid.
+fn recover_eoa_signature(hash: H256, signature: &[u8]) -> Option<EthAddress> {
+    let signature = <[u8; 65]>::try_from(signature).ok()?;
+    // Accept both the raw recovery id and the `27 + recovery_id` encoding
+    let v = match signature[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return None,
+    };
+    let signature = EthSignature {
+        v: RecoveryId::new(u64::from(v)),
+        r: U256::from_big_endian(&signature[0..32]),
+        s: U256::from_big_endian(&signature[32..64]),
+    };
+    <DefaultCrypto as Crypto>::secp256k1_ecdsa_recover(&signature, hash).ok()
+}
+
+/// Returns `true` if the metadata parameters set the nonce of the transaction.
+const fn has_explicit_nonce(params: &GenericMetadataParams) -> bool {
+    match params {
+        GenericMetadataParams::Ethereum(params) => params.nonce.is_some(),
+        GenericMetadataParams::Astar(params) => params.0.nonce.is_some(),
+        GenericMetadataParams::Polkadot(params) => params.nonce.is_some(),
+    }
+}
+
 /// Updates the metadata parameters with the given nonce and gas limit.
 fn update_metadata_params(
     params: &mut GenericMetadataParams,
@@ -0,0 +1,85 @@
+use crate::{
+    client::GenericClient,
+    crypto::{Algorithm, PublicKey as CryptoPublicKey, Signature},
+    signer::RemoteSigner,
+    types::PublicKey,
+    Wallet,
+};
+use anyhow::Result;
+use rosetta_core::BlockchainClient;
+use serde::{Deserialize, Serialize};
+
+/// Public description of a wallet account, without any secret, used to register the account as
+/// watch-only with [`Wallet::from_descriptor`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDescriptor {
+    /// Blockchain of the account, ex: `ethereum`.
+    pub blockchain: String,
+    /// Network of the account, ex: `mainnet`.
+    pub network: String,
+    /// Address of the account, in the address format of the blockchain.
+    pub address: String,
+    /// Public key of the account, the curve type identifies the signing algorithm.
+    pub public_key: PublicKey,
+}
+
+/// Signer of a watch-only wallet, which knows the public key but fails to sign.
+#[derive(Clone, Copy, Debug)]
+pub struct WatchOnly(CryptoPublicKey);
+
+#[async_trait::async_trait]
+impl RemoteSigner for WatchOnly {
+    async fn sign(&self, _payload: &[u8], _algorithm: Algorithm) -> Result<Signature> {
+        anyhow::bail!("watch-only wallets can't sign")
+    }
+
+    fn public_key(&self) -> CryptoPublicKey {
+        self.0
+    }
+}
+
+impl<S: RemoteSigner> Wallet<S> {
+    /// Returns the public description of the wallet account.
+    #[must_use]
+    pub fn account_descriptor(&self) -> AccountDescriptor {
+        let config = self.config();
+        AccountDescriptor {
+            blockchain: config.blockchain.into(),
+            network: config.network.into(),
+            address: self.account().address.clone(),
+            public_key: self.public_key().clone(),
+        }
+    }
+}
+
+impl Wallet<WatchOnly> {
+    /// Creates a watch-only wallet of the `descriptor` account, which can query the chain but
+    /// fails to sign.
+    ///
+    /// # Errors
+    /// Returns `Err` if the descriptor belongs to another chain than `client`, or its public key
+    /// doesn't match its address.
+    pub fn from_descriptor(client: GenericClient, descriptor: &AccountDescriptor) -> Result<Self> {
+        let config = client.config();
+        if descriptor.blockchain != config.blockchain || descriptor.network != config.network {
+            anyhow::bail!(
+                "the account belongs to {} {}, expected {} {}",
+                descriptor.blockchain,
+                descriptor.network,
+                config.blockchain,
+                config.network
+            );
+        }
+        let bytes = hex::decode(descriptor.public_key.hex_bytes.trim_start_matches("0x"))?;
+        let public_key = CryptoPublicKey::from_bytes(config.algorithm, &bytes)?;
+        let address = public_key.to_address(config.address_format);
+        if address.address() != descriptor.address {
+            anyhow::bail!(
+                "the public key maps to the address {}, expected {}",
+                address.address(),
+                descriptor.address
+            );
+        }
+        Self::from_signer(client, WatchOnly(public_key))
+    }
+}
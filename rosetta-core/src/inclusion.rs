@@ -0,0 +1,56 @@
+/// Estimate of whether a transaction is included before it expires, ex: before the end of the
+/// mortality era of a substrate extrinsic, or before the deadline of an ethereum call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InclusionEstimate {
+    /// `true` if the transaction is expected to be included before it expires.
+    pub likely: bool,
+    /// Blocks left between the expected inclusion and the expiry, negative when the transaction
+    /// is expected to expire first.
+    pub margin: i64,
+}
+
+impl InclusionEstimate {
+    /// Estimates the inclusion of a transaction queued behind `pending` transactions, when about
+    /// `per_block` transactions are included in each block and the transaction can still be
+    /// included in the next `remaining_blocks` blocks.
+    #[must_use]
+    pub fn new(pending: u64, per_block: u64, remaining_blocks: u64) -> Self {
+        // The transaction is included in the block after the pending ones
+        let expected_blocks = pending / per_block.max(1) + 1;
+        let margin = i64::try_from(remaining_blocks)
+            .unwrap_or(i64::MAX)
+            .saturating_sub(i64::try_from(expected_blocks).unwrap_or(i64::MAX));
+        Self { likely: margin >= 0, margin }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn likely_without_congestion() {
+        let estimate = InclusionEstimate::new(0, 10, 3);
+        assert_eq!(estimate, InclusionEstimate { likely: true, margin: 2 });
+
+        // Included in the last block before the expiry
+        let estimate = InclusionEstimate::new(25, 10, 3);
+        assert_eq!(estimate, InclusionEstimate { likely: true, margin: 0 });
+    }
+
+    #[test]
+    fn unlikely_under_congestion() {
+        let estimate = InclusionEstimate::new(100, 10, 3);
+        assert_eq!(estimate, InclusionEstimate { likely: false, margin: -8 });
+
+        // Nothing was included recently, assume one transaction per block
+        let estimate = InclusionEstimate::new(5, 0, 3);
+        assert_eq!(estimate, InclusionEstimate { likely: false, margin: -3 });
+    }
+
+    #[test]
+    fn unlikely_when_expired() {
+        let estimate = InclusionEstimate::new(0, 10, 0);
+        assert_eq!(estimate, InclusionEstimate { likely: false, margin: -1 });
+    }
+}
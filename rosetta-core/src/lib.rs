@@ -1,11 +1,13 @@
+mod inclusion;
 mod node_uri;
+mod preflight;
 pub mod traits;
 pub mod types;
 
 use crate::{
     crypto::{
         address::{Address, AddressFormat},
-        Algorithm, PublicKey, SecretKey,
+        Algorithm, PublicKey, SecretKey, Signature,
     },
     types::{Block, CurveType, SignatureType},
 };
@@ -15,11 +17,15 @@ pub use futures_util::{future, stream};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     fmt::{Debug, Display},
+    future::Future,
     sync::Arc,
+    time::Duration,
 };
 
 use futures_util::stream::Empty;
+pub use inclusion::InclusionEstimate;
 pub use node_uri::{NodeUri, NodeUriError};
+pub use preflight::{check_synced, PREFLIGHT_MAX_BLOCKS_BEHIND};
 pub use rosetta_crypto as crypto;
 
 type NodeCommand = Arc<dyn Fn(&str, u16) -> Vec<String> + Send + Sync + 'static>;
@@ -38,10 +44,52 @@ pub struct BlockchainConfig {
     pub currency_decimals: u32,
     pub node_uri: NodeUri<'static>,
     pub node_image: &'static str,
+    /// Overrides the entrypoint of `node_image`, `None` keeps the image default.
+    pub node_entrypoint: Option<&'static [&'static str]>,
     pub node_command: NodeCommand,
     pub node_additional_ports: &'static [u16],
     pub connector_port: u16,
     pub testnet: bool,
+    /// Block explorer of the network, `None` for local or unknown networks.
+    pub block_explorer_url: Option<&'static str>,
+}
+
+impl BlockchainConfig {
+    /// Returns the EIP-3085 `wallet_addEthereumChain` parameters, used by frontends to add the
+    /// chain to wallets like MetaMask.
+    ///
+    /// # Errors
+    /// Returns `Err` if this isn't an EVM config, or `rpc_urls` is empty.
+    pub fn to_add_chain_params(
+        &self,
+        chain_id: u64,
+        rpc_urls: &[&str],
+    ) -> Result<serde_json::Value> {
+        if self.address_format != AddressFormat::Eip55 {
+            anyhow::bail!("{} is not an EVM chain", self.blockchain);
+        }
+        if rpc_urls.is_empty() {
+            anyhow::bail!("at least one rpc url is required");
+        }
+        let mut blockchain = self.blockchain.chars();
+        let chain_name = blockchain.next().map_or_else(String::new, |first| {
+            format!("{}{} {}", first.to_ascii_uppercase(), blockchain.as_str(), self.network)
+        });
+        let mut params = serde_json::json!({
+            "chainId": format!("0x{chain_id:x}"),
+            "chainName": chain_name,
+            "nativeCurrency": {
+                "name": self.currency_symbol,
+                "symbol": self.currency_symbol,
+                "decimals": self.currency_decimals,
+            },
+            "rpcUrls": rpc_urls,
+        });
+        if let Some(explorer) = self.block_explorer_url {
+            params["blockExplorerUrls"] = serde_json::json!([explorer]);
+        }
+        Ok(params)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -153,7 +201,7 @@ pub trait BlockchainClient: Sized + Send + Sync + 'static {
     type CallResult: Send + Sync + Sized + 'static;
 
     type AtBlock: Clone + Send + Sync + Sized + Eq + From<Self::BlockIdentifier> + 'static;
-    type BlockIdentifier: Clone + Send + Sync + Sized + Eq + 'static;
+    type BlockIdentifier: Clone + Send + Sync + Sized + Eq + BlockHeight + 'static;
 
     type Query: traits::Query;
     type Transaction: Clone + Send + Sync + Sized + Eq + 'static;
@@ -168,6 +216,20 @@ pub trait BlockchainClient: Sized + Send + Sync + 'static {
     async fn current_block(&self) -> Result<Self::BlockIdentifier>;
     async fn finalized_block(&self) -> Result<Self::BlockIdentifier>;
     async fn balance(&self, address: &Address, block: &Self::AtBlock) -> Result<u128>;
+
+    /// Returns the balances of `addresses` at `block`, in the same order as `addresses`. By
+    /// default calls [`BlockchainClient::balance`] for each address.
+    ///
+    /// # Errors
+    /// Returns `Err` if the balance of any of the addresses can't be fetched.
+    async fn balances(&self, addresses: &[Address], block: &Self::AtBlock) -> Result<Vec<u128>> {
+        let mut balances = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            balances.push(self.balance(address, block).await?);
+        }
+        Ok(balances)
+    }
+
     async fn faucet(
         &self,
         address: &Address,
@@ -189,6 +251,80 @@ pub trait BlockchainClient: Sized + Send + Sync + 'static {
     async fn listen<'a>(&'a self) -> Result<Option<Self::EventStream<'a>>> {
         Ok(None)
     }
+
+    /// Waits until the chain reaches the block `height`, returns the first block observed at or
+    /// above it. By default polls [`BlockchainClient::current_block`].
+    ///
+    /// # Errors
+    /// Fails with [`BlockTimeout`] if `height` isn't reached within `timeout`.
+    async fn wait_for_block(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<Self::BlockIdentifier> {
+        poll_for_block(self, height, timeout).await
+    }
+}
+
+/// Interval between [`BlockchainClient::current_block`] calls in [`poll_for_block`].
+pub const WAIT_FOR_BLOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Block identifiers which expose the block height.
+pub trait BlockHeight {
+    /// Returns the height of the block, also known as the block number.
+    fn block_height(&self) -> u64;
+}
+
+impl BlockHeight for types::BlockIdentifier {
+    fn block_height(&self) -> u64 {
+        self.index
+    }
+}
+
+/// Returned by [`BlockchainClient::wait_for_block`] when the chain doesn't reach the expected
+/// height in time, can be retrieved with [`anyhow::Error::downcast_ref`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("block {height} not reached after {timeout:?}")]
+pub struct BlockTimeout {
+    pub height: u64,
+    pub timeout: Duration,
+}
+
+/// Default implementation of [`BlockchainClient::wait_for_block`], polls the current block
+/// every [`WAIT_FOR_BLOCK_POLL_INTERVAL`] until `height` is reached.
+///
+/// # Errors
+/// Fails with [`BlockTimeout`] if `height` isn't reached within `timeout`.
+pub async fn poll_for_block<C: BlockchainClient>(
+    client: &C,
+    height: u64,
+    timeout: Duration,
+) -> Result<C::BlockIdentifier> {
+    let poll = async {
+        loop {
+            let block = client.current_block().await?;
+            if block.block_height() >= height {
+                return Ok(block);
+            }
+            futures_timer::Delay::new(WAIT_FOR_BLOCK_POLL_INTERVAL).await;
+        }
+    };
+    with_block_timeout(height, timeout, poll).await
+}
+
+/// Resolves `future`, or fails with [`BlockTimeout`] if it doesn't complete within `timeout`.
+///
+/// # Errors
+/// Fails with [`BlockTimeout`] on timeout, or with the error returned by `future`.
+pub async fn with_block_timeout<T, F>(height: u64, timeout: Duration, future: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let future = core::pin::pin!(future);
+    match future::select(future, futures_timer::Delay::new(timeout)).await {
+        future::Either::Left((result, _)) => result,
+        future::Either::Right(_) => Err(BlockTimeout { height, timeout }.into()),
+    }
 }
 
 #[async_trait]
@@ -235,6 +371,10 @@ where
         BlockchainClient::balance(Self::as_ref(self), address, block).await
     }
 
+    async fn balances(&self, addresses: &[Address], block: &Self::AtBlock) -> Result<Vec<u128>> {
+        BlockchainClient::balances(Self::as_ref(self), addresses, block).await
+    }
+
     async fn faucet(
         &self,
         address: &Address,
@@ -265,6 +405,14 @@ where
     async fn subscribe(&self, sub: &Self::Subscription) -> Result<u32> {
         BlockchainClient::subscribe(Self::as_ref(self), sub).await
     }
+
+    async fn wait_for_block(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<Self::BlockIdentifier> {
+        BlockchainClient::wait_for_block(Self::as_ref(self), height, timeout).await
+    }
 }
 
 pub trait RosettaAlgorithm {
@@ -326,4 +474,30 @@ pub trait TransactionBuilder: Default + Sized {
         metdata: &Self::Metadata,
         secret_key: &SecretKey,
     ) -> Vec<u8>;
+
+    /// Returns the payload which must be signed by the sender, used by signers that don't
+    /// expose their secret key, like hardware wallets.
+    ///
+    /// # Errors
+    /// Returns `Err` if the payload cannot be constructed.
+    fn signing_payload(
+        &self,
+        config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+    ) -> Result<Vec<u8>>;
+
+    /// Encodes the transaction using a signature of the payload returned by
+    /// [`TransactionBuilder::signing_payload`].
+    ///
+    /// # Errors
+    /// Returns `Err` if the signature or public key is not supported by the chain.
+    fn encode_signed(
+        &self,
+        config: &BlockchainConfig,
+        metadata_params: &Self::MetadataParams,
+        metadata: &Self::Metadata,
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> Result<Vec<u8>>;
 }
@@ -0,0 +1,46 @@
+/// Number of blocks a node may be behind the highest block known by its peers before the
+/// preflight check of the client constructors fails.
+pub const PREFLIGHT_MAX_BLOCKS_BEHIND: u64 = 10;
+
+/// Verifies the node is synced, `current_block` is the best block of the node and
+/// `highest_block` the highest block announced by its peers.
+///
+/// # Errors
+/// Returns `Err` if the node is more than `max_blocks_behind` blocks behind.
+pub fn check_synced(
+    current_block: u64,
+    highest_block: u64,
+    max_blocks_behind: u64,
+) -> anyhow::Result<()> {
+    let behind = highest_block.saturating_sub(current_block);
+    if behind > max_blocks_behind {
+        anyhow::bail!(
+            "node is not synced: best block {current_block} is {behind} blocks behind the highest \
+             block {highest_block}, at most {max_blocks_behind} are tolerated"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synced_within_tolerance() {
+        assert!(check_synced(100, 100, 0).is_ok());
+        assert!(check_synced(100, 110, 10).is_ok());
+        // The node may see blocks its peers didn't announce yet
+        assert!(check_synced(110, 100, 0).is_ok());
+    }
+
+    #[test]
+    fn out_of_sync() {
+        let error = check_synced(100, 111, 10).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "node is not synced: best block 100 is 11 blocks behind the highest block 111, at \
+             most 10 are tolerated"
+        );
+    }
+}
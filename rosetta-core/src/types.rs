@@ -7,8 +7,8 @@ use core::{
 use serde::{Deserialize, Serialize};
 
 pub use rosetta_types::{
-    AccountIdentifier, CallRequest, CurveType, Operation, OperationIdentifier, PublicKey,
-    SignatureType, TransactionIdentifier,
+    AccountIdentifier, Amount, CallRequest, CallResponse, Currency, CurveType, Operation,
+    OperationIdentifier, Peer, PublicKey, SignatureType, TransactionIdentifier,
 };
 
 use std::{fmt::Display, vec::Vec};
@@ -80,29 +80,88 @@ pub struct PartialBlockIdentifier {
     pub index: Option<u64>,
     #[serde(skip_serializing)]
     pub hash: Option<[u8; 32]>,
+    /// Refers to a block by its position in the chain, the index and hash are ignored when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<BlockTag>,
+}
+
+/// Block referred by its position in the chain instead of its index or hash, see
+/// [`PartialBlockIdentifier::tag`]. The current best block is referred by an empty
+/// [`PartialBlockIdentifier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockTag {
+    /// The latest finalized block.
+    Finalized,
+    /// The earliest block whose state the node serves, the genesis block unless the node
+    /// pruned the older states.
+    Earliest,
 }
 
 impl From<u64> for PartialBlockIdentifier {
     fn from(block_number: u64) -> Self {
-        Self { index: Some(block_number), hash: None }
+        Self::by_number(block_number)
     }
 }
 
 impl From<[u8; 32]> for PartialBlockIdentifier {
     fn from(block_hash: [u8; 32]) -> Self {
-        Self { index: None, hash: Some(block_hash) }
+        Self::by_hash(block_hash)
     }
 }
 
 impl From<&[u8; 32]> for PartialBlockIdentifier {
     fn from(block_hash: &[u8; 32]) -> Self {
-        Self { index: None, hash: Some(*block_hash) }
+        Self::by_hash(*block_hash)
     }
 }
 
 impl From<BlockIdentifier> for PartialBlockIdentifier {
     fn from(block_identifier: BlockIdentifier) -> Self {
-        Self { index: Some(block_identifier.index), hash: Some(block_identifier.hash) }
+        Self { index: Some(block_identifier.index), hash: Some(block_identifier.hash), tag: None }
+    }
+}
+
+impl From<&BlockIdentifier> for PartialBlockIdentifier {
+    fn from(block_identifier: &BlockIdentifier) -> Self {
+        Self { index: Some(block_identifier.index), hash: Some(block_identifier.hash), tag: None }
+    }
+}
+
+/// Error returned when converting an incomplete [`PartialBlockIdentifier`] into a
+/// [`BlockIdentifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BlockIdentifierError {
+    #[error("block index missing")]
+    MissingIndex,
+    #[error("block hash missing")]
+    MissingHash,
+    #[error("the finalized block tag doesn't identify a block")]
+    Finalized,
+    #[error("the earliest block tag doesn't identify a block")]
+    Earliest,
+}
+
+impl TryFrom<PartialBlockIdentifier> for BlockIdentifier {
+    type Error = BlockIdentifierError;
+
+    fn try_from(block_identifier: PartialBlockIdentifier) -> Result<Self, Self::Error> {
+        Self::try_from(&block_identifier)
+    }
+}
+
+impl TryFrom<&PartialBlockIdentifier> for BlockIdentifier {
+    type Error = BlockIdentifierError;
+
+    fn try_from(block_identifier: &PartialBlockIdentifier) -> Result<Self, Self::Error> {
+        match block_identifier.tag {
+            Some(BlockTag::Finalized) => return Err(BlockIdentifierError::Finalized),
+            Some(BlockTag::Earliest) => return Err(BlockIdentifierError::Earliest),
+            None => {},
+        }
+        let index = block_identifier.index.ok_or(BlockIdentifierError::MissingIndex)?;
+        let hash = block_identifier.hash.ok_or(BlockIdentifierError::MissingHash)?;
+        Ok(Self { index, hash })
     }
 }
 
@@ -112,7 +171,62 @@ impl PartialBlockIdentifier {
     /// the current block.
     #[must_use]
     pub const fn new() -> Self {
-        Self { index: None, hash: None }
+        Self { index: None, hash: None, tag: None }
+    }
+
+    /// Refers to the current best block, same as [`PartialBlockIdentifier::new`].
+    #[must_use]
+    pub const fn latest() -> Self {
+        Self::new()
+    }
+
+    /// Refers to the block with the given `hash`.
+    #[must_use]
+    pub const fn by_hash(hash: [u8; 32]) -> Self {
+        Self { index: None, hash: Some(hash), tag: None }
+    }
+
+    /// Refers to the block at the given height.
+    #[must_use]
+    pub const fn by_number(number: u64) -> Self {
+        Self { index: Some(number), hash: None, tag: None }
+    }
+
+    /// Refers to the block with the given `tag`.
+    #[must_use]
+    pub const fn by_tag(tag: BlockTag) -> Self {
+        Self { index: None, hash: None, tag: Some(tag) }
+    }
+
+    /// Returns `true` if neither the index, the hash nor the tag are specified, which refers to
+    /// the current best block.
+    #[must_use]
+    pub const fn is_latest(&self) -> bool {
+        matches!(self, Self { index: None, hash: None, tag: None })
+    }
+
+    /// Refers to the latest finalized block, see [`BlockTag::Finalized`].
+    #[must_use]
+    pub const fn finalized() -> Self {
+        Self::by_tag(BlockTag::Finalized)
+    }
+
+    /// Returns `true` if this refers to the latest finalized block.
+    #[must_use]
+    pub const fn is_finalized(&self) -> bool {
+        matches!(self.tag, Some(BlockTag::Finalized))
+    }
+
+    /// Refers to the earliest block the node serves, see [`BlockTag::Earliest`].
+    #[must_use]
+    pub const fn earliest() -> Self {
+        Self::by_tag(BlockTag::Earliest)
+    }
+
+    /// Returns `true` if this refers to the earliest block.
+    #[must_use]
+    pub const fn is_earliest(&self) -> bool {
+        matches!(self.tag, Some(BlockTag::Earliest))
     }
 }
 
@@ -128,3 +242,101 @@ pub struct Transaction {
     /// Raw transaction bytes
     pub raw_tx_receipt: Option<Vec<u8>>,
 }
+
+/// Status of operations performed by a successful transaction, or fee payments.
+pub const OPERATION_STATUS_SUCCESS: &str = "SUCCESS";
+/// Status of operations performed by a failed transaction, they have no effect on balances.
+pub const OPERATION_STATUS_FAILURE: &str = "FAILURE";
+
+/// Returns the identifier of the operation at `index` in its transaction.
+#[allow(clippy::cast_possible_wrap)]
+#[must_use]
+pub const fn operation_identifier(index: usize) -> OperationIdentifier {
+    OperationIdentifier::new(index as i64)
+}
+
+/// A transaction included in a block and the operations it performed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTransaction {
+    pub transaction_identifier: TransactionIdentifier,
+    /// Index of the transaction in the block.
+    pub index: u64,
+    /// `true` if the transaction didn't fail, the operations of a failed transaction have the
+    /// [`OPERATION_STATUS_FAILURE`] status, except for the fee payment.
+    pub success: bool,
+    pub operations: Vec<Operation>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_block_identifier_constructors() {
+        assert_eq!(PartialBlockIdentifier::latest(), PartialBlockIdentifier::default());
+        assert!(PartialBlockIdentifier::latest().is_latest());
+
+        let by_number = PartialBlockIdentifier::by_number(42);
+        assert_eq!(by_number, PartialBlockIdentifier { index: Some(42), hash: None, tag: None });
+        assert_eq!(by_number, PartialBlockIdentifier::from(42));
+        assert!(!by_number.is_latest());
+
+        let by_hash = PartialBlockIdentifier::by_hash([1; 32]);
+        assert_eq!(by_hash, PartialBlockIdentifier { index: None, hash: Some([1; 32]), tag: None });
+        assert_eq!(by_hash, PartialBlockIdentifier::from(&[1; 32]));
+        assert!(!by_hash.is_latest());
+
+        assert!(!PartialBlockIdentifier::finalized().is_latest());
+        assert!(PartialBlockIdentifier::earliest().is_earliest());
+        assert!(!PartialBlockIdentifier::earliest().is_finalized());
+        assert!(!PartialBlockIdentifier::by_number(0).is_earliest());
+
+        // The tags don't reserve any block index
+        assert!(!PartialBlockIdentifier::by_number(u64::MAX).is_finalized());
+        assert!(!PartialBlockIdentifier::by_number(u64::MAX - 1).is_earliest());
+    }
+
+    #[test]
+    fn partial_block_identifier_serde() {
+        let finalized = serde_json::to_value(PartialBlockIdentifier::finalized()).unwrap();
+        assert_eq!(finalized, serde_json::json!({ "tag": "finalized" }));
+        let by_number = serde_json::to_value(PartialBlockIdentifier::by_number(42)).unwrap();
+        assert_eq!(by_number, serde_json::json!({ "index": 42 }));
+
+        let earliest: PartialBlockIdentifier =
+            serde_json::from_value(serde_json::json!({ "tag": "earliest" })).unwrap();
+        assert!(earliest.is_earliest());
+        let latest: PartialBlockIdentifier = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(latest.is_latest());
+    }
+
+    #[test]
+    fn block_identifier_conversions() {
+        let block = BlockIdentifier::new(42, [1; 32]);
+        let partial = PartialBlockIdentifier::from(&block);
+        assert_eq!(
+            partial,
+            PartialBlockIdentifier { index: Some(42), hash: Some([1; 32]), tag: None }
+        );
+        assert_eq!(partial, PartialBlockIdentifier::from(block.clone()));
+        assert_eq!(BlockIdentifier::try_from(&partial), Ok(block.clone()));
+        assert_eq!(BlockIdentifier::try_from(partial), Ok(block));
+
+        assert_eq!(
+            BlockIdentifier::try_from(PartialBlockIdentifier::by_hash([1; 32])),
+            Err(BlockIdentifierError::MissingIndex)
+        );
+        assert_eq!(
+            BlockIdentifier::try_from(PartialBlockIdentifier::by_number(42)),
+            Err(BlockIdentifierError::MissingHash)
+        );
+        assert_eq!(
+            BlockIdentifier::try_from(PartialBlockIdentifier::finalized()),
+            Err(BlockIdentifierError::Finalized)
+        );
+        assert_eq!(
+            BlockIdentifier::try_from(PartialBlockIdentifier::earliest()),
+            Err(BlockIdentifierError::Earliest)
+        );
+    }
+}
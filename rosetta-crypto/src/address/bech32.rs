@@ -1,4 +1,5 @@
-use bech32::{u5, ToBase32, Variant};
+use crate::error::AddressError;
+use bech32::{u5, FromBase32, ToBase32, Variant};
 use sha2::Digest;
 
 #[allow(clippy::unwrap_used)]
@@ -11,10 +12,75 @@ pub fn bech32_encode(hrp: &str, public_key: &[u8]) -> String {
     bech32::encode(hrp, bytes, Variant::Bech32).unwrap()
 }
 
+/// Encodes the `ripemd160(sha256(public_key))` account id used by Cosmos SDK chains, unlike
+/// [`bech32_encode`] the payload has no segwit version.
+pub fn cosmos_encode(hrp: &str, public_key: &[u8]) -> Result<String, AddressError> {
+    let sha2 = sha2::Sha256::digest(public_key);
+    let ripemd = ripemd::Ripemd160::digest(sha2);
+    bech32::encode(hrp, ripemd.to_base32(), Variant::Bech32)
+        .map_err(|_| AddressError::InvalidBech32Prefix)
+}
+
+/// Decodes the account id of a Cosmos SDK `address`, its human readable prefix must be `hrp`.
+pub fn cosmos_decode(hrp: &str, address: &str) -> Result<[u8; 20], AddressError> {
+    let (prefix, data, variant) =
+        bech32::decode(address).map_err(|_| AddressError::FailedToDecodeAddress)?;
+    if !prefix.eq_ignore_ascii_case(hrp) {
+        return Err(AddressError::InvalidBech32Prefix);
+    }
+    if variant != Variant::Bech32 {
+        return Err(AddressError::FailedToDecodeAddress);
+    }
+    let account_id =
+        Vec::<u8>::from_base32(&data).map_err(|_| AddressError::FailedToDecodeAddress)?;
+    <[u8; 20]>::try_from(account_id).map_err(|_| AddressError::FailedToDecodeAddress)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Public keys and addresses from the cosmjs `pubkeyToAddress` tests.
+    const COSMOS_VECTORS: [(&str, &str); 2] = [
+        (
+            "034f04181eeba35391b858633a765c4a0c189697b40d216354d50890d350c70290",
+            "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs6",
+        ),
+        (
+            "02d41a0aa167b21699429eab224bc03f2cd386f0af5d20cefbd0336f1544aea24f",
+            "cosmos1h806c7khnvmjlywdrkdgk2vrayy2mmvf9rxk2r",
+        ),
+    ];
+
+    #[test]
+    fn cosmos_addresses() {
+        for (public_key, address) in COSMOS_VECTORS {
+            let public_key = hex::decode(public_key).unwrap();
+            assert_eq!(cosmos_encode("cosmos", &public_key).unwrap(), address);
+
+            // The same account on another Cosmos SDK chain only differs by its prefix
+            let account_id = cosmos_decode("cosmos", address).unwrap();
+            let terra = cosmos_encode("terra", &public_key).unwrap();
+            assert!(terra.starts_with("terra1"));
+            assert_eq!(cosmos_decode("terra", &terra).unwrap(), account_id);
+        }
+    }
+
+    #[test]
+    fn cosmos_decode_validates_the_prefix() {
+        let (_, address) = COSMOS_VECTORS[0];
+        assert_eq!(cosmos_decode("terra", address), Err(AddressError::InvalidBech32Prefix));
+        assert_eq!(
+            cosmos_decode("cosmos", "cosmos1pkptre7fdkl6gfrzlesjjvhxhlc3r4gmmk8rs7"),
+            Err(AddressError::FailedToDecodeAddress)
+        );
+        // Segwit addresses carry a version before the account id
+        assert_eq!(
+            cosmos_decode("bcrt", "bcrt1qsqxddufe9qz0phxnntsgytg3wr8sl9z4czyj5k"),
+            Err(AddressError::FailedToDecodeAddress)
+        );
+    }
+
     #[test]
     fn test_derive() {
         let pubkey = "0x03f349dec2b5205707c778534a7f134125ea31e82134e5aa987417f1091103e263";
@@ -0,0 +1,140 @@
+use super::{eip55::eip55_encode_bytes, Address, AddressFormat};
+use crate::error::AddressError;
+use sha3::Digest;
+use sp_core::H160;
+
+/// Parses an [`AddressFormat::Eip55`] address into its 20 bytes representation.
+fn evm_address_bytes(address: &Address) -> Result<H160, AddressError> {
+    if address.format != AddressFormat::Eip55 {
+        return Err(AddressError::InvalidAddressFormat);
+    }
+    address.address.parse().map_err(|_| AddressError::FailedToDecodeAddress)
+}
+
+/// Computes the address of a contract deployed by `sender` using the `CREATE` opcode, which is
+/// `keccak256(rlp([sender, nonce]))[12..]`.
+///
+/// # Errors
+/// Will return `Err` when `sender` is not a valid [`AddressFormat::Eip55`] address.
+#[allow(clippy::cast_possible_truncation)]
+pub fn compute_create_address(sender: &Address, nonce: u64) -> Result<Address, AddressError> {
+    let sender = evm_address_bytes(sender)?;
+
+    // rlp encoded nonce, big endian without leading zeros
+    let nonce_bytes = nonce.to_be_bytes();
+    let nonce_bytes = &nonce_bytes[nonce_bytes.iter().take_while(|b| **b == 0).count()..];
+    let mut encoded_nonce = Vec::with_capacity(9);
+    match nonce_bytes {
+        [] => encoded_nonce.push(0x80),
+        [byte] if *byte < 0x80 => encoded_nonce.push(*byte),
+        bytes => {
+            encoded_nonce.push(0x80 + bytes.len() as u8);
+            encoded_nonce.extend_from_slice(bytes);
+        },
+    }
+
+    // rlp list header, the payload is always shorter than 56 bytes
+    let payload_len = (1 + sender.as_bytes().len() + encoded_nonce.len()) as u8;
+    let mut rlp = Vec::with_capacity(usize::from(payload_len) + 1);
+    rlp.push(0xc0 + payload_len);
+    rlp.push(0x94);
+    rlp.extend_from_slice(sender.as_bytes());
+    rlp.extend_from_slice(&encoded_nonce);
+
+    let digest = sha3::Keccak256::digest(&rlp);
+    Ok(Address::new(AddressFormat::Eip55, eip55_encode_bytes(&digest[12..])))
+}
+
+/// Computes the address of a contract deployed by `sender` using the `CREATE2` opcode, as
+/// specified by [EIP-1014](https://eips.ethereum.org/EIPS/eip-1014).
+///
+/// # Errors
+/// Will return `Err` when `sender` is not a valid [`AddressFormat::Eip55`] address.
+pub fn compute_create2_address(
+    sender: &Address,
+    salt: [u8; 32],
+    init_code_hash: [u8; 32],
+) -> Result<Address, AddressError> {
+    let sender = evm_address_bytes(sender)?;
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update([0xff]);
+    hasher.update(sender.as_bytes());
+    hasher.update(salt);
+    hasher.update(init_code_hash);
+    let digest = hasher.finalize();
+    Ok(Address::new(AddressFormat::Eip55, eip55_encode_bytes(&digest[12..])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(address: &str) -> Address {
+        Address::new(AddressFormat::Eip55, address.into())
+    }
+
+    #[test]
+    fn create_address_vectors() {
+        let sender = address("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0");
+        let vectors = [
+            (0, "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"),
+            (1, "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"),
+            (2, "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91"),
+            (3, "0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c"),
+        ];
+        for (nonce, expected) in vectors {
+            let contract = compute_create_address(&sender, nonce).unwrap();
+            assert_eq!(contract.format(), AddressFormat::Eip55);
+            assert_eq!(contract.address().to_lowercase(), expected);
+        }
+    }
+
+    #[test]
+    fn create2_address_vectors() {
+        // https://eips.ethereum.org/EIPS/eip-1014#examples
+        let vectors: [(&str, &str, &[u8], &str); 4] = [
+            (
+                "0x0000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                &[0x00],
+                "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38",
+            ),
+            (
+                "0xdeadbeef00000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                &[0x00],
+                "0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3",
+            ),
+            (
+                "0xdeadbeef00000000000000000000000000000000",
+                "000000000000000000000000feed000000000000000000000000000000000000",
+                &[0x00],
+                "0xD04116cDd17beBE565EB2422F2497E06cC1C9833",
+            ),
+            (
+                "0x0000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                &[],
+                "0xE33C0C7F7df4809055C3ebA6c09CFe4BaF1BD9e0",
+            ),
+        ];
+        for (sender, salt, init_code, expected) in vectors {
+            let mut salt_bytes = [0u8; 32];
+            hex::decode_to_slice(salt, &mut salt_bytes).unwrap();
+            let mut init_code_hash = [0u8; 32];
+            init_code_hash.copy_from_slice(&sha3::Keccak256::digest(init_code));
+            let contract =
+                compute_create2_address(&address(sender), salt_bytes, init_code_hash).unwrap();
+            assert_eq!(contract.address(), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_non_evm_sender() {
+        let sender = Address::new(
+            AddressFormat::Bech32("bc"),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+        );
+        assert_eq!(compute_create_address(&sender, 0), Err(AddressError::InvalidAddressFormat));
+    }
+}
@@ -11,7 +11,7 @@ pub fn eip55_encode(public_key: &[u8]) -> String {
 }
 
 #[allow(clippy::unwrap_used)]
-fn eip55_encode_bytes(bytes: &[u8]) -> String {
+pub fn eip55_encode_bytes(bytes: &[u8]) -> String {
     let address = hex::encode(bytes);
     let hashed_address = hex::encode(sha3::Keccak256::digest(&address));
     let mut result = String::with_capacity(42);
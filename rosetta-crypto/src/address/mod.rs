@@ -7,9 +7,11 @@ use sp_core::{
 };
 
 mod bech32;
+mod contract;
 mod eip55;
 mod ss58;
 
+pub use contract::{compute_create2_address, compute_create_address};
 pub use ss58::{Ss58AddressFormat, Ss58AddressFormatRegistry};
 
 /// Address format.
@@ -60,6 +62,32 @@ impl Address {
         Self::new(format, address)
     }
 
+    /// Formats the public key as a Cosmos SDK address, the bech32 encoding of
+    /// `ripemd160(sha256(public_key))` with the human readable prefix `hrp`, ex: `cosmos`.
+    ///
+    /// Unlike the [`AddressFormat::Bech32`] addresses of [`Self::from_public_key_bytes`], the
+    /// payload has no segwit version.
+    ///
+    /// # Errors
+    /// Will return `Err` when `hrp` isn't a valid bech32 human readable prefix.
+    pub fn from_public_key_bech32(
+        hrp: &'static str,
+        public_key: &[u8],
+    ) -> Result<Self, AddressError> {
+        let address = bech32::cosmos_encode(hrp, public_key)?;
+        Ok(Self::new(AddressFormat::Bech32(hrp), address))
+    }
+
+    /// Parses a Cosmos SDK `address`, see [`Self::from_public_key_bech32`].
+    ///
+    /// # Errors
+    /// Will return `Err` when the human readable prefix of `address` isn't `hrp`, or `address`
+    /// isn't a valid bech32 address.
+    pub fn from_bech32(hrp: &'static str, address: &str) -> Result<Self, AddressError> {
+        bech32::cosmos_decode(hrp, address)?;
+        Ok(Self::new(AddressFormat::Bech32(hrp), address.to_ascii_lowercase()))
+    }
+
     /// Converts an EVM address to its corresponding SS58 address.
     /// reference: [evmToAddress.ts](https://github.com/polkadot-js/common/blob/v12.3.2/packages/util-crypto/src/address/evmToAddress.ts)
     ///
@@ -275,6 +275,22 @@ impl DerivedSecretKey {
             _ => self.bip32_derive(child),
         }
     }
+
+    /// Hard derives a child key from a named substrate junction, ex: `Alice` for `//Alice`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the [`SecretKey`] isn't sr25519
+    pub fn derive_hard_junction(&self, junction: &str) -> Result<Self> {
+        let SecretKey::Sr25519(secret, _) = &self.secret_key else {
+            anyhow::bail!("only sr25519 supports substrate junctions");
+        };
+        let chain_code = sp_core::crypto::DeriveJunction::hard(junction).unwrap_inner();
+        let (minisecret, _) = secret
+            .hard_derive_mini_secret_key(Some(schnorrkel::derive::ChainCode(chain_code)), b"");
+        let secret = minisecret.expand_to_keypair(schnorrkel::MiniSecretKey::ED25519_MODE);
+        Ok(Self { secret_key: SecretKey::Sr25519(secret, Some(minisecret)), chain_code })
+    }
 }
 
 /// Public key and chain code used for hierarchical key derivation.
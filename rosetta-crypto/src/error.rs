@@ -8,4 +8,7 @@ pub enum AddressError {
 
     #[error("Failed to decode address")]
     FailedToDecodeAddress,
+
+    #[error("Invalid bech32 human readable prefix")]
+    InvalidBech32Prefix,
 }
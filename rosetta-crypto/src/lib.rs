@@ -11,6 +11,7 @@ use ecdsa::{
 use sha2::Digest;
 
 pub mod address;
+pub use address::{compute_create2_address, compute_create_address};
 pub mod bip32;
 pub use bip39;
 pub mod bip44;
@@ -125,6 +126,23 @@ impl SecretKey {
         })
     }
 
+    /// Creates an Ed25519 secret key from a separate secret and public key pair.
+    ///
+    /// # Errors
+    /// Will return `Err` if `public` doesn't match the public key derived from `secret`
+    pub fn from_ed25519_parts(secret: &[u8; 32], public: &[u8; 32]) -> Result<Self> {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(secret);
+        let derived = signing_key.verifying_key();
+        if derived.as_bytes() != public {
+            anyhow::bail!(
+                "invalid Ed25519 keypair, public key {} doesn't match the secret key, expected {}",
+                hex::encode(public),
+                hex::encode(derived.as_bytes())
+            );
+        }
+        Ok(Self::Ed25519(signing_key))
+    }
+
     /// Returns a byte sequence representing the secret key.
     #[must_use]
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -153,6 +171,30 @@ impl SecretKey {
         }
     }
 
+    /// Adds the scalar `tweak` to a secp256k1 secret key, the public key of the tweaked key is
+    /// the [`PublicKey::add_tweak`] of the public key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when:
+    /// - The key isn't a secp256k1 key
+    /// - The `tweak` isn't lower than the curve order
+    /// - The tweaked secret key is zero
+    pub fn add_tweak(&self, tweak: &[u8; 32]) -> Result<Self> {
+        let (Self::EcdsaSecp256k1(secret) | Self::EcdsaRecoverableSecp256k1(secret)) = self else {
+            anyhow::bail!("key tweaking is only supported by secp256k1 keys");
+        };
+        let scalar = secret.as_nonzero_scalar().as_ref() + secp256k1_tweak(tweak)?;
+        let scalar = Option::<k256::NonZeroScalar>::from(k256::NonZeroScalar::new(scalar))
+            .context("the tweaked secret key is zero")?;
+        let secret = ecdsa::SigningKey::from(scalar);
+        Ok(if self.algorithm().is_recoverable() {
+            Self::EcdsaRecoverableSecp256k1(secret)
+        } else {
+            Self::EcdsaSecp256k1(secret)
+        })
+    }
+
     /// Signs a message and returns it's signature.
     #[must_use]
     #[allow(clippy::missing_panics_doc)]
@@ -286,6 +328,23 @@ impl PublicKey {
     /// - Signature is invalid
     /// - The `sig` type doesn't match `self` type.
     pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<()> {
+        self.verify_with_context(msg, sig, "")
+    }
+
+    /// Verifies a signature made with [`SecretKey::sign`] using the signing context
+    /// `context_param`, which is only used by [`PublicKey::Sr25519`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when:
+    /// - Signature is invalid
+    /// - The `sig` type doesn't match `self` type.
+    pub fn verify_with_context(
+        &self,
+        msg: &[u8],
+        sig: &Signature,
+        context_param: &str,
+    ) -> Result<()> {
         match (self, &sig) {
             (Self::EcdsaSecp256k1(public), Signature::EcdsaSecp256k1(sig)) => {
                 public.verify(msg, sig)?;
@@ -299,12 +358,44 @@ impl PublicKey {
             },
             (Self::Ed25519(public), Signature::Ed25519(sig)) => public.verify(msg, sig)?,
             (Self::Sr25519(public), Signature::Sr25519(sig)) => {
-                public.verify_simple(&[], msg, sig).map_err(|err| anyhow::anyhow!("{}", err))?;
+                public
+                    .verify_simple(context_param.as_bytes(), msg, sig)
+                    .map_err(|err| anyhow::anyhow!("{}", err))?;
             },
             (_, _) => anyhow::bail!("unsupported signature scheme"),
         };
         Ok(())
     }
+
+    /// Adds the point `tweak * G` to a secp256k1 public key, see [`SecretKey::add_tweak`].
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when:
+    /// - The key isn't a secp256k1 key
+    /// - The `tweak` isn't lower than the curve order
+    /// - The tweaked public key is the point at infinity
+    pub fn add_tweak(&self, tweak: &[u8; 32]) -> Result<Self> {
+        let (Self::EcdsaSecp256k1(public) | Self::EcdsaRecoverableSecp256k1(public)) = self else {
+            anyhow::bail!("key tweaking is only supported by secp256k1 keys");
+        };
+        let point = k256::ProjectivePoint::from(public.as_affine()) +
+            k256::ProjectivePoint::GENERATOR * secp256k1_tweak(tweak)?;
+        let public = ecdsa::VerifyingKey::from_affine(point.to_affine())
+            .context("the tweaked public key is the point at infinity")?;
+        Ok(if self.algorithm().is_recoverable() {
+            Self::EcdsaRecoverableSecp256k1(public)
+        } else {
+            Self::EcdsaSecp256k1(public)
+        })
+    }
+}
+
+/// Parses a secp256k1 key tweak, rejects the tweaks which aren't lower than the curve order.
+fn secp256k1_tweak(tweak: &[u8; 32]) -> Result<k256::Scalar> {
+    use k256::elliptic_curve::PrimeField;
+    Option::<k256::Scalar>::from(k256::Scalar::from_repr((*tweak).into()))
+        .context("the tweak is out of range")
 }
 
 /// Signature.
@@ -431,6 +522,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn ed25519_parts() -> Result<()> {
+        let mut rng = thread_rng();
+        let mut secret = [0; 32];
+        rng.fill_bytes(&mut secret);
+        let public_key = SecretKey::from_bytes(Algorithm::Ed25519, &secret)?.public_key();
+        let mut public = [0; 32];
+        public.copy_from_slice(&public_key.to_bytes());
+
+        let secret_key = SecretKey::from_ed25519_parts(&secret, &public)?;
+        assert_eq!(secret_key.public_key(), public_key);
+        assert_eq!(secret_key.to_bytes(), secret);
+
+        // Corrupted public key
+        public[0] ^= 0xff;
+        assert!(SecretKey::from_ed25519_parts(&secret, &public).is_err());
+        Ok(())
+    }
+
     #[test]
     fn public_key_bytes() -> Result<()> {
         let mut rng = thread_rng();
@@ -479,6 +589,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sign_verify_with_context() -> Result<()> {
+        let mut rng = thread_rng();
+        let mut secret = [0; 32];
+        rng.fill_bytes(&mut secret);
+        let mut msg = [0; 32];
+        rng.fill_bytes(&mut msg);
+        let secret_key = SecretKey::from_bytes(Algorithm::Sr25519, &secret[..])?;
+        let public_key = secret_key.public_key();
+        let signature = secret_key.sign(&msg, "substrate");
+        public_key.verify_with_context(&msg, &signature, "substrate")?;
+        assert!(public_key.verify(&msg, &signature).is_err());
+        Ok(())
+    }
+
     #[test]
     fn sign_recover_pubkey() -> Result<()> {
         let mut rng = thread_rng();
@@ -493,4 +618,49 @@ mod tests {
         assert_eq!(public_key, recovered_key);
         Ok(())
     }
+
+    #[test]
+    fn add_tweak() -> Result<()> {
+        let mut rng = thread_rng();
+        let mut secret = [0; 32];
+        rng.fill_bytes(&mut secret);
+        let mut tweak = [0; 32];
+        rng.fill_bytes(&mut tweak);
+        for algorithm in [Algorithm::EcdsaSecp256k1, Algorithm::EcdsaRecoverableSecp256k1] {
+            let secret_key = SecretKey::from_bytes(algorithm, &secret[..])?;
+            let tweaked = secret_key.add_tweak(&tweak)?;
+            assert_eq!(tweaked.algorithm(), algorithm);
+            assert_ne!(tweaked.to_bytes(), secret_key.to_bytes());
+            assert_eq!(tweaked.public_key(), secret_key.public_key().add_tweak(&tweak)?);
+        }
+        for algorithm in [Algorithm::EcdsaSecp256r1, Algorithm::Ed25519, Algorithm::Sr25519] {
+            let secret_key = SecretKey::from_bytes(algorithm, &secret[..])?;
+            assert!(secret_key.add_tweak(&tweak).is_err());
+            assert!(secret_key.public_key().add_tweak(&tweak).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn add_tweak_rejects_invalid_tweaks() -> Result<()> {
+        use k256::elliptic_curve::PrimeField;
+
+        let mut rng = thread_rng();
+        let mut secret = [0; 32];
+        rng.fill_bytes(&mut secret);
+        let secret_key = SecretKey::from_bytes(Algorithm::EcdsaSecp256k1, &secret[..])?;
+        let public_key = secret_key.public_key();
+
+        // Not lower than the curve order
+        assert!(secret_key.add_tweak(&[0xff; 32]).is_err());
+        assert!(public_key.add_tweak(&[0xff; 32]).is_err());
+
+        // The negated secret key cancels it out
+        let scalar = Option::<k256::Scalar>::from(k256::Scalar::from_repr(secret.into())).unwrap();
+        let mut tweak = [0; 32];
+        tweak.copy_from_slice(&(-scalar).to_bytes());
+        assert!(secret_key.add_tweak(&tweak).is_err());
+        assert!(public_key.add_tweak(&tweak).is_err());
+        Ok(())
+    }
 }
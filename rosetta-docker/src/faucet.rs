@@ -0,0 +1,111 @@
+use anyhow::Result;
+use rosetta_client::Wallet;
+use std::future::Future;
+
+/// Faucets the missing amount to reach the `target` balance, then verifies the balance reached
+/// it. If the faucet under-delivered, faucets the remaining amount once more before giving up.
+///
+/// # Errors
+/// Returns `Err` if the balance is still below `target` after the retry, or if reading the
+/// balance fails.
+#[allow(clippy::future_not_send)]
+pub async fn top_up<F, FFut, B, BFut>(target: u128, mut faucet: F, mut balance: B) -> Result<u128>
+where
+    F: FnMut(u128) -> FFut,
+    FFut: Future<Output = Result<()>>,
+    B: FnMut() -> BFut,
+    BFut: Future<Output = Result<u128>>,
+{
+    let mut current = balance().await?;
+    for attempt in 0..2 {
+        if current >= target {
+            return Ok(current);
+        }
+        if attempt > 0 {
+            log::warn!("faucet under-delivered, balance {current} is below {target}, retrying");
+        }
+        // The balance check below reports a short faucet
+        if let Err(error) = faucet(target - current).await {
+            log::warn!("faucet failed: {error}");
+        }
+        current = balance().await?;
+    }
+    if current < target {
+        anyhow::bail!(
+            "faucet under-delivered, balance {current} is still below {target} after retry"
+        );
+    }
+    Ok(current)
+}
+
+/// Same as [`top_up`], using the faucet of the `wallet` chain.
+///
+/// # Errors
+/// Returns `Err` if the wallet balance is still below `target` after the retry.
+#[allow(clippy::future_not_send)]
+pub async fn top_up_wallet(wallet: &Wallet, target: u128) -> Result<u128> {
+    top_up(
+        target,
+        |amount| async move { wallet.faucet(amount, None).await.map(|_| ()) },
+        || wallet.balance(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, future::ready};
+    use std::cell::Cell;
+
+    /// Simulated chain whose faucet delivers `delivered(call index, requested amount)`.
+    struct Simulated {
+        balance: Cell<u128>,
+        calls: Cell<usize>,
+        delivered: fn(usize, u128) -> u128,
+    }
+
+    impl Simulated {
+        const fn new(delivered: fn(usize, u128) -> u128) -> Self {
+            Self { balance: Cell::new(0), calls: Cell::new(0), delivered }
+        }
+
+        fn top_up(&self, target: u128) -> Result<u128> {
+            let faucet = |amount| {
+                let call = self.calls.replace(self.calls.get() + 1);
+                self.balance.set(self.balance.get() + (self.delivered)(call, amount));
+                ready(Ok(()))
+            };
+            block_on(top_up(target, faucet, || ready(Ok(self.balance.get()))))
+        }
+    }
+
+    #[test]
+    fn reaches_target_when_the_first_faucet_under_delivers() {
+        let chain = Simulated::new(|call, amount| if call == 0 { amount / 2 } else { amount });
+        assert_eq!(chain.top_up(1000).unwrap(), 1000);
+        assert_eq!(chain.calls.get(), 2);
+    }
+
+    #[test]
+    fn faucets_once_when_it_delivers() {
+        let chain = Simulated::new(|_, amount| amount);
+        assert_eq!(chain.top_up(1000).unwrap(), 1000);
+        assert_eq!(chain.calls.get(), 1);
+
+        // Nothing to do once the target is reached
+        assert_eq!(chain.top_up(500).unwrap(), 1000);
+        assert_eq!(chain.calls.get(), 1);
+    }
+
+    #[test]
+    fn fails_when_still_short_after_retry() {
+        let chain = Simulated::new(|_, amount| amount / 2);
+        let error = chain.top_up(1000).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "faucet under-delivered, balance 750 is still below 1000 after retry"
+        );
+        assert_eq!(chain.calls.get(), 2);
+    }
+}
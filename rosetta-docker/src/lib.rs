@@ -1,28 +1,78 @@
 mod config;
+mod faucet;
+mod logs;
 
 use anyhow::{Context, Result};
 use docker_api::{
-    conn::TtyChunk,
     opts::{
         ContainerCreateOpts, ContainerListOpts, ContainerStopOpts, HostPort, LogsOpts, PublishPort,
     },
     ApiVersion, Container, Docker,
 };
-use futures::stream::StreamExt;
+use logs::forward_logs;
 use rosetta_client::Wallet;
 use rosetta_core::{BlockchainClient, BlockchainConfig};
-use std::{future::Future, sync::Arc, time::Duration};
-use tokio_retry::{strategy::ExponentialBackoff, RetryIf};
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
+use tokio_retry::{strategy::FixedInterval, RetryIf};
+
+pub use faucet::{top_up, top_up_wallet};
+pub use logs::{ContainerLogs, SUMMARY_LINES};
 
 pub struct Env<T> {
     client: Arc<T>,
     node: Container,
 }
 
+/// Controls how long to wait for the node to be ready after its container started.
+///
+/// Nodes exposing an `http` or `ws` endpoint are polled until the endpoint responds, other
+/// nodes are considered ready if the container is still running after the last attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HealthCheck {
+    /// Delay before the first attempt.
+    pub initial_delay: Duration,
+    /// Delay between two consecutive attempts.
+    pub poll_interval: Duration,
+    /// Maximum number of attempts before giving up.
+    pub max_attempts: usize,
+}
+
+impl HealthCheck {
+    /// Returns the default health check for a node exposing an endpoint with the given `scheme`.
+    #[must_use]
+    pub fn for_scheme(scheme: &str) -> Self {
+        match scheme {
+            "http" | "https" | "ws" | "wss" => Self {
+                initial_delay: Duration::ZERO,
+                poll_interval: Duration::from_millis(500),
+                max_attempts: 80,
+            },
+            // Wait 15 seconds to guarantee the node didn't crash
+            _ => Self {
+                initial_delay: Duration::from_secs(15),
+                poll_interval: Duration::from_secs(1),
+                max_attempts: 1,
+            },
+        }
+    }
+}
+
 impl<T: BlockchainClient> Env<T> {
     #[allow(clippy::missing_errors_doc)]
     pub async fn new<Fut, F>(
         prefix: &str,
+        config: BlockchainConfig,
+        start_connector: F,
+    ) -> Result<Self>
+    where
+        Fut: Future<Output = Result<T>> + Send,
+        F: FnMut(BlockchainConfig) -> Fut + Send,
+    {
+        EnvBuilder::new(prefix)?.build(config, start_connector).await
+    }
+
+    async fn start<Fut, F>(
+        builder: EnvBuilder<'_>,
         mut config: BlockchainConfig,
         start_connector: F,
     ) -> Result<Self>
@@ -30,7 +80,6 @@ impl<T: BlockchainClient> Env<T> {
         Fut: Future<Output = Result<T>> + Send,
         F: FnMut(BlockchainConfig) -> Fut + Send,
     {
-        let builder = EnvBuilder::new(prefix)?;
         let node_port = random_port();
         config.node_uri.port = node_port;
         log::info!("node: {}", node_port);
@@ -64,6 +113,17 @@ impl<T: BlockchainClient> Env<T> {
         Wallet::from_config(config, &node_uri, None, None).await
     }
 
+    /// Stops the node container, keeping the connector alive, ex: to test how the connector
+    /// handles the node going away.
+    ///
+    /// # Errors
+    /// Will return `Err` if it fails to stop the container for some reason
+    pub async fn stop_node(&self) -> Result<()> {
+        let opts = ContainerStopOpts::builder().build();
+        self.node.stop(&opts).await?;
+        Ok(())
+    }
+
     /// Stop all containers
     ///
     /// # Errors
@@ -75,17 +135,78 @@ impl<T: BlockchainClient> Env<T> {
     }
 }
 
-struct EnvBuilder<'a> {
+/// Configures how the node container of an [`Env`] is started, ex:
+///
+/// ```ignore
+/// let env = EnvBuilder::new("my-test")?
+///     .health_check(health_check)
+///     .mounts(mounts)
+///     .build(config, start_connector)
+///     .await?;
+/// ```
+pub struct EnvBuilder<'a> {
     prefix: &'a str,
     docker: Docker,
+    health_check: Option<HealthCheck>,
+    container_logs: ContainerLogs,
+    mounts: Vec<(PathBuf, String)>,
 }
 
 impl<'a> EnvBuilder<'a> {
+    /// Creates a builder whose containers names start with `prefix`.
+    ///
+    /// # Errors
+    /// Returns `Err` if the docker endpoint is invalid.
     pub fn new(prefix: &'a str) -> Result<Self> {
         let version = ApiVersion::new(1, Some(41), None);
         let endpoint = config::docker_endpoint();
         let docker = Docker::new_versioned(endpoint, version)?;
-        Ok(Self { prefix, docker })
+        Ok(Self {
+            prefix,
+            docker,
+            health_check: None,
+            container_logs: ContainerLogs::default(),
+            mounts: Vec::new(),
+        })
+    }
+
+    /// Overrides the default [`HealthCheck`] of the node scheme.
+    #[must_use]
+    pub const fn health_check(mut self, health_check: HealthCheck) -> Self {
+        self.health_check = Some(health_check);
+        self
+    }
+
+    /// Overrides where the containers output is forwarded, see [`ContainerLogs`].
+    #[must_use]
+    pub fn container_logs(mut self, container_logs: ContainerLogs) -> Self {
+        self.container_logs = container_logs;
+        self
+    }
+
+    /// Bind mounts each `(host_path, container_path)` read-only into the node container, ex: a
+    /// chain spec referenced by the node command.
+    #[must_use]
+    pub fn mounts(mut self, mounts: Vec<(PathBuf, String)>) -> Self {
+        self.mounts = mounts;
+        self
+    }
+
+    /// Starts the node container and the connector, see [`Env::new`].
+    ///
+    /// # Errors
+    /// Returns `Err` if the node or the connector fail to start.
+    pub async fn build<T, Fut, F>(
+        self,
+        config: BlockchainConfig,
+        start_connector: F,
+    ) -> Result<Env<T>>
+    where
+        T: BlockchainClient,
+        Fut: Future<Output = Result<T>> + Send,
+        F: FnMut(BlockchainConfig) -> Fut + Send,
+    {
+        Env::start(self, config, start_connector).await
     }
 
     fn node_name(&self, config: &BlockchainConfig) -> String {
@@ -123,26 +244,10 @@ impl<'a> EnvBuilder<'a> {
 
         log::info!("starting {}", name);
         let container = Container::new(self.docker.clone(), id.clone());
+        let container_logs = self.container_logs.clone();
         tokio::task::spawn(async move {
             let opts = LogsOpts::builder().all().follow(true).stdout(true).stderr(true).build();
-            let mut logs = container.logs(&opts);
-            while let Some(chunk) = logs.next().await {
-                match chunk {
-                    Ok(TtyChunk::StdOut(stdout)) => {
-                        let stdout = std::str::from_utf8(&stdout).unwrap_or_default();
-                        log::info!("{}: stdout: {}", name, stdout);
-                    },
-                    Ok(TtyChunk::StdErr(stderr)) => {
-                        let stderr = std::str::from_utf8(&stderr).unwrap_or_default();
-                        log::info!("{}: stderr: {}", name, stderr);
-                    },
-                    Err(err) => {
-                        log::error!("{}", err);
-                    },
-                    Ok(TtyChunk::StdIn(_)) => unreachable!(),
-                }
-            }
-            log::info!("{}: exited", name);
+            forward_logs(&name, container.logs(&opts), &container_logs).await;
         });
 
         let container = Container::new(self.docker.clone(), id.clone());
@@ -177,9 +282,26 @@ impl<'a> EnvBuilder<'a> {
             let port = u32::from(*port);
             opts = opts.expose(PublishPort::tcp(port), port);
         }
+        if let Some(entrypoint) = config.node_entrypoint {
+            opts = opts.entrypoint(entrypoint.iter().copied());
+        }
+        if !self.mounts.is_empty() {
+            let mut binds = Vec::with_capacity(self.mounts.len());
+            for (host_path, container_path) in &self.mounts {
+                // Docker requires absolute host paths
+                let host_path = std::fs::canonicalize(host_path)
+                    .with_context(|| format!("invalid mount {}", host_path.display()))?;
+                binds.push(format!("{}:{container_path}:ro", host_path.display()));
+            }
+            opts = opts.volumes(binds);
+        }
         let container = self.run_container(name, &opts.build()).await?;
 
         // TODO: replace this by a proper healthcheck
+        let health_check = self
+            .health_check
+            .unwrap_or_else(|| HealthCheck::for_scheme(config.node_uri.scheme));
+        tokio::time::sleep(health_check.initial_delay).await;
         let maybe_error = if matches!(config.node_uri.scheme, "http" | "https" | "ws" | "wss") {
             wait_for_http(
                 config
@@ -188,13 +310,12 @@ impl<'a> EnvBuilder<'a> {
                     .with_host("127.0.0.1")
                     .to_string(),
                 &container,
+                &health_check,
             )
             .await
             .err()
         } else {
-            // Wait 15 seconds to guarantee the node didn't crash
-            tokio::time::sleep(Duration::from_secs(15)).await;
-            health(&container).await.err()
+            wait_for_container(&container, &health_check).await.err()
         };
 
         if let Some(err) = maybe_error {
@@ -279,12 +400,32 @@ enum RetryError {
     ContainerExited(anyhow::Error),
 }
 
-async fn wait_for_http<S: AsRef<str> + Send>(url: S, container: &Container) -> Result<()> {
+/// Returns the delays between the attempts of the `health_check`.
+fn retry_strategy(health_check: &HealthCheck) -> impl Iterator<Item = Duration> {
+    // The first attempt is not delayed by the strategy
+    FixedInterval::new(health_check.poll_interval).take(health_check.max_attempts.saturating_sub(1))
+}
+
+/// Checks the container health `max_attempts` times, fails if the container exits or is
+/// unhealthy.
+async fn wait_for_container(container: &Container, health_check: &HealthCheck) -> Result<()> {
+    let mut delays = retry_strategy(health_check);
+    loop {
+        if matches!(health(container).await?, Some(Health::Unhealthy)) {
+            anyhow::bail!("healthcheck reports unhealthy");
+        }
+        let Some(delay) = delays.next() else { return Ok(()) };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+async fn wait_for_http<S: AsRef<str> + Send>(
+    url: S,
+    container: &Container,
+    health_check: &HealthCheck,
+) -> Result<()> {
     let url = url.as_ref();
-    let retry_strategy = ExponentialBackoff::from_millis(2)
-        .factor(100)
-        .max_delay(Duration::from_secs(2))
-        .take(20); // limit to 20 retries
+    let retry_strategy = retry_strategy(health_check);
 
     RetryIf::spawn(
         retry_strategy,
@@ -423,8 +564,7 @@ pub mod tests {
         crate::run_test(env, |env| async move {
             let value = 100 * u128::pow(10, config.currency_decimals);
             let wallet = env.ephemeral_wallet().await.unwrap();
-            wallet.faucet(value, None).await.unwrap();
-            let balance = wallet.balance().await.unwrap();
+            let balance = crate::top_up_wallet(&wallet, value).await.unwrap();
             assert_eq!(balance, value);
         })
         .await;
@@ -461,8 +601,7 @@ pub mod tests {
             assert_eq!(balance, 0);
 
             // Transfer faucets to alice
-            alice.faucet(faucet, None).await.unwrap();
-            let balance = alice.balance().await.unwrap();
+            let balance = crate::top_up_wallet(&alice, faucet).await.unwrap();
             assert_eq!(balance, faucet);
 
             // Alice transfers to bob
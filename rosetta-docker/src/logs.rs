@@ -0,0 +1,168 @@
+use docker_api::conn::TtyChunk;
+use futures::stream::{Stream, StreamExt};
+use std::{collections::VecDeque, fmt::Display, fs::File, io::Write, path::PathBuf};
+
+/// Number of lines logged by [`ContainerLogs::Summary`] when the container exits.
+pub const SUMMARY_LINES: usize = 20;
+
+/// Controls where the stdout and stderr of the containers are forwarded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ContainerLogs {
+    /// Discard the container output.
+    Silence,
+    /// Log only the last [`SUMMARY_LINES`] lines when the container exits.
+    Summary,
+    /// Log every line with `log::info!`.
+    #[default]
+    Full,
+    /// Write every line to `<dir>/<container name>.log` instead of the logger.
+    File(PathBuf),
+}
+
+/// Forwards the output of the container `name` as configured by `container_logs`.
+pub async fn forward_logs<S, E>(name: &str, mut logs: S, container_logs: &ContainerLogs)
+where
+    S: Stream<Item = Result<TtyChunk, E>> + Unpin,
+    E: Display,
+{
+    let mut file = match container_logs {
+        ContainerLogs::File(dir) => {
+            let path = dir.join(format!("{name}.log"));
+            match File::create(&path) {
+                Ok(file) => Some(file),
+                Err(err) => {
+                    log::error!("{name}: failed to create {}: {err}", path.display());
+                    return;
+                },
+            }
+        },
+        _ => None,
+    };
+    let mut summary = VecDeque::with_capacity(SUMMARY_LINES);
+    while let Some(chunk) = logs.next().await {
+        let (stream, output) = match chunk {
+            Ok(TtyChunk::StdOut(stdout)) => ("stdout", stdout),
+            Ok(TtyChunk::StdErr(stderr)) => ("stderr", stderr),
+            Err(err) => {
+                log::error!("{}", err);
+                continue;
+            },
+            Ok(TtyChunk::StdIn(_)) => unreachable!(),
+        };
+        let output = std::str::from_utf8(&output).unwrap_or_default();
+        match container_logs {
+            ContainerLogs::Silence => {},
+            ContainerLogs::Summary => {
+                if summary.len() == SUMMARY_LINES {
+                    summary.pop_front();
+                }
+                summary.push_back(format!("{name}: {stream}: {output}"));
+            },
+            ContainerLogs::Full => log::info!("{}: {}: {}", name, stream, output),
+            ContainerLogs::File(_) => {
+                if let Some(file) = file.as_mut() {
+                    let newline = if output.ends_with('\n') { "" } else { "\n" };
+                    if let Err(err) = write!(file, "{stream}: {output}{newline}") {
+                        log::error!("{name}: failed to write logs: {err}");
+                    }
+                }
+            },
+        }
+    }
+    for line in summary {
+        log::info!("{}", line);
+    }
+    log::info!("{}: exited", name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::{Log, Metadata, Record};
+    use std::sync::{Mutex, Once};
+
+    /// Captures the records of every test, each test filters by its own container name.
+    struct CaptureLogger(Mutex<Vec<String>>);
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(Vec::new()));
+
+    fn captured(name: &str) -> Vec<String> {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+        LOGGER
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|line| line.starts_with(name))
+            .cloned()
+            .collect()
+    }
+
+    fn run(name: &str, lines: usize, container_logs: &ContainerLogs) -> Vec<String> {
+        // Install the logger before forwarding
+        captured(name);
+        let chunks = (0..lines).map(|i| {
+            let chunk = format!("line {i}\n").into_bytes();
+            let chunk = if i % 2 == 0 { TtyChunk::StdOut(chunk) } else { TtyChunk::StdErr(chunk) };
+            Ok::<_, std::io::Error>(chunk)
+        });
+        let logs = futures::stream::iter(chunks);
+        futures::executor::block_on(forward_logs(name, logs, container_logs));
+        captured(name)
+    }
+
+    #[test]
+    fn full_logs_every_line() {
+        let lines = run("test-full", 3, &ContainerLogs::Full);
+        assert_eq!(
+            lines,
+            vec![
+                "test-full: stdout: line 0\n",
+                "test-full: stderr: line 1\n",
+                "test-full: stdout: line 2\n",
+                "test-full: exited",
+            ]
+        );
+    }
+
+    #[test]
+    fn silence_logs_no_container_line() {
+        let lines = run("test-silence", 10, &ContainerLogs::Silence);
+        assert_eq!(lines, vec!["test-silence: exited"]);
+    }
+
+    #[test]
+    fn summary_logs_the_last_lines() {
+        let lines = run("test-summary", SUMMARY_LINES + 5, &ContainerLogs::Summary);
+        assert_eq!(lines.len(), SUMMARY_LINES + 1);
+        assert_eq!(lines[0], "test-summary: stderr: line 5\n");
+        assert_eq!(lines[SUMMARY_LINES], "test-summary: exited");
+    }
+
+    #[test]
+    fn file_writes_to_the_container_log() {
+        let dir = std::env::temp_dir().join(format!("rosetta-docker-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let lines = run("test-file", 2, &ContainerLogs::File(dir.clone()));
+        assert_eq!(lines, vec!["test-file: exited"]);
+        let contents = std::fs::read_to_string(dir.join("test-file.log")).unwrap();
+        assert_eq!(contents, "stdout: line 0\nstderr: line 1\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
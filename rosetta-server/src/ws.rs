@@ -4,14 +4,16 @@ mod jsonrpsee_client;
 mod reconnect;
 mod reconnect_impl;
 mod retry_strategy;
+mod subscriptions;
 mod tungstenite_jsonrpsee;
 
 use crate::ws::{
     reconnect::{AutoReconnectClient, Reconnect},
     reconnect_impl::{Config as ReconnectConfig, DefaultStrategy},
     retry_strategy::RetryStrategy,
+    subscriptions::{SubscriptionTracker, TrackingSender},
 };
-pub use config::{RpcClientConfig, WsTransportClient};
+pub use config::{RetryStrategyConfig, RpcClientConfig, WsTransportClient};
 use futures_util::{future::BoxFuture, FutureExt};
 use jsonrpsee::{
     client_transport::ws::WsTransportClientBuilder,
@@ -20,34 +22,61 @@ use jsonrpsee::{
         ClientError as JsonRpseeError,
     },
 };
-use std::time::Duration;
+use std::{
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 pub use tungstenite_jsonrpsee::{TungsteniteClient, WsError};
 use url::Url;
 
 pub type DefaultClient = AutoReconnectClient<DefaultStrategy<DefaultReconnectConfig>>;
 pub type HttpClient = jsonrpsee::http_client::HttpClient;
 
-async fn connect_client(url: Url, config: RpcClientConfig) -> Result<Client, JsonRpseeError> {
+/// Connection status of a websocket client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionStatus {
+    /// The client is connected and ready to process requests.
+    Connected,
+
+    /// The connection dropped, requests wait for the client to reconnect.
+    Reconnecting,
+
+    /// The connection was closed after being idle, the next request reconnects.
+    Idle,
+
+    /// The client gave up reconnecting, all requests fail.
+    Closed,
+}
+
+async fn connect_client(
+    url: Url,
+    config: RpcClientConfig,
+    subscriptions: Arc<SubscriptionTracker>,
+) -> Result<Client, JsonRpseeError> {
     let builder = ClientBuilder::from(&config);
     let client = match config.client {
         WsTransportClient::Auto => {
             tracing::info!("Connecting using Socketto...");
-            match build_socketto_client(builder, url.clone(), &config).await {
+            match build_socketto_client(builder, url.clone(), &config, subscriptions.clone()).await
+            {
                 Ok(client) => client,
                 Err(error) => {
                     tracing::warn!("Socketto failed: {}", error);
                     tracing::trace!("Retrying to connect using Tungstenite.");
-                    build_tungstenite_client(builder, url, &config).await?
+                    build_tungstenite_client(builder, url, &config, subscriptions).await?
                 },
             }
         },
         WsTransportClient::Socketto => {
-            let client = build_socketto_client(builder, url.clone(), &config).await?;
+            let client =
+                build_socketto_client(builder, url.clone(), &config, subscriptions).await?;
             tracing::info!("Connected to {} using Socketto", url);
             client
         },
         WsTransportClient::Tungstenite => {
-            let client = build_tungstenite_client(builder, url.clone(), &config).await?;
+            let client =
+                build_tungstenite_client(builder, url.clone(), &config, subscriptions).await?;
             tracing::info!("Connected to {} using Tungstenite", url);
             client
         },
@@ -62,6 +91,16 @@ pub struct DefaultReconnectConfig {
 
     /// RPC Client configuration.
     pub config: RpcClientConfig,
+
+    /// Live subscriptions of the last connection.
+    subscriptions: Mutex<Arc<SubscriptionTracker>>,
+}
+
+impl DefaultReconnectConfig {
+    #[must_use]
+    pub fn new(url: Url, config: RpcClientConfig) -> Self {
+        Self { url, config, subscriptions: Mutex::default() }
+    }
 }
 
 impl ReconnectConfig for DefaultReconnectConfig {
@@ -82,12 +121,37 @@ impl ReconnectConfig for DefaultReconnectConfig {
     fn connect(&self) -> Self::ConnectFuture {
         let url = self.url.clone();
         let config = self.config.clone();
-        connect_client(url, config).boxed()
+        // Each connection serves its own subscriptions
+        let subscriptions = Arc::new(SubscriptionTracker::default());
+        if let Ok(mut guard) = self.subscriptions.lock() {
+            *guard = subscriptions.clone();
+        }
+        connect_client(url, config, subscriptions).boxed()
     }
 
     fn is_connected(&self, client: &Self::Client) -> Option<bool> {
         Some(client.is_connected())
     }
+
+    fn max_reconnect_attempts(&self) -> Option<NonZeroU32> {
+        self.config.max_reconnect_attempts
+    }
+
+    fn idle_timeout(&self) -> Option<Duration> {
+        self.config.idle_timeout
+    }
+
+    fn subscribed(&self, unsubscribe_method: Option<&str>) {
+        if let Ok(subscriptions) = self.subscriptions.lock() {
+            subscriptions.opened(unsubscribe_method);
+        }
+    }
+
+    fn has_subscriptions(&self) -> bool {
+        self.subscriptions
+            .lock()
+            .map_or(true, |subscriptions| subscriptions.has_subscriptions())
+    }
 }
 
 /// Creates an Json-RPC client with default settings
@@ -101,7 +165,7 @@ pub async fn default_client(
 ) -> Result<DefaultClient, JsonRpseeError> {
     let config = config.unwrap_or_default();
     let url = url.parse::<Url>().map_err(|e| JsonRpseeError::Transport(e.into()))?;
-    let reconnect_config = DefaultReconnectConfig { url, config };
+    let reconnect_config = DefaultReconnectConfig::new(url, config);
 
     DefaultStrategy::connect(reconnect_config).await.map(Reconnect::into_client)
 }
@@ -111,8 +175,19 @@ pub async fn default_client(
 /// # Errors
 /// Returns `Err` if the url is not valid
 pub fn default_http_client(url: &str) -> Result<HttpClient, JsonRpseeError> {
+    default_http_client_with_config(url, &RpcClientConfig::default())
+}
+
+/// Creates an Json-RPC HTTP client with the given settings
+///
+/// # Errors
+/// Returns `Err` if the url is not valid
+pub fn default_http_client_with_config(
+    url: &str,
+    config: &RpcClientConfig,
+) -> Result<HttpClient, JsonRpseeError> {
     let url = url.parse::<Url>().map_err(|e| JsonRpseeError::Transport(e.into()))?;
-    let client = jsonrpsee::http_client::HttpClientBuilder::new().build(url)?;
+    let client = jsonrpsee::http_client::HttpClientBuilder::from(config).build(url)?;
     Ok(client)
 }
 
@@ -121,12 +196,13 @@ async fn build_socketto_client(
     builder: ClientBuilder,
     url: Url,
     config: &RpcClientConfig,
+    subscriptions: Arc<SubscriptionTracker>,
 ) -> Result<Client, JsonRpseeError> {
     let (sender, receiver) = WsTransportClientBuilder::from(config)
         .build(url)
         .await
         .map_err(|error| JsonRpseeError::Transport(error.into()))?;
-    let client = builder.build_with_tokio(sender, receiver);
+    let client = builder.build_with_tokio(TrackingSender::new(sender, subscriptions), receiver);
     Ok(client)
 }
 
@@ -135,11 +211,190 @@ async fn build_tungstenite_client(
     builder: ClientBuilder,
     url: Url,
     config: &RpcClientConfig,
+    subscriptions: Arc<SubscriptionTracker>,
 ) -> Result<Client, JsonRpseeError> {
     let client = TungsteniteClient::new(url, config)
         .await
         .map_err(|error| JsonRpseeError::Transport(error.into()))?;
     let (sender, receiver) = client.split();
-    let client = builder.build_with_tokio(sender, receiver);
+    let client = builder.build_with_tokio(TrackingSender::new(sender, subscriptions), receiver);
     Ok(client)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt};
+    use jsonrpsee::core::{client::ClientT, params::ArrayParams};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Serves a single HTTP request, replying with a JSON-RPC result of `size` bytes.
+    async fn mock_server(size: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            let body = format!(r#"{{"jsonrpc":"2.0","id":0,"result":"{}"}}"#, "a".repeat(size));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        format!("http://{addr}")
+    }
+
+    /// Serves JSON-RPC over websocket, replying `result` to every request and a subscription id to
+    /// the subscriptions, returns the url, the number of accepted connections and the number of
+    /// open connections.
+    async fn mock_ws_server(
+        result: serde_json::Value,
+    ) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let open = Arc::new(AtomicUsize::new(0));
+        let (accepted_count, open_count) = (accepted.clone(), open.clone());
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                accepted_count.fetch_add(1, Ordering::SeqCst);
+                open_count.fetch_add(1, Ordering::SeqCst);
+                let open_count = open_count.clone();
+                let result = result.clone();
+                tokio::spawn(async move {
+                    let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                    while let Some(Ok(message)) = ws.next().await {
+                        let Message::Text(request) = message else { continue };
+                        let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                        let result = if request["method"].as_str().unwrap().ends_with("_subscribe")
+                        {
+                            serde_json::json!("0x1")
+                        } else {
+                            result.clone()
+                        };
+                        let response = serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result});
+                        if ws.send(Message::Text(response.to_string())).await.is_err() {
+                            break;
+                        }
+                    }
+                    open_count.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        (format!("ws://{addr}"), accepted, open)
+    }
+
+    #[tokio::test]
+    async fn ws_client_closes_idle_connection() {
+        let config = RpcClientConfig {
+            idle_timeout: Some(Duration::from_millis(200)),
+            ..RpcClientConfig::default()
+        };
+        let (url, accepted, open) = mock_ws_server(serde_json::json!(true)).await;
+        let client = default_client(&url, Some(config)).await.unwrap();
+        assert!(client.request::<bool, _>("mock_call", ArrayParams::new()).await.unwrap());
+        assert_eq!(client.connection_status(), ConnectionStatus::Connected);
+
+        // The connection is closed once idle
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while open.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(client.connection_status(), ConnectionStatus::Idle);
+
+        // And transparently reopened by the next request
+        assert!(client.request::<bool, _>("mock_call", ArrayParams::new()).await.unwrap());
+        assert_eq!(client.connection_status(), ConnectionStatus::Connected);
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn ws_client_keeps_subscribed_connection() {
+        use jsonrpsee::core::client::SubscriptionClientT;
+
+        let config = RpcClientConfig {
+            idle_timeout: Some(Duration::from_millis(200)),
+            ..RpcClientConfig::default()
+        };
+        let (url, accepted, open) = mock_ws_server(serde_json::json!(true)).await;
+        let client = default_client(&url, Some(config)).await.unwrap();
+        let subscription = client
+            .subscribe::<serde_json::Value, _>(
+                "mock_subscribe",
+                ArrayParams::new(),
+                "mock_unsubscribe",
+            )
+            .await
+            .unwrap();
+
+        // The connection serving the subscription is never idle
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(client.connection_status(), ConnectionStatus::Connected);
+        assert_eq!(open.load(Ordering::SeqCst), 1);
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+
+        // Until the subscription is dropped
+        drop(subscription);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while open.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(client.connection_status(), ConnectionStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn http_client_rejects_oversized_responses() {
+        let config = RpcClientConfig { max_response_size: 1024, ..RpcClientConfig::default() };
+
+        let url = mock_server(512).await;
+        let client = default_http_client_with_config(&url, &config).unwrap();
+        let result: String = client.request("mock_call", ArrayParams::new()).await.unwrap();
+        assert_eq!(result.len(), 512);
+
+        let url = mock_server(2048).await;
+        let client = default_http_client_with_config(&url, &config).unwrap();
+        let error = client.request::<String, _>("mock_call", ArrayParams::new()).await.unwrap_err();
+        assert!(matches!(error, JsonRpseeError::Transport(_)), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn ws_client_rejects_oversized_responses() {
+        let config = RpcClientConfig {
+            max_response_size: 1024,
+            max_reconnect_attempts: NonZeroU32::new(1),
+            ..RpcClientConfig::default()
+        };
+
+        let (url, _, _) = mock_ws_server(serde_json::json!("a".repeat(512))).await;
+        let client = default_client(&url, Some(config.clone())).await.unwrap();
+        let result: String = client.request("mock_call", ArrayParams::new()).await.unwrap();
+        assert_eq!(result.len(), 512);
+
+        let (url, _, _) = mock_ws_server(serde_json::json!("a".repeat(2048))).await;
+        let client = default_client(&url, Some(config)).await.unwrap();
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            client.request::<String, _>("mock_call", ArrayParams::new()),
+        )
+        .await
+        .unwrap();
+        assert!(result.is_err(), "{result:?}");
+    }
+}
@@ -1,7 +1,11 @@
-use core::{num::NonZeroUsize, time::Duration};
+use core::{
+    num::{NonZeroU32, NonZeroUsize},
+    time::Duration,
+};
 use jsonrpsee::{
     client_transport::ws::WsTransportClientBuilder,
     core::client::{async_client::PingConfig, ClientBuilder, IdKind},
+    http_client::HttpClientBuilder,
 };
 
 /// Ten megabytes.
@@ -93,6 +97,10 @@ pub struct RpcClientConfig {
     /// by a malicious user.
     pub max_frame_size: Option<usize>,
 
+    /// The maximum size of a JSON-RPC response in bytes, larger responses are rejected instead
+    /// of being buffered in memory. The default value is 10 MiB.
+    pub max_response_size: usize,
+
     /// Whether to accept unmasked frames from the peer. The default value is `false`.
     /// from the client. According to the RFC 6455, the server must close the
     /// connection to the client in such cases, however it seems like there are
@@ -142,6 +150,16 @@ pub struct RpcClientConfig {
     /// Default is [`RetryStrategyConfig::FibonacciBackoff`] with 5 seconds base and
     /// 30 seconds maximum between retries.
     pub retry_strategy: RetryStrategyConfig,
+
+    /// Maximum number of reconnect attempts, after which the client is closed and all requests
+    /// fail. Default is `None`, retry forever.
+    pub max_reconnect_attempts: Option<NonZeroU32>,
+
+    /// Closes the connection after no request was made during this duration, the client
+    /// reconnects on the next request. The connection is kept open while it serves a live
+    /// subscription, and can be closed once all of them are dropped. Default is `None`, the
+    /// connection is kept open.
+    pub idle_timeout: Option<Duration>,
 }
 
 impl Default for RpcClientConfig {
@@ -153,6 +171,7 @@ impl Default for RpcClientConfig {
             max_write_buffer_size: usize::MAX,
             max_message_size: Some(TEN_MB_SIZE_BYTES),
             max_frame_size: Some(16 << 20),
+            max_response_size: TEN_MB_SIZE_BYTES,
             accept_unmasked_frames: false,
 
             // Default JSON-RPC config.
@@ -169,6 +188,8 @@ impl Default for RpcClientConfig {
                 factor: None,
                 max_delay: Some(Duration::from_secs(30)),
             },
+            max_reconnect_attempts: None,
+            idle_timeout: None,
         }
     }
 }
@@ -194,9 +215,23 @@ impl From<&RpcClientConfig> for WsTransportClientBuilder {
     fn from(config: &RpcClientConfig) -> Self {
         let message_size =
             u32::try_from(config.max_message_size.unwrap_or(TEN_MB_SIZE_BYTES)).unwrap_or(u32::MAX);
+        let response_size = u32::try_from(config.max_response_size).unwrap_or(u32::MAX);
         Self::default()
             .max_request_size(message_size)
-            .max_response_size(message_size)
+            .max_response_size(response_size)
             .max_redirections(5)
     }
 }
+
+impl From<&RpcClientConfig> for HttpClientBuilder {
+    fn from(config: &RpcClientConfig) -> Self {
+        let message_size =
+            u32::try_from(config.max_message_size.unwrap_or(TEN_MB_SIZE_BYTES)).unwrap_or(u32::MAX);
+        let response_size = u32::try_from(config.max_response_size).unwrap_or(u32::MAX);
+        Self::new()
+            .request_timeout(config.rpc_request_timeout)
+            .id_format(config.rpc_id_kind)
+            .max_request_size(message_size)
+            .max_response_size(response_size)
+    }
+}
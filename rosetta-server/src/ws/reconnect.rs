@@ -55,6 +55,10 @@ pub trait Reconnect: 'static + Sized + Send + Sync {
     /// Force reconnect and return a new client.
     fn reconnect(&self) -> Self::ReconnectFuture<'_>;
 
+    /// Callback called when a subscription is opened on `client`, `unsubscribe_method` is `None`
+    /// for the method subscriptions.
+    fn subscribed(&self, _client: &Self::ClientRef, _unsubscribe_method: Option<&str>) {}
+
     /// Return a reference to the client.
     fn into_client(self) -> AutoReconnectClient<Self> {
         AutoReconnectClient::new(self)
@@ -279,7 +283,10 @@ where
         )
         .await
         {
-            Ok(subscription) => return Ok(subscription),
+            Ok(subscription) => {
+                self.client.subscribed(&client, Some(unsubscribe_method));
+                return Ok(subscription);
+            },
             Err(error) => error,
         };
 
@@ -289,13 +296,15 @@ where
                     .restart_needed(message, client)
                     .await
                     .map_err(CloneableJsonRpseeError::as_error)?;
-                SubscriptionClientT::subscribe::<Notif, _>(
+                let subscription = SubscriptionClientT::subscribe::<Notif, _>(
                     client.as_ref(),
                     subscribe_method,
                     params,
                     unsubscribe_method,
                 )
-                .await
+                .await?;
+                self.client.subscribed(&client, Some(unsubscribe_method));
+                Ok(subscription)
             },
             error => {
                 tracing::error!("subscription to '{subscribe_method}' failed: {error:?}");
@@ -314,7 +323,10 @@ where
         let _enter = self.span.enter();
         let client = self.ready().await.map_err(CloneableJsonRpseeError::as_error)?;
         let error = match SubscriptionClientT::subscribe_to_method(client.as_ref(), method).await {
-            Ok(subscription) => return Ok(subscription),
+            Ok(subscription) => {
+                self.client.subscribed(&client, None);
+                return Ok(subscription);
+            },
             Err(error) => error,
         };
 
@@ -324,7 +336,10 @@ where
                     .restart_needed(message, client)
                     .await
                     .map_err(CloneableJsonRpseeError::as_error)?;
-                SubscriptionClientT::subscribe_to_method(client.as_ref(), method).await
+                let subscription =
+                    SubscriptionClientT::subscribe_to_method(client.as_ref(), method).await?;
+                self.client.subscribed(&client, None);
+                Ok(subscription)
             },
             error => {
                 tracing::error!("subscription to '{method}' failed: {error:?}");
@@ -1,4 +1,4 @@
-use super::reconnect::Reconnect;
+use super::{reconnect::Reconnect, ConnectionStatus};
 use futures_timer::Delay;
 use futures_util::{
     future::{Either, Select, Shared},
@@ -12,9 +12,9 @@ use std::{
     num::NonZeroU32,
     ops::Deref,
     pin::Pin,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock, Weak},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 pub trait Config: 'static + Sized + Send + Sync + Debug {
@@ -46,6 +46,29 @@ pub trait Config: 'static + Sized + Send + Sync + Debug {
     fn is_connected(&self, _client: &Self::Client) -> Option<bool> {
         None
     }
+
+    /// Maximum number of reconnect attempts before the client is closed for good.
+    /// returns None to retry forever.
+    fn max_reconnect_attempts(&self) -> Option<NonZeroU32> {
+        None
+    }
+
+    /// Duration without requests after which the connection is closed, the client reconnects on
+    /// the next request.
+    /// returns None to keep the connection open.
+    fn idle_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Callback called when a subscription is opened on the current connection,
+    /// `unsubscribe_method` is `None` for the method subscriptions.
+    fn subscribed(&self, _unsubscribe_method: Option<&str>) {}
+
+    /// Returns `true` if the current connection serves live subscriptions, it is never idle.
+    /// returns false if the subscriptions aren't tracked.
+    fn has_subscriptions(&self) -> bool {
+        false
+    }
 }
 
 /// The default reconnect strategy.
@@ -57,10 +80,13 @@ pub trait Config: 'static + Sized + Send + Sync + Debug {
 /// - While reconnecting, this strategy will hold all the requests until the reconnect finishes
 /// - When the reconnect fails, all pending requests fails with the same error message
 /// - When the reconnect succeed, all pending requests are processed
+/// - After [`Config::max_reconnect_attempts`] failed attempts the client is closed, all the
+///   following requests fail immediately
+/// - When no request is made during [`Config::idle_timeout`], the connection is closed and reopened
+///   by the next request
 ///
 /// # TODO:
 /// - add a timeout for the reconnect
-/// - set a max number of reconnect attempts, shutdown when the max number is reached
 /// - automatically restore the subscriptions after reconnecting
 #[derive(Debug)]
 pub struct DefaultStrategy<T: Config> {
@@ -76,21 +102,48 @@ impl<T: Config> Clone for DefaultStrategy<T> {
 impl<T: Config> DefaultStrategy<T> {
     pub async fn connect(config: T) -> Result<Self, Error> {
         let client = Arc::new(config.connect().await?);
-        Ok(Self {
-            inner: Arc::new(SharedState {
-                config,
-                connection_status: RwLock::new(ConnectionStatus::Ready(client)),
-            }),
-        })
+        let idle_timeout = config.idle_timeout();
+        let inner = Arc::new(SharedState {
+            config,
+            connection_status: RwLock::new(ClientStatus::Ready(client)),
+            last_activity: Mutex::new(Instant::now()),
+        });
+        if let Some(idle_timeout) = idle_timeout {
+            tokio::spawn(close_when_idle(Arc::downgrade(&inner), idle_timeout));
+        }
+        Ok(Self { inner })
     }
 
     pub fn state(&self) -> Arc<SharedState<T>> {
         self.inner.clone()
     }
 
+    /// Returns the current connection status, without making any request.
+    ///
+    /// A dropped connection is reported as [`ConnectionStatus::Reconnecting`], the client
+    /// reconnects on the next request.
+    #[must_use]
+    pub fn connection_status(&self) -> ConnectionStatus {
+        let Ok(guard) = self.inner.connection_status.read() else {
+            return ConnectionStatus::Closed;
+        };
+        match &*guard {
+            ClientStatus::Ready(client)
+                if self.inner.config.is_connected(client) == Some(false) =>
+            {
+                ConnectionStatus::Reconnecting
+            },
+            ClientStatus::Ready(_) => ConnectionStatus::Connected,
+            ClientStatus::Reconnecting(_) => ConnectionStatus::Reconnecting,
+            ClientStatus::Idle => ConnectionStatus::Idle,
+            ClientStatus::Closed(_) => ConnectionStatus::Closed,
+        }
+    }
+
     /// Creates a future that is immediately ready if the client is idle. or pending if
     /// reconnecting.
     pub fn acquire_client(&self) -> ReadyOrWaitFuture<T> {
+        self.inner.touch();
         let connection_status = match self.inner.connection_status.read() {
             Ok(guard) => guard.deref().clone(),
             Err(error) => {
@@ -101,10 +154,12 @@ impl<T: Config> DefaultStrategy<T> {
         };
 
         match connection_status {
-            ConnectionStatus::Ready(client) => ReadyOrWaitFuture::ready(Ok(client)),
-            ConnectionStatus::Reconnecting(future) => {
+            ClientStatus::Ready(client) => ReadyOrWaitFuture::ready(Ok(client)),
+            ClientStatus::Reconnecting(future) => {
                 ReadyOrWaitFuture::<T>::wait(self.inner.config.max_pending_delay(), future)
             },
+            ClientStatus::Closed(error) => ReadyOrWaitFuture::closed(error),
+            ClientStatus::Idle => self.reconnect_or_wait(),
         }
     }
 
@@ -121,14 +176,19 @@ impl<T: Config> DefaultStrategy<T> {
         };
 
         // If the client is already reconnecting, reuse the same future
-        if let ConnectionStatus::Reconnecting(future) = &*guard {
+        if let ClientStatus::Reconnecting(future) = &*guard {
             return ReadyOrWaitFuture::wait(self.inner.config.max_pending_delay(), future.clone());
         };
 
+        // The client gave up reconnecting
+        if let ClientStatus::Closed(error) = &*guard {
+            return ReadyOrWaitFuture::closed(Arc::clone(error));
+        }
+
         // Update the connection status to reconnecting
         // TODO: Reconnect in another task/thread
         let reconnect_future = ReconnectFuture::new(self.inner.clone()).shared();
-        *guard = ConnectionStatus::Reconnecting(reconnect_future.clone());
+        *guard = ClientStatus::Reconnecting(reconnect_future.clone());
         drop(guard);
         ReadyOrWaitFuture::wait(self.inner.config.max_pending_delay(), reconnect_future)
     }
@@ -161,24 +221,55 @@ impl<T: Config> Reconnect for DefaultStrategy<T> {
     fn reconnect(&self) -> Self::ReconnectFuture<'_> {
         self.reconnect_or_wait()
     }
+
+    fn subscribed(&self, client: &Self::ClientRef, unsubscribe_method: Option<&str>) {
+        // Subscriptions opened on a previous connection are already closed
+        let Ok(guard) = self.inner.connection_status.read() else {
+            return;
+        };
+        if matches!(&*guard, ClientStatus::Ready(current) if Arc::ptr_eq(current, client)) {
+            self.inner.config.subscribed(unsubscribe_method);
+        }
+    }
+}
+
+/// Closes the connection once no request was made during `idle_timeout`, stops when the state
+/// is dropped.
+async fn close_when_idle<T: Config>(state: Weak<SharedState<T>>, idle_timeout: Duration) {
+    let mut remaining = idle_timeout;
+    loop {
+        Delay::new(remaining).await;
+        let Some(state) = state.upgrade() else {
+            break;
+        };
+        remaining = state.close_if_idle(idle_timeout);
+    }
 }
 
 /// The connection status of the client.
 #[derive(Debug)]
-pub enum ConnectionStatus<T: Config> {
+pub enum ClientStatus<T: Config> {
     /// The client is idle and ready to receive requests.
     Ready(Arc<T::Client>),
 
     /// The client is reconnecting.
     /// This stores a shared future which will resolves when the reconnect completes.
     Reconnecting(Shared<ReconnectFuture<T>>),
+
+    /// The connection was closed after being idle, the next request reconnects.
+    Idle,
+
+    /// The client exhausted the reconnect attempts, this stores the last reconnect error.
+    Closed(Arc<Error>),
 }
 
-impl<T: Config> Clone for ConnectionStatus<T> {
+impl<T: Config> Clone for ClientStatus<T> {
     fn clone(&self) -> Self {
         match self {
             Self::Ready(client) => Self::Ready(client.clone()),
             Self::Reconnecting(future) => Self::Reconnecting(Shared::clone(future)),
+            Self::Idle => Self::Idle,
+            Self::Closed(error) => Self::Closed(Arc::clone(error)),
         }
     }
 }
@@ -188,13 +279,54 @@ impl<T: Config> Clone for ConnectionStatus<T> {
 #[derive(Debug)]
 pub struct SharedState<T: Config> {
     pub config: T,
-    connection_status: RwLock<ConnectionStatus<T>>,
+    connection_status: RwLock<ClientStatus<T>>,
+    last_activity: Mutex<Instant>,
+}
+
+impl<T: Config> SharedState<T> {
+    /// Records a request, postponing the idle timeout.
+    fn touch(&self) {
+        if let Ok(mut last_activity) = self.last_activity.lock() {
+            *last_activity = Instant::now();
+        }
+    }
+
+    /// Closes the connection if no request was made during `idle_timeout`, none is in progress
+    /// and it serves no subscription, returns the time left before the connection can be closed.
+    fn close_if_idle(&self, idle_timeout: Duration) -> Duration {
+        let Ok(last_activity) = self.last_activity.lock().map(|guard| *guard) else {
+            return idle_timeout;
+        };
+        let remaining = idle_timeout.saturating_sub(last_activity.elapsed());
+        if !remaining.is_zero() {
+            return remaining;
+        }
+        let previous = {
+            let Ok(mut guard) = self.connection_status.write() else {
+                return idle_timeout;
+            };
+            // In progress requests hold a reference to the client, subscriptions don't
+            let idle = matches!(
+                &*guard,
+                ClientStatus::Ready(client)
+                    if Arc::strong_count(client) == 1 && !self.config.has_subscriptions()
+            );
+            if !idle {
+                return idle_timeout;
+            }
+            std::mem::replace(&mut *guard, ClientStatus::Idle)
+        };
+        tracing::info!("closing the connection after {idle_timeout:?} without requests");
+        drop(previous);
+        idle_timeout
+    }
 }
 
 /// Future that resolves when the client is connected or
 /// when the timeout is reached.
 pub enum ReadyOrWaitState<T: Config> {
     Ready(Result<Arc<T::Client>, Error>),
+    Closed(Arc<Error>),
     Waiting(Select<Delay, Shared<ReconnectFuture<T>>>),
 }
 
@@ -208,6 +340,10 @@ impl<T: Config> ReadyOrWaitFuture<T> {
         Self { state: Some(ReadyOrWaitState::Ready(result)) }
     }
 
+    pub const fn closed(error: Arc<Error>) -> Self {
+        Self { state: Some(ReadyOrWaitState::Closed(error)) }
+    }
+
     pub fn wait(timeout: Duration, future: Shared<ReconnectFuture<T>>) -> Self {
         let future = futures_util::future::select(Delay::new(timeout), future);
         Self { state: Some(ReadyOrWaitState::Waiting(future)) }
@@ -227,6 +363,7 @@ impl<T: Config> Future for ReadyOrWaitFuture<T> {
         let this = self.project();
         match this.state.take() {
             Some(ReadyOrWaitState::Ready(result)) => Poll::Ready(result.map_err(Arc::new)),
+            Some(ReadyOrWaitState::Closed(error)) => Poll::Ready(Err(error)),
             Some(ReadyOrWaitState::Waiting(mut future)) => {
                 match future.poll_unpin(cx) {
                     // The request delay timeout
@@ -307,7 +444,7 @@ pub enum ReconnectStateMachine<T: Config> {
     /// The connection was reestablished successfully
     ///
     /// # Description
-    /// Update the `ConnectionStatus` on the [`SharedState`] and return the client
+    /// Update the `ClientStatus` on the [`SharedState`] and return the client
     ///
     /// # State Transitions
     /// This state is final, may return an error if the `connection_status` at [`SharedState`] was
@@ -456,6 +593,26 @@ impl<T: Config> Future for ReconnectFuture<T> {
                         *this.attempt,
                         error
                     );
+
+                    // Give up once the max number of attempts is reached
+                    if this
+                        .state
+                        .config
+                        .max_reconnect_attempts()
+                        .is_some_and(|max_attempts| *this.attempt >= max_attempts)
+                    {
+                        tracing::error!("Giving up after {} reconnect attempts", *this.attempt);
+                        let error = Arc::new(error);
+                        match this.state.connection_status.write() {
+                            Ok(mut guard) => *guard = ClientStatus::Closed(Arc::clone(&error)),
+                            Err(poisoned) => {
+                                tracing::error!(
+                                    "FATAL ERROR: client lock was poisoned: {poisoned}"
+                                );
+                            },
+                        }
+                        return Poll::Ready(Err(error));
+                    }
                     *this.state_machine = maybe_delay.map_or_else(
                         || Some(ReconnectStateMachine::Retry),
                         |delay| Some(ReconnectStateMachine::Waiting(delay)),
@@ -495,14 +652,14 @@ impl<T: Config> Future for ReconnectFuture<T> {
                         },
                     };
 
-                    if let ConnectionStatus::Ready(client) = &*guard {
+                    if let ClientStatus::Ready(client) = &*guard {
                         tracing::warn!(
                             "Racing condition detected, two reconnects running at the same time"
                         );
                         return Poll::Ready(Ok(client.clone()));
                     }
 
-                    *guard = ConnectionStatus::Ready(client.clone());
+                    *guard = ClientStatus::Ready(client.clone());
                     drop(guard);
                     return Poll::Ready(Ok(client));
                 },
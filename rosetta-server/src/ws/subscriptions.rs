@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use jsonrpsee::core::client::TransportSenderT;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Counts the live subscriptions of a connection.
+///
+/// A subscription is counted when opened, and released when its unsubscribe request is sent,
+/// which jsonrpsee does when the subscription is dropped.
+#[derive(Debug, Default)]
+pub struct SubscriptionTracker {
+    /// Number of live subscriptions by unsubscribe method, the method subscriptions have no
+    /// unsubscribe method and are stored under `None`, they live as long as the connection.
+    live: Mutex<HashMap<Option<String>, usize>>,
+}
+
+impl SubscriptionTracker {
+    /// Records a subscription opened on this connection.
+    pub fn opened(&self, unsubscribe_method: Option<&str>) {
+        if let Ok(mut live) = self.live.lock() {
+            *live.entry(unsubscribe_method.map(str::to_string)).or_default() += 1;
+        }
+    }
+
+    /// Returns `true` if a subscription is still alive.
+    pub fn has_subscriptions(&self) -> bool {
+        self.live.lock().map_or(true, |live| !live.is_empty())
+    }
+
+    /// Releases a subscription if `message` is one of the tracked unsubscribe requests.
+    fn sending(&self, message: &str) {
+        let Ok(mut live) = self.live.lock() else {
+            return;
+        };
+        // Only parse the messages which may be unsubscribe requests
+        if !live.keys().flatten().any(|method| message.contains(method.as_str())) {
+            return;
+        }
+        let Ok(request) = serde_json::from_str::<serde_json::Value>(message) else {
+            return;
+        };
+        let Some(method) = request.get("method").and_then(serde_json::Value::as_str) else {
+            return;
+        };
+        let method = Some(method.to_string());
+        if let Some(count) = live.get_mut(&method) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                live.remove(&method);
+            }
+        }
+    }
+}
+
+/// Transport sender which releases the subscriptions of a [`SubscriptionTracker`] when their
+/// unsubscribe request is sent.
+pub struct TrackingSender<S> {
+    inner: S,
+    subscriptions: Arc<SubscriptionTracker>,
+}
+
+impl<S> TrackingSender<S> {
+    pub const fn new(inner: S, subscriptions: Arc<SubscriptionTracker>) -> Self {
+        Self { inner, subscriptions }
+    }
+}
+
+#[async_trait]
+impl<S: TransportSenderT + Send> TransportSenderT for TrackingSender<S> {
+    type Error = S::Error;
+
+    async fn send(&mut self, msg: String) -> Result<(), Self::Error> {
+        self.subscriptions.sending(&msg);
+        self.inner.send(msg).await
+    }
+
+    async fn send_ping(&mut self) -> Result<(), Self::Error> {
+        self.inner.send_ping().await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.inner.close().await
+    }
+}
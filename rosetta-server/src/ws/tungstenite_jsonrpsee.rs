@@ -22,7 +22,12 @@ impl From<&RpcClientConfig> for WebSocketConfig {
         Self {
             write_buffer_size: config.write_buffer_size,
             max_write_buffer_size: config.max_write_buffer_size,
-            max_message_size: config.max_message_size,
+            // Tungstenite only applies this limit to incoming messages
+            max_message_size: Some(
+                config.max_message_size.map_or(config.max_response_size, |max_message_size| {
+                    max_message_size.min(config.max_response_size)
+                }),
+            ),
             max_frame_size: config.max_frame_size,
             accept_unmasked_frames: config.accept_unmasked_frames,
             ..Self::default()
@@ -42,9 +47,9 @@ impl TungsteniteClient {
     /// # Errors
     /// Returns `Err` if the handshake fails
     pub async fn new(url: Url, config: &RpcClientConfig) -> Result<Self, WsError> {
-        let config = WebSocketConfig::from(config);
+        let ws_config = WebSocketConfig::from(config);
         let (ws_stream, response) =
-            connect_async_with_config(url.to_string(), Some(config), false).await?;
+            connect_async_with_config(url.to_string(), Some(ws_config), false).await?;
         let (send, receive) = ws_stream.split();
         tracing::trace!(
             "Successfully connected to the server using Tungstenite. Handshake HTTP code: {}",